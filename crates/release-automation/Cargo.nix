@@ -673,7 +673,7 @@ rec {
           }
           {
             name = "env_logger";
-            packageId = "env_logger 0.9.3";
+            packageId = "env_logger";
           }
           {
             name = "filetime";
@@ -2161,7 +2161,7 @@ rec {
         dependencies = [
           {
             name = "quote";
-            packageId = "quote 1.0.23";
+            packageId = "quote 1.0.41";
           }
           {
             name = "syn";
@@ -2333,7 +2333,7 @@ rec {
         dependencies = [
           {
             name = "proc-macro2";
-            packageId = "proc-macro2 1.0.49";
+            packageId = "proc-macro2 1.0.103";
           }
           {
             name = "syn";
@@ -2407,13 +2407,13 @@ rec {
           }
           {
             name = "proc-macro2";
-            packageId = "proc-macro2 1.0.49";
+            packageId = "proc-macro2 1.0.103";
             usesDefaultFeatures = false;
             features = [ "span-locations" ];
           }
           {
             name = "quote";
-            packageId = "quote 1.0.23";
+            packageId = "quote 1.0.41";
             usesDefaultFeatures = false;
           }
           {
@@ -2455,11 +2455,11 @@ rec {
         dependencies = [
           {
             name = "proc-macro2";
-            packageId = "proc-macro2 1.0.49";
+            packageId = "proc-macro2 1.0.103";
           }
           {
             name = "quote";
-            packageId = "quote 1.0.23";
+            packageId = "quote 1.0.41";
           }
           {
             name = "syn";
@@ -2520,11 +2520,11 @@ rec {
           }
           {
             name = "proc-macro2";
-            packageId = "proc-macro2 1.0.49";
+            packageId = "proc-macro2 1.0.103";
           }
           {
             name = "quote";
-            packageId = "quote 1.0.23";
+            packageId = "quote 1.0.41";
           }
           {
             name = "strsim";
@@ -2559,7 +2559,7 @@ rec {
           }
           {
             name = "quote";
-            packageId = "quote 1.0.23";
+            packageId = "quote 1.0.41";
           }
           {
             name = "syn";
@@ -2748,11 +2748,11 @@ rec {
           }
           {
             name = "proc-macro2";
-            packageId = "proc-macro2 1.0.49";
+            packageId = "proc-macro2 1.0.103";
           }
           {
             name = "quote";
-            packageId = "quote 1.0.23";
+            packageId = "quote 1.0.41";
           }
           {
             name = "syn";
@@ -2824,11 +2824,11 @@ rec {
           }
           {
             name = "proc-macro2";
-            packageId = "proc-macro2 1.0.49";
+            packageId = "proc-macro2 1.0.103";
           }
           {
             name = "quote";
-            packageId = "quote 1.0.23";
+            packageId = "quote 1.0.41";
           }
           {
             name = "syn";
@@ -2866,11 +2866,11 @@ rec {
           }
           {
             name = "proc-macro2";
-            packageId = "proc-macro2 1.0.49";
+            packageId = "proc-macro2 1.0.103";
           }
           {
             name = "quote";
-            packageId = "quote 1.0.23";
+            packageId = "quote 1.0.41";
           }
           {
             name = "serde_derive_internals";
@@ -2895,11 +2895,11 @@ rec {
         dependencies = [
           {
             name = "proc-macro2";
-            packageId = "proc-macro2 1.0.49";
+            packageId = "proc-macro2 1.0.103";
           }
           {
             name = "quote";
-            packageId = "quote 1.0.23";
+            packageId = "quote 1.0.41";
           }
         ];
 
@@ -2936,11 +2936,11 @@ rec {
         dependencies = [
           {
             name = "proc-macro2";
-            packageId = "proc-macro2 1.0.49";
+            packageId = "proc-macro2 1.0.103";
           }
           {
             name = "quote";
-            packageId = "quote 1.0.23";
+            packageId = "quote 1.0.41";
           }
           {
             name = "syn";
@@ -2950,53 +2950,7 @@ rec {
         ];
 
       };
-      "env_logger 0.8.4" = rec {
-        crateName = "env_logger";
-        version = "0.8.4";
-        edition = "2018";
-        sha256 = "1qzw8g11dbdfi7ixm44ldykwcqsxqkh8vx5cgpd88zmclgz8g4d1";
-        authors = [
-          "The Rust Project Developers"
-        ];
-        dependencies = [
-          {
-            name = "atty";
-            packageId = "atty";
-            optional = true;
-          }
-          {
-            name = "humantime";
-            packageId = "humantime";
-            optional = true;
-          }
-          {
-            name = "log";
-            packageId = "log";
-            features = [ "std" ];
-          }
-          {
-            name = "regex";
-            packageId = "regex";
-            optional = true;
-            usesDefaultFeatures = false;
-            features = [ "std" "perf" ];
-          }
-          {
-            name = "termcolor";
-            packageId = "termcolor";
-            optional = true;
-          }
-        ];
-        features = {
-          "atty" = [ "dep:atty" ];
-          "default" = [ "termcolor" "atty" "humantime" "regex" ];
-          "humantime" = [ "dep:humantime" ];
-          "regex" = [ "dep:regex" ];
-          "termcolor" = [ "dep:termcolor" ];
-        };
-        resolvedDefaultFeatures = [ "atty" "default" "humantime" "regex" "termcolor" ];
-      };
-      "env_logger 0.9.3" = rec {
+      "env_logger" = rec {
         crateName = "env_logger";
         version = "0.9.3";
         edition = "2018";
@@ -4510,6 +4464,22 @@ rec {
         };
         resolvedDefaultFeatures = [ "std" ];
       };
+      "matchers" = rec {
+        crateName = "matchers";
+        version = "0.1.0";
+        edition = "2018";
+        sha256 = "0n2mbk7lg2vf962c8xwzdq96yrc9i0p8dbmm4wa1nnkcp1dhfqw2";
+        authors = [
+          "Eliza Weisman <eliza@buoyant.io>"
+        ];
+        dependencies = [
+          {
+            name = "regex-automata";
+            packageId = "regex-automata";
+          }
+        ];
+
+      };
       "memchr" = rec {
         crateName = "memchr";
         version = "2.5.0";
@@ -4601,6 +4571,34 @@ rec {
         ];
 
       };
+      "nu-ansi-term" = rec {
+        crateName = "nu-ansi-term";
+        version = "0.46.0";
+        edition = "2018";
+        sha256 = "115sywxh53p190lyw97alm14nc004qj5jm5lvdj608z84rbida3p";
+        authors = [
+          "ogham@bsago.me"
+          "Ryan Scheel (Havvy) <ryan.havvy@gmail.com>"
+          "Josh Triplett <josh@joshtriplett.org>"
+          "The Nushell Project Developers"
+        ];
+        dependencies = [
+          {
+            name = "overload";
+            packageId = "overload";
+          }
+          {
+            name = "winapi";
+            packageId = "winapi";
+            target = { target, features }: ("windows" == target."os");
+            features = [ "consoleapi" "errhandlingapi" "fileapi" "handleapi" "processenv" ];
+          }
+        ];
+        features = {
+          "derive_serde_style" = [ "serde" ];
+          "serde" = [ "dep:serde" ];
+        };
+      };
       "num-bigint" = rec {
         crateName = "num-bigint";
         version = "0.4.3";
@@ -4845,11 +4843,11 @@ rec {
         dependencies = [
           {
             name = "proc-macro2";
-            packageId = "proc-macro2 1.0.49";
+            packageId = "proc-macro2 1.0.103";
           }
           {
             name = "quote";
-            packageId = "quote 1.0.23";
+            packageId = "quote 1.0.41";
           }
           {
             name = "syn";
@@ -5006,6 +5004,16 @@ rec {
           }
         ];
 
+      };
+      "overload" = rec {
+        crateName = "overload";
+        version = "0.1.1";
+        edition = "2018";
+        sha256 = "0fdgbaqwknillagy1xq7xfgv60qdbk010diwl7s1p0qx7hb16n5i";
+        authors = [
+          "Daniel Salvadori <danaugrs@gmail.com>"
+        ];
+
       };
       "pathdiff" = rec {
         crateName = "pathdiff";
@@ -5112,11 +5120,11 @@ rec {
           }
           {
             name = "proc-macro2";
-            packageId = "proc-macro2 1.0.49";
+            packageId = "proc-macro2 1.0.103";
           }
           {
             name = "quote";
-            packageId = "quote 1.0.23";
+            packageId = "quote 1.0.41";
           }
           {
             name = "syn";
@@ -5169,6 +5177,14 @@ rec {
         };
         resolvedDefaultFeatures = [ "default" "eq-separator" ];
       };
+      "pin-project-lite" = rec {
+        crateName = "pin-project-lite";
+        version = "0.2.17";
+        edition = "2018";
+        sha256 = "1kfmwvs271si96zay4mm8887v5khw0c27jc9srw1a75ykvgj54x8";
+        libName = "pin_project_lite";
+
+      };
       "pkg-config" = rec {
         crateName = "pkg-config";
         version = "0.3.26";
@@ -5404,11 +5420,11 @@ rec {
           }
           {
             name = "proc-macro2";
-            packageId = "proc-macro2 1.0.49";
+            packageId = "proc-macro2 1.0.103";
           }
           {
             name = "quote";
-            packageId = "quote 1.0.23";
+            packageId = "quote 1.0.41";
           }
           {
             name = "syn";
@@ -5442,11 +5458,11 @@ rec {
         dependencies = [
           {
             name = "proc-macro2";
-            packageId = "proc-macro2 1.0.49";
+            packageId = "proc-macro2 1.0.103";
           }
           {
             name = "quote";
-            packageId = "quote 1.0.23";
+            packageId = "quote 1.0.41";
           }
         ];
         buildDependencies = [
@@ -5476,11 +5492,12 @@ rec {
         };
         resolvedDefaultFeatures = [ "default" "proc-macro" ];
       };
-      "proc-macro2 1.0.49" = rec {
+      "proc-macro2 1.0.103" = rec {
         crateName = "proc-macro2";
-        version = "1.0.49";
-        edition = "2018";
-        sha256 = "19b3xdfmnay9mchza82lhb3n8qjrfzkxwd23f50xxzy4z6lyra2p";
+        version = "1.0.103";
+        edition = "2021";
+        sha256 = "1s29bz20xl2qk5ffs2mbdqknaj43ri673dz86axdbf47xz25psay";
+        libName = "proc_macro2";
         authors = [
           "David Tolnay <dtolnay@gmail.com>"
           "Alex Crichton <alex@alexcrichton.com>"
@@ -5517,18 +5534,18 @@ rec {
         };
         resolvedDefaultFeatures = [ "default" "proc-macro" ];
       };
-      "quote 1.0.23" = rec {
+      "quote 1.0.41" = rec {
         crateName = "quote";
-        version = "1.0.23";
+        version = "1.0.41";
         edition = "2018";
-        sha256 = "0ywwzw5xfwwgq62ihp4fbjbfdjb3ilss2vh3fka18ai59lvdhml8";
+        sha256 = "1lg108nb57lwbqlnpsii89cchk6i8pkcvrv88xh1p7a9gdz7c9ff";
         authors = [
           "David Tolnay <dtolnay@gmail.com>"
         ];
         dependencies = [
           {
             name = "proc-macro2";
-            packageId = "proc-macro2 1.0.49";
+            packageId = "proc-macro2 1.0.103";
             usesDefaultFeatures = false;
           }
         ];
@@ -5766,6 +5783,13 @@ rec {
         authors = [
           "Andrew Gallant <jamslam@gmail.com>"
         ];
+        dependencies = [
+          {
+            name = "regex-syntax";
+            packageId = "regex-syntax";
+            optional = true;
+          }
+        ];
         features = {
           "default" = [ "std" ];
           "fst" = [ "dep:fst" ];
@@ -5773,6 +5797,7 @@ rec {
           "std" = [ "regex-syntax" ];
           "transducer" = [ "std" "fst" ];
         };
+        resolvedDefaultFeatures = [ "default" "regex-syntax" "std" ];
       };
       "regex-syntax" = rec {
         crateName = "regex-syntax";
@@ -5826,6 +5851,10 @@ rec {
             name = "cargo-next";
             packageId = "cargo-next";
           }
+          {
+            name = "cargo-platform";
+            packageId = "cargo-platform";
+          }
           {
             name = "chrono";
             packageId = "chrono";
@@ -5840,6 +5869,10 @@ rec {
             name = "crates-index-helper";
             packageId = "crates-index-helper";
           }
+          {
+            name = "curl";
+            packageId = "curl";
+          }
           {
             name = "custom_debug";
             packageId = "custom_debug";
@@ -5856,10 +5889,6 @@ rec {
             name = "enumflags2";
             packageId = "enumflags2";
           }
-          {
-            name = "env_logger";
-            packageId = "env_logger 0.8.4";
-          }
           {
             name = "fancy-regex";
             packageId = "fancy-regex";
@@ -5868,6 +5897,10 @@ rec {
             name = "git2";
             packageId = "git2";
           }
+          {
+            name = "glob";
+            packageId = "glob";
+          }
           {
             name = "indoc";
             packageId = "indoc";
@@ -5892,6 +5925,10 @@ rec {
             name = "once_cell";
             packageId = "once_cell";
           }
+          {
+            name = "rayon";
+            packageId = "rayon";
+          }
           {
             name = "regex";
             packageId = "regex";
@@ -5932,6 +5969,16 @@ rec {
             name = "toml_edit";
             packageId = "toml_edit 0.2.1";
           }
+          {
+            name = "tracing";
+            packageId = "tracing";
+            features = [ "log" ];
+          }
+          {
+            name = "tracing-subscriber";
+            packageId = "tracing-subscriber";
+            features = [ "env-filter" ];
+          }
           {
             name = "yaml-rust";
             packageId = "yaml-rust";
@@ -5971,7 +6018,10 @@ rec {
             packageId = "test-case";
           }
         ];
-
+        features = {
+          "indicatif" = [ "dep:indicatif" ];
+          "progress-bar" = [ "indicatif" ];
+        };
       };
       "remove_dir_all" = rec {
         crateName = "remove_dir_all";
@@ -6393,11 +6443,11 @@ rec {
         dependencies = [
           {
             name = "proc-macro2";
-            packageId = "proc-macro2 1.0.49";
+            packageId = "proc-macro2 1.0.103";
           }
           {
             name = "quote";
-            packageId = "quote 1.0.23";
+            packageId = "quote 1.0.41";
           }
           {
             name = "syn";
@@ -6421,11 +6471,11 @@ rec {
         dependencies = [
           {
             name = "proc-macro2";
-            packageId = "proc-macro2 1.0.49";
+            packageId = "proc-macro2 1.0.103";
           }
           {
             name = "quote";
-            packageId = "quote 1.0.23";
+            packageId = "quote 1.0.41";
           }
           {
             name = "syn";
@@ -6550,11 +6600,11 @@ rec {
           }
           {
             name = "proc-macro2";
-            packageId = "proc-macro2 1.0.49";
+            packageId = "proc-macro2 1.0.103";
           }
           {
             name = "quote";
-            packageId = "quote 1.0.23";
+            packageId = "quote 1.0.41";
           }
           {
             name = "syn";
@@ -6635,6 +6685,24 @@ rec {
           "std" = [ "digest/std" ];
         };
       };
+      "sharded-slab" = rec {
+        crateName = "sharded-slab";
+        version = "0.1.7";
+        edition = "2018";
+        sha256 = "1xipjr4nqsgw34k7a2cgj9zaasl2ds6jwn89886kww93d32a637l";
+        authors = [
+          "Eliza Weisman <eliza@buoyant.io>"
+        ];
+        dependencies = [
+          {
+            name = "lazy_static";
+            packageId = "lazy_static";
+          }
+        ];
+        features = {
+          "loom" = [ "dep:loom" ];
+        };
+      };
       "shell-escape" = rec {
         crateName = "shell-escape";
         version = "0.1.5";
@@ -6706,6 +6774,25 @@ rec {
         };
         resolvedDefaultFeatures = [ "default" "std" ];
       };
+      "smallvec" = rec {
+        crateName = "smallvec";
+        version = "1.16.3";
+        edition = "2018";
+        sha256 = "06z0akmcq2zc9qg22dxda298p50gg3di5ggl3y2nllag8ypwhgav";
+        authors = [
+          "The Servo Project Developers"
+        ];
+        features = {
+          "arbitrary" = [ "dep:arbitrary" ];
+          "bincode" = [ "dep:bincode" ];
+          "const_new" = [ "const_generics" ];
+          "drain_keep_rest" = [ "drain_filter" ];
+          "impl_bincode" = [ "bincode" "unty" ];
+          "malloc_size_of" = [ "dep:malloc_size_of" ];
+          "serde" = [ "dep:serde" ];
+          "unty" = [ "dep:unty" ];
+        };
+      };
       "smartstring" = rec {
         crateName = "smartstring";
         version = "1.0.1";
@@ -7034,11 +7121,11 @@ rec {
           }
           {
             name = "proc-macro2";
-            packageId = "proc-macro2 1.0.49";
+            packageId = "proc-macro2 1.0.103";
           }
           {
             name = "quote";
-            packageId = "quote 1.0.23";
+            packageId = "quote 1.0.41";
           }
           {
             name = "syn";
@@ -7093,12 +7180,12 @@ rec {
         dependencies = [
           {
             name = "proc-macro2";
-            packageId = "proc-macro2 1.0.49";
+            packageId = "proc-macro2 1.0.103";
             usesDefaultFeatures = false;
           }
           {
             name = "quote";
-            packageId = "quote 1.0.23";
+            packageId = "quote 1.0.41";
             optional = true;
             usesDefaultFeatures = false;
           }
@@ -7116,6 +7203,39 @@ rec {
         };
         resolvedDefaultFeatures = [ "clone-impls" "default" "derive" "extra-traits" "full" "parsing" "printing" "proc-macro" "quote" "visit" ];
       };
+      "syn 2.0.106" = rec {
+        crateName = "syn";
+        version = "2.0.106";
+        edition = "2021";
+        sha256 = "19mddxp1ia00hfdzimygqmr1jqdvyl86k48427bkci4d08wc9rzd";
+        authors = [
+          "David Tolnay <dtolnay@gmail.com>"
+        ];
+        dependencies = [
+          {
+            name = "proc-macro2";
+            packageId = "proc-macro2 1.0.103";
+            usesDefaultFeatures = false;
+          }
+          {
+            name = "quote";
+            packageId = "quote 1.0.41";
+            optional = true;
+            usesDefaultFeatures = false;
+          }
+          {
+            name = "unicode-ident";
+            packageId = "unicode-ident";
+          }
+        ];
+        features = {
+          "default" = [ "derive" "parsing" "printing" "clone-impls" "proc-macro" ];
+          "printing" = [ "dep:quote" ];
+          "proc-macro" = [ "proc-macro2/proc-macro" "quote?/proc-macro" ];
+          "test" = [ "syn-test-suite/all-features" ];
+        };
+        resolvedDefaultFeatures = [ "clone-impls" "extra-traits" "full" "parsing" "printing" "proc-macro" "visit-mut" ];
+      };
       "synstructure" = rec {
         crateName = "synstructure";
         version = "0.12.6";
@@ -7127,12 +7247,12 @@ rec {
         dependencies = [
           {
             name = "proc-macro2";
-            packageId = "proc-macro2 1.0.49";
+            packageId = "proc-macro2 1.0.103";
             usesDefaultFeatures = false;
           }
           {
             name = "quote";
-            packageId = "quote 1.0.23";
+            packageId = "quote 1.0.41";
             usesDefaultFeatures = false;
           }
           {
@@ -7317,11 +7437,11 @@ rec {
           }
           {
             name = "proc-macro2";
-            packageId = "proc-macro2 1.0.49";
+            packageId = "proc-macro2 1.0.103";
           }
           {
             name = "quote";
-            packageId = "quote 1.0.23";
+            packageId = "quote 1.0.41";
           }
           {
             name = "syn";
@@ -7379,11 +7499,11 @@ rec {
         dependencies = [
           {
             name = "proc-macro2";
-            packageId = "proc-macro2 1.0.49";
+            packageId = "proc-macro2 1.0.103";
           }
           {
             name = "quote";
-            packageId = "quote 1.0.23";
+            packageId = "quote 1.0.41";
           }
           {
             name = "syn";
@@ -7593,6 +7713,260 @@ rec {
         ];
 
       };
+      "tracing" = rec {
+        crateName = "tracing";
+        version = "0.1.44";
+        edition = "2018";
+        sha256 = "006ilqkg1lmfdh3xhg3z762izfwmxcvz0w7m4qx2qajbz9i1drv3";
+        authors = [
+          "Eliza Weisman <eliza@buoyant.io>"
+          "Tokio Contributors <team@tokio.rs>"
+        ];
+        dependencies = [
+          {
+            name = "log";
+            packageId = "log";
+            optional = true;
+          }
+          {
+            name = "pin-project-lite";
+            packageId = "pin-project-lite";
+          }
+          {
+            name = "tracing-attributes";
+            packageId = "tracing-attributes";
+            optional = true;
+          }
+          {
+            name = "tracing-core";
+            packageId = "tracing-core";
+            usesDefaultFeatures = false;
+          }
+        ];
+        devDependencies = [
+          {
+            name = "log";
+            packageId = "log";
+          }
+        ];
+        features = {
+          "attributes" = [ "tracing-attributes" ];
+          "default" = [ "std" "attributes" ];
+          "log" = [ "dep:log" ];
+          "log-always" = [ "log" ];
+          "std" = [ "tracing-core/std" ];
+          "tracing-attributes" = [ "dep:tracing-attributes" ];
+          "valuable" = [ "tracing-core/valuable" ];
+        };
+        resolvedDefaultFeatures = [ "attributes" "default" "log" "std" "tracing-attributes" ];
+      };
+      "tracing-attributes" = rec {
+        crateName = "tracing-attributes";
+        version = "0.1.31";
+        edition = "2018";
+        sha256 = "1np8d77shfvz0n7camx2bsf1qw0zg331lra0hxb4cdwnxjjwz43l";
+        procMacro = true;
+        libName = "tracing_attributes";
+        authors = [
+          "Tokio Contributors <team@tokio.rs>"
+          "Eliza Weisman <eliza@buoyant.io>"
+          "David Barsky <dbarsky@amazon.com>"
+        ];
+        dependencies = [
+          {
+            name = "proc-macro2";
+            packageId = "proc-macro2 1.0.103";
+          }
+          {
+            name = "quote";
+            packageId = "quote 1.0.41";
+          }
+          {
+            name = "syn";
+            packageId = "syn 2.0.106";
+            usesDefaultFeatures = false;
+            features = [ "full" "parsing" "printing" "visit-mut" "clone-impls" "extra-traits" "proc-macro" ];
+          }
+        ];
+        features = {
+        };
+      };
+      "tracing-core" = rec {
+        crateName = "tracing-core";
+        version = "0.1.36";
+        edition = "2018";
+        sha256 = "16mpbz6p8vd6j7sf925k9k8wzvm9vdfsjbynbmaxxyq6v7wwm5yv";
+        libName = "tracing_core";
+        authors = [
+          "Tokio Contributors <team@tokio.rs>"
+        ];
+        dependencies = [
+          {
+            name = "once_cell";
+            packageId = "once_cell";
+            optional = true;
+          }
+          {
+            name = "valuable";
+            packageId = "valuable";
+            optional = true;
+            usesDefaultFeatures = false;
+            target = { target, features }: (target."tracing_unstable" or false);
+          }
+        ];
+        features = {
+          "default" = [ "std" "valuable?/std" ];
+          "once_cell" = [ "dep:once_cell" ];
+          "std" = [ "once_cell" ];
+          "valuable" = [ "dep:valuable" ];
+        };
+        resolvedDefaultFeatures = [ "default" "once_cell" "std" ];
+      };
+      "tracing-log" = rec {
+        crateName = "tracing-log";
+        version = "0.2.0";
+        edition = "2018";
+        sha256 = "1hs77z026k730ij1a9dhahzrl0s073gfa2hm5p0fbl0b80gmz1gf";
+        authors = [
+          "Tokio Contributors <team@tokio.rs>"
+        ];
+        dependencies = [
+          {
+            name = "log";
+            packageId = "log";
+          }
+          {
+            name = "once_cell";
+            packageId = "once_cell";
+          }
+          {
+            name = "tracing-core";
+            packageId = "tracing-core";
+          }
+        ];
+        features = {
+          "ahash" = [ "dep:ahash" ];
+          "default" = [ "log-tracer" "std" ];
+          "interest-cache" = [ "lru" "ahash" ];
+          "lru" = [ "dep:lru" ];
+          "std" = [ "log/std" ];
+        };
+        resolvedDefaultFeatures = [ "log-tracer" "std" ];
+      };
+      "tracing-subscriber" = rec {
+        crateName = "tracing-subscriber";
+        version = "0.3.19";
+        edition = "2018";
+        sha256 = "0220rignck8072i89jjsh140vmh14ydwpdwnifyaf3xcnpn9s678";
+        libName = "tracing_subscriber";
+        authors = [
+          "Eliza Weisman <eliza@buoyant.io>"
+          "David Barsky <me@davidbarsky.com>"
+          "Tokio Contributors <team@tokio.rs>"
+        ];
+        dependencies = [
+          {
+            name = "matchers";
+            packageId = "matchers";
+            optional = true;
+          }
+          {
+            name = "nu-ansi-term";
+            packageId = "nu-ansi-term";
+            optional = true;
+          }
+          {
+            name = "once_cell";
+            packageId = "once_cell";
+            optional = true;
+          }
+          {
+            name = "regex";
+            packageId = "regex";
+            optional = true;
+            usesDefaultFeatures = false;
+            features = [ "std" "unicode-case" "unicode-perl" ];
+          }
+          {
+            name = "sharded-slab";
+            packageId = "sharded-slab";
+            optional = true;
+          }
+          {
+            name = "smallvec";
+            packageId = "smallvec";
+            optional = true;
+          }
+          {
+            name = "thread_local";
+            packageId = "thread_local";
+            optional = true;
+          }
+          {
+            name = "tracing";
+            packageId = "tracing";
+            optional = true;
+            usesDefaultFeatures = false;
+          }
+          {
+            name = "tracing-core";
+            packageId = "tracing-core";
+            usesDefaultFeatures = false;
+          }
+          {
+            name = "tracing-log";
+            packageId = "tracing-log";
+            optional = true;
+            usesDefaultFeatures = false;
+            features = [ "log-tracer" "std" ];
+          }
+        ];
+        devDependencies = [
+          {
+            name = "regex";
+            packageId = "regex";
+            usesDefaultFeatures = false;
+            features = [ "std" ];
+          }
+          {
+            name = "tracing";
+            packageId = "tracing";
+          }
+          {
+            name = "tracing-log";
+            packageId = "tracing-log";
+          }
+        ];
+        features = {
+          "ansi" = [ "fmt" "nu-ansi-term" ];
+          "chrono" = [ "dep:chrono" ];
+          "default" = [ "smallvec" "fmt" "ansi" "tracing-log" "std" ];
+          "env-filter" = [ "matchers" "regex" "once_cell" "tracing" "std" "thread_local" ];
+          "fmt" = [ "registry" "std" ];
+          "json" = [ "tracing-serde" "serde" "serde_json" ];
+          "local-time" = [ "time/local-offset" ];
+          "matchers" = [ "dep:matchers" ];
+          "nu-ansi-term" = [ "dep:nu-ansi-term" ];
+          "once_cell" = [ "dep:once_cell" ];
+          "parking_lot" = [ "dep:parking_lot" ];
+          "regex" = [ "dep:regex" ];
+          "registry" = [ "sharded-slab" "thread_local" "std" ];
+          "serde" = [ "dep:serde" ];
+          "serde_json" = [ "dep:serde_json" ];
+          "sharded-slab" = [ "dep:sharded-slab" ];
+          "smallvec" = [ "dep:smallvec" ];
+          "std" = [ "alloc" "tracing-core/std" ];
+          "thread_local" = [ "dep:thread_local" ];
+          "time" = [ "dep:time" ];
+          "tracing" = [ "dep:tracing" ];
+          "tracing-log" = [ "dep:tracing-log" ];
+          "tracing-serde" = [ "dep:tracing-serde" ];
+          "valuable" = [ "tracing-core/valuable" "valuable_crate" "valuable-serde" "tracing-serde/valuable" ];
+          "valuable-serde" = [ "dep:valuable-serde" ];
+          "valuable_crate" = [ "dep:valuable_crate" ];
+        };
+        resolvedDefaultFeatures = [ "alloc" "ansi" "default" "env-filter" "fmt" "matchers" "nu-ansi-term" "once_cell" "regex" "registry" "sharded-slab" "smallvec" "std" "thread_local" "tracing" "tracing-log" ];
+      };
       "twoway" = rec {
         crateName = "twoway";
         version = "0.2.2";
@@ -7842,6 +8216,19 @@ rec {
         };
         resolvedDefaultFeatures = [ "default" ];
       };
+      "valuable" = rec {
+        crateName = "valuable";
+        version = "0.1.1";
+        edition = "2021";
+        sha256 = "0r9srp55v7g27s5bg7a2m095fzckrcdca5maih6dy9bay6fflwxs";
+        features = {
+          "default" = [ "std" ];
+          "derive" = [ "valuable-derive" ];
+          "std" = [ "alloc" ];
+          "valuable-derive" = [ "dep:valuable-derive" ];
+        };
+        resolvedDefaultFeatures = [ "alloc" "std" ];
+      };
       "vcpkg" = rec {
         crateName = "vcpkg";
         version = "0.2.15";
@@ -7945,11 +8332,11 @@ rec {
         dependencies = [
           {
             name = "proc-macro2";
-            packageId = "proc-macro2 1.0.49";
+            packageId = "proc-macro2 1.0.103";
           }
           {
             name = "quote";
-            packageId = "quote 1.0.23";
+            packageId = "quote 1.0.41";
           }
         ];
 
@@ -8103,11 +8490,11 @@ rec {
           }
           {
             name = "proc-macro2";
-            packageId = "proc-macro2 1.0.49";
+            packageId = "proc-macro2 1.0.103";
           }
           {
             name = "quote";
-            packageId = "quote 1.0.23";
+            packageId = "quote 1.0.41";
           }
           {
             name = "syn";
@@ -8136,7 +8523,7 @@ rec {
         dependencies = [
           {
             name = "quote";
-            packageId = "quote 1.0.23";
+            packageId = "quote 1.0.41";
           }
           {
             name = "wasm-bindgen-macro-support";
@@ -8160,11 +8547,11 @@ rec {
         dependencies = [
           {
             name = "proc-macro2";
-            packageId = "proc-macro2 1.0.49";
+            packageId = "proc-macro2 1.0.103";
           }
           {
             name = "quote";
-            packageId = "quote 1.0.23";
+            packageId = "quote 1.0.41";
           }
           {
             name = "syn";
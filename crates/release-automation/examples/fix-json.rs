@@ -50,7 +50,7 @@ fn get_clippy_output() -> Result<String, Error> {
         "-D",
         "clippy::correctness",
     ]);
-    log::debug!("running {:#?}", &cmd);
+    tracing::debug!("running {:#?}", &cmd);
 
     let clippy_output_raw = cmd.output()?.stdout;
     let clippy_output_string = String::from_utf8(clippy_output_raw)?;
@@ -68,10 +68,10 @@ fn get_clippy_output() -> Result<String, Error> {
 }
 
 fn main() -> Result<(), Error> {
-    env_logger::builder()
-        // .filter_level(args.log_level.to_level_filter())
-        .filter_level(log::LevelFilter::Info)
-        .format_timestamp(None)
+    tracing_subscriber::fmt()
+        .with_max_level(tracing::Level::INFO)
+        .with_writer(std::io::stderr)
+        .without_time()
         .init();
 
     let mut iterations = 0;
@@ -80,7 +80,7 @@ fn main() -> Result<(), Error> {
 
     loop {
         iterations += 1;
-        log::info!("[{}] running clippy...", iterations);
+        tracing::info!("[{}] running clippy...", iterations);
 
         let json = get_clippy_output()?;
 
@@ -126,7 +126,7 @@ fn main() -> Result<(), Error> {
             }
         }
 
-        log::info!(
+        tracing::info!(
             "[{}]: got {} lints and {} files with {} suggestions",
             iterations,
             clippy_lint_elements.len(),
@@ -135,7 +135,7 @@ fn main() -> Result<(), Error> {
         );
 
         if num_suggestions == 0 {
-            log::info!("[{}] no more suggestions found, stopping", iterations);
+            tracing::info!("[{}] no more suggestions found, stopping", iterations);
             break;
         }
 
@@ -157,7 +157,7 @@ fn main() -> Result<(), Error> {
             fs::write(source_file, fixes)?;
         }
 
-        log::info!(
+        tracing::info!(
             "[{}]: suggestions processed {}, of which successful: {}",
             iterations,
             new_suggestions_processed,
@@ -168,7 +168,7 @@ fn main() -> Result<(), Error> {
         suggestions_processed_successfully += new_suggestions_processed_successfully;
     }
 
-    log::info!(
+    tracing::info!(
         "{} iterations. suggestions processed {}, of which successful: {}",
         iterations,
         suggestions_processed,
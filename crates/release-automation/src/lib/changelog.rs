@@ -6,15 +6,21 @@ use anyhow::bail;
 use comrak::nodes::Ast;
 use comrak::nodes::{AstNode, NodeValue};
 use comrak::{format_commonmark, parse_document, Arena, ComrakOptions};
-use log::{debug, trace, warn};
-use once_cell::unsync::OnceCell;
+use semver::Version;
 use serde::{Deserialize, Serialize};
 use serde_with::skip_serializing_none;
 use std::io::Write;
 use std::ops::Add;
 use std::path::{Path, PathBuf};
-use std::{cell::RefCell, convert::TryFrom};
-use std::{collections::HashSet, convert::TryInto};
+use std::{
+    cell::{Cell, RefCell},
+    convert::TryFrom,
+};
+use std::{
+    collections::{HashMap, HashSet},
+    convert::TryInto,
+};
+use tracing::{debug, info, trace, warn};
 
 /// This data structure helps implement the YAML-type frontmatter for `ChangelogT`.
 /// Please see the [serde_yaml docs](https://docs.rs/serde_yaml/0.9.11/serde_yaml/index.html#using-serde-derive)
@@ -26,24 +32,71 @@ pub struct Frontmatter {
     #[serde(skip_serializing_if = "Option::is_none")]
     default_unreleasable: Option<bool>,
 
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "deserialize_semver_increment_mode"
+    )]
     semver_increment_mode: Option<SemverIncrementMode>,
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "deserialize_semver_increment_mode"
+    )]
     default_semver_increment_mode: Option<SemverIncrementMode>,
+
+    /// The tag of the previous release if it doesn't follow the release tag template, e.g. because the crate was renamed since.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    previous_release_tag: Option<String>,
+}
+
+/// Accepts the plain names of `SemverIncrementMode::from_str` besides the tagged modes, e.g. `rc` as well as `!pre rc`.
+fn deserialize_semver_increment_mode<'de, D>(
+    deserializer: D,
+) -> Result<Option<SemverIncrementMode>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    use serde::de::Error;
+
+    match serde_yaml::Value::deserialize(deserializer)? {
+        serde_yaml::Value::Null => Ok(None),
+        serde_yaml::Value::String(name) => name.parse().map(Some).map_err(D::Error::custom),
+        value => SemverIncrementMode::deserialize(value)
+            .map(Some)
+            .map_err(D::Error::custom),
+    }
 }
 
 impl Frontmatter {
     pub fn unreleasable(&self) -> bool {
-        self.unreleasable
-            .unwrap_or_else(|| self.default_unreleasable.unwrap_or_default())
+        self.declared_unreleasable().unwrap_or_default()
+    }
+
+    /// Whether the changelog declares to be unreleasable, either explicitly or by default, if it declares either.
+    pub fn declared_unreleasable(&self) -> Option<bool> {
+        self.unreleasable.or(self.default_unreleasable)
+    }
+
+    /// The `default_unreleasable` of the workspace changelog applies to the crates that don't declare whether they're unreleasable.
+    pub fn default_unreleasable(&self) -> Option<bool> {
+        self.default_unreleasable
     }
 
     pub fn semver_increment_mode(&self) -> SemverIncrementMode {
-        self.semver_increment_mode.clone().unwrap_or_else(|| {
-            self.default_semver_increment_mode
-                .clone()
-                .unwrap_or_default()
-        })
+        self.declared_semver_increment_mode().unwrap_or_default()
+    }
+
+    /// The increment mode for the unreleased changes, or the default one of the crate, if either is set.
+    pub fn declared_semver_increment_mode(&self) -> Option<SemverIncrementMode> {
+        self.semver_increment_mode
+            .clone()
+            .or_else(|| self.default_semver_increment_mode.clone())
+    }
+
+    /// The tag to look up the previous release with if the one according to the release tag template doesn't exist.
+    pub fn previous_release_tag(&self) -> Option<&str> {
+        self.previous_release_tag.as_deref()
     }
 
     pub fn is_empty(&self) -> bool {
@@ -51,6 +104,7 @@ impl Frontmatter {
             && self.default_unreleasable.is_none()
             && self.semver_increment_mode.is_none()
             && self.default_semver_increment_mode.is_none()
+            && self.previous_release_tag.is_none()
     }
 
     /// Remove any non-default values in the frontmatter.
@@ -85,11 +139,49 @@ impl ReleaseChange {
     }
 }
 
+/// The keep-a-changelog categories the entries of a release are grouped by, in their canonical order.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ChangeCategory {
+    Added,
+    Changed,
+    Deprecated,
+    Removed,
+    Fixed,
+    Security,
+    /// Entries that don't appear below a category heading.
+    Other,
+}
+
+impl ChangeCategory {
+    /// Parses the text of a category heading, e.g. `Added` or `[Fixed]`.
+    pub fn from_heading(heading: &str) -> Option<Self> {
+        match normalize_heading_name(heading).to_lowercase().as_str() {
+            "added" => Some(Self::Added),
+            "changed" => Some(Self::Changed),
+            "deprecated" => Some(Self::Deprecated),
+            "removed" => Some(Self::Removed),
+            "fixed" => Some(Self::Fixed),
+            "security" => Some(Self::Security),
+            _ => None,
+        }
+    }
+}
+
+/// A list item below a release or the unreleased heading.
+#[derive(Debug, PartialEq, Eq)]
+pub struct ChangeEntry {
+    pub category: ChangeCategory,
+    /// The Markdown content of the list item.
+    pub text: String,
+}
+
 #[derive(Debug, PartialEq, Eq)]
 pub enum ChangeT {
     Release(ReleaseChange),
     Unreleased,
     Changelog,
+    /// An entry of the preceding release or unreleased change.
+    Entry(ChangeEntry),
     None,
 }
 
@@ -132,7 +224,9 @@ impl<'a> ChangeT {
                             ));
                         }
                         CrateChangelog::RELEASE_HEADING_LEVEL => {
-                            change = ChangeT::Release(ReleaseChange::CrateReleaseChange(title));
+                            change = ChangeT::Release(ReleaseChange::CrateReleaseChange(
+                                release_heading_version(&title).to_string(),
+                            ));
                         }
 
                         _ => {}
@@ -182,7 +276,7 @@ pub struct Machinery<'a> {
     #[debug(skip)]
     arena: Arena<AstNode<'a>>,
     #[debug(skip)]
-    root: OnceCell<&'a comrak::arena_tree::Node<'a, RefCell<Ast>>>,
+    root: Cell<Option<&'a comrak::arena_tree::Node<'a, RefCell<Ast>>>>,
     #[debug(skip)]
     options: ComrakOptions,
 }
@@ -261,11 +355,20 @@ where
         Self(Machinery::with_path(path), PhantomData::<T>)
     }
 
-    fn root(&'a self) -> Fallible<&&'a comrak::arena_tree::Node<'a, RefCell<Ast>>> {
-        self.0.root.get_or_try_init(|| {
-            let s = std::fs::read_to_string(&self.0.path)?;
-            Ok(parse_document(&self.0.arena, &s, &self.0.options))
-        })
+    fn root(&'a self) -> Fallible<&'a comrak::arena_tree::Node<'a, RefCell<Ast>>> {
+        match self.0.root.get() {
+            Some(root) => Ok(root),
+            None => self.reload(),
+        }
+    }
+
+    /// Parses the file anew, which is required to observe the changes that were written to it.
+    fn reload(&'a self) -> Fallible<&'a comrak::arena_tree::Node<'a, RefCell<Ast>>> {
+        let s = std::fs::read_to_string(&self.0.path)?;
+        let root = parse_document(&self.0.arena, &s, &self.0.options);
+        self.0.root.set(Some(root));
+
+        Ok(root)
     }
 
     pub fn path(&'a self) -> &'a Path {
@@ -280,32 +383,124 @@ where
         &self.0.options
     }
 
+    /// The releases and the unreleased changes in the order of the document,
+    /// each followed by its entries with the category of the heading they appear below.
     pub fn changes(&'a self) -> Fallible<Vec<ChangeT>> {
         let root = self.root()?;
         let mut changes = vec![];
 
+        // only set within a release or the unreleased section
+        let mut category = None;
+
         for (i, node) in root.children().enumerate() {
-            // we're only interested in the headings here
-            if let NodeValue::Heading(heading) = node.data.borrow().value {
-                let mut msg = format!("[{}] heading at level {}", i, heading.level);
+            let heading_level = match node.data.borrow().value {
+                NodeValue::Heading(heading) => Some(heading.level),
+                _ => None,
+            };
 
-                if heading.level == T::RELEASE_HEADING_LEVEL {
-                    match ChangeT::from_heading_node(node, T::RELEASE_HEADING_LEVEL)? {
-                        ChangeT::None => {}
-                        change => {
-                            msg += &format!(" => [{}] derived change '{:?}'", i, change);
-                            changes.push(change);
+            match heading_level {
+                Some(level) => {
+                    let mut msg = format!("[{}] heading at level {}", i, level);
+
+                    if level == T::RELEASE_HEADING_LEVEL {
+                        category = None;
+
+                        match ChangeT::from_heading_node(node, T::RELEASE_HEADING_LEVEL)? {
+                            ChangeT::None => {}
+                            change => {
+                                msg += &format!(" => [{}] derived change '{:?}'", i, change);
+                                if matches!(change, ChangeT::Release(_) | ChangeT::Unreleased) {
+                                    category = Some(ChangeCategory::Other);
+                                }
+                                changes.push(change);
+                            }
                         }
+                    } else if level > T::RELEASE_HEADING_LEVEL && category.is_some() {
+                        category = Some(category_of_heading(node).unwrap_or(ChangeCategory::Other));
+                        msg += &format!(" => category {:?}", category);
                     }
+
+                    trace!("{}", msg);
                 }
 
-                trace!("{}", msg);
+                None => {
+                    let category = match category {
+                        Some(category) => category,
+                        None => continue,
+                    };
+
+                    if let NodeValue::List(_) = node.data.borrow().value {
+                        for item in node.children() {
+                            changes.push(ChangeT::Entry(ChangeEntry {
+                                category,
+                                text: render_children(item, self.options())?,
+                            }));
+                        }
+                    }
+                }
             }
         }
 
         Ok(changes)
     }
 
+    /// Renders the content below the release heading with the given title, without the heading itself.
+    /// The title may be followed by the date of the release in the heading.
+    pub fn release_section(&'a self, title: &str) -> Fallible<Option<String>> {
+        self.section(|heading| release_heading_version(heading) == title)
+    }
+
+    /// Renders the content below the unreleased heading, without the heading itself.
+    pub fn unreleased_section(&'a self) -> Fallible<Option<String>> {
+        self.section(|heading| normalize_heading_name(heading).to_lowercase() == "unreleased")
+    }
+
+    /// Renders the content below the first release level heading that matches the predicate, grouped by category.
+    fn section<F>(&'a self, is_heading: F) -> Fallible<Option<String>>
+    where
+        F: Fn(&str) -> bool,
+    {
+        let mut section: Option<Vec<&'a AstNode<'a>>> = None;
+
+        for node in self.root()?.children() {
+            let heading_level = match &node.data.borrow().value {
+                NodeValue::Heading(heading) => Some(heading.level),
+                _ => None,
+            };
+
+            match heading_level {
+                Some(level)
+                    if section.is_none()
+                        && level == T::RELEASE_HEADING_LEVEL
+                        && matches!(get_heading_text(node), Some(text) if is_heading(&text)) =>
+                {
+                    section = Some(vec![]);
+                }
+                Some(level) if section.is_some() && level <= T::RELEASE_HEADING_LEVEL => break,
+                _ => {
+                    if let Some(nodes) = section.as_mut() {
+                        nodes.push(node);
+                    }
+                }
+            }
+        }
+
+        section
+            .map(|nodes| {
+                let mut buf = vec![];
+                for node in group_by_category(nodes) {
+                    // separate the blocks by an empty line
+                    if !buf.is_empty() {
+                        buf.push(b'\n');
+                    }
+                    format_commonmark(node, self.options(), &mut buf)?;
+                }
+
+                Ok(String::from_utf8(buf)?.trim().to_string())
+            })
+            .transpose()
+    }
+
     fn changes_filtered<F>(&'a self, filter: F) -> Fallible<Vec<ChangeT>>
     where
         F: FnMut(&ChangeT) -> bool,
@@ -323,6 +518,33 @@ where
             .flatten())
     }
 
+    /// Writes the given Frontmatter back to the changelog file, replacing the existing one or adding it at the top.
+    /// Everything after the closing delimiter is left as is. An empty Frontmatter removes the block entirely.
+    pub fn set_front_matter(&'a self, fm: &Frontmatter) -> Fallible<()> {
+        let cl_str = std::fs::read_to_string(self.path())?;
+        let (body, separator) = match split_front_matter(&cl_str) {
+            Some((_, body)) => (body, ""),
+            // separate the newly added block from the existing content
+            None => (cl_str.as_str(), "\n"),
+        };
+
+        let cl_final = if fm.is_empty() {
+            body.trim_start_matches(&['\r', '\n'][..]).to_string()
+        } else {
+            let fm_str = serde_yaml::to_string(&fm)?;
+            trace!("new frontmatter:\n{}", fm_str);
+
+            format!("---\n{}---\n{}{}", fm_str, separator, body)
+        };
+
+        trace!("new changelog:\n{}", cl_final);
+
+        std::fs::File::create(self.path())?.write_all(cl_final.as_bytes())?;
+        self.reload()?;
+
+        Ok(())
+    }
+
     /// Find and parse the frontmatter of this crate's changelog file.
     pub fn front_matter(&'a self) -> Fallible<Option<Frontmatter>> {
         for (i, node) in self.root()?.children().enumerate() {
@@ -375,91 +597,124 @@ impl<'a> HeadingLevel for ChangelogT<'a, CrateChangelog> {
 }
 
 impl<'a> ChangelogT<'a, CrateChangelog> {
-    /// Create a new release heading for the items currently under the Unreleased heading.
-    /// The target heading will be created regardless of whether one with the same name exists.
-    pub fn add_release(&'a self, title: String) -> Fallible<()> {
-        let root = self.root()?;
-
-        let mut unreleased_node = None;
-        let mut topmost_release = None;
-
-        for (i, node) in root.children().enumerate() {
-            if let NodeValue::Heading(heading) = &node.data.borrow().value {
-                let mut msg = format!("[{}] heading at level {}", i, heading.level);
-
-                if heading.level == CrateChangelog::RELEASE_HEADING_LEVEL {
-                    if let Some(text_str) = get_heading_text(node) {
-                        msg += &format!(" => [{}] found heading text '{}'", i, text_str);
+    /// Moves the content below the unreleased heading to a new release heading `{version} - {date}` right below it,
+    /// which leaves an empty unreleased section. If there's no unreleased heading it's created together with the release,
+    /// above the previous releases. The frontmatter and everything outside of the unreleased section is left as is.
+    pub fn add_release(
+        &'a self,
+        version: &Version,
+        date: chrono::NaiveDate,
+        empty_unreleased_policy: EmptyUnreleasedPolicy,
+    ) -> Fallible<()> {
+        let cl_str = std::fs::read_to_string(self.path())?;
+        let body = split_front_matter(&cl_str).map_or(cl_str.as_str(), |(_, body)| body);
+        let front_matter = &cl_str[..cl_str.len() - body.len()];
 
-                        if text_str.to_lowercase().contains("unreleased") {
-                            // identified unreleased heading
+        let lines = body.split_inclusive('\n').collect::<Vec<_>>();
+        let release_headings = headings(&lines)
+            .into_iter()
+            .filter(|(_, level)| *level <= Self::RELEASE_HEADING_LEVEL)
+            .collect::<Vec<_>>();
+        let is_unreleased =
+            |line: &str| normalize_heading_name(heading_text(line)).to_lowercase() == "unreleased";
+
+        let first_release = release_headings
+            .iter()
+            .position(|(_, level)| *level == Self::RELEASE_HEADING_LEVEL);
+        let unreleased = release_headings.iter().position(|(i, level)| {
+            *level == Self::RELEASE_HEADING_LEVEL && is_unreleased(lines[*i])
+        });
 
-                            if let Some(top) = topmost_release {
-                                bail!(
-                                    "expected the unreleased heading to be first heading with level {}. found instead: {:?}",
-                                    Self::RELEASE_HEADING_LEVEL,
-                                    get_heading_text(top)
-                                );
-                            }
+        let (before, unreleased_heading, section, after) = match (unreleased, first_release) {
+            (Some(unreleased), Some(first_release)) if unreleased != first_release => bail!(
+                "expected the unreleased heading to be first heading with level {} in {:?}. found instead: {:?}",
+                Self::RELEASE_HEADING_LEVEL,
+                self.path(),
+                heading_text(lines[release_headings[first_release].0])
+            ),
 
-                            msg += " => found unreleased section";
-                            unreleased_node = Some(node);
-                            break;
-                        };
-                    }
+            (Some(unreleased), _) => {
+                let line = release_headings[unreleased].0;
+                let end = match release_headings.get(unreleased + 1) {
+                    Some((next, _)) => *next,
+                    // the link reference definitions at the end of the file don't belong to the section
+                    None => line + 1 + link_definitions_start(&lines[line + 1..]),
+                };
 
-                    if topmost_release.is_none() {
-                        topmost_release = Some(node);
-                    }
+                let mut unreleased_heading = lines[line].to_string();
+                if !unreleased_heading.ends_with('\n') {
+                    unreleased_heading.push('\n');
                 }
 
-                trace!("{}", msg);
+                (
+                    lines[..line].concat(),
+                    unreleased_heading,
+                    lines[line + 1..end].concat(),
+                    lines[end..].concat(),
+                )
             }
-        }
 
-        // construct the new heading node
-        let heading_value = NodeValue::Heading(comrak::nodes::NodeHeading {
-            level: Self::RELEASE_HEADING_LEVEL,
-            setext: false,
-        });
-        let heading_ast = comrak::nodes::Ast::new(heading_value);
-        let heading = self
-            .arena()
-            .alloc(comrak::arena_tree::Node::new(core::cell::RefCell::new(
-                heading_ast,
-            )));
+            (None, _) => {
+                debug!(
+                    "[{:?}] no unreleased heading found, creating it",
+                    self.path()
+                );
 
-        let text_value = NodeValue::Text(title.into_bytes());
-        let text_ast = comrak::nodes::Ast::new(text_value);
-        let text = self
-            .arena()
-            .alloc(comrak::arena_tree::Node::new(core::cell::RefCell::new(
-                text_ast,
-            )));
-        heading.append(text);
+                let end = match first_release {
+                    Some(first_release) => release_headings[first_release].0,
+                    None => link_definitions_start(&lines),
+                };
 
-        match (topmost_release, unreleased_node) {
-            (Some(_), Some(_)) => {
-                unreachable!("the loop should have bailed on this condition")
+                let mut before = lines[..end].concat();
+                if !before.is_empty() {
+                    while !before.ends_with("\n\n") {
+                        before.push('\n');
+                    }
+                }
+
+                (
+                    before,
+                    format!(
+                        "{} Unreleased\n",
+                        "#".repeat(Self::RELEASE_HEADING_LEVEL as usize)
+                    ),
+                    String::new(),
+                    lines[end..].concat(),
+                )
             }
+        };
 
-            // no release nor unreleased headings found, append at the end of the document.
-            (None, None) => root.append(heading),
+        let section = match section.trim_matches(&['\r', '\n'][..]) {
+            section if !section.trim().is_empty() => section,
+            _ => match empty_unreleased_policy {
+                EmptyUnreleasedPolicy::Error => bail!(
+                    "cannot add the release {}: the unreleased section of {:?} is empty",
+                    version,
+                    self.path()
+                ),
+                EmptyUnreleasedPolicy::Placeholder => EMPTY_RELEASE_PLACEHOLDER,
+            },
+        };
 
-            // no unreleased heading found, insert before the first release heading.
-            (Some(top), None) => top.insert_before(heading),
+        let mut cl_final = format!(
+            "{}{}{}\n{} {} - {}\n\n{}\n",
+            front_matter,
+            before,
+            unreleased_heading,
+            "#".repeat(Self::RELEASE_HEADING_LEVEL as usize),
+            version,
+            date,
+            section
+        );
+        if !after.is_empty() && !after.starts_with(&['\r', '\n'][..]) {
+            cl_final.push('\n');
+        }
+        cl_final += &after;
 
-            // unreleased heading found, insert immediately after it, thus
-            // passing on its content to the new release heading.
-            (None, Some(unreleased)) => unreleased.insert_after(heading),
-        };
+        trace!("new changelog:\n{}", cl_final);
 
-        // write the file
-        let mut buf = vec![];
-        format_commonmark(root, self.options(), &mut buf).unwrap();
-        let mut output_file = std::fs::File::create(&self.path())?;
-        output_file.write_all(&buf)?;
-        output_file.flush()?;
+        std::fs::File::create(self.path())?.write_all(cl_final.as_bytes())?;
+        self.reload()?;
 
         Ok(())
     }
@@ -479,29 +734,6 @@ impl<'a> ChangelogT<'a, CrateChangelog> {
         Ok(cl_edited)
     }
 
-    /// Writes the given Frontmatter back to the changelog file
-    pub fn set_front_matter(&'a self, fm: &Frontmatter) -> Fallible<()> {
-        let cl_str = if self.front_matter()?.is_some() {
-            self.erase_front_matter(false)?
-        } else {
-            std::fs::read_to_string(self.path())?
-        };
-
-        let cl_final = sanitize(if fm.is_empty() {
-            cl_str
-        } else {
-            let fm_str = serde_yaml::to_string(&fm)?;
-            trace!("new frontmatter:\n{}", fm_str);
-            indoc::formatdoc!("---\n{}---\n\n{}", fm_str, cl_str)
-        });
-
-        trace!("new changelog:\n{}", cl_final);
-
-        std::fs::File::create(&self.path())?.write_all(cl_final.as_bytes())?;
-
-        Ok(())
-    }
-
     /// Calls `Frontmatter::reset_to_defaults`
     pub fn reset_front_matter_to_defaults(&'a self) -> Fallible<()> {
         if let Some(fm) = self.front_matter()? {
@@ -515,6 +747,30 @@ impl<'a> ChangelogT<'a, CrateChangelog> {
 
         Ok(())
     }
+
+    /// Lints this changelog, see `lint_crate_changelog`.
+    pub fn lint(&'a self, crate_name: &str) -> Fallible<Vec<ChangelogLintFinding>> {
+        let cl_str = std::fs::read_to_string(self.path())?;
+
+        Ok(lint_crate_changelog(crate_name, &cl_str))
+    }
+
+    /// Rewrites this changelog into its canonical structure, see `normalize_crate_changelog`.
+    /// Returns whether the file was modified.
+    pub fn normalize(&'a self) -> Fallible<bool> {
+        let cl_str = std::fs::read_to_string(self.path())?;
+        let cl_normalized = normalize_crate_changelog(&cl_str);
+        if cl_normalized == cl_str {
+            return Ok(false);
+        }
+
+        trace!("normalized changelog:\n{}", cl_normalized);
+
+        std::fs::File::create(self.path())?.write_all(cl_normalized.as_bytes())?;
+        self.reload()?;
+
+        Ok(true)
+    }
 }
 
 impl<'a> HeadingLevel for ChangelogT<'a, WorkspaceChangelog> {
@@ -718,12 +974,12 @@ impl<'a> ChangelogT<'a, WorkspaceChangelog> {
                 _ => panic!("expected at least one set"),
             };
 
-            // add all siblings between here and the next headline
+            // add all siblings between here and the next headline, grouped by category
             let count = content_unreleased_heading
                 .map(|content_unreleased_heading| {
                     let mut new_nodes = vec![content_unreleased_heading];
 
-                    new_nodes.extend(
+                    new_nodes.extend(group_by_category(
                         content_unreleased_heading
                             .following_siblings()
                             .skip(1)
@@ -746,8 +1002,9 @@ impl<'a> ChangelogT<'a, WorkspaceChangelog> {
                                         _ => true,
                                     }
                                 })
-                            }),
-                    );
+                            })
+                            .collect(),
+                    ));
                     if new_nodes.len() == 1 {
                         trace!("[{}] skipping empty unreleased heading", name);
 
@@ -776,11 +1033,15 @@ impl<'a> ChangelogT<'a, WorkspaceChangelog> {
         Ok(())
     }
 
-    /// Add a new release to this WorkspaceChangelog.
+    /// Add a new release with the given name to this WorkspaceChangelog, above the previous releases.
+    ///
+    /// It contains a subsection for each of the given crate names, versions and their release sections,
+    /// in reverse order so that the dependants come before their dependencies if the crates are given in release order.
+    /// The headings of the released crates are removed from the unreleased section.
     pub fn add_release(
         &'a self,
-        title: String,
-        crate_release_headings: &[WorkspaceCrateReleaseHeading<'a>],
+        release_name: &str,
+        crate_sections: &[(String, Version, String)],
     ) -> Fallible<()> {
         let root = self.root()?;
 
@@ -830,7 +1091,7 @@ impl<'a> ChangelogT<'a, WorkspaceChangelog> {
                 heading_ast,
             )));
 
-        let text_value = NodeValue::Text(title.into_bytes());
+        let text_value = NodeValue::Text(release_name.as_bytes().to_vec());
         let text_ast = comrak::nodes::Ast::new(text_value);
         let text = self
             .arena()
@@ -845,9 +1106,9 @@ impl<'a> ChangelogT<'a, WorkspaceChangelog> {
         if let Some(unreleased_node) = maybe_unreleased {
             // look for the crates that were released and remove their headings
 
-            let release_crate_names = crate_release_headings
+            let release_crate_names = crate_sections
                 .iter()
-                .map(|wcrh| normalize_heading_name(&wcrh.prefix).to_lowercase())
+                .map(|(name, _, _)| normalize_heading_name(name).to_lowercase())
                 .collect::<HashSet<_>>();
             trace!(
                 "will remove headings that match '{:?}'",
@@ -929,112 +1190,56 @@ impl<'a> ChangelogT<'a, WorkspaceChangelog> {
             }
         }
 
-        // todo: add non-heading sibling items from the unreleased heading
-        // if let Some(unreleased) = maybe
-
-        // iterate over all crates and add their respective changes
-        for WorkspaceCrateReleaseHeading {
-            prefix,
-            suffix: _,
-            changelog,
-        } in crate_release_headings.iter().rev()
-        {
-            let recent_release = changelog
-                .topmost_release()?
-                .ok_or_else(|| anyhow::anyhow!("expect {} to have a previous release", prefix))?
-                .title()
-                .to_owned();
+        // add a subsection with the release section of each crate
+        for (name, version, section) in crate_sections.iter().rev() {
             trace!(
-                "[{:?}] looking for heading with text '{}'",
-                changelog.path(),
-                recent_release,
+                "[{:?}] adding the release {}-{}",
+                self.path(),
+                name,
+                version
             );
-            for node in changelog.root()?.children() {
-                // we're only interested in the headings here
-                if let NodeValue::Heading(found_heading_value) = node.data.borrow().value {
-                    if found_heading_value.level == CrateChangelog::RELEASE_HEADING_LEVEL {
-                        let found_heading_text = if let Some(text_str) = get_heading_text(node) {
-                            text_str
-                        } else {
-                            continue;
-                        };
-
-                        trace!(
-                            "[{:?}] found heading with level {}: {}",
-                            changelog.path(),
-                            found_heading_value.level,
-                            found_heading_text,
-                        );
-
-                        if found_heading_text != recent_release {
-                            trace!(
-                                "[{:?}] skipping heading with text '{}'",
-                                changelog.path(),
-                                found_heading_text
-                            );
-
-                            continue;
-                        } else {
-                            trace!(
-                                "[{:?}] found heading with text '{}', queueing for insertion...",
-                                changelog.path(),
-                                found_heading_text
-                            );
-                        }
-
-                        {
-                            // create and append the crate release heading for placement in the workspace changelog
-
-                            let heading_value = NodeValue::Heading(comrak::nodes::NodeHeading {
-                                level: CrateChangelog::RELEASE_HEADING_LEVEL,
-                                setext: false,
-                            });
-                            let heading_ast = comrak::nodes::Ast::new(heading_value);
-                            let heading_node = self.arena().alloc(comrak::arena_tree::Node::new(
-                                core::cell::RefCell::new(heading_ast),
-                            ));
-
-                            let heading_text_value = NodeValue::Text(
-                                format!("{}-{}", prefix, found_heading_text).into_bytes(),
-                            );
-                            let text_ast = comrak::nodes::Ast::new(heading_text_value);
-                            let text_node = self.arena().alloc(comrak::arena_tree::Node::new(
-                                core::cell::RefCell::new(text_ast),
-                            ));
-
-                            let link_value = NodeValue::Link(comrak::nodes::NodeLink {
-                                // todo: derive this path dynamically
-                                url: format!("crates/{}/CHANGELOG.md#{}", prefix, recent_release)
-                                    .as_bytes()
-                                    .to_vec(),
-                                title: Default::default(),
-                            });
-                            let link_ast = comrak::nodes::Ast::new(link_value);
-                            let link_node = self.arena().alloc(comrak::arena_tree::Node::new(
-                                core::cell::RefCell::new(link_ast),
-                            ));
-                            link_node.append(text_node);
-                            heading_node.append(link_node);
-
-                            new_nodes.push(heading_node);
-                        }
-
-                        // add all siblings until the next release heading is reached
-                        for sibling in node.following_siblings().skip(1) {
-                            match sibling.data.borrow().value {
-                                NodeValue::Heading(sibling_heading_value)
-                                    if sibling_heading_value.level
-                                        == CrateChangelog::RELEASE_HEADING_LEVEL =>
-                                {
-                                    break
-                                }
 
-                                _ => new_nodes.push(sibling),
-                            };
-                        }
-                    }
-                }
-            }
+            // create and append the crate release heading
+            let heading_value = NodeValue::Heading(comrak::nodes::NodeHeading {
+                level: CrateChangelog::RELEASE_HEADING_LEVEL,
+                setext: false,
+            });
+            let heading_ast = comrak::nodes::Ast::new(heading_value);
+            let heading_node =
+                self.arena()
+                    .alloc(comrak::arena_tree::Node::new(core::cell::RefCell::new(
+                        heading_ast,
+                    )));
+
+            let heading_text_value = NodeValue::Text(format!("{}-{}", name, version).into_bytes());
+            let text_ast = comrak::nodes::Ast::new(heading_text_value);
+            let text_node =
+                self.arena()
+                    .alloc(comrak::arena_tree::Node::new(core::cell::RefCell::new(
+                        text_ast,
+                    )));
+
+            let link_value = NodeValue::Link(comrak::nodes::NodeLink {
+                // todo: derive this path dynamically
+                url: format!("crates/{}/CHANGELOG.md#{}", name, version)
+                    .as_bytes()
+                    .to_vec(),
+                title: Default::default(),
+            });
+            let link_ast = comrak::nodes::Ast::new(link_value);
+            let link_node =
+                self.arena()
+                    .alloc(comrak::arena_tree::Node::new(core::cell::RefCell::new(
+                        link_ast,
+                    )));
+            link_node.append(text_node);
+            heading_node.append(link_node);
+
+            new_nodes.push(heading_node);
+
+            // followed by the content of the crate's release section
+            let section_root = parse_document(self.arena(), section, self.options());
+            new_nodes.extend(section_root.children().collect::<Vec<_>>());
         }
 
         for node in new_nodes {
@@ -1082,72 +1287,534 @@ fn get_heading_text<'a>(node: &'a comrak::arena_tree::Node<'a, RefCell<Ast>>) ->
     })
 }
 
-/// Used to pass information about the new crate release headings to `WorkspaceChangelog::add_release`.
-pub struct WorkspaceCrateReleaseHeading<'a> {
-    pub prefix: String,
-    pub suffix: String,
-    pub changelog: &'a ChangelogT<'a, CrateChangelog>,
+/// The category of the given node if it's a category heading.
+fn category_of_heading<'a>(node: &'a AstNode<'a>) -> Option<ChangeCategory> {
+    if !matches!(node.data.borrow().value, NodeValue::Heading(_)) {
+        return None;
+    }
+
+    get_heading_text(node).and_then(|text| ChangeCategory::from_heading(&text))
 }
 
-impl<'a> WorkspaceCrateReleaseHeading<'a> {
-    pub fn title(&self) -> String {
-        format!("{}-{}", self.prefix, self.suffix)
+/// Reorders the nodes of a section so that the content below the category headings is grouped by category
+/// in their canonical order, merging repeated headings of the same category.
+/// The content that doesn't appear below a category heading comes first.
+fn group_by_category<'a>(nodes: Vec<&'a AstNode<'a>>) -> Vec<&'a AstNode<'a>> {
+    let mut groups = std::collections::BTreeMap::<ChangeCategory, Vec<&'a AstNode<'a>>>::new();
+    let mut category = ChangeCategory::Other;
+
+    for node in nodes {
+        if let Some(heading_category) = category_of_heading(node) {
+            category = heading_category;
+
+            let group = groups.entry(category).or_default();
+            if !group.is_empty() {
+                trace!("merging repeated {:?} heading", category);
+                continue;
+            }
+        }
+
+        groups.entry(category).or_default().push(node);
     }
-}
 
-/// Applies an opinionated format to  a Markdown string.
-pub fn sanitize(s: String) -> String {
-    let arena = Arena::new();
-    let mut options = ComrakOptions::default();
-    options.parse.smart = true;
-    options.extension.front_matter_delimiter = Some("---".to_owned());
-    options.render.hardbreaks = true;
+    let uncategorized = groups.remove(&ChangeCategory::Other).unwrap_or_default();
 
-    let root = parse_document(&arena, &s, &options);
+    uncategorized
+        .into_iter()
+        .chain(groups.into_values().flatten())
+        .collect()
+}
+
+/// Renders the Markdown content of the given node without the node itself, e.g. of a list item without its marker.
+fn render_children<'a>(node: &'a AstNode<'a>, options: &ComrakOptions) -> Fallible<String> {
     let mut buf = vec![];
-    format_commonmark(root, &options, &mut buf).unwrap();
+    for child in node.children() {
+        format_commonmark(child, options, &mut buf)?;
+    }
 
-    String::from_utf8(buf).unwrap()
+    Ok(String::from_utf8(buf)?.trim().to_string())
 }
 
-fn print_node<'a>(
-    node: &'a comrak::arena_tree::Node<'a, core::cell::RefCell<comrak::nodes::Ast>>,
-    options: Option<ComrakOptions>,
-) {
-    let mut buf = vec![];
-    format_commonmark(node, &options.unwrap_or_default(), &mut buf).unwrap();
-    trace!("{}", String::from_utf8(buf).unwrap())
+/// How `ChangelogT::<CrateChangelog>::add_release` handles an unreleased section without any content.
+#[derive(enum_utils::FromStr, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EmptyUnreleasedPolicy {
+    /// Fail to add the release.
+    Error,
+    /// Add the release with `EMPTY_RELEASE_PLACEHOLDER` as its content.
+    Placeholder,
 }
 
-fn recursive_node_fn<'a, F>(
-    node: &'a comrak::arena_tree::Node<'a, core::cell::RefCell<comrak::nodes::Ast>>,
-    _reverse: bool,
-    f: F,
-) where
-    F: Fn(&'a comrak::arena_tree::Node<'a, core::cell::RefCell<comrak::nodes::Ast>>),
-{
-    f(node);
-    for d in node.children().skip(1) {
-        f(d)
-    }
+/// The content of a release that was added for an empty unreleased section.
+pub const EMPTY_RELEASE_PLACEHOLDER: &str = "No changes.";
+
+/// The version of a crate release heading, which may be followed by the date of the release as in `0.1.0 - 2021-06-30`.
+pub fn release_heading_version(heading: &str) -> &str {
+    heading
+        .split_once(" - ")
+        .map_or(heading, |(version, _)| version)
+        .trim()
 }
 
-fn recursive_detach<'a>(
-    node: &'a comrak::arena_tree::Node<'a, core::cell::RefCell<comrak::nodes::Ast>>,
-) {
-    recursive_node_fn(node, false, |n| n.detach());
+/// The level of the ATX heading on the given line, e.g. 2 for `## 0.1.0`.
+fn heading_level(line: &str) -> Option<u32> {
+    let level = line.chars().take_while(|c| *c == '#').count();
+    let rest = &line[level..];
+
+    if (1..=6).contains(&level) && (rest.trim().is_empty() || rest.starts_with(&[' ', '\t'][..])) {
+        Some(level as u32)
+    } else {
+        None
+    }
 }
 
-/// Implements the "aggregate" CLI subcommand.
-pub fn cmd(args: &crate::cli::Args, cmd_args: &crate::cli::ChangelogArgs) -> crate::CommandResult {
-    debug!("cmd_args: {:#?}", cmd_args);
+/// The text of the ATX heading on the given line without its markers.
+fn heading_text(line: &str) -> &str {
+    line.trim()
+        .trim_start_matches('#')
+        .trim_end_matches('#')
+        .trim()
+}
 
-    let ws = ReleaseWorkspace::try_new_with_criteria(
-        args.workspace_path.clone(),
-        crate::release::SelectionCriteria {
-            match_filter: args.match_filter.clone(),
+/// The indices and levels of the ATX headings among the given lines, skipping fenced code blocks.
+fn headings(lines: &[&str]) -> Vec<(usize, u32)> {
+    let mut in_code_block = false;
+
+    lines
+        .iter()
+        .enumerate()
+        .filter_map(|(i, line)| {
+            let trimmed = line.trim_start();
+            if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+                in_code_block = !in_code_block;
+                return None;
+            }
 
-            ..Default::default()
+            if in_code_block {
+                None
+            } else {
+                heading_level(line).map(|level| (i, level))
+            }
+        })
+        .collect()
+}
+
+/// The index of the first of the link reference definitions and blank lines at the end of the given lines,
+/// e.g. `[Unreleased]: https://...`, or the number of lines if there are none.
+fn link_definitions_start(lines: &[&str]) -> usize {
+    let link_definition_re = regex::Regex::new(r"^ {0,3}\[[^\]]+\]:\s*\S").unwrap();
+
+    lines
+        .iter()
+        .rposition(|line| !line.trim().is_empty() && !link_definition_re.is_match(line))
+        .map_or(0, |last_content| last_content + 1)
+}
+
+/// Splits the given Markdown string into the content of its frontmatter block and everything after the closing delimiter.
+/// Returns `None` if the string doesn't start with a frontmatter block.
+pub fn split_front_matter(s: &str) -> Option<(&str, &str)> {
+    let is_delimiter = |line: &str| line.trim_end() == "---";
+
+    let mut lines = s.split_inclusive('\n');
+    let opening = lines.next().filter(|line| is_delimiter(line))?;
+
+    let mut offset = opening.len();
+    for line in lines {
+        if is_delimiter(line) {
+            return Some((&s[opening.len()..offset], &s[offset + line.len()..]));
+        }
+        offset += line.len();
+    }
+
+    None
+}
+
+/// The kinds of problems that `lint_crate_changelog` finds.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChangelogLintKind {
+    /// A release heading or the unreleased heading doesn't have the release heading level,
+    /// or a category heading isn't nested below the release headings.
+    WrongHeadingLevel,
+    /// A release heading has no date, e.g. `## 0.1.0` instead of `## 0.1.0 - 2021-06-30`.
+    MissingReleaseDate,
+    /// The date of a release heading isn't formatted as `YYYY-MM-DD`.
+    InvalidReleaseDate,
+    /// There's another unreleased heading after the first one.
+    DuplicateUnreleased,
+    /// The unreleased heading isn't the first release heading.
+    MisplacedUnreleased,
+    /// A release heading is listed below the heading of an older release.
+    UnsortedReleases,
+    /// A line ends with whitespace.
+    TrailingWhitespace,
+}
+
+impl ChangelogLintKind {
+    /// Whether `normalize_crate_changelog` fixes this kind of problem.
+    /// The dates of the releases can't be recovered.
+    pub fn is_fixable(&self) -> bool {
+        !matches!(self, Self::MissingReleaseDate | Self::InvalidReleaseDate)
+    }
+}
+
+/// A problem in the changelog of a crate, see `ReleaseWorkspace::lint_changelogs`.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChangelogLintFinding {
+    #[serde(rename = "crate")]
+    pub crate_name: String,
+    /// The line of the changelog file the problem was found on, starting at 1.
+    pub line: usize,
+    pub kind: ChangelogLintKind,
+    pub message: String,
+}
+
+impl std::fmt::Display for ChangelogLintFinding {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}: {}", self.crate_name, self.line, self.message)
+    }
+}
+
+/// A heading of a crate changelog that starts the section of a release, see `release_headings`.
+enum ReleaseHeading<'a> {
+    Unreleased,
+    Release {
+        version: Version,
+        date: Option<&'a str>,
+    },
+}
+
+/// The indices, levels and contents of the unreleased and release headings among the given lines, regardless of their level.
+fn release_headings<'b>(lines: &[&'b str]) -> Vec<(usize, u32, ReleaseHeading<'b>)> {
+    headings(lines)
+        .into_iter()
+        .filter_map(|(i, level)| {
+            let text = heading_text(lines[i]);
+            if normalize_heading_name(text).to_lowercase() == "unreleased" {
+                return Some((i, level, ReleaseHeading::Unreleased));
+            }
+
+            let (version, date) = match text.split_once(" - ") {
+                Some((version, date)) => (version, Some(date.trim())),
+                None => (text, None),
+            };
+            let version =
+                Version::parse(version.trim().trim_start_matches('[').trim_end_matches(']'))
+                    .ok()?;
+
+            Some((i, level, ReleaseHeading::Release { version, date }))
+        })
+        .collect()
+}
+
+/// The heading on the given line with the given level.
+fn with_heading_level(line: &str, level: u32) -> String {
+    format!("{} {}", "#".repeat(level as usize), heading_text(line))
+}
+
+/// The given lines without the blank lines at their start and end.
+fn trim_blank_lines(lines: &[String]) -> &[String] {
+    let start = lines
+        .iter()
+        .position(|line| !line.trim().is_empty())
+        .unwrap_or(lines.len());
+    let end = lines
+        .iter()
+        .rposition(|line| !line.trim().is_empty())
+        .map_or(start, |last| last + 1);
+
+    &lines[start..end]
+}
+
+/// Finds the problems in the structure of the given crate changelog content that trip up the parsing of its releases.
+pub fn lint_crate_changelog(crate_name: &str, content: &str) -> Vec<ChangelogLintFinding> {
+    let body = split_front_matter(content).map_or(content, |(_, body)| body);
+    let first_line = content[..content.len() - body.len()].matches('\n').count() + 1;
+    let lines = body.lines().collect::<Vec<_>>();
+
+    let mut problems = vec![];
+
+    for (i, line) in lines.iter().enumerate() {
+        if line.ends_with(char::is_whitespace) {
+            problems.push((
+                i,
+                ChangelogLintKind::TrailingWhitespace,
+                "the line ends with whitespace".to_string(),
+            ));
+        }
+    }
+
+    for (i, level) in headings(&lines) {
+        let text = heading_text(lines[i]);
+        if level <= CrateChangelog::RELEASE_HEADING_LEVEL
+            && ChangeCategory::from_heading(text).is_some()
+        {
+            problems.push((
+                i,
+                ChangelogLintKind::WrongHeadingLevel,
+                format!(
+                    "the category heading '{}' has level {}, expected {}",
+                    text,
+                    level,
+                    CrateChangelog::RELEASE_HEADING_LEVEL + 1
+                ),
+            ));
+        }
+    }
+
+    let release_headings = release_headings(&lines);
+    let mut unreleased_found = false;
+    let mut oldest_release: Option<&Version> = None;
+
+    for (position, (i, level, heading)) in release_headings.iter().enumerate() {
+        let text = heading_text(lines[*i]);
+        if *level != CrateChangelog::RELEASE_HEADING_LEVEL {
+            problems.push((
+                *i,
+                ChangelogLintKind::WrongHeadingLevel,
+                format!(
+                    "the heading '{}' has level {}, expected {}",
+                    text,
+                    level,
+                    CrateChangelog::RELEASE_HEADING_LEVEL
+                ),
+            ));
+        }
+
+        match heading {
+            ReleaseHeading::Unreleased if unreleased_found => problems.push((
+                *i,
+                ChangelogLintKind::DuplicateUnreleased,
+                format!("the heading '{}' repeats the unreleased heading", text),
+            )),
+
+            ReleaseHeading::Unreleased => {
+                unreleased_found = true;
+
+                if position > 0 {
+                    problems.push((
+                        *i,
+                        ChangelogLintKind::MisplacedUnreleased,
+                        format!(
+                            "the unreleased heading follows the release heading '{}'",
+                            heading_text(lines[release_headings[0].0])
+                        ),
+                    ));
+                }
+            }
+
+            ReleaseHeading::Release { version, date } => {
+                match date {
+                    None => problems.push((
+                        *i,
+                        ChangelogLintKind::MissingReleaseDate,
+                        format!("the release {} has no date", version),
+                    )),
+                    Some(date) if chrono::NaiveDate::parse_from_str(date, "%Y-%m-%d").is_err() => {
+                        problems.push((
+                            *i,
+                            ChangelogLintKind::InvalidReleaseDate,
+                            format!(
+                                "the date of the release {} isn't formatted as YYYY-MM-DD: '{}'",
+                                version, date
+                            ),
+                        ))
+                    }
+                    Some(_) => {}
+                }
+
+                if let Some(oldest) = oldest_release {
+                    if version > oldest {
+                        problems.push((
+                            *i,
+                            ChangelogLintKind::UnsortedReleases,
+                            format!(
+                                "the release {} is listed below the older release {}",
+                                version, oldest
+                            ),
+                        ));
+                    }
+                }
+                if !matches!(oldest_release, Some(oldest) if version >= oldest) {
+                    oldest_release = Some(version);
+                }
+            }
+        }
+    }
+
+    problems.sort_by_key(|(i, _, _)| *i);
+    problems
+        .into_iter()
+        .map(|(i, kind, message)| ChangelogLintFinding {
+            crate_name: crate_name.to_string(),
+            line: first_line + i,
+            kind,
+            message,
+        })
+        .collect()
+}
+
+/// Rewrites the given crate changelog content into its canonical structure, which fixes the problems found by `lint_crate_changelog`
+/// except for the dates of the releases.
+///
+/// The release headings get the release heading level and the category headings are nested below them,
+/// the unreleased sections are merged into a single one at the top and the releases are sorted by their version descending.
+/// Trailing whitespace and superfluous blank lines are removed.
+/// The frontmatter, the content before the first release heading and the trailing link reference definitions stay in place.
+pub fn normalize_crate_changelog(content: &str) -> String {
+    let body = split_front_matter(content).map_or(content, |(_, body)| body);
+    let front_matter = &content[..content.len() - body.len()];
+
+    let trimmed = body.lines().map(str::trim_end).collect::<Vec<_>>();
+    let release_headings = release_headings(&trimmed);
+    let release_heading_lines = release_headings
+        .iter()
+        .map(|(i, _, _)| *i)
+        .collect::<HashSet<_>>();
+    let heading_levels = headings(&trimmed).into_iter().collect::<HashMap<_, _>>();
+
+    let lines = trimmed
+        .iter()
+        .enumerate()
+        .map(|(i, line)| match heading_levels.get(&i) {
+            Some(_) if release_heading_lines.contains(&i) => {
+                with_heading_level(line, CrateChangelog::RELEASE_HEADING_LEVEL)
+            }
+            Some(level)
+                if *level <= CrateChangelog::RELEASE_HEADING_LEVEL
+                    && ChangeCategory::from_heading(heading_text(line)).is_some() =>
+            {
+                with_heading_level(line, CrateChangelog::RELEASE_HEADING_LEVEL + 1)
+            }
+            _ => line.to_string(),
+        })
+        .collect::<Vec<_>>();
+    let text = |range: std::ops::Range<usize>| trim_blank_lines(&lines[range]).join("\n");
+
+    // the link reference definitions at the end of the file don't belong to the last section
+    let end = release_headings.last().map_or(lines.len(), |(last, _, _)| {
+        last + 1 + link_definitions_start(&trimmed[last + 1..])
+    });
+    let first = release_headings.first().map_or(end, |(first, _, _)| *first);
+
+    let mut unreleased = vec![];
+    let mut releases = vec![];
+    for (position, (i, _, heading)) in release_headings.iter().enumerate() {
+        let section_end = release_headings
+            .get(position + 1)
+            .map_or(end, |(next, _, _)| *next);
+        let section = (lines[*i].clone(), text(i + 1..section_end));
+
+        match heading {
+            ReleaseHeading::Unreleased => unreleased.push(section),
+            ReleaseHeading::Release { version, .. } => releases.push((version, section)),
+        }
+    }
+    // the sort is stable, which keeps the order of releases with the same version
+    releases.sort_by(|(a, _), (b, _)| b.cmp(a));
+
+    let mut blocks = vec![text(0..first)];
+    if let Some((heading, _)) = unreleased.first() {
+        blocks.push(heading.clone());
+    }
+    blocks.extend(unreleased.into_iter().map(|(_, content)| content));
+    for (_, (heading, content)) in releases {
+        blocks.push(heading);
+        blocks.push(content);
+    }
+    blocks.push(text(end..lines.len()));
+
+    let mut cl_normalized = front_matter.to_string();
+    if !front_matter.is_empty() {
+        cl_normalized.push('\n');
+    }
+    cl_normalized += &blocks
+        .into_iter()
+        .filter(|block| !block.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n\n");
+    cl_normalized.push('\n');
+
+    cl_normalized
+}
+
+impl<'a> ReleaseWorkspace<'a> {
+    /// Lints the changelogs of all members, see `changelog::lint_crate_changelog`.
+    /// The changelogs are read as plain text, so this works even if they can't be parsed.
+    ///
+    /// With `fix` the changelogs are normalized first, so that only the problems which can't be fixed automatically are found.
+    pub fn lint_changelogs(&'a self, fix: bool) -> Fallible<Vec<ChangelogLintFinding>> {
+        let mut findings = vec![];
+
+        for member in self.members()? {
+            let changelog = match member.changelog() {
+                Some(changelog) => changelog,
+                None => continue,
+            };
+
+            if fix && changelog.normalize()? {
+                info!("[{}] normalized {:?}", member.name(), changelog.path());
+            }
+
+            findings.extend(changelog.lint(&member.name())?);
+        }
+
+        Ok(findings)
+    }
+}
+
+/// Applies an opinionated format to  a Markdown string.
+pub fn sanitize(s: String) -> String {
+    let arena = Arena::new();
+    let mut options = ComrakOptions::default();
+    options.parse.smart = true;
+    options.extension.front_matter_delimiter = Some("---".to_owned());
+    options.render.hardbreaks = true;
+
+    let root = parse_document(&arena, &s, &options);
+    let mut buf = vec![];
+    format_commonmark(root, &options, &mut buf).unwrap();
+
+    String::from_utf8(buf).unwrap()
+}
+
+fn print_node<'a>(
+    node: &'a comrak::arena_tree::Node<'a, core::cell::RefCell<comrak::nodes::Ast>>,
+    options: Option<ComrakOptions>,
+) {
+    let mut buf = vec![];
+    format_commonmark(node, &options.unwrap_or_default(), &mut buf).unwrap();
+    trace!("{}", String::from_utf8(buf).unwrap())
+}
+
+fn recursive_node_fn<'a, F>(
+    node: &'a comrak::arena_tree::Node<'a, core::cell::RefCell<comrak::nodes::Ast>>,
+    _reverse: bool,
+    f: F,
+) where
+    F: Fn(&'a comrak::arena_tree::Node<'a, core::cell::RefCell<comrak::nodes::Ast>>),
+{
+    f(node);
+    for d in node.children().skip(1) {
+        f(d)
+    }
+}
+
+fn recursive_detach<'a>(
+    node: &'a comrak::arena_tree::Node<'a, core::cell::RefCell<comrak::nodes::Ast>>,
+) {
+    recursive_node_fn(node, false, |n| n.detach());
+}
+
+/// Implements the "aggregate" CLI subcommand.
+pub fn cmd(args: &crate::cli::Args, cmd_args: &crate::cli::ChangelogArgs) -> crate::CommandResult {
+    debug!("cmd_args: {:#?}", cmd_args);
+
+    let ws = ReleaseWorkspace::try_new_with_criteria(
+        args.workspace_path.clone(),
+        crate::release::SelectionCriteria {
+            match_filter: args.match_filter.clone(),
+
+            ..Default::default()
         },
     )?;
 
@@ -1193,6 +1860,24 @@ pub fn cmd(args: &crate::cli::Args, cmd_args: &crate::cli::ChangelogArgs) -> cra
                 )
             }
         }
+        crate::cli::ChangelogCommands::Lint(lint_args) => {
+            let findings = ws.lint_changelogs(lint_args.fix)?;
+
+            match lint_args.output_format {
+                crate::cli::OutputFormat::Json => {
+                    println!("{}", serde_json::to_string_pretty(&findings)?)
+                }
+                crate::cli::OutputFormat::Text => {
+                    for finding in &findings {
+                        println!("{}", finding);
+                    }
+                }
+            }
+
+            if !findings.is_empty() {
+                bail!("found {} problems in the changelogs", findings.len());
+            }
+        }
     };
 
     Ok(())
@@ -1299,7 +1984,7 @@ mod tests {
             name: &str,
             release_name: &str,
             expected: &str,
-        ) -> WorkspaceCrateReleaseHeading<'a> {
+        ) -> (String, Version, String) {
             let cl = workspace
                 .members()
                 .unwrap()
@@ -1309,23 +1994,27 @@ mod tests {
                 .changelog()
                 .unwrap();
 
-            cl.add_release(String::from(release_name)).unwrap();
+            cl.add_release(
+                &Version::parse(release_name).unwrap(),
+                chrono::NaiveDate::from_ymd_opt(2021, 6, 30).unwrap(),
+                EmptyUnreleasedPolicy::Error,
+            )
+            .unwrap();
 
             let result = std::fs::read_to_string(cl.path()).unwrap();
-            let expected = sanitize(String::from(expected));
 
             assert_eq!(
                 result,
                 expected,
                 "\ndiff:\n{}",
-                prettydiff::text::diff_lines(&result, &expected).format()
+                prettydiff::text::diff_lines(&result, expected).format()
             );
 
-            WorkspaceCrateReleaseHeading {
-                prefix: String::from(name),
-                suffix: String::from(release_name),
-                changelog: cl,
-            }
+            (
+                String::from(name),
+                Version::parse(release_name).unwrap(),
+                cl.release_section(release_name).unwrap().unwrap(),
+            )
         }
 
         let crate_releases = vec![
@@ -1343,7 +2032,8 @@ mod tests {
 
                 ## [Unreleased]
 
-                ## 0.0.1
+                ## 0.0.1 - 2021-06-30
+
                 Awesome changes!
 
                 ### Breaking
@@ -1363,7 +2053,8 @@ mod tests {
 
                 ## [Unreleased]
 
-                ## 0.0.1
+                ## 0.0.1 - 2021-06-30
+
                 Awesome changes!
 
                 [Unreleased]: file:///dev/null
@@ -1374,7 +2065,7 @@ mod tests {
 
         let release_name = "2021.mock";
         ws_changelog
-            .add_release(release_name.to_string(), &crate_releases)
+            .add_release(release_name, &crate_releases)
             .unwrap();
 
         let result = std::fs::read_to_string(ws_changelog.path()).unwrap();
@@ -1433,6 +2124,13 @@ mod tests {
         );
     }
 
+    fn entry(category: ChangeCategory, text: &str) -> ChangeT {
+        ChangeT::Entry(ChangeEntry {
+            category,
+            text: text.to_string(),
+        })
+    }
+
     #[test]
     fn find_crate_changes() {
         let workspace_mocker = example_workspace_1().unwrap();
@@ -1443,13 +2141,23 @@ mod tests {
                 workspace_mocker.root().join("crates/crate_a/CHANGELOG.md"),
                 vec![
                     ChangeT::Unreleased,
+                    entry(ChangeCategory::Added, "`InstallAppBundle`"),
+                    entry(ChangeCategory::Added, "`DnaSource`"),
+                    entry(ChangeCategory::Removed, "BREAKING:  `InstallAppDnaPayload`"),
+                    entry(ChangeCategory::Removed, "BREAKING: `DnaSource(Path)`"),
                     ChangeT::Release(ReleaseChange::CrateReleaseChange("0.0.1".to_string())),
                 ],
             ),
             (
                 "crate_b",
                 workspace_mocker.root().join("crates/crate_b/CHANGELOG.md"),
-                vec![ChangeT::Unreleased],
+                vec![
+                    ChangeT::Unreleased,
+                    entry(
+                        ChangeCategory::Changed,
+                        "`Signature` is a 64 byte \u{2018}secure primitive\u{2019}",
+                    ),
+                ],
             ),
             (
                 "crate_c",
@@ -1467,6 +2175,52 @@ mod tests {
         }
     }
 
+    #[test]
+    fn release_section() {
+        let workspace_mocker = example_workspace_1().unwrap();
+        workspace_mocker.add_or_replace_file(
+            "crates/crate_a/CHANGELOG.md",
+            indoc::indoc! {r#"
+            # Changelog
+
+            ## Unreleased
+
+            ## 0.0.2
+
+            ### Added
+
+            Something new.
+
+            ## 0.0.1
+
+            The initial release.
+            "#,
+            },
+        );
+
+        let changelog = ChangelogT::<CrateChangelog>::at_path(
+            &workspace_mocker.root().join("crates/crate_a/CHANGELOG.md"),
+        );
+
+        assert_eq!(
+            Some("### Added\n\nSomething new.".to_string()),
+            changelog.release_section("0.0.2").unwrap()
+        );
+        assert_eq!(
+            Some("The initial release.".to_string()),
+            changelog.release_section("0.0.1").unwrap()
+        );
+        assert_eq!(
+            Some("".to_string()),
+            changelog.release_section("Unreleased").unwrap()
+        );
+        assert_eq!(None, changelog.release_section("0.0.3").unwrap());
+        assert_eq!(
+            Some("".to_string()),
+            changelog.unreleased_section().unwrap()
+        );
+    }
+
     #[test]
     fn find_workspace_changes() {
         let workspace_mocker = example_workspace_1().unwrap();
@@ -1479,15 +2233,149 @@ mod tests {
             vec![
                 ChangeT::Changelog,
                 ChangeT::Unreleased,
+                entry(ChangeCategory::Added, "`InstallAppBundle`"),
                 ChangeT::Release(ReleaseChange::WorkspaceReleaseChange(
                     "[20210304.120604]".to_string(),
                     vec!["hdk-0.0.100".to_string()]
                 )),
+                entry(
+                    ChangeCategory::Changed,
+                    "hdk: fixup the autogenerated hdk documentation."
+                ),
             ],
             changes
         );
     }
 
+    const CATEGORIZED_CHANGELOG: &str = indoc::indoc! {r#"
+        # Changelog
+
+        The format is based on Keep a Changelog.
+
+        - not part of any release
+
+        ## Unreleased
+
+        - an uncategorized change
+
+        ### Fixed
+
+        - a fix of the unreleased version
+
+        ### Added
+
+        - a new feature
+        - another feature
+
+        ## 0.1.0
+
+        ### Added
+
+        - the first feature
+
+        See the docs.
+
+        ### Security
+
+        - a security fix
+
+        ### Notes
+
+        - a note on the release
+
+        ### Added
+
+        - a forgotten feature
+
+        ## 0.0.1
+
+        The initial release.
+
+        - the initial crate
+        "#
+    };
+
+    #[test]
+    fn changes_are_categorized_by_their_heading() {
+        let workspace_mocker = example_workspace_1().unwrap();
+        workspace_mocker.add_or_replace_file("crates/crate_a/CHANGELOG.md", CATEGORIZED_CHANGELOG);
+
+        let changelog = ChangelogT::<CrateChangelog>::at_path(
+            &workspace_mocker.root().join("crates/crate_a/CHANGELOG.md"),
+        );
+
+        assert_eq!(
+            vec![
+                ChangeT::Unreleased,
+                entry(ChangeCategory::Other, "an uncategorized change"),
+                entry(ChangeCategory::Fixed, "a fix of the unreleased version"),
+                entry(ChangeCategory::Added, "a new feature"),
+                entry(ChangeCategory::Added, "another feature"),
+                ChangeT::Release(ReleaseChange::CrateReleaseChange("0.1.0".to_string())),
+                entry(ChangeCategory::Added, "the first feature"),
+                entry(ChangeCategory::Security, "a security fix"),
+                entry(ChangeCategory::Other, "a note on the release"),
+                entry(ChangeCategory::Added, "a forgotten feature"),
+                ChangeT::Release(ReleaseChange::CrateReleaseChange("0.0.1".to_string())),
+                entry(ChangeCategory::Other, "the initial crate"),
+            ],
+            changelog.changes().unwrap()
+        );
+    }
+
+    #[test]
+    fn sections_are_grouped_by_category() {
+        let workspace_mocker = example_workspace_1().unwrap();
+        workspace_mocker.add_or_replace_file("crates/crate_a/CHANGELOG.md", CATEGORIZED_CHANGELOG);
+
+        let changelog = ChangelogT::<CrateChangelog>::at_path(
+            &workspace_mocker.root().join("crates/crate_a/CHANGELOG.md"),
+        );
+
+        assert_eq!(
+            Some(
+                indoc::indoc! {r#"
+                - an uncategorized change
+
+                ### Added
+
+                - a new feature
+                - another feature
+
+                ### Fixed
+
+                - a fix of the unreleased version"#
+                }
+                .to_string()
+            ),
+            changelog.unreleased_section().unwrap()
+        );
+
+        assert_eq!(
+            Some(
+                indoc::indoc! {r#"
+                ### Added
+
+                - the first feature
+
+                See the docs.
+
+                - a forgotten feature
+
+                ### Security
+
+                - a security fix
+
+                ### Notes
+
+                - a note on the release"#
+                }
+                .to_string()
+            ),
+            changelog.release_section("0.1.0").unwrap()
+        );
+    }
+
     use test_case::test_case;
 
     #[test_case(Frontmatter::default(), SemverIncrementMode::default())]
@@ -1572,4 +2460,549 @@ mod tests {
             );
         }
     }
+
+    const CHANGELOG_BODY: &str = "\n# Changelog  \n\n## Unreleased\n- a change with trailing whitespace   \n\n\n## 0.0.1\n\n- the initial release\n\n\n";
+
+    fn write_changelog(dir: &Path, content: &str) -> PathBuf {
+        let path = dir.join("CHANGELOG.md");
+        std::fs::write(&path, content).unwrap();
+        path
+    }
+
+    #[test]
+    fn set_front_matter_preserves_the_body() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = write_changelog(
+            dir.path(),
+            &format!(
+                "---\nunreleasable: true\nsemver_increment_mode: minor\n---\n{}",
+                CHANGELOG_BODY
+            ),
+        );
+
+        let mut fm = ChangelogT::<CrateChangelog>::at_path(&path)
+            .front_matter()
+            .unwrap()
+            .unwrap();
+        fm.unreleasable = Some(false);
+        ChangelogT::<CrateChangelog>::at_path(&path)
+            .set_front_matter(&fm)
+            .unwrap();
+
+        assert_eq!(
+            Some(fm),
+            ChangelogT::<CrateChangelog>::at_path(&path)
+                .front_matter()
+                .unwrap()
+        );
+
+        let written = std::fs::read_to_string(&path).unwrap();
+        let (fm_str, body) = split_front_matter(&written).unwrap();
+        assert!(fm_str.contains("unreleasable: false"), "{}", fm_str);
+        assert_eq!(CHANGELOG_BODY, body);
+    }
+
+    #[test]
+    fn set_front_matter_creates_the_delimiters() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = write_changelog(dir.path(), CHANGELOG_BODY.trim_start());
+
+        let fm: Frontmatter = serde_yaml::from_str("default_unreleasable: true").unwrap();
+        ChangelogT::<CrateChangelog>::at_path(&path)
+            .set_front_matter(&fm)
+            .unwrap();
+
+        assert_eq!(
+            Some(fm),
+            ChangelogT::<CrateChangelog>::at_path(&path)
+                .front_matter()
+                .unwrap()
+        );
+
+        let written = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(
+            Some(CHANGELOG_BODY),
+            split_front_matter(&written).map(|(_, body)| body)
+        );
+    }
+
+    #[test]
+    fn set_empty_front_matter_removes_the_block() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = write_changelog(
+            dir.path(),
+            &format!("---\nunreleasable: true\n---\n{}", CHANGELOG_BODY),
+        );
+
+        ChangelogT::<CrateChangelog>::at_path(&path)
+            .set_front_matter(&Frontmatter::default())
+            .unwrap();
+
+        assert_eq!(
+            CHANGELOG_BODY.trim_start(),
+            std::fs::read_to_string(&path).unwrap()
+        );
+    }
+
+    #[test]
+    fn split_front_matter_requires_both_delimiters() {
+        assert_eq!(
+            Some(("a: b\n", "\n# Changelog\n")),
+            split_front_matter("---\na: b\n---\n\n# Changelog\n")
+        );
+        assert_eq!(None, split_front_matter("---\na: b\n# Changelog\n"));
+        assert_eq!(None, split_front_matter("# Changelog\n---\n"));
+    }
+
+    const RELEASE_DATE: &str = "2021-06-30";
+
+    #[test_case(
+        indoc::indoc! {r#"
+            ---
+            semver_increment_mode: minor
+            ---
+
+            # Changelog
+            The format is based on [Keep a Changelog](https://keepachangelog.com/en/1.0.0/).
+
+            ## [Unreleased]
+            ### Added
+            * a new feature  
+
+            ```
+            ## not a heading
+            ```
+
+            ## 0.0.1 - 2021-01-01
+            * the 'initial' release   
+
+            [Unreleased]: https://example.com
+            "#
+        },
+        EmptyUnreleasedPolicy::Error,
+        Some(indoc::indoc! {r#"
+            ---
+            semver_increment_mode: minor
+            ---
+
+            # Changelog
+            The format is based on [Keep a Changelog](https://keepachangelog.com/en/1.0.0/).
+
+            ## [Unreleased]
+
+            ## 0.1.0 - 2021-06-30
+
+            ### Added
+            * a new feature  
+
+            ```
+            ## not a heading
+            ```
+
+            ## 0.0.1 - 2021-01-01
+            * the 'initial' release   
+
+            [Unreleased]: https://example.com
+            "#
+        })
+        ; "moves the unreleased content and preserves the rest"
+    )]
+    #[test_case(
+        indoc::indoc! {r#"
+            # Changelog
+
+            ## Unreleased
+            - the last change
+
+            [Unreleased]: https://example.com
+            "#
+        },
+        EmptyUnreleasedPolicy::Error,
+        Some(indoc::indoc! {r#"
+            # Changelog
+
+            ## Unreleased
+
+            ## 0.1.0 - 2021-06-30
+
+            - the last change
+
+            [Unreleased]: https://example.com
+            "#
+        })
+        ; "keeps the link definitions at the end"
+    )]
+    #[test_case(
+        "# Changelog\n\n## Unreleased\n\n## 0.0.1\n",
+        EmptyUnreleasedPolicy::Error,
+        None
+        ; "fails on an empty unreleased section"
+    )]
+    #[test_case(
+        "# Changelog\n\n## Unreleased\n\n## 0.0.1\n",
+        EmptyUnreleasedPolicy::Placeholder,
+        Some("# Changelog\n\n## Unreleased\n\n## 0.1.0 - 2021-06-30\n\nNo changes.\n\n## 0.0.1\n")
+        ; "adds a placeholder for an empty unreleased section"
+    )]
+    #[test_case(
+        "# Changelog\nHello\n## 0.0.1\n- the initial release\n",
+        EmptyUnreleasedPolicy::Placeholder,
+        Some("# Changelog\nHello\n\n## Unreleased\n\n## 0.1.0 - 2021-06-30\n\nNo changes.\n\n## 0.0.1\n- the initial release\n")
+        ; "creates a missing unreleased heading"
+    )]
+    #[test_case(
+        "# Changelog",
+        EmptyUnreleasedPolicy::Placeholder,
+        Some("# Changelog\n\n## Unreleased\n\n## 0.1.0 - 2021-06-30\n\nNo changes.\n")
+        ; "creates a missing unreleased heading in an empty changelog"
+    )]
+    #[test_case(
+        "# Changelog\n\n## 0.0.1\n\n## Unreleased\n",
+        EmptyUnreleasedPolicy::Placeholder,
+        None
+        ; "fails if the unreleased heading is not the first one"
+    )]
+    fn crate_changelog_add_release(
+        input: &str,
+        empty_unreleased_policy: EmptyUnreleasedPolicy,
+        expected: Option<&str>,
+    ) {
+        let dir = tempfile::tempdir().unwrap();
+        let path = write_changelog(dir.path(), input);
+
+        let changelog = ChangelogT::<CrateChangelog>::at_path(&path);
+        let result = changelog.add_release(
+            &Version::new(0, 1, 0),
+            RELEASE_DATE.parse().unwrap(),
+            empty_unreleased_policy,
+        );
+
+        let written = std::fs::read_to_string(&path).unwrap();
+        match expected {
+            Some(expected) => {
+                result.unwrap();
+                assert_eq!(
+                    expected,
+                    written,
+                    "\ndiff:\n{}",
+                    prettydiff::text::diff_lines(&written, expected).format()
+                );
+
+                // the changelog observes its own changes
+                assert_eq!(
+                    Some(ReleaseChange::CrateReleaseChange("0.1.0".to_string())),
+                    changelog.topmost_release().unwrap()
+                );
+            }
+            None => {
+                assert!(result.is_err());
+                assert_eq!(input, written);
+            }
+        }
+    }
+
+    const LINTED_CHANGELOG: &str = indoc::indoc! {r#"
+        ---
+        default_semver_increment_mode: patch
+        ---
+
+        # Changelog
+
+        ## Unreleased
+
+        ### Added
+
+        - a feature
+
+        ## 0.2.0 - 2021-07-01
+
+        ### Fixed
+
+        - a fix
+
+        ## 0.1.0 - 2021-06-30
+
+        - the initial release
+
+        [Unreleased]: https://example.com
+        "#
+    };
+
+    #[test_case(LINTED_CHANGELOG, &[], LINTED_CHANGELOG ; "leaves a canonical changelog as is")]
+    #[test_case(
+        indoc::indoc! {r#"
+            # Changelog
+
+            # Unreleased
+            ## Added
+            - a feature
+
+            ### 0.1.0 - 2021-06-30
+            - the initial release
+            "#
+        },
+        &[
+            (3, ChangelogLintKind::WrongHeadingLevel),
+            (4, ChangelogLintKind::WrongHeadingLevel),
+            (7, ChangelogLintKind::WrongHeadingLevel),
+        ],
+        "# Changelog\n\n## Unreleased\n\n### Added\n- a feature\n\n## 0.1.0 - 2021-06-30\n\n- the initial release\n"
+        ; "normalizes the heading levels"
+    )]
+    #[test_case(
+        "# Changelog\n\n## Unreleased\n\n## 0.2.0\n\n## 0.1.0 - June 2021\n",
+        &[
+            (5, ChangelogLintKind::MissingReleaseDate),
+            (7, ChangelogLintKind::InvalidReleaseDate),
+        ],
+        "# Changelog\n\n## Unreleased\n\n## 0.2.0\n\n## 0.1.0 - June 2021\n"
+        ; "keeps the releases without a valid date"
+    )]
+    #[test_case(
+        indoc::indoc! {r#"
+            ---
+            ---
+            # Changelog
+
+            ## 0.1.0 - 2021-06-30
+            - the initial release
+
+            ## Unreleased
+            ### Added
+            - a feature
+
+            ## [Unreleased]
+            ### Fixed
+            - a fix
+            "#
+        },
+        &[
+            (8, ChangelogLintKind::MisplacedUnreleased),
+            (12, ChangelogLintKind::DuplicateUnreleased),
+        ],
+        "---\n---\n\n# Changelog\n\n## Unreleased\n\n### Added\n- a feature\n\n### Fixed\n- a fix\n\n## 0.1.0 - 2021-06-30\n\n- the initial release\n"
+        ; "merges the unreleased sections at the top"
+    )]
+    #[test_case(
+        indoc::indoc! {r#"
+            # Changelog
+
+            ## Unreleased
+
+            ## 0.1.0 - 2021-06-30
+            - the initial release
+
+            ## 0.2.0 - 2021-08-01
+            - a feature
+
+            ## 0.1.1 - 2021-07-01
+            - a fix
+
+            [Unreleased]: https://example.com
+            "#
+        },
+        &[
+            (8, ChangelogLintKind::UnsortedReleases),
+            (11, ChangelogLintKind::UnsortedReleases),
+        ],
+        indoc::indoc! {r#"
+            # Changelog
+
+            ## Unreleased
+
+            ## 0.2.0 - 2021-08-01
+
+            - a feature
+
+            ## 0.1.1 - 2021-07-01
+
+            - a fix
+
+            ## 0.1.0 - 2021-06-30
+
+            - the initial release
+
+            [Unreleased]: https://example.com
+            "#
+        }
+        ; "sorts the releases descending"
+    )]
+    #[test_case(
+        "# Changelog \n\n## Unreleased  \n- a change\t\n\n## 0.1.0 - 2021-06-30\n- the initial release\n",
+        &[
+            (1, ChangelogLintKind::TrailingWhitespace),
+            (3, ChangelogLintKind::TrailingWhitespace),
+            (4, ChangelogLintKind::TrailingWhitespace),
+        ],
+        "# Changelog\n\n## Unreleased\n\n- a change\n\n## 0.1.0 - 2021-06-30\n\n- the initial release\n"
+        ; "removes trailing whitespace"
+    )]
+    fn crate_changelog_lint(
+        input: &str,
+        expected_findings: &[(usize, ChangelogLintKind)],
+        expected_normalized: &str,
+    ) {
+        let findings = lint_crate_changelog("crate_a", input);
+        assert_eq!(
+            expected_findings,
+            findings
+                .iter()
+                .map(|finding| (finding.line, finding.kind))
+                .collect::<Vec<_>>(),
+            "{:#?}",
+            findings
+        );
+
+        let normalized = normalize_crate_changelog(input);
+        assert_eq!(
+            expected_normalized,
+            normalized,
+            "\ndiff:\n{}",
+            prettydiff::text::diff_lines(&normalized, expected_normalized).format()
+        );
+        assert_eq!(normalized, normalize_crate_changelog(&normalized));
+
+        // only the problems that can't be fixed remain
+        let kinds_and_messages = |findings: Vec<ChangelogLintFinding>| {
+            findings
+                .into_iter()
+                .map(|finding| (finding.kind, finding.message))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            kinds_and_messages(
+                findings
+                    .into_iter()
+                    .filter(|finding| !finding.kind.is_fixable())
+                    .collect()
+            ),
+            kinds_and_messages(lint_crate_changelog("crate_a", &normalized))
+        );
+    }
+
+    #[test]
+    fn dated_release_headings_are_found_by_their_version() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = write_changelog(
+            dir.path(),
+            "# Changelog\n\n## Unreleased\n\n## 0.1.0 - 2021-06-30\n\n- a change\n",
+        );
+        let changelog = ChangelogT::<CrateChangelog>::at_path(&path);
+
+        assert_eq!(
+            Some(ReleaseChange::CrateReleaseChange("0.1.0".to_string())),
+            changelog.topmost_release().unwrap()
+        );
+        assert_eq!(
+            Some("- a change".to_string()),
+            changelog.release_section("0.1.0").unwrap()
+        );
+    }
+
+    #[test]
+    fn workspace_changelog_add_release() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = write_changelog(
+            dir.path(),
+            indoc::indoc! {r#"
+            # Changelog
+            This file conveniently consolidates all of the crates individual CHANGELOG.md files.
+
+            # Unreleased
+            Overarching release notes.
+
+            ## crate_a
+            - a preview of crate_a
+
+            ## crate_x
+            - not released yet
+
+            # 20210101.000000
+
+            ## [crate_a-0.0.1](crates/crate_a/CHANGELOG.md#0.0.1)
+            - the initial release
+            "#
+            },
+        );
+
+        let crate_sections = [
+            (
+                "crate_a".to_string(),
+                Version::new(0, 1, 0),
+                "### Added\n\n- a feature of crate_a\n\n### Changed\n\n- a change of crate_a"
+                    .to_string(),
+            ),
+            (
+                "crate_b".to_string(),
+                Version::new(0, 2, 0),
+                EMPTY_RELEASE_PLACEHOLDER.to_string(),
+            ),
+            (
+                "crate_c".to_string(),
+                Version::new(0, 1, 0),
+                "### Fixed\n\n- a fix in crate_c".to_string(),
+            ),
+        ];
+
+        ChangelogT::<WorkspaceChangelog>::at_path(&path)
+            .add_release("20261016.120000", &crate_sections)
+            .unwrap();
+
+        let result = std::fs::read_to_string(&path).unwrap();
+        let expected = sanitize(
+            indoc::indoc! {r#"
+            # Changelog
+            This file conveniently consolidates all of the crates individual CHANGELOG.md files.
+
+            # Unreleased
+
+            ## crate_x
+            - not released yet
+
+            # 20261016.120000
+            Overarching release notes.
+
+            ## [crate_c-0.1.0](crates/crate_c/CHANGELOG.md#0.1.0)
+            ### Fixed
+            - a fix in crate_c
+
+            ## [crate_b-0.2.0](crates/crate_b/CHANGELOG.md#0.2.0)
+            No changes.
+
+            ## [crate_a-0.1.0](crates/crate_a/CHANGELOG.md#0.1.0)
+            ### Added
+            - a feature of crate_a
+
+            ### Changed
+            - a change of crate_a
+
+            # 20210101.000000
+
+            ## [crate_a-0.0.1](crates/crate_a/CHANGELOG.md#0.0.1)
+            - the initial release
+            "#
+            }
+            .to_string(),
+        );
+
+        assert_eq!(
+            result,
+            expected,
+            "\ndiff:\n{}",
+            prettydiff::text::diff_lines(&result, &expected).format()
+        );
+
+        let changelog = ChangelogT::<WorkspaceChangelog>::at_path(&path);
+        assert_eq!(
+            Some(ReleaseChange::WorkspaceReleaseChange(
+                "20261016.120000".to_string(),
+                vec![
+                    "crate_c-0.1.0".to_string(),
+                    "crate_b-0.2.0".to_string(),
+                    "crate_a-0.1.0".to_string(),
+                ]
+            )),
+            changelog.topmost_release().unwrap()
+        );
+    }
 }
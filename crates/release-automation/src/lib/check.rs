@@ -7,23 +7,135 @@ pub fn cmd(args: &cli::Args, cmd_args: &cli::CheckArgs) -> CommandResult {
     let ws = crate_selection::ReleaseWorkspace::try_new_with_criteria(
         args.workspace_path.clone(),
         cmd_args.to_selection_criteria(&args),
-    )?;
+    )?
+    .with_progress_reporter(args.progress_reporter()?);
+
+    if cmd_args.output_format == cli::OutputFormat::Json {
+        let mut states_report = ws.states_report()?;
+        if cmd_args.semver_checks {
+            states_report.crates.extend(ws.semver_checks()?.clone());
+        }
+
+        // print the report even if the selection check fails so the caller can inspect the blockers
+        println!("{}", serde_json::to_string_pretty(&states_report)?);
+        if common::selection_check(cmd_args, &ws)?.is_empty() {
+            bail!(crate_selection::EmptySelectionError);
+        }
+
+        return Ok(());
+    }
 
     let release_candidates = common::selection_check(cmd_args, &ws)?;
+    let version_bumps = ws.compute_version_bumps()?;
+    let semver_checks = if cmd_args.semver_checks {
+        ws.semver_checks()?.clone()
+    } else {
+        Default::default()
+    };
+
+    let untagged_releases = crate_selection::CratesStateReport::new(
+        "The following crates are published on crates.io but their release tags are missing.",
+        ws.members()?
+            .iter()
+            .map(|member| Ok((member, member.state()?)))
+            .filter(|result| {
+                result.as_ref().map_or(true, |(_, state)| {
+                    state.contains(crate_selection::CrateStateFlags::PreviousReleaseUntagged)
+                })
+            })
+            .map(|result| {
+                result.map(|(member, state)| {
+                    (
+                        member.name(),
+                        crate_selection::CrateStateReport::new(&member.version(), &state),
+                    )
+                })
+            })
+            .collect::<Fallible<Vec<_>>>()?,
+    );
+    if !untagged_releases.is_empty() {
+        println!("{}", untagged_releases);
+    }
+
+    let excluded_crates = ws.excluded_crates()?;
+    if !excluded_crates.is_empty() {
+        let mut msg = format!(
+            "\n{0:-<80}\n{1}\n",
+            "", "The following crates are present in the workspace but excluded."
+        );
+        for excluded in excluded_crates {
+            msg += &format!("{}\n", excluded);
+        }
+        println!("{}", msg);
+    }
 
     println!(
         "{}",
-        crate_selection::CrateState::format_crates_states(
-            &release_candidates
-                .iter()
-                .map(|member| (member.name(), member.state()))
-                .collect::<Vec<_>>(),
+        crate_selection::CratesStateReport::new(
             "The following crates would have been selected for the release process.",
-            false,
-            true,
-            false,
+            release_candidates
+                .iter()
+                .map(|member| {
+                    // includes the semver checks' findings if they ran
+                    let report = match semver_checks.get(&member.name()) {
+                        Some(report) => report.clone(),
+                        None => crate_selection::CrateStateReport::new(
+                            &member.version(),
+                            &member.state()?,
+                        ),
+                    };
+
+                    Ok((member.name(), report))
+                })
+                .collect::<Fallible<Vec<_>>>()?,
         )
+        .showing(false, true, false)
     );
 
+    if !version_bumps.is_empty() {
+        let mut msg = format!(
+            "\n{0:-<80}\n{1}\n",
+            "", "The following version bumps would have been applied."
+        );
+        for crt in &release_candidates {
+            let (current_version, next_version) = &version_bumps[&crt.name()];
+            msg += &format!(
+                "{name:<30}{current_version} -> {next_version}",
+                name = crt.name(),
+                current_version = current_version,
+                next_version = next_version,
+            );
+
+            let state = crt.state()?;
+            if !state.cascade_chain().is_empty() {
+                msg += &format!(
+                    " (escalated via {} -> {})",
+                    state.cascade_chain().join(" -> "),
+                    crt.name()
+                );
+            }
+            msg += "\n";
+        }
+        println!("{}", msg);
+    }
+
+    // lets CI skip the rest of the pipeline, see `exit_code::ExitCode::EmptySelection`
+    if release_candidates.is_empty() {
+        bail!(crate_selection::EmptySelectionError);
+    }
+
+    Ok(())
+}
+
+/// Prints why the given crate is or isn't selected for the release.
+pub fn explain(args: &cli::Args, cmd_args: &cli::ExplainArgs) -> CommandResult {
+    let ws = crate_selection::ReleaseWorkspace::try_new_with_criteria(
+        args.workspace_path.clone(),
+        cmd_args.check_args.to_selection_criteria(args),
+    )?
+    .with_progress_reporter(args.progress_reporter()?);
+
+    println!("{}", ws.explain(&cmd_args.crate_name)?);
+
     Ok(())
 }
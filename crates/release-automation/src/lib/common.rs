@@ -17,6 +17,27 @@ pub fn selection_check<'a>(
 
     let release_selection = ws.release_selection()?;
 
+    if cmd_args.semver_checks {
+        let blocked_report = crate_selection::CratesStateReport::new(
+            "DISALLOWED BLOCKING CRATES",
+            ws.semver_checks()?
+                .iter()
+                .filter(|(_, report)| !report.disallowed_blocking_flags.is_empty())
+                .map(|(name, report)| (name.clone(), report.clone()))
+                .collect(),
+        )
+        .showing(true, false, false);
+
+        if !blocked_report.is_empty() {
+            bail!(crate_selection::BlockedSelectionError {
+                report: blocked_report,
+                never_release: vec![],
+                excluded: vec![],
+                publish_false: vec![],
+            });
+        }
+    }
+
     info!(
         "crates selected for the release process: {:#?}",
         release_selection
@@ -63,7 +84,7 @@ pub fn load_from_file(path: &Path) -> Fallible<String> {
     Ok(s)
 }
 
-#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Deserialize, Serialize)]
 #[serde(rename_all = "snake_case")]
 pub enum SemverIncrementMode {
     Major,
@@ -73,6 +94,12 @@ pub enum SemverIncrementMode {
     PreMajor(String),
     PreMinor(String),
     PrePatch(String),
+    /// An incompatible change, see `concrete`.
+    Breaking,
+    /// A compatible addition, see `concrete`.
+    Feature,
+    /// A compatible fix, see `concrete`.
+    Fix,
 }
 
 impl Default for SemverIncrementMode {
@@ -81,6 +108,51 @@ impl Default for SemverIncrementMode {
     }
 }
 
+/// Parses the modes without an argument by their names, and `rc` as a shorthand for `Pre("rc")`.
+impl std::str::FromStr for SemverIncrementMode {
+    type Err = anyhow::Error;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        use SemverIncrementMode::*;
+
+        Ok(match input {
+            "major" => Major,
+            "minor" => Minor,
+            "patch" => Patch,
+            "breaking" => Breaking,
+            "feature" => Feature,
+            "fix" => Fix,
+            "rc" => Pre("rc".to_string()),
+            _ => bail!(
+                "unknown semver increment mode '{}', expected one of major, minor, patch, breaking, feature, fix, rc, \
+                or a pre-release mode with its identifier like `!pre_minor rc`",
+                input
+            ),
+        })
+    }
+}
+
+impl SemverIncrementMode {
+    /// Maps the semantic modes onto the version component that cargo considers for compatibility with the given version.
+    ///
+    /// For versions >= 1.0.0 these are major, minor and patch respectively. For 0.y.z versions the minor component is
+    /// incompatible and patch is compatible, so a breaking change increments the minor component and anything else the patch.
+    /// For 0.0.z versions every increment is incompatible, which only leaves the patch component.
+    pub fn concrete(&self, version: &semver::Version) -> Self {
+        use SemverIncrementMode::*;
+
+        match (self, version.major, version.minor) {
+            (Breaking, 0, 0) | (Feature, 0, 0) | (Fix, 0, 0) => Patch,
+            (Breaking, 0, _) => Minor,
+            (Feature, 0, _) | (Fix, 0, _) => Patch,
+            (Breaking, _, _) => Major,
+            (Feature, _, _) => Minor,
+            (Fix, _, _) => Patch,
+            (mode, _, _) => mode.clone(),
+        }
+    }
+}
+
 #[derive(thiserror::Error, Debug, PartialEq)]
 pub enum SemverIncrementError {
     #[error("resulting version ({result}) is lower than on entry ({entry})")]
@@ -122,7 +194,7 @@ pub fn increment_semver<'a>(v: &'a mut semver::Version, mode: SemverIncrementMod
 
     let entry_version = v.clone();
 
-    match mode {
+    match mode.concrete(v) {
         Major => {
             if !v.pre.is_empty() && v.patch == 0 && v.minor == 0 {
                 v.pre = semver::Prerelease::EMPTY;
@@ -153,7 +225,7 @@ pub fn increment_semver<'a>(v: &'a mut semver::Version, mode: SemverIncrementMod
 
         pre_modes => {
             let (suffix, counter) = match &pre_modes {
-                Major | Minor | Patch => unreachable!(
+                Major | Minor | Patch | Breaking | Feature | Fix => unreachable!(
                     r"
                         this arm is already fully covered in the surrounding match statement.
                         i'm surprised the compiler doesn't understand this ¯\_(ツ)_/¯
@@ -289,6 +361,39 @@ mod test {
     #[test_case(PrePatch("rc".to_string()), "0.0.0-rc", "0.0.0-rc.0")]
     // TODO: check with someone else if this seems counter-intuitive
     #[test_case(PrePatch("rc".to_string()), "0.0.0-rc.0", "0.0.0-rc.1")]
+    //
+    // Breaking
+    //
+    #[test_case(Breaking, "0.0.1", "0.0.2")]
+    #[test_case(Breaking, "0.0.1-rc.0", "0.0.1")]
+    #[test_case(Breaking, "0.1.0", "0.2.0")]
+    #[test_case(Breaking, "0.2.3", "0.3.0")]
+    #[test_case(Breaking, "0.3.0-rc.2", "0.3.0")]
+    #[test_case(Breaking, "0.3.1-rc.2", "0.4.0")]
+    #[test_case(Breaking, "1.0.0", "2.0.0")]
+    #[test_case(Breaking, "1.2.3", "2.0.0")]
+    #[test_case(Breaking, "2.0.0-rc.1", "2.0.0")]
+    #[test_case(Breaking, "1.2.3+build.1", "2.0.0")]
+    //
+    // Feature
+    //
+    #[test_case(Feature, "0.0.1", "0.0.2")]
+    #[test_case(Feature, "0.1.0", "0.1.1")]
+    #[test_case(Feature, "0.2.3", "0.2.4")]
+    #[test_case(Feature, "0.3.0-rc.2", "0.3.0")]
+    #[test_case(Feature, "1.0.0", "1.1.0")]
+    #[test_case(Feature, "1.2.3", "1.3.0")]
+    #[test_case(Feature, "1.3.0-rc.1", "1.3.0")]
+    //
+    // Fix
+    //
+    #[test_case(Fix, "0.0.1", "0.0.2")]
+    #[test_case(Fix, "0.1.0", "0.1.1")]
+    #[test_case(Fix, "0.2.3", "0.2.4")]
+    #[test_case(Fix, "0.2.4-rc.0", "0.2.4")]
+    #[test_case(Fix, "1.0.0", "1.0.1")]
+    #[test_case(Fix, "1.2.3", "1.2.4")]
+    #[test_case(Fix, "1.2.3+build.1", "1.2.4")]
     fn increment_semver_consistency(
         increment_mode: SemverIncrementMode,
         input_version: &str,
@@ -301,6 +406,42 @@ mod test {
         assert_eq!(expected_version, working_version);
     }
 
+    #[test_case(Breaking, "0.0.3", Patch)]
+    #[test_case(Feature, "0.0.3", Patch)]
+    #[test_case(Fix, "0.0.3", Patch)]
+    #[test_case(Breaking, "0.1.0", Minor)]
+    #[test_case(Feature, "0.1.0", Patch)]
+    #[test_case(Fix, "0.1.0", Patch)]
+    #[test_case(Breaking, "1.0.0", Major)]
+    #[test_case(Feature, "1.0.0", Minor)]
+    #[test_case(Fix, "1.0.0", Patch)]
+    #[test_case(Breaking, "1.0.0-rc.1", Major)]
+    #[test_case(Minor, "0.0.3", Minor)]
+    #[test_case(Pre("rc".to_string()), "1.0.0", Pre("rc".to_string()))]
+    fn semver_increment_mode_concrete(
+        mode: SemverIncrementMode,
+        version: &str,
+        expected: SemverIncrementMode,
+    ) {
+        assert_eq!(
+            expected,
+            mode.concrete(&semver::Version::parse(version).unwrap())
+        );
+    }
+
+    #[test_case("breaking", Breaking)]
+    #[test_case("feature", Feature)]
+    #[test_case("fix", Fix)]
+    fn semantic_semver_increment_mode_parsing(value: &str, expected: SemverIncrementMode) {
+        let frontmatter: crate::changelog::Frontmatter =
+            serde_yaml::from_str(&format!("semver_increment_mode: {}", value)).unwrap();
+        assert_eq!(expected, frontmatter.semver_increment_mode());
+
+        let metadata: crate::crate_selection::CrateMetadataConfig =
+            serde_json::from_str(&format!(r#"{{ "semver-increment": "{}" }}"#, value)).unwrap();
+        assert_eq!(Some(expected), metadata.semver_increment);
+    }
+
     //
     // errors
     //
@@ -3,14 +3,14 @@ use bstr::ByteSlice;
 use cargo::util::VersionExt;
 use linked_hash_map::LinkedHashMap;
 use linked_hash_set::LinkedHashSet;
-use log::{debug, info, trace, warn};
 use semver::{Comparator, Version, VersionReq};
 use std::collections::{HashMap, HashSet};
 use structopt::StructOpt;
+use tracing::{debug, info, trace, warn};
 
 use crate::{
     common::{increment_semver, SemverIncrementMode},
-    crate_selection::Crate,
+    crate_selection::{Crate, DependentsScope},
     release::ReleaseWorkspace,
     CommandResult, Fallible,
 };
@@ -241,7 +241,7 @@ fn make_pinned_dep<'a>(
             subcmd_args.crt
         ))?;
 
-    for dependant in crt.dependants_in_workspace()? {
+    for dependant in crt.dependants_in_workspace(DependentsScope::Transitive)? {
         dependant.set_dependency_version(
             &crt.name(),
             &crt.version(),
@@ -404,12 +404,12 @@ pub fn apply_dev_versions<'a>(
     commit: bool,
     no_verify: bool,
 ) -> Fallible<()> {
-    let applicable_crates = ws
-        .members()?
-        .iter()
-        .filter(|crt| crt.state().changed_since_previous_release())
-        .cloned()
-        .collect::<Vec<_>>();
+    let mut applicable_crates = vec![];
+    for crt in ws.members()? {
+        if crt.state()?.changed_since_previous_release() {
+            applicable_crates.push(*crt);
+        }
+    }
 
     let msg = apply_dev_vesrions_to_selection(applicable_crates, dev_suffix, dry_run)?;
 
@@ -432,7 +432,7 @@ pub fn apply_dev_versions<'a>(
             }
 
             if commit {
-                ws.git_add_all_and_commit(&commit_msg, None)?;
+                ws.git_add_all_and_commit(&commit_msg, None, false)?;
             }
         }
     }
@@ -480,7 +480,7 @@ pub fn apply_dev_vesrions_to_selection<'a>(
             if applicable_crates
                 .insert(changed_dependant.name(), changed_dependant)
                 .is_none()
-                && changed_dependant.state().has_previous_release()
+                && changed_dependant.state()?.has_previous_release()
             {
                 queue.push(changed_dependant);
             }
@@ -588,7 +588,7 @@ pub fn fixup_unpublished_releases<'a>(
             };
 
             if commit {
-                ws.git_add_all_and_commit(&commit_msg, None)?;
+                ws.git_add_all_and_commit(&commit_msg, None, false)?;
             }
         }
     }
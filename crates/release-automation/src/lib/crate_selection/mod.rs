@@ -1,11 +1,21 @@
 //! Select which crates to include in the release process.
 
 use crate::changelog::{
-    self, ChangeT, ChangelogT, ChangelogType, CrateChangelog, WorkspaceChangelog,
+    self, ChangeT, ChangelogT, ChangelogType, CrateChangelog, Frontmatter, WorkspaceChangelog,
 };
+use crate::common::{increment_semver, SemverIncrementMode};
+use crate::crates_index::{
+    CratesIndex, CurlHttpClient, HttpClient, SparseIndex, CRATES_IO_REGISTRY_NAME,
+    CRATES_IO_SPARSE_INDEX_URL,
+};
+use crate::phases::ReleasePhase;
+use crate::plan::ReleasePlan;
+use crate::progress::{NoopProgressReporter, ProgressReporter};
+use crate::rollback::JournalEntry;
 use crate::Fallible;
 use cargo::core::Dependency;
-use log::{debug, info, trace, warn};
+use cargo::util::VersionExt;
+use tracing::{debug, error_span, info, trace, warn};
 
 use anyhow::Context;
 use anyhow::{anyhow, bail};
@@ -13,16 +23,20 @@ use educe::{self, Educe};
 use enumflags2::{bitflags, BitFlags};
 use linked_hash_map::LinkedHashMap;
 use linked_hash_set::LinkedHashSet;
-use once_cell::unsync::{Lazy, OnceCell};
+use once_cell::sync::OnceCell;
+use rayon::prelude::*;
 use regex::Regex;
 use semver::{Comparator, Op, Version, VersionReq};
-use std::cell::Cell;
-use std::collections::{BTreeSet, HashMap, HashSet};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 use std::fmt;
 use std::io::Write;
 use std::iter::FromIterator;
 use std::path::{Path, PathBuf};
 use std::process::Command;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Mutex, MutexGuard, PoisonError};
+use std::time::Duration;
 
 pub mod aliases {
     pub use cargo::core::dependency::DepKind as CargoDepKind;
@@ -43,13 +57,24 @@ type DependenciesT = LinkedHashMap<String, Vec<cargo::core::Dependency>>;
 #[derive(custom_debug::Debug)]
 pub struct Crate<'a> {
     package: CargoPackage,
+    /// The minimum supported Rust version as declared via `rust-version` in the Cargo.toml.
+    rust_version: Option<Version>,
     changelog: Option<ChangelogT<'a, CrateChangelog>>,
     #[debug(with = "releaseworkspace_path_only_fmt")]
     workspace: &'a ReleaseWorkspace<'a>,
     #[debug(skip)]
     dependencies_in_workspace: OnceCell<DependenciesT>,
+    /// The shortest distance to each of the `dependencies_in_workspace`, direct dependencies have a depth of 1.
+    #[debug(skip)]
+    dependency_depths: OnceCell<HashMap<String, usize>>,
     #[debug(skip)]
     dependants_in_workspace: OnceCell<Vec<&'a Crate<'a>>>,
+    #[debug(skip)]
+    direct_dependants_in_workspace: OnceCell<Vec<&'a Crate<'a>>>,
+    #[debug(skip)]
+    dependants_in_workspace_filtered: OnceCell<Vec<&'a Crate<'a>>>,
+    #[debug(skip)]
+    metadata_config: OnceCell<CrateMetadataConfig>,
 }
 
 impl<'a> Crate<'a> {
@@ -67,12 +92,25 @@ impl<'a> Crate<'a> {
             }
         };
 
+        let rust_version = package
+            .rust_version()
+            .map(|rust_version| {
+                parse_rust_version(rust_version)
+                    .context(format!("parsing the rust-version of {}", package.name()))
+            })
+            .transpose()?;
+
         Ok(Self {
             package,
+            rust_version,
             changelog,
             workspace,
             dependencies_in_workspace: Default::default(),
+            dependency_depths: Default::default(),
+            metadata_config: Default::default(),
             dependants_in_workspace: Default::default(),
+            direct_dependants_in_workspace: Default::default(),
+            dependants_in_workspace_filtered: Default::default(),
         })
     }
 
@@ -81,6 +119,59 @@ impl<'a> Crate<'a> {
         self.package.manifest_path()
     }
 
+    /// Returns the version of the topmost release in the crate's changelog, if there is one.
+    pub fn previous_release_version(&'a self) -> Fallible<Option<Version>> {
+        let changelog = match self.changelog() {
+            Some(changelog) => changelog,
+            None => return Ok(None),
+        };
+
+        changelog
+            .topmost_release()?
+            .map(|release| Version::parse(release.title()))
+            .transpose()
+            .context(format!(
+                "[{}] parsing the topmost release in {:?} as a semantic version",
+                self.name(),
+                changelog.path(),
+            ))
+    }
+
+    /// Returns the version that follows the previous release according to the given increment mode, see `increment_semver`.
+    /// Fails if the previous release is greater than the manifest version.
+    ///
+    /// A crate without a previous release is released with its manifest version, which is only incremented if it's a pre-release.
+    /// Build metadata is dropped, and a pre-release is completed by the non pre-release modes, e.g. `0.2.0-beta.3` becomes `0.2.0` for `Patch`.
+    pub fn next_version(&'a self, mode: SemverIncrementMode) -> Fallible<Version> {
+        let current_version = self.version();
+
+        let mut version = match self.previous_release_version()? {
+            Some(previous_release_version) if previous_release_version > current_version => bail!(
+                "[{}] previously documented release version '{}' is greater than this release version '{}'",
+                self.name(),
+                previous_release_version,
+                current_version
+            ),
+            Some(previous_release_version) => previous_release_version,
+            None if current_version.is_prerelease() => current_version,
+            None => return Ok(current_version),
+        };
+
+        let context = format!("[{}] incrementing version {}", self.name(), version);
+        increment_semver(&mut version, mode).context(context)?;
+
+        Ok(version)
+    }
+
+    /// Returns the manifest version without pre-release identifier and build metadata, e.g. `0.3.0` for `0.3.0-rc.2`.
+    pub fn stable_version(&self) -> Version {
+        let mut version = self.version();
+        version.pre = semver::Prerelease::EMPTY;
+        version.build = semver::BuildMetadata::EMPTY;
+
+        version
+    }
+
     /// Sets the new version for the given crate, updates all workspace dependants,
     /// and returns a refrence to them for post-processing.
     pub fn set_version(
@@ -94,10 +185,8 @@ impl<'a> Crate<'a> {
             self.manifest_path(),
         );
 
-        let release_version_str = release_version.to_string();
-
         if !dry_run {
-            cargo_next::set_version(self.manifest_path(), release_version_str.as_str())?;
+            self.set_manifest_version(release_version)?;
         }
 
         let dependants = self
@@ -115,6 +204,81 @@ impl<'a> Crate<'a> {
         Ok(dependants)
     }
 
+    /// Whether the crate inherits its version from the `[workspace.package]` table via `version.workspace = true`.
+    pub fn inherits_version(&self) -> Fallible<bool> {
+        let manifest: toml_edit::Document = crate::common::load_from_file(self.manifest_path())?
+            .parse()
+            .context(format!("parsing manifest at {:?}", self.manifest_path()))?;
+
+        Ok(is_workspace_inherited(&manifest["package"]["version"]))
+    }
+
+    /// Reads the version from the manifest on disk, which is taken from the root manifest if it's inherited from the workspace.
+    /// Unlike `version` this reflects the modifications since the workspace was loaded.
+    pub fn manifest_version(&self) -> Fallible<Version> {
+        let manifest: toml_edit::Document = crate::common::load_from_file(self.manifest_path())?
+            .parse()
+            .context(format!("parsing manifest at {:?}", self.manifest_path()))?;
+
+        let version = if is_workspace_inherited(&manifest["package"]["version"]) {
+            let root_manifest_path = self.workspace.root_manifest_path();
+            let root_manifest: toml_edit::Document =
+                crate::common::load_from_file(&root_manifest_path)?
+                    .parse()
+                    .context(format!("parsing manifest at {:?}", root_manifest_path))?;
+
+            root_manifest["workspace"]["package"]["version"]
+                .as_str()
+                .map(str::to_string)
+        } else {
+            manifest["package"]["version"].as_str().map(str::to_string)
+        };
+
+        let version = version.ok_or_else(|| {
+            anyhow!(
+                "[{}] expected a version string in the manifest at {:?}",
+                self.name(),
+                self.manifest_path()
+            )
+        })?;
+
+        Ok(Version::parse(&version)?)
+    }
+
+    /// Writes the given version to the `[package]` table of the crate's manifest.
+    /// Only the version value is replaced, comments, ordering and formatting of the manifest are preserved.
+    ///
+    /// Fails if the version is inherited from the workspace via `version.workspace = true`.
+    pub fn set_manifest_version(&self, version: &Version) -> Fallible<()> {
+        let manifest_path = self.manifest_path();
+        let mut manifest: toml_edit::Document = crate::common::load_from_file(manifest_path)?
+            .parse()
+            .context(format!("parsing manifest at {:?}", manifest_path))?;
+
+        let current_version = &mut manifest["package"]["version"];
+        if is_workspace_inherited(current_version) {
+            bail!(
+                "[{}] can't set the version to {} in {:?} because it is inherited from the workspace via `version.workspace = true`",
+                self.name(),
+                version,
+                manifest_path,
+            );
+        }
+
+        if current_version.as_str().is_none() {
+            bail!(
+                "[{}] expected a version string in the [package] table of {:?}",
+                self.name(),
+                manifest_path,
+            );
+        }
+        replace_str_value(current_version, version.to_string());
+
+        std::fs::write(manifest_path, manifest.to_string_in_original_order())?;
+
+        Ok(())
+    }
+
     /// Set a dependency to a specific version
     // Adapted from https://github.com/sunng87/cargo-release/blob/f94938c3f20ef20bc8f971d59de75574a0b18931/src/cargo.rs#L122-L154
     pub fn set_dependency_version(
@@ -230,18 +394,91 @@ impl<'a> Crate<'a> {
         Ok(())
     }
 
+    /// Rewrites the requirements on the given dependency that don't accommodate its new version,
+    /// in all dependency tables of the manifest including the target-specific ones.
+    /// The operator and the precision of the existing requirements are kept where possible, see `rewrite_version_req`.
+    ///
+    /// Returns whether the manifest was modified.
+    pub fn update_dependency_requirement(&self, name: &str, version: &Version) -> Fallible<bool> {
+        let manifest_path = self.manifest_path();
+        let mut manifest: toml_edit::Document = crate::common::load_from_file(manifest_path)?
+            .parse()
+            .context(format!("parsing manifest at {:?}", manifest_path))?;
+
+        let mut modified =
+            update_dependency_requirements_in(manifest.as_table_mut(), name, version)?;
+
+        if let Some(targets) = manifest
+            .as_table_mut()
+            .get_mut("target")
+            .and_then(toml_edit::Item::as_table_mut)
+        {
+            let target_names = targets
+                .iter()
+                .map(|(target_name, _)| target_name.to_string())
+                .collect::<Vec<_>>();
+
+            for target_name in target_names {
+                if let Some(target) = targets
+                    .get_mut(&target_name)
+                    .and_then(toml_edit::Item::as_table_mut)
+                {
+                    modified |= update_dependency_requirements_in(target, name, version)?;
+                }
+            }
+        }
+
+        if modified {
+            debug!(
+                "[{}] updated the requirements on {} to accommodate {}",
+                self.name(),
+                name,
+                version
+            );
+            std::fs::write(manifest_path, manifest.to_string_in_original_order())?;
+        }
+
+        Ok(modified)
+    }
+
     /// Return a reference to the package.
     pub fn package(&self) -> &CargoPackage {
         &self.package
     }
 
-    pub fn state(&self) -> CrateState {
+    /// Returns the release configuration of the crate's manifest, or the defaults if there is none.
+    pub fn metadata_config(&self) -> Fallible<&CrateMetadataConfig> {
+        self.metadata_config.get_or_try_init(|| {
+            let config = match self
+                .package
+                .manifest()
+                .custom_metadata()
+                .and_then(|metadata| metadata.get(ReleaseWorkspace::METADATA_KEY))
+            {
+                Some(value) => value.clone().try_into().context(format!(
+                    "[{}] parsing package.metadata.{} in {:?}",
+                    self.name(),
+                    ReleaseWorkspace::METADATA_KEY,
+                    self.manifest_path(),
+                ))?,
+                None => Default::default(),
+            };
+
+            Ok(config)
+        })
+    }
+
+    pub(crate) fn state(&self) -> Fallible<CrateState> {
         self.workspace
-            .members_states()
-            .expect("should be initialised")
+            .members_states()?
             .get(&self.name())
-            .expect("should be found")
-            .clone()
+            .cloned()
+            .ok_or_else(|| anyhow!("no state found for crate '{}'", self.name()))
+    }
+
+    /// Whether the manifest allows publishing this crate, i.e. it doesn't set `publish = false`.
+    pub fn publishable(&self) -> bool {
+        !matches!(self.package.publish(), Some(registries) if registries.is_empty())
     }
 
     /// This crate's name as given in the Cargo.toml file
@@ -254,6 +491,11 @@ impl<'a> Crate<'a> {
         self.package.version().to_owned()
     }
 
+    /// The minimum supported Rust version as declared via `rust-version` in the Cargo.toml.
+    pub fn rust_version(&self) -> Option<&Version> {
+        self.rust_version.as_ref()
+    }
+
     /// Return a string in the from of '{package_name}-{package_version}'
     pub fn name_version(&self) -> String {
         format!("{}-{}", self.name(), self.version())
@@ -265,71 +507,154 @@ impl<'a> Crate<'a> {
     }
 
     /// Returns the crates in the same workspace that this crate depends on.
+    ///
+    /// Fails if the dependencies contain a cycle.
     pub fn dependencies_in_workspace(&'a self) -> Fallible<&'a DependenciesT> {
         self.dependencies_in_workspace.get_or_try_init(|| {
             // LinkedHashSet automatically deduplicates while maintaining the insertion order.
             let mut dependencies = LinkedHashMap::new();
-            let ws_members: std::collections::HashMap<_, _> = self
+            let ws_members: HashMap<_, _> = self
                 .workspace
                 .members_unsorted()?
                 .iter()
                 .map(|m| (m.name(), &m.package))
                 .collect();
 
-            // This vector is used to implement a depth-first-search to capture all transitive dependencies.
-            // Starting with the package in self and traversing down from it.
-            let mut queue = vec![&self.package];
-            let mut seen = HashSet::new();
+            // The path is used to detect cycles while traversing the dependency tree depth-first,
+            // starting with the package in self.
+            let mut path = vec![self.name()];
+            let mut depths = HashMap::new();
+
+            self.collect_dependencies_in_workspace(
+                &self.package,
+                &ws_members,
+                &mut path,
+                &mut depths,
+                &mut dependencies,
+            )?;
 
-            while let Some(package) = queue.pop() {
-                for dep in package.dependencies() {
-                    let dep_name = dep.package_name().to_string();
+            let _ = self.dependency_depths.set(depths);
 
-                    // todo: write a test-case for this
-                    if dep.is_optional() && self.workspace.criteria.exclude_optional_deps {
-                        trace!(
-                            "[{}] excluding optional dependency '{}'",
-                            package.name(),
-                            dep_name,
-                        );
+            Ok(dependencies)
+        })
+    }
 
-                        continue;
-                    }
+    /// Returns the members this crate depends on, in the order of `dependencies_in_workspace`.
+    pub fn workspace_dependency_crates(&'a self) -> Fallible<Vec<&'a Crate<'a>>> {
+        let members = self
+            .workspace
+            .members_unsorted()?
+            .iter()
+            .map(|member| (member.name(), member))
+            .collect::<HashMap<_, _>>();
 
-                    // only consider workspace members
-                    if let Some(dep_package) = ws_members.get(&dep.package_name().to_string()) {
-                        // only consider non-star version requirements
-                        if dep.specified_req() && dep.version_req().to_string() != "*" {
-                            // don't add this package to its own dependencies
-                            if dep_package.name() != package.name() {
-                                dependencies
-                                    .entry(dep_name.clone())
-                                    .or_insert_with(|| vec![])
-                                    .push(dep.to_owned());
-
-                                if !seen.contains(&dep_name) {
-                                    queue.push(dep_package);
-                                }
-                            } else {
-                                warn!(
-                                    "encountered dependency cycle: {:?} <-> {:?}",
-                                    self.name(),
-                                    package.name()
-                                );
-                            }
+        self.dependencies_in_workspace()?
+            .keys()
+            .map(|dep_name| {
+                members
+                    .get(dep_name)
+                    .copied()
+                    .ok_or_else(|| anyhow!("'{}' is not a member of the workspace", dep_name))
+            })
+            .collect()
+    }
+
+    /// Returns the shortest distance to the given workspace dependency if this crate depends on it.
+    /// Direct dependencies have a depth of 1.
+    pub fn dependency_depth(&'a self, name: &str) -> Fallible<Option<usize>> {
+        self.dependencies_in_workspace()?;
+
+        Ok(self
+            .dependency_depths
+            .get()
+            .and_then(|depths| depths.get(name))
+            .copied())
+    }
+
+    /// Recursively captures all transitive workspace dependencies of the given package.
+    fn collect_dependencies_in_workspace(
+        &self,
+        package: &CargoPackage,
+        ws_members: &HashMap<String, &CargoPackage>,
+        path: &mut Vec<String>,
+        depths: &mut HashMap<String, usize>,
+        dependencies: &mut DependenciesT,
+    ) -> Fallible<()> {
+        for dep in self
+            .workspace
+            .workspace_dependencies_of(package, ws_members)?
+        {
+            let dep_name = dep.package_name().to_string();
+            let dep_package = ws_members[&dep_name];
+
+            if path.contains(&dep_name) {
+                bail!(
+                    "encountered dependency cycle: {} -> {}",
+                    path.join(" -> "),
+                    dep_name
+                );
+            }
+
+            let dep_entries = dependencies.entry(dep_name.clone()).or_default();
+            if !dep_entries.contains(dep) {
+                dep_entries.push(dep.to_owned());
+            }
+
+            // descend again if the dependency is reached on a shorter path to correct the depths below it
+            let depth = path.len();
+            if !matches!(depths.get(&dep_name), Some(known) if *known <= depth) {
+                depths.insert(dep_name.clone(), depth);
+
+                path.push(dep_name);
+                self.collect_dependencies_in_workspace(
+                    dep_package,
+                    ws_members,
+                    path,
+                    depths,
+                    dependencies,
+                )?;
+                path.pop();
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Returns a reference to the workspace crates that depend on this crate within the given scope,
+    /// in the order of `ReleaseWorkspace::members`.
+    pub fn dependants_in_workspace(
+        &'a self,
+        scope: DependentsScope,
+    ) -> Fallible<&'a Vec<&'a Crate<'a>>> {
+        let index = self.workspace.reverse_dependency_index()?;
+        let members_named = |names: &BTreeSet<String>| -> Fallible<Vec<&'a Crate<'a>>> {
+            Ok(self
+                .workspace
+                .members()?
+                .iter()
+                .filter(|member| names.contains(&member.name()))
+                .copied()
+                .collect())
+        };
+
+        match scope {
+            DependentsScope::Direct => self.direct_dependants_in_workspace.get_or_try_init(|| {
+                members_named(index.get(&self.name()).unwrap_or(&BTreeSet::new()))
+            }),
+            DependentsScope::Transitive => self.dependants_in_workspace.get_or_try_init(|| {
+                let mut dependants = BTreeSet::new();
+                let mut queue = vec![self.name()];
+                while let Some(name) = queue.pop() {
+                    for dependant in index.get(&name).into_iter().flatten() {
+                        if dependants.insert(dependant.clone()) {
+                            queue.push(dependant.clone());
                         }
                     }
                 }
-                seen.insert(package.name().to_string());
-            }
-            Ok(dependencies)
-        })
-    }
 
-    /// Returns a reference to all workspace crates that depend on this crate.
-    // todo: write a unit test for this
-    pub fn dependants_in_workspace(&'a self) -> Fallible<&'a Vec<&'a Crate<'a>>> {
-        self.dependants_in_workspace_filtered(|_| true)
+                members_named(&dependants)
+            }),
+        }
     }
 
     /// Returns a reference to all workspace crates that depend on this crate.
@@ -343,18 +668,15 @@ impl<'a> Crate<'a> {
         F: Fn(&(&String, &Vec<Dependency>)) -> bool,
         F: Copy,
     {
-        self.dependants_in_workspace.get_or_try_init(|| {
+        self.dependants_in_workspace_filtered.get_or_try_init(|| {
             let members_dependants = self.workspace.members()?.iter().try_fold(
                 LinkedHashMap::<String, &'a Crate<'a>>::new(),
                 |mut acc, member| -> Fallible<_> {
-                    if member
-                        .dependencies_in_workspace()?
-                        .iter()
-                        .filter(filter_fn)
-                        .map(|(dep_name, _)| dep_name)
-                        .collect::<LinkedHashSet<_>>()
-                        .contains(&self.name())
-                    {
+                    let name = self.name();
+                    if matches!(
+                        member.dependencies_in_workspace()?.get(&name),
+                        Some(deps) if filter_fn(&(&name, deps))
+                    ) {
                         acc.insert(member.name(), *member);
                     };
 
@@ -370,6 +692,60 @@ impl<'a> Crate<'a> {
         self.package.root()
     }
 
+    /// Returns the path of the first existing license file that's shipped with the package.
+    /// The crate root is probed first, followed by the `license-file` and the license files in `include` of the manifest,
+    /// which may point outside of the crate.
+    pub fn license_file(&self) -> Option<PathBuf> {
+        let manifest = self.package.manifest();
+
+        ReleaseWorkspace::LICENSE_FILENAMES
+            .iter()
+            .map(|filename| self.root().join(filename))
+            .chain(
+                manifest
+                    .metadata()
+                    .license_file
+                    .iter()
+                    .map(|license_file| self.root().join(license_file)),
+            )
+            .chain(
+                manifest
+                    .include()
+                    .iter()
+                    .filter(|include| {
+                        matches!(
+                            Path::new(include).file_name().and_then(|filename| filename.to_str()),
+                            Some(filename) if filename.starts_with("LICENSE")
+                        )
+                    })
+                    .map(|include| self.root().join(include)),
+            )
+            .find(|path| path.is_file())
+    }
+
+    /// Returns the files that would be packaged for publishing and their sizes in bytes, the largest first.
+    pub fn package_files(&self) -> Fallible<Vec<(PathBuf, u64)>> {
+        let source = cargo::sources::PathSource::new(
+            self.root(),
+            self.package.package_id().source_id(),
+            &self.workspace.cargo_config,
+        );
+
+        let mut files = source
+            .list_files(&self.package)?
+            .into_iter()
+            .map(|path| {
+                let size = std::fs::metadata(&path)
+                    .context(format!("reading the size of {:?}", path))?
+                    .len();
+                Ok((path, size))
+            })
+            .collect::<Fallible<Vec<_>>>()?;
+        files.sort_by(|(_, a), (_, b)| b.cmp(a));
+
+        Ok(files)
+    }
+
     pub fn workspace(&self) -> &'a ReleaseWorkspace<'a> {
         self.workspace
     }
@@ -377,12 +753,236 @@ impl<'a> Crate<'a> {
 
 type MemberStates = LinkedHashMap<String, CrateState>;
 
+/// Git tag names mapped to the object they refer to.
+pub type TagIndex = HashMap<String, git2::Oid>;
+
+/// Borrows the workspace's tag index, which stays locked until this is dropped.
+pub struct TagIndexGuard<'a>(MutexGuard<'a, Option<TagIndex>>);
+
+impl<'a> std::ops::Deref for TagIndexGuard<'a> {
+    type Target = TagIndex;
+
+    fn deref(&self) -> &TagIndex {
+        self.0
+            .as_ref()
+            .expect("initialized by ReleaseWorkspace::tag_index")
+    }
+}
+
+/// Locks one of the workspace's caches.
+/// A panic while the lock was held can't leave a cache half-updated, so poisoning is ignored.
+pub(crate) fn lock_cache<T>(cache: &Mutex<T>) -> MutexGuard<'_, T> {
+    cache.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// The names of the members that depend directly on the member with the given name.
+pub type ReverseDependencyIndex = HashMap<String, BTreeSet<String>>;
+
+/// Changed files keyed by the directory and the resolved revisions they were computed for.
+type ChangedFilesCache = HashMap<(PathBuf, git2::Oid, git2::Oid), Vec<PathBuf>>;
+
+/// The facts about a member that don't depend on the states of the other members, see `ReleaseWorkspace::analyze_members`.
+#[derive(Clone, Debug, Default)]
+struct MemberAnalysis {
+    readme_present: bool,
+    /// Unset if the member has no changelog.
+    changelog: Option<ChangelogAnalysis>,
+    /// The files in the crate that changed since the previous release.
+    changed_files: Vec<PathBuf>,
+    /// Whether any of the workspace change paths changed since the previous release.
+    workspace_files_changed: bool,
+}
+
+/// What a member's changelog says about its previous release.
+#[derive(Clone, Debug, Default)]
+struct ChangelogAnalysis {
+    unreleasable: bool,
+    /// Whether `unreleasable` is the default of the workspace changelog as the crate's changelog doesn't declare it.
+    unreleasable_via_workspace_default: bool,
+    /// The title of the topmost release and the version it was parsed as.
+    previous_release: Option<(String, Result<Version, String>)>,
+    /// The release tag of the previous release if it exists.
+    previous_release_tag: Option<String>,
+    /// The commit that set the published version if the previous release wasn't tagged, see `reconcile_untagged_release`.
+    untagged_release_commit: Option<String>,
+    /// Why the changelog couldn't be parsed if that's tolerated by the criteria.
+    parse_error: Option<String>,
+}
+
+impl ChangelogAnalysis {
+    /// The revision the changes since the previous release are detected from.
+    fn previous_release_rev(&self) -> Option<&str> {
+        self.previous_release_tag
+            .as_deref()
+            .or(self.untagged_release_commit.as_deref())
+    }
+}
+
+/// Machine-readable summary of a crate's state, as found in the `StatesReport` and the `CratesStateReport`.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CrateStateReport {
+    pub version: String,
+    #[serde(with = "bitflags_serde")]
+    pub flags: BitFlags<CrateStateFlags>,
+    #[serde(with = "bitflags_serde")]
+    pub blocking_flags: BitFlags<CrateStateFlags>,
+    /// The blocking flags that weren't allowed.
+    #[serde(default, with = "bitflags_serde")]
+    pub disallowed_blocking_flags: BitFlags<CrateStateFlags>,
+    #[serde(with = "bitflags_serde")]
+    pub meta_flags: BitFlags<MetaCrateStateFlags>,
+    #[serde(with = "bitflags_serde")]
+    pub change_classes: BitFlags<ChangeClass>,
+    pub release_selection: bool,
+    /// The matched crates this crate depends on if it was selected because of them.
+    #[serde(default)]
+    pub dependent_of: BTreeSet<String>,
+    /// The matched crates that depend on this crate, directly or transitively, if it was selected because of them.
+    #[serde(default)]
+    pub required_by: BTreeSet<String>,
+    /// The publish metadata fields that are missing in the manifest.
+    #[serde(default)]
+    pub missing_publish_metadata: Vec<String>,
+    /// The largest packaged files and their sizes in bytes if the package is too large.
+    #[serde(default)]
+    pub largest_package_files: Vec<(String, u64)>,
+    /// The commit that is treated as the release of the published but untagged manifest version.
+    #[serde(default)]
+    pub untagged_release_commit: Option<String>,
+    /// Why the changelog couldn't be parsed, see `SelectionCriteria::tolerate_changelog_parse_errors`.
+    #[serde(default)]
+    pub changelog_parse_error: Option<String>,
+    /// The crates whose version bumps escalated the bump of this crate, starting with the one that triggered it.
+    #[serde(default)]
+    pub cascade_chain: Vec<String>,
+    /// The outcome of `cargo semver-checks` if it was run for this crate.
+    #[serde(default)]
+    pub semver_checks: Option<SemverChecksReport>,
+}
+
+/// The states of all workspace members and the crates that are present but excluded from the workspace.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StatesReport {
+    /// The states of the workspace members keyed by crate name.
+    pub crates: BTreeMap<String, CrateStateReport>,
+    /// The crates within the workspace directory that aren't members of the workspace.
+    #[serde(default)]
+    pub excluded_crates: Vec<ExcludedCrate>,
+}
+
+impl std::ops::Index<&str> for StatesReport {
+    type Output = CrateStateReport;
+
+    fn index(&self, name: &str) -> &Self::Output {
+        &self.crates[name]
+    }
+}
+
+/// Why a crate is or isn't part of the release selection, see `ReleaseWorkspace::explain`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SelectionExplanation {
+    pub name: String,
+    /// The name matches `SelectionCriteria::match_filter`.
+    pub filter_matched: bool,
+    /// The crate is matched, either by the filter, by name or by its path.
+    pub matched: bool,
+    /// The shortest chain of dependencies from a matched crate to this one if it was selected as a dependency.
+    pub dependency_path: Option<Vec<String>>,
+    /// The matched crates this crate depends on if it was selected because of them.
+    pub dependent_of: BTreeSet<String>,
+    pub blocking_flags: BitFlags<CrateStateFlags>,
+    /// The blocking flags that were allowed by the criteria or the manifest metadata.
+    pub allowed_blockers: BitFlags<CrateStateFlags>,
+    /// The tag or commit of the previous release the changes are detected against.
+    pub previous_release: Option<String>,
+    pub changed: bool,
+    /// The files that triggered the release since the previous release, relative to the crate root.
+    pub changed_files: Vec<PathBuf>,
+    pub workspace_files_changed: bool,
+    pub dependency_changed: bool,
+    /// The verdict of `ReleaseWorkspace::release_selection` for this crate.
+    pub release_selection: bool,
+}
+
+impl fmt::Display for SelectionExplanation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let yes_no = |value: bool| if value { "yes" } else { "no" };
+        let flags = |flags: BitFlags<CrateStateFlags>| {
+            if flags.is_empty() {
+                "none".to_string()
+            } else {
+                format!("{:?}", flags.iter().collect::<Vec<_>>())
+            }
+        };
+
+        writeln!(f, "{}", self.name)?;
+        writeln!(
+            f,
+            "  matched by the filter: {}, matched: {}",
+            yes_no(self.filter_matched),
+            yes_no(self.matched)
+        )?;
+
+        if let Some(path) = &self.dependency_path {
+            writeln!(
+                f,
+                "  selected as a dependency of {} via {}",
+                path.first().map(String::as_str).unwrap_or_default(),
+                path.join(" -> ")
+            )?;
+        }
+
+        if !self.dependent_of.is_empty() {
+            writeln!(
+                f,
+                "  selected because it depends on {}",
+                self.dependent_of
+                    .iter()
+                    .cloned()
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            )?;
+        }
+
+        writeln!(f, "  blocking flags: {}", flags(self.blocking_flags))?;
+        writeln!(f, "  allowed blockers: {}", flags(self.allowed_blockers))?;
+
+        match &self.previous_release {
+            Some(previous_release) => writeln!(
+                f,
+                "  changed since {}: {}",
+                previous_release,
+                yes_no(self.changed)
+            )?,
+            None => writeln!(
+                f,
+                "  no previous release, changed: {}",
+                yes_no(self.changed)
+            )?,
+        }
+
+        for path in &self.changed_files {
+            writeln!(f, "  - {}", normalized_path(path))?;
+        }
+
+        if self.workspace_files_changed {
+            writeln!(f, "  workspace files changed")?;
+        }
+
+        writeln!(
+            f,
+            "  dependency changed: {}",
+            yes_no(self.dependency_changed)
+        )?;
+        write!(f, "  release selection: {}", yes_no(self.release_selection))
+    }
+}
+
 #[derive(custom_debug::Debug)]
 pub struct ReleaseWorkspace<'a> {
-    root_path: PathBuf,
-    criteria: SelectionCriteria,
-    git_config_name: String,
-    git_config_email: String,
+    pub(crate) root_path: PathBuf,
+    pub(crate) criteria: SelectionCriteria,
+    git_options: GitOptions,
 
     changelog: Option<ChangelogT<'a, WorkspaceChangelog>>,
 
@@ -393,117 +993,899 @@ pub struct ReleaseWorkspace<'a> {
     members_sorted: OnceCell<Vec<&'a Crate<'a>>>,
     members_matched: OnceCell<Vec<&'a Crate<'a>>>,
     members_states: OnceCell<MemberStates>,
+    excluded_crates: OnceCell<Vec<ExcludedCrate>>,
+    /// The cfg values of the `dependency_platform_filter` target.
+    target_cfgs: OnceCell<Vec<cargo_platform::Cfg>>,
+    release_metadata: OnceCell<WorkspaceReleaseMetadata>,
+    #[debug(skip)]
+    changed_files_cache: Mutex<ChangedFilesCache>,
+    #[debug(skip)]
+    changed_files_computations: AtomicUsize,
+    #[debug(skip)]
+    pub(crate) tag_index: Mutex<Option<TagIndex>>,
     #[debug(skip)]
-    git_repo: git2::Repository,
+    tag_index_builds: AtomicUsize,
+    /// Overrides the sparse index of the configured registry.
+    #[debug(skip)]
+    crates_index: Option<Box<dyn CratesIndex>>,
+    /// Resolved from the configured registry on first use.
+    crates_index_url: OnceCell<String>,
+    #[debug(skip)]
+    published_versions_cache: Mutex<HashMap<String, Vec<Version>>>,
+    #[debug(skip)]
+    crates_index_queries: AtomicUsize,
+    #[debug(skip)]
+    pub(crate) command_runner: Box<dyn CommandRunner>,
+    #[debug(skip)]
+    pub(crate) http_client: Box<dyn HttpClient>,
+    #[debug(skip)]
+    pub(crate) progress_reporter: Box<dyn ProgressReporter>,
+    #[debug(skip)]
+    reverse_dependency_index: OnceCell<ReverseDependencyIndex>,
+    semver_checks: OnceCell<BTreeMap<String, CrateStateReport>>,
+    #[debug(skip)]
+    reverse_dependency_index_builds: AtomicUsize,
+    pub(crate) created_tags: Mutex<Vec<String>>,
+    pub(crate) journal_enabled: bool,
+    /// `git2::Repository` is `Send` but not `Sync`. It's not wrapped in a mutex because the workspace can't be shared
+    /// between threads anyway, as the packages and dependencies of cargo are reference counted with `Rc`.
+    #[debug(skip)]
+    pub(crate) git_repo: git2::Repository,
 }
 
-/// Configuration criteria for the crate selection.
-#[derive(Educe, Debug)]
-#[educe(Default)]
-pub struct SelectionCriteria {
-    #[educe(Default(expression = r#"fancy_regex::Regex::new(".*").expect("matching anything is valid")"#r))]
-    pub match_filter: fancy_regex::Regex,
-    pub enforced_version_reqs: Vec<semver::VersionReq>,
-    pub disallowed_version_reqs: Vec<semver::VersionReq>,
-    pub allowed_dev_dependency_blockers: BitFlags<CrateStateFlags>,
-    pub allowed_selection_blockers: BitFlags<CrateStateFlags>,
-    pub exclude_optional_deps: bool,
+/// A crate within the workspace directory that isn't a member of the workspace, see `ReleaseWorkspace::excluded_crates`.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExcludedCrate {
+    /// Unset if the manifest has no package, e.g. a nested workspace.
+    pub name: Option<String>,
+    /// The crate directory relative to the workspace root.
+    pub path: PathBuf,
+    /// Whether it's listed in `workspace.exclude`, otherwise it's not covered by `workspace.members`.
+    pub listed_in_exclude: bool,
 }
 
-/// Defines detailed crate's state in terms of the release process.
-#[bitflags]
-#[repr(u32)]
-#[derive(enum_utils::FromStr, Copy, Clone, Debug, PartialEq, Eq, Hash)]
-pub enum CrateStateFlags {
-    /// matches a package filter
-    Matched,
-    /// in the dependency tree of a matched package
-    IsWorkspaceDependency,
-    /// in the dev-dependency tree of a matched package
-    IsWorkspaceDevDependency,
-    /// has changed since previous release if any
-    HasPreviousRelease,
-    /// Has no previous release
-    NoPreviousRelease,
-    /// Has a previous release but its tag is missing
-    MissingReleaseTag,
-    /// has changed since previous release
-    ChangedSincePreviousRelease,
-    /// At least one dependency is marked as changed.
-    DependencyChanged,
+impl fmt::Display for ExcludedCrate {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{name:<30}{path} ({reason})",
+            name = self.name.as_deref().unwrap_or("-"),
+            path = normalized_path(&self.path),
+            reason = if self.listed_in_exclude {
+                "listed in workspace.exclude"
+            } else {
+                "not covered by workspace.members"
+            }
+        )
+    }
+}
 
-    /// has `unreleasable: true` set in changelog
-    MissingChangelog,
-    MissingReadme,
-    UnreleasableViaChangelogFrontmatter,
-    EnforcedVersionReqViolated,
-    DisallowedVersionReqViolated,
-    /// Has no description in the Cargo.toml
-    MissingDescription,
-    /// Has no license in the Cargo.toml
-    MissingLicense,
-    /// Has a dependency that contains '*'
-    HasWildcardDependency,
-    /// Has a dev-dependency that contains '*'
-    HasWildcardDevDependency,
-    /// One of the manifest keywords is too long
-    ManifestKeywordExceeds20Chars,
-    ManifestKeywordContainsInvalidChar,
-    ManifestKeywordsMoreThan5,
+/// Release configuration that is read from the `[workspace.metadata.release-automation]` table of the workspace manifest.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize)]
+pub struct WorkspaceReleaseMetadata {
+    /// Names of crates that must never be released, e.g. benches or internal fixtures.
+    #[serde(default)]
+    pub never_release: BTreeSet<String>,
 }
 
-/// Defines the meta states that can be derived from the more detailed `CrateStateFlags`.
-#[bitflags]
-#[repr(u16)]
-#[derive(enum_utils::FromStr, Copy, Clone, Debug, PartialEq, Eq, Hash)]
-pub enum MetaCrateStateFlags {
-    Allowed,
-    Blocked,
-    Changed,
-    Selected,
+/// Release configuration that is read from the `[package.metadata.release-automation]` table of a member's manifest.
+/// Takes precedence over the workspace-level configuration.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+pub struct CrateMetadataConfig {
+    /// Never match this crate.
+    #[serde(default)]
+    pub skip: bool,
+    /// Overrides the semver increment mode of the changelog frontmatter.
+    pub semver_increment: Option<crate::common::SemverIncrementMode>,
+    /// Overrides the release tag template of the selection criteria.
+    pub tag_template: Option<String>,
+    /// Blockers that are allowed for this crate in addition to the ones allowed by the selection criteria.
+    #[serde(default)]
+    pub allowed_blockers: Vec<CrateStateFlags>,
 }
 
-impl CrateStateFlags {
-    pub fn empty_set() -> BitFlags<Self> {
-        BitFlags::empty()
+impl CrateMetadataConfig {
+    pub fn allowed_blockers(&self) -> BitFlags<CrateStateFlags> {
+        self.allowed_blockers.iter().copied().collect()
     }
 }
 
-/// Implements the logic for determining a crate's starte in terms of the release process.
-#[derive(Default, Clone, Debug, PartialEq, Eq, Hash)]
-pub struct CrateState {
-    flags: BitFlags<CrateStateFlags>,
-    meta_flags: BitFlags<MetaCrateStateFlags>,
+/// Inconsistencies between the `never_release` list and the workspace members.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct NeverReleaseDrift {
+    /// Listed crates that don't exist in the workspace (anymore).
+    pub unknown: BTreeSet<String>,
+    /// Crates with `publish = false` in their manifest that aren't listed.
+    pub unlisted_unpublishable: BTreeSet<String>,
+}
 
-    allowed_dev_dependency_blockers: BitFlags<CrateStateFlags>,
-    allowed_selection_blockers: BitFlags<CrateStateFlags>,
+impl NeverReleaseDrift {
+    pub fn is_empty(&self) -> bool {
+        self.unknown.is_empty() && self.unlisted_unpublishable.is_empty()
+    }
 }
 
-impl CrateState {
-    pub const BLOCKING_STATES: BitFlags<CrateStateFlags> = enumflags2::make_bitflags!(
-        CrateStateFlags::{MissingChangelog
-            | MissingReadme
-            | UnreleasableViaChangelogFrontmatter
-            | DisallowedVersionReqViolated
-            | EnforcedVersionReqViolated
-            | MissingDescription
-            | MissingLicense
-            | HasWildcardDependency
-            | ManifestKeywordExceeds20Chars
-            | ManifestKeywordContainsInvalidChar
-            | ManifestKeywordsMoreThan5
+/// Explicit git identity for the commits and tags created during the release process.
+///
+/// Unset fields are looked up in the repository's git config and fall back to a default.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct GitOptions {
+    pub name: Option<String>,
+    pub email: Option<String>,
+    /// Sign commits and tags with GPG.
+    pub sign: bool,
+    /// The key used for signing, defaults to `user.signingkey` of the git config.
+    pub signing_key: Option<String>,
+    /// Used as the branch name when HEAD is detached, defaults to the short commit id.
+    pub detached_head_placeholder: Option<String>,
+    /// Passed as `GNUPGHOME` to the gpg and git commands that sign, defaults to the inherited environment.
+    pub gnupg_home: Option<PathBuf>,
+}
+
+/// The state of the repository's HEAD.
+pub enum HeadState<'repo> {
+    Branch(git2::Branch<'repo>, git2::BranchType),
+    Detached(git2::Oid),
+}
+
+/// The kind of git tag to create.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TagKind {
+    Annotated { message: String },
+    Lightweight,
+}
+
+impl TagKind {
+    /// An annotated tag with the default message for the given tag name.
+    pub fn default_annotated(name: &str) -> Self {
+        Self::Annotated {
+            message: format!("tag for release {}", name),
+        }
+    }
+}
+
+/// A tag that was created by `ReleaseWorkspace::git_tag`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CreatedTag {
+    pub name: String,
+    /// The id of the tag object for annotated tags, or of the tagged commit for lightweight tags.
+    pub oid: git2::Oid,
+}
+
+/// Options for `ReleaseWorkspace::publish`.
+#[derive(Clone, Debug, PartialEq, Eq, Educe)]
+#[educe(Default)]
+pub struct PublishOptions {
+    /// Crates that are published with `--no-verify`, i.e. without building them first.
+    pub no_verify: HashSet<String>,
+    pub allow_dirty: bool,
+    pub cargo_target_dir: Option<PathBuf>,
+    /// How often a transient failure, e.g. a server error or a timeout, is retried.
+    #[educe(Default(expression = "3"))]
+    pub max_retries: usize,
+    /// The delay before the first retry, which doubles with every further retry.
+    #[educe(Default(expression = "Duration::from_secs(10)"))]
+    pub retry_delay: Duration,
+    /// The number of crates that can be published in a row before `rate_limit_interval` applies.
+    /// Defaults to the burst that crates.io allows for new versions.
+    #[educe(Default(expression = "30"))]
+    pub rate_limit_burst: usize,
+    /// The pause between two publishes once the burst is used up.
+    #[educe(Default(expression = "Duration::from_secs(60)"))]
+    pub rate_limit_interval: Duration,
+    /// Wait for each published crate to become available in the crates index before publishing the next one.
+    #[educe(Default(
+        expression = "Some(crate::crates_index::DEFAULT_INDEX_AVAILABILITY_TIMEOUT)"
+    ))]
+    pub index_availability_timeout: Option<Duration>,
+}
+
+/// The outcome of `ReleaseWorkspace::publish`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct PublishReport {
+    /// The crate versions that were published.
+    pub published: Vec<String>,
+    /// The crate versions that were skipped because they were already published.
+    pub skipped: Vec<String>,
+}
+
+/// The outcome of `ReleaseWorkspace::verify_ownership`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct OwnershipReport {
+    /// The crates that are owned by the expected owner.
+    pub verified: Vec<String>,
+    /// The crates that don't exist on crates.io yet.
+    pub first_publish: Vec<String>,
+}
+
+/// The outcome of `cargo semver-checks check-release` against the previous release of a crate.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct SemverChecksReport {
+    /// The revision the crate was compared against.
+    pub baseline_rev: String,
+    /// The increment the detected changes require, `None` if all checks passed.
+    pub required_increment: Option<SemverIncrementMode>,
+    /// The failed checks, e.g. `function_missing: pub fn removed or renamed`.
+    pub findings: Vec<String>,
+}
+
+impl SemverChecksReport {
+    /// Whether any of the checks failed.
+    pub fn changes_detected(&self) -> bool {
+        self.required_increment.is_some()
+    }
+}
+
+/// The result of running an external command.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct CommandOutput {
+    pub success: bool,
+    pub stdout: String,
+    pub stderr: String,
+}
+
+/// Runs external commands and waits, which allows replacing both in tests.
+pub trait CommandRunner {
+    fn run(&self, cmd: &mut Command) -> Fallible<CommandOutput>;
+
+    fn sleep(&self, duration: Duration) {
+        std::thread::sleep(duration)
+    }
+}
+
+/// Runs the commands as child processes.
+pub struct SystemCommandRunner;
+
+impl CommandRunner for SystemCommandRunner {
+    fn run(&self, cmd: &mut Command) -> Fallible<CommandOutput> {
+        let output = cmd.output().context(format!("running {:?}", cmd))?;
+
+        Ok(CommandOutput {
+            success: output.status.success(),
+            stdout: String::from_utf8_lossy(&output.stdout).to_string(),
+            stderr: String::from_utf8_lossy(&output.stderr).to_string(),
+        })
+    }
+}
+
+/// Which of the crates that depend on a crate are returned by `Crate::dependants_in_workspace`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DependentsScope {
+    /// Only the crates that depend on it directly.
+    Direct,
+    /// Also the crates that depend on it via other crates.
+    Transitive,
+}
+
+/// Determines how the selection filters are matched against the crate names.
+#[derive(enum_utils::FromStr, Clone, Copy, Debug, PartialEq, Eq, Educe, Serialize)]
+#[educe(Default)]
+pub enum SelectionFilterMode {
+    /// The filter matches if it matches any part of the name.
+    #[educe(Default)]
+    Substring,
+    /// The filter needs to match the whole name.
+    FullMatch,
+}
+
+impl SelectionFilterMode {
+    /// Returns the filter that implements this mode.
+    pub fn apply(&self, filter: &fancy_regex::Regex) -> Fallible<fancy_regex::Regex> {
+        Ok(match self {
+            Self::Substring => filter.clone(),
+            Self::FullMatch => fancy_regex::Regex::new(&format!("^(?:{})$", filter.as_str()))?,
+        })
+    }
+}
+
+/// Determines how to proceed when a tag that's about to be created already exists.
+#[derive(enum_utils::FromStr, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ExistingTagPolicy {
+    /// Fail before creating any tag.
+    Error,
+    /// Consider the tag as already created and continue.
+    Skip,
+    /// Recreate the tag, but only if it points at the same tree as HEAD.
+    Force,
+}
+
+/// The outcome of `ReleaseWorkspace::create_release_tags`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ReleaseTagsReport {
+    /// Tags that were created, or would have been created in dry-run mode.
+    pub created: Vec<String>,
+    /// Tags that were skipped because they already existed.
+    pub skipped: Vec<String>,
+}
+
+/// Determines how to proceed when a branch that's about to be created already exists.
+#[derive(enum_utils::FromStr, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BranchPolicy {
+    /// Fail with an error naming the existing branch.
+    ErrorIfExists,
+    /// Check out the existing branch as it is.
+    ReuseExisting,
+    /// Delete the existing branch and recreate it from HEAD.
+    Recreate,
+}
+
+#[derive(thiserror::Error, Debug, PartialEq, Eq)]
+pub enum GitHeadError {
+    #[error("a branch is required but HEAD is detached at {0}")]
+    Detached(git2::Oid),
+}
+
+/// The current branch doesn't match any of the allowed branch patterns, see `SelectionCriteria::allowed_branch_patterns`.
+#[derive(thiserror::Error, Debug, PartialEq, Eq)]
+#[error(
+    "current branch '{branch}' does not match allowed patterns: {}",
+    .patterns.join(", ")
+)]
+pub struct DisallowedBranchError {
+    pub branch: String,
+    pub patterns: Vec<String>,
+}
+
+/// Local changes in the working tree that prevent a release.
+#[derive(thiserror::Error, Debug, PartialEq, Eq)]
+#[error(
+    "repository is not clean.{}{}",
+    format_dirty_paths("modified tracked files", .modified),
+    format_dirty_paths("untracked files", .untracked)
+)]
+pub struct DirtyWorktreeError {
+    pub modified: Vec<String>,
+    pub untracked: Vec<String>,
+}
+
+/// No crates are selected for the release.
+#[derive(thiserror::Error, Debug, PartialEq, Eq)]
+#[error("no crates are selected for the release")]
+pub struct EmptySelectionError;
+
+/// Crates that are required for the release are blocked, see `ReleaseWorkspace::release_selection`.
+#[derive(thiserror::Error, Debug, PartialEq, Eq)]
+#[error(
+    "the following crates are blocked but required for the release: \n{}{}{}{}",
+    .report,
+    format_required_via("the following crates are listed in never_release but are required via", .never_release, ""),
+    format_required_via("the following crates are excluded but are required via", .excluded, "\nconsider allowing excluded crates as dependencies"),
+    format_required_via("the following crates set `publish = false` but are required via", .publish_false, "")
+)]
+pub struct BlockedSelectionError {
+    pub report: CratesStateReport,
+    /// The dependency chains that require crates which are listed in never_release, e.g. `crate_a -> crate_b`.
+    pub never_release: Vec<String>,
+    /// The dependency chains that require excluded crates.
+    pub excluded: Vec<String>,
+    /// The dependency chains that require crates which set `publish = false`.
+    pub publish_false: Vec<String>,
+}
+
+fn format_required_via(title: &str, chains: &[String], hint: &str) -> String {
+    if chains.is_empty() {
+        return String::new();
+    }
+
+    format!(
+        "\n{}: {}{}",
+        title,
+        chains
+            .iter()
+            .map(|chain| format!("\n- {}", chain))
+            .collect::<String>(),
+        hint
+    )
+}
+
+fn format_dirty_paths(title: &str, paths: &[String]) -> String {
+    if paths.is_empty() {
+        return String::new();
+    }
+
+    format!(
+        "\n{}:{}",
+        title,
+        paths
+            .iter()
+            .map(|path| format!("\n- {}", path))
+            .collect::<String>()
+    )
+}
+
+/// Configuration criteria for the crate selection.
+///
+/// Serializes deterministically so that it can be embedded in a release plan, see `ReleasePlan`.
+#[derive(Educe, Debug, Serialize)]
+#[educe(Default)]
+pub struct SelectionCriteria {
+    #[educe(Default(expression = r#"fancy_regex::Regex::new(".*").expect("matching anything is valid")"#r))]
+    #[serde(serialize_with = "criteria_serde::regex")]
+    pub match_filter: fancy_regex::Regex,
+    /// Determines how `match_filter` and `exclusion_filter` are matched against the crate names.
+    pub selection_filter_mode: SelectionFilterMode,
+    /// Names of crates that are matched in addition to the ones matching `match_filter`.
+    /// Each of them must be a member of the workspace.
+    pub selection_names: Vec<String>,
+    /// Crates whose root, relative to the workspace root, matches any of these globs are matched
+    /// in addition to the ones matching `match_filter`. Paths are separated by `/` on all platforms.
+    #[serde(serialize_with = "criteria_serde::display_seq")]
+    pub selection_path_globs: Vec<glob::Pattern>,
+    /// Crates matching this filter are never matched, even if they match `match_filter` or `selection_names`.
+    #[serde(serialize_with = "criteria_serde::optional_regex")]
+    pub exclusion_filter: Option<fancy_regex::Regex>,
+    /// Names of crates that are never matched, even if they match `match_filter` or `selection_names`.
+    pub excluded_names: Vec<String>,
+    /// Allow excluded crates to be released as dependencies of matched crates. Otherwise they block the release.
+    pub allow_excluded_as_dependency: bool,
+    /// Additionally select all crates that depend on a matched crate, directly or transitively.
+    pub include_dependents: bool,
+    /// Only select workspace dependencies of matched crates up to this depth, direct dependencies have a depth of 1.
+    /// All transitive dependencies are selected if unset.
+    pub max_dependency_depth: Option<usize>,
+    #[serde(serialize_with = "criteria_serde::display_seq")]
+    pub enforced_version_reqs: Vec<semver::VersionReq>,
+    #[serde(serialize_with = "criteria_serde::display_seq")]
+    pub disallowed_version_reqs: Vec<semver::VersionReq>,
+    #[serde(serialize_with = "bitflags_serde::serialize")]
+    pub allowed_dev_dependency_blockers: BitFlags<CrateStateFlags>,
+    #[serde(serialize_with = "bitflags_serde::serialize")]
+    pub allowed_selection_blockers: BitFlags<CrateStateFlags>,
+    /// Crates with any of these flags are blocked from being released.
+    #[educe(Default(expression = "CrateState::BLOCKING_STATES"))]
+    #[serde(serialize_with = "bitflags_serde::serialize")]
+    pub blocking_states: BitFlags<CrateStateFlags>,
+    pub exclude_optional_deps: bool,
+    /// Per-crate overrides of `exclude_optional_deps` for the optional dependencies of the crate with the given name.
+    #[serde(serialize_with = "criteria_serde::sorted_map")]
+    pub optional_deps_overrides: HashMap<String, bool>,
+    /// Dev-dependencies with git sources don't block the release, as crates.io strips them on publish.
+    pub allow_git_dev_dependencies: bool,
+    /// Dependencies of these kinds are not considered.
+    #[serde(serialize_with = "criteria_serde::dep_kinds")]
+    pub exclude_dep_kinds: HashSet<CargoDepKind>,
+    /// Per-crate overrides of `exclude_dep_kinds` for the dependencies of the crate with the given name.
+    #[serde(serialize_with = "criteria_serde::dep_kind_overrides")]
+    pub dep_kind_overrides: HashMap<String, HashSet<CargoDepKind>>,
+    /// Mark crates whose changelog can't be parsed with `ChangelogParseError` instead of failing.
+    pub tolerate_changelog_parse_errors: bool,
+    /// Only consider the target-specific dependencies that apply to this target triple, e.g. `x86_64-unknown-linux-gnu`.
+    /// All dependencies are considered if unset.
+    pub dependency_platform_filter: Option<String>,
+    /// Changes to files matching any of these globs, relative to the crate root, don't mark a crate as changed.
+    #[educe(Default(expression = r#"vec![glob::Pattern::new("CHANGELOG.md").expect("pattern is valid")]"#r))]
+    #[serde(serialize_with = "criteria_serde::display_seq")]
+    pub change_detection_ignore_globs: Vec<glob::Pattern>,
+    /// Changes to any of these workspace-relative paths mark all crates with a previous release as changed.
+    pub workspace_change_paths: Vec<PathBuf>,
+    /// Only changes of these classes mark a crate as changed since its previous release.
+    #[educe(Default(expression = "BitFlags::all()"))]
+    #[serde(serialize_with = "bitflags_serde::serialize")]
+    pub change_classes_triggering_release: BitFlags<ChangeClass>,
+    /// Compute the changed files every time instead of reusing previous results.
+    pub no_change_detection_cache: bool,
+    /// The number of threads that compute the changed files of the members. Defaults to the number of CPUs.
+    #[serde(skip)]
+    pub analysis_threads: Option<usize>,
+    /// Fetch all tags from this remote before determining the previous releases.
+    pub fetch_tags_from_remote: Option<String>,
+    /// Allow releasing with local modifications or untracked files in the working tree.
+    pub allow_dirty: bool,
+    /// Template for the release tag names, `{name}` and `{version}` are replaced with the crate's name and version.
+    /// Defaults to `DEFAULT_RELEASE_TAG_TEMPLATE`.
+    pub release_tag_template: Option<String>,
+    /// Block crates whose manifest version is older than the latest release in their changelog.
+    pub block_on_version_changelog_mismatch: bool,
+    /// The highest `rust-version` the selected crates may declare.
+    #[serde(serialize_with = "criteria_serde::optional_display")]
+    pub max_msrv: Option<semver::Version>,
+    /// Look up whether the versions of the crates that are about to be released are already published in the registry.
+    pub check_crates_io: bool,
+    /// Fail if any of the online checks can't be performed, instead of warning and continuing.
+    pub strict: bool,
+    /// The name of the registry to release to as configured in the cargo configuration. Defaults to crates.io.
+    pub registry: Option<String>,
+    /// The crates.io user or team, e.g. `github:{org}:{team}`, that must own all selected crates which are already published.
+    pub expected_owner: Option<String>,
+    /// Treat a crate whose manifest version is published on crates.io but lacks its release tag as released
+    /// at the commit that last set the manifest version.
+    pub reconcile_untagged_releases: bool,
+    /// Sum up the sizes of the files that would be packaged for each selected crate, which is slow.
+    pub verify_package_contents: bool,
+    /// The maximum size in bytes of the files that would be packaged when `verify_package_contents` is set.
+    #[educe(Default(expression = "DEFAULT_MAX_PACKAGE_SIZE"))]
+    pub max_package_size: u64,
+    /// How to increment the versions of the selected crates unless overridden per crate.
+    pub semver_increment_mode: SemverIncrementMode,
+    /// Increment modes per crate name, which take precedence over the crates' manifest metadata.
+    #[serde(serialize_with = "criteria_serde::sorted_map")]
+    pub semver_increment_overrides: HashMap<String, SemverIncrementMode>,
+    /// Only allow releasing from branches matching any of these patterns. All branches are allowed if empty.
+    #[serde(serialize_with = "criteria_serde::regexes")]
+    pub allowed_branch_patterns: Vec<fancy_regex::Regex>,
+    /// Allow releasing from a detached HEAD.
+    pub allow_detached_head: bool,
+    /// Select crates whose latest release is a pre-release for the promotion to the corresponding stable release,
+    /// even if they haven't changed since.
+    pub promote: bool,
+    /// Version the crates that inherit their version from the workspace in lockstep,
+    /// i.e. release them with the highest of their next versions instead of failing if those differ.
+    pub lockstep: bool,
+}
+
+/// Defines detailed crate's state in terms of the release process.
+#[bitflags]
+#[repr(u64)]
+#[derive(enum_utils::FromStr, Copy, Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum CrateStateFlags {
+    /// matches a package filter
+    Matched,
+    /// in the dependency tree of a matched package
+    IsWorkspaceDependency,
+    /// in the dev-dependency tree of a matched package
+    IsWorkspaceDevDependency,
+    /// has changed since previous release if any
+    HasPreviousRelease,
+    /// Has no previous release
+    NoPreviousRelease,
+    /// Has a previous release but its tag is missing
+    MissingReleaseTag,
+    /// The manifest version is published on crates.io but its release tag is missing
+    PreviousReleaseUntagged,
+    /// has changed since previous release
+    ChangedSincePreviousRelease,
+    /// The latest release is a pre-release which is to be promoted to the stable release
+    PendingStablePromotion,
+    /// At least one dependency is marked as changed.
+    DependencyChanged,
+    /// The version bump was escalated because the next version of a dependency falls outside the requirement on it
+    CascadeBumped,
+
+    /// has `unreleasable: true` set in changelog
+    MissingChangelog,
+    MissingReadme,
+    /// Has neither a LICENSE, LICENSE-APACHE or LICENSE-MIT file nor a license file referred to by the Cargo.toml
+    MissingLicenseFile,
+    UnreleasableViaChangelogFrontmatter,
+    /// The changelog couldn't be parsed, which is only tolerated if configured
+    ChangelogParseError,
+    EnforcedVersionReqViolated,
+    DisallowedVersionReqViolated,
+    /// Lacks a description, license or repository in the Cargo.toml, which crates.io requires for publishing
+    #[serde(alias = "MissingDescription", alias = "MissingLicense")]
+    MissingPublishMetadata,
+    /// Has a dependency that contains '*'
+    HasWildcardDependency,
+    /// Has a dev-dependency that contains '*'
+    HasWildcardDevDependency,
+    /// Has a dependency with a git source
+    HasGitDependency,
+    /// Has a path dependency without a version requirement
+    PathDepMissingVersion,
+    /// The files that would be packaged exceed the configured maximum size
+    PackageTooLarge,
+    /// The manifest version is older than the latest release in the changelog
+    VersionChangelogMismatch,
+    /// The manifest version is already published in the registry
+    VersionAlreadyPublished,
+    /// Requires a newer Rust version than the workspace policy or depends on a crate that does
+    MsrvViolation,
+    /// The manifest keywords or categories exceed the limits of crates.io
+    #[serde(
+        alias = "ManifestKeywordExceeds20Chars",
+        alias = "ManifestKeywordContainsInvalidChar",
+        alias = "ManifestKeywordsMoreThan5"
+    )]
+    InvalidKeywordsOrCategories,
+    /// The `publish` list in the manifest doesn't contain the registry to release to
+    RegistryNotAllowed,
+    /// `cargo semver-checks` detected changes that require a stronger version increment than the declared one
+    SemverIncrementTooWeak,
+    /// Listed in `never_release` of the workspace manifest's release-automation metadata
+    NeverRelease,
+    /// One of the configured workspace-level files has changed since previous release
+    WorkspaceFilesChanged,
+    /// Matches the exclusion criteria and may not be released as a dependency either
+    Excluded,
+    /// Depends on a matched package, directly or transitively
+    IsDependentOfMatched,
+    /// Sets `publish = false` in the Cargo.toml
+    ManifestPublishFalse,
+}
+
+/// Defines the meta states that can be derived from the more detailed `CrateStateFlags`.
+#[bitflags]
+#[repr(u16)]
+#[derive(enum_utils::FromStr, Copy, Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum MetaCrateStateFlags {
+    Allowed,
+    Blocked,
+    Changed,
+    Selected,
+}
+
+/// Classifies changed files by their location within the crate.
+#[bitflags]
+#[repr(u8)]
+#[derive(enum_utils::FromStr, Copy, Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ChangeClass {
+    /// `src/`, `Cargo.toml` and `build.rs`
+    Source,
+    /// `tests/`
+    Tests,
+    /// `benches/`
+    Benches,
+    /// `examples/`
+    Examples,
+    /// `docs/` and markdown files
+    Docs,
+    /// Anything else
+    Other,
+}
+
+impl ChangeClass {
+    /// Classifies the given path which is expected to be relative to the crate root.
+    pub fn classify(path: &Path) -> Self {
+        match path
+            .components()
+            .next()
+            .and_then(|c| c.as_os_str().to_str())
+        {
+            Some("src") | Some("Cargo.toml") | Some("build.rs") => Self::Source,
+            Some("tests") => Self::Tests,
+            Some("benches") => Self::Benches,
+            Some("examples") => Self::Examples,
+            Some("docs") | Some("doc") => Self::Docs,
+            _ if path.extension().and_then(|ext| ext.to_str()) == Some("md") => Self::Docs,
+            _ => Self::Other,
+        }
+    }
+}
+
+impl CrateStateFlags {
+    pub fn empty_set() -> BitFlags<Self> {
+        BitFlags::empty()
+    }
+
+    /// Maps the deprecated names of flags that were merged into another flag to their replacement.
+    pub fn from_deprecated_name(name: &str) -> Option<Self> {
+        match name {
+            "MissingDescription" | "MissingLicense" => Some(Self::MissingPublishMetadata),
+            "ManifestKeywordExceeds20Chars"
+            | "ManifestKeywordContainsInvalidChar"
+            | "ManifestKeywordsMoreThan5" => Some(Self::InvalidKeywordsOrCategories),
+            _ => None,
+        }
+    }
+}
+
+/// (De)serializes `BitFlags` as a list of flag names rather than their numeric representation.
+mod bitflags_serde {
+    use enumflags2::{BitFlag, BitFlags};
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    pub(crate) fn serialize<T, S>(flags: &BitFlags<T>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        T: BitFlag + Serialize,
+        S: Serializer,
+    {
+        serializer.collect_seq(flags.iter())
+    }
+
+    pub(crate) fn deserialize<'de, T, D>(deserializer: D) -> Result<BitFlags<T>, D::Error>
+    where
+        T: BitFlag + Deserialize<'de>,
+        D: Deserializer<'de>,
+    {
+        Ok(Vec::<T>::deserialize(deserializer)?.into_iter().collect())
+    }
+}
+
+/// Serializers for the fields of `SelectionCriteria` whose types aren't serializable,
+/// or whose iteration order isn't deterministic.
+mod criteria_serde {
+    use super::aliases::CargoDepKind;
+    use serde::{Serialize, Serializer};
+    use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+    use std::fmt::Display;
+
+    pub(crate) fn regex<S: Serializer>(
+        regex: &fancy_regex::Regex,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(regex.as_str())
+    }
+
+    pub(crate) fn optional_regex<S: Serializer>(
+        regex: &Option<fancy_regex::Regex>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        regex
+            .as_ref()
+            .map(fancy_regex::Regex::as_str)
+            .serialize(serializer)
+    }
+
+    pub(crate) fn regexes<S: Serializer>(
+        regexes: &[fancy_regex::Regex],
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(regexes.iter().map(fancy_regex::Regex::as_str))
+    }
+
+    pub(crate) fn display_seq<T: Display, S: Serializer>(
+        items: &[T],
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(items.iter().map(ToString::to_string))
+    }
+
+    pub(crate) fn optional_display<T: Display, S: Serializer>(
+        item: &Option<T>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        item.as_ref().map(ToString::to_string).serialize(serializer)
+    }
+
+    pub(crate) fn sorted_map<V: Serialize, S: Serializer>(
+        map: &HashMap<String, V>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.collect_map(map.iter().collect::<BTreeMap<_, _>>())
+    }
+
+    /// Uses the names that are accepted on the command line.
+    fn dep_kind_names(kinds: &HashSet<CargoDepKind>) -> BTreeSet<&'static str> {
+        kinds
+            .iter()
+            .map(|kind| match kind {
+                CargoDepKind::Normal => "normal",
+                CargoDepKind::Development => "development",
+                CargoDepKind::Build => "build",
+            })
+            .collect()
+    }
+
+    pub(crate) fn dep_kinds<S: Serializer>(
+        kinds: &HashSet<CargoDepKind>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        dep_kind_names(kinds).serialize(serializer)
+    }
+
+    pub(crate) fn dep_kind_overrides<S: Serializer>(
+        overrides: &HashMap<String, HashSet<CargoDepKind>>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.collect_map(
+            overrides
+                .iter()
+                .map(|(name, kinds)| (name, dep_kind_names(kinds)))
+                .collect::<BTreeMap<_, _>>(),
+        )
+    }
+}
+
+/// Implements the logic for determining a crate's starte in terms of the release process.
+#[derive(Educe, Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[educe(Default)]
+pub struct CrateState {
+    #[serde(with = "bitflags_serde")]
+    flags: BitFlags<CrateStateFlags>,
+    #[serde(with = "bitflags_serde")]
+    meta_flags: BitFlags<MetaCrateStateFlags>,
+
+    #[serde(with = "bitflags_serde")]
+    allowed_dev_dependency_blockers: BitFlags<CrateStateFlags>,
+    #[serde(with = "bitflags_serde")]
+    allowed_selection_blockers: BitFlags<CrateStateFlags>,
+
+    /// Flags that block the release, defaults to the `BLOCKING_STATES`.
+    #[serde(
+        with = "bitflags_serde",
+        default = "CrateState::default_blocking_states"
+    )]
+    #[educe(Default(expression = "CrateState::BLOCKING_STATES"))]
+    blocking_states: BitFlags<CrateStateFlags>,
+
+    /// Classes of the files that changed since the previous release.
+    #[serde(with = "bitflags_serde")]
+    change_classes: BitFlags<ChangeClass>,
+
+    /// The matched crates this crate depends on, directly or transitively.
+    #[serde(default)]
+    dependent_of: BTreeSet<String>,
+
+    /// The matched crates that depend on this crate, directly or transitively.
+    #[serde(default)]
+    required_by: BTreeSet<String>,
+
+    /// The publish metadata fields that are missing in the manifest.
+    #[serde(default)]
+    missing_publish_metadata: Vec<String>,
+
+    /// The largest packaged files and their sizes in bytes if the package is too large.
+    #[serde(default)]
+    largest_package_files: Vec<(String, u64)>,
+
+    /// The commit that is treated as the release of the published but untagged manifest version.
+    #[serde(default)]
+    untagged_release_commit: Option<String>,
+
+    /// The revision the changes were detected from, i.e. the release tag or the commit of the untagged release.
+    #[serde(default)]
+    previous_release_rev: Option<String>,
+
+    /// The files that changed since the previous release and aren't ignored, relative to the crate root.
+    #[serde(default)]
+    changed_files: Vec<PathBuf>,
+
+    /// Why the changelog couldn't be parsed, see `SelectionCriteria::tolerate_changelog_parse_errors`.
+    #[serde(default)]
+    changelog_parse_error: Option<String>,
+
+    /// The crates whose version bumps escalated the bump of this crate, starting with the one that triggered it.
+    #[serde(default)]
+    cascade_chain: Vec<String>,
+
+    /// The outcome of `cargo semver-checks` if it was run for this crate.
+    #[serde(default)]
+    semver_checks: Option<SemverChecksReport>,
+}
+
+impl CrateState {
+    /// The flags that block the release unless configured otherwise via `SelectionCriteria::blocking_states`.
+    pub const BLOCKING_STATES: BitFlags<CrateStateFlags> = enumflags2::make_bitflags!(
+        CrateStateFlags::{MissingChangelog
+            | MissingReadme
+            | MissingLicenseFile
+            | UnreleasableViaChangelogFrontmatter
+            | ChangelogParseError
+            | DisallowedVersionReqViolated
+            | EnforcedVersionReqViolated
+            | MissingPublishMetadata
+            | HasWildcardDependency
+            | HasGitDependency
+            | PathDepMissingVersion
+            | PackageTooLarge
+            | MsrvViolation
+            | VersionAlreadyPublished
+            | InvalidKeywordsOrCategories
+            | RegistryNotAllowed
+            | SemverIncrementTooWeak
+            | NeverRelease
+            | Excluded
+            | ManifestPublishFalse
     });
 
+    fn default_blocking_states() -> BitFlags<CrateStateFlags> {
+        Self::BLOCKING_STATES
+    }
+
     pub fn new(
         flags: BitFlags<CrateStateFlags>,
         allowed_dev_dependency_blockers: BitFlags<CrateStateFlags>,
         allowed_selection_blockers: BitFlags<CrateStateFlags>,
+        blocking_states: BitFlags<CrateStateFlags>,
     ) -> Self {
         let mut new = Self {
             flags,
             meta_flags: Default::default(),
             allowed_dev_dependency_blockers,
             allowed_selection_blockers,
+            blocking_states,
+            change_classes: Default::default(),
+            dependent_of: Default::default(),
+            required_by: Default::default(),
+            missing_publish_metadata: Default::default(),
+            largest_package_files: Default::default(),
+            untagged_release_commit: Default::default(),
+            previous_release_rev: Default::default(),
+            changed_files: Default::default(),
+            changelog_parse_error: Default::default(),
+            cascade_chain: Default::default(),
+            semver_checks: Default::default(),
         };
         new.update_meta_flags();
         new
@@ -515,9 +1897,77 @@ impl CrateState {
 
     pub fn merge(&mut self, other: Self) {
         self.flags.extend(other.flags.iter());
+        self.change_classes.extend(other.change_classes.iter());
+        self.update_meta_flags();
+    }
+
+    pub fn change_classes(&self) -> BitFlags<ChangeClass> {
+        self.change_classes
+    }
+
+    /// The flags of this crate that would block its release according to its blocking states but were allowed,
+    /// either by the selection criteria or by the crate's manifest metadata.
+    pub fn allowed_blockers(&self) -> BitFlags<CrateStateFlags> {
+        self.flags & self.blocking_states & !self.disallowed_blockers()
+    }
+
+    /// Allows the given blockers in addition to the ones that are already allowed.
+    pub fn allow_blockers(&mut self, blockers: BitFlags<CrateStateFlags>) {
+        self.allowed_selection_blockers.insert(blockers);
+        self.allowed_dev_dependency_blockers.insert(blockers);
         self.update_meta_flags();
     }
 
+    /// The matched crates this crate depends on if it was selected because of them.
+    pub fn dependent_of(&self) -> &BTreeSet<String> {
+        &self.dependent_of
+    }
+
+    /// The matched crates that depend on this crate if it was selected as their dependency.
+    pub fn required_by(&self) -> &BTreeSet<String> {
+        &self.required_by
+    }
+
+    /// The publish metadata fields that are missing in the manifest.
+    pub fn missing_publish_metadata(&self) -> &[String] {
+        &self.missing_publish_metadata
+    }
+
+    /// The largest packaged files and their sizes in bytes if the package is too large.
+    pub fn largest_package_files(&self) -> &[(String, u64)] {
+        &self.largest_package_files
+    }
+
+    /// The commit that is treated as the release of the published but untagged manifest version.
+    pub fn untagged_release_commit(&self) -> Option<&str> {
+        self.untagged_release_commit.as_deref()
+    }
+
+    /// The revision the changes were detected from if there is a previous release.
+    pub fn previous_release_rev(&self) -> Option<&str> {
+        self.previous_release_rev.as_deref()
+    }
+
+    /// The files that changed since the previous release and aren't ignored, relative to the crate root.
+    pub fn changed_files(&self) -> &[PathBuf] {
+        &self.changed_files
+    }
+
+    /// Why the changelog couldn't be parsed if that was tolerated.
+    pub fn changelog_parse_error(&self) -> Option<&str> {
+        self.changelog_parse_error.as_deref()
+    }
+
+    /// The crates whose version bumps escalated the bump of this crate, starting with the one that triggered it.
+    pub fn cascade_chain(&self) -> &[String] {
+        &self.cascade_chain
+    }
+
+    /// The outcome of `cargo semver-checks` if it was run for this crate.
+    pub fn semver_checks(&self) -> Option<&SemverChecksReport> {
+        self.semver_checks.as_ref()
+    }
+
     pub fn insert(&mut self, flag: CrateStateFlags) {
         self.flags.insert(flag);
         self.update_meta_flags();
@@ -563,10 +2013,18 @@ impl CrateState {
     }
 
     fn blocked_by(&self) -> BitFlags<CrateStateFlags> {
-        Self::BLOCKING_STATES.intersection_c(self.flags)
+        self.blocking_states.intersection_c(self.flags)
     }
 
     fn disallowed_blockers(&self) -> BitFlags<CrateStateFlags> {
+        // unpublishable crates are never released, so nothing prevents using them as dev-dependencies
+        if self.contains(CrateStateFlags::ManifestPublishFalse)
+            && !self.is_matched()
+            && !self.is_dependency()
+        {
+            return BitFlags::empty();
+        }
+
         let mut blocking_flags = self.blocked_by();
 
         match (self.is_matched(), self.is_dev_dependency()) {
@@ -578,7 +2036,7 @@ impl CrateState {
         blocking_flags
     }
 
-    fn blocked(&self) -> bool {
+    pub(crate) fn blocked(&self) -> bool {
         !self.blocked_by().is_empty()
     }
 
@@ -593,6 +2051,7 @@ impl CrateState {
             || self
                 .flags
                 .contains(CrateStateFlags::ChangedSincePreviousRelease)
+            || self.flags.contains(CrateStateFlags::PendingStablePromotion)
     }
 
     /// At least one dependency is marked as changed.
@@ -613,81 +2072,252 @@ impl CrateState {
 
     /// Has been matched explicitly or as a consequence of a dependency.
     pub fn selected(&self) -> bool {
-        self.is_matched() || self.is_dependency() || self.is_dev_dependency()
+        self.is_matched()
+            || self.is_dependency()
+            || self.is_dev_dependency()
+            || self.flags.contains(CrateStateFlags::IsDependentOfMatched)
     }
 
     /// Will be included in the release
     pub fn release_selection(&self) -> bool {
         !self.blocked() && (self.changed() || self.dependency_changed()) && self.selected()
     }
+}
+
+impl CrateStateReport {
+    pub fn new(version: &Version, state: &CrateState) -> Self {
+        Self {
+            version: version.to_string(),
+            flags: state.flags,
+            blocking_flags: state.blocked_by(),
+            disallowed_blocking_flags: state.disallowed_blockers(),
+            meta_flags: state.meta_flags,
+            change_classes: state.change_classes,
+            release_selection: state.release_selection(),
+            dependent_of: state.dependent_of.clone(),
+            required_by: state.required_by.clone(),
+            missing_publish_metadata: state.missing_publish_metadata.clone(),
+            largest_package_files: state.largest_package_files.clone(),
+            untagged_release_commit: state.untagged_release_commit.clone(),
+            changelog_parse_error: state.changelog_parse_error.clone(),
+            cascade_chain: state.cascade_chain.clone(),
+            semver_checks: state.semver_checks.clone(),
+        }
+    }
 
-    /// Returns a formatted string with an overview of crates and their states.
-    pub fn format_crates_states<'cs, CS>(
-        states: CS,
-        title: &str,
+    /// The lines of details that follow the name and version, depending on which states are shown.
+    fn detail_lines(
+        &self,
+        name: &str,
         show_blocking: bool,
         show_flags: bool,
         show_meta: bool,
-    ) -> String
-    where
-        CS: std::iter::IntoIterator<Item = &'cs (String, CrateState)>,
-    {
-        let mut states_shown = if show_blocking || show_flags || show_meta {
-            "Showing states: "
-        } else {
-            ""
-        }
-        .to_string();
+    ) -> Vec<String> {
+        let mut lines = vec![];
+
         if show_blocking {
-            states_shown += "* Disallowed Blocking "
+            lines.push(format!(
+                "{:?}",
+                self.disallowed_blocking_flags.iter().collect::<Vec<_>>()
+            ));
         }
-        if show_flags {
-            states_shown += "* Flags "
+
+        if let Some(commit) = &self.untagged_release_commit {
+            lines.push(format!(
+                "published but untagged, released at commit {}",
+                commit
+            ));
         }
-        if show_meta {
-            states_shown += "* Meta"
+
+        if let Some(err) = &self.changelog_parse_error {
+            lines.push(format!("unparseable changelog: {}", err));
         }
-        if !states_shown.is_empty() {
-            states_shown += "\n";
+
+        if !self.cascade_chain.is_empty() {
+            lines.push(format!(
+                "version bump escalated via {} -> {}",
+                self.cascade_chain.join(" -> "),
+                name
+            ));
         }
 
-        let mut msg = format!("\n{0:-<80}\n{1}\n{2}", "", title.to_owned(), states_shown,);
-        for (name, state) in states {
-            msg += &format!("{empty:-<80}\n{name:<30}", empty = "", name = name);
-            if show_blocking {
-                msg += &format!(
-                    "{blocking_flags:?}\n{empty:<30}",
-                    empty = "",
-                    blocking_flags = state.disallowed_blockers().iter().collect::<Vec<_>>(),
-                );
+        if let Some(report) = &self.semver_checks {
+            if let Some(required_increment) = &report.required_increment {
+                lines.push(format!(
+                    "semver-checks against {} require a {:?} increment: {}",
+                    report.baseline_rev,
+                    required_increment,
+                    report.findings.join(", "),
+                ));
             }
+        }
 
-            if show_flags {
-                msg += &format!(
-                    "{flags:?}\n{empty:<30}",
-                    empty = "",
-                    flags = state.flags.iter().collect::<Vec<_>>(),
-                );
-            };
-
-            if show_meta {
-                msg += &format!(
-                    "{meta_flags:?}",
-                    meta_flags = state.meta_flags.iter().collect::<Vec<_>>(),
-                );
-            };
-
-            msg += &"\n".to_string();
+        if (show_blocking || show_flags) && !self.largest_package_files.is_empty() {
+            lines.push(format!(
+                "largest packaged files: {}",
+                self.largest_package_files
+                    .iter()
+                    .map(|(path, size)| format!("{} ({} bytes)", path, size))
+                    .collect::<Vec<_>>()
+                    .join(", "),
+            ));
         }
 
-        msg
-    }
-}
+        if show_flags {
+            lines.push(format!("{:?}", self.flags.iter().collect::<Vec<_>>()));
 
-impl<'a> ReleaseWorkspace<'a> {
+            if !self.change_classes.is_empty() {
+                lines.push(format!(
+                    "changes: {:?}",
+                    self.change_classes.iter().collect::<Vec<_>>()
+                ));
+            }
+
+            if !self.dependent_of.is_empty() {
+                lines.push(format!(
+                    "selected because it depends on {}",
+                    self.dependent_of
+                        .iter()
+                        .cloned()
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                ));
+            }
+
+            if !self.required_by.is_empty() {
+                lines.push(format!(
+                    "required by: {}",
+                    self.required_by
+                        .iter()
+                        .cloned()
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                ));
+            }
+
+            if !self.missing_publish_metadata.is_empty() {
+                lines.push(format!(
+                    "missing publish metadata: {}",
+                    self.missing_publish_metadata.join(", ")
+                ));
+            }
+        }
+
+        if show_meta {
+            lines.push(format!("{:?}", self.meta_flags.iter().collect::<Vec<_>>()));
+        }
+
+        lines
+    }
+}
+
+/// An overview of crates and their states.
+///
+/// The `Display` implementation renders the states that are chosen via `CratesStateReport::showing`.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CratesStateReport {
+    pub title: String,
+    /// The crates by name.
+    pub rows: Vec<(String, CrateStateReport)>,
+    #[serde(skip)]
+    pub show_blocking: bool,
+    #[serde(skip)]
+    pub show_flags: bool,
+    #[serde(skip)]
+    pub show_meta: bool,
+}
+
+impl CratesStateReport {
+    pub fn new(title: &str, rows: Vec<(String, CrateStateReport)>) -> Self {
+        Self {
+            title: title.to_string(),
+            rows,
+            ..Default::default()
+        }
+    }
+
+    /// Chooses which states are rendered in addition to the name and version of the crates.
+    pub fn showing(mut self, show_blocking: bool, show_flags: bool, show_meta: bool) -> Self {
+        self.show_blocking = show_blocking;
+        self.show_flags = show_flags;
+        self.show_meta = show_meta;
+        self
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rows.is_empty()
+    }
+
+    /// Renders the crates as a GitHub-flavored markdown table, regardless of the states that are chosen to be shown.
+    pub fn to_markdown_table(&self) -> String {
+        fn join<T: fmt::Debug>(flags: impl Iterator<Item = T>) -> String {
+            flags
+                .map(|flag| format!("{:?}", flag))
+                .collect::<Vec<_>>()
+                .join(", ")
+        }
+
+        let mut table = String::from(
+            "| crate | version | flags | blocking flags | meta flags | release selection |\n\
+            | --- | --- | --- | --- | --- | --- |\n",
+        );
+
+        for (name, row) in &self.rows {
+            table += &format!(
+                "| {} | {} | {} | {} | {} | {} |\n",
+                name,
+                row.version,
+                join(row.flags.iter()),
+                join(row.blocking_flags.iter()),
+                join(row.meta_flags.iter()),
+                if row.release_selection { "yes" } else { "no" },
+            );
+        }
+
+        table
+    }
+}
+
+impl fmt::Display for CratesStateReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let states_shown = [
+            (self.show_blocking, "Disallowed Blocking"),
+            (self.show_flags, "Flags"),
+            (self.show_meta, "Meta"),
+        ]
+        .iter()
+        .filter(|(shown, _)| *shown)
+        .map(|(_, states)| format!("* {}", states))
+        .collect::<Vec<_>>();
+
+        write!(f, "\n{0:-<80}\n{1}\n", "", self.title)?;
+        if !states_shown.is_empty() {
+            writeln!(f, "Showing states: {}", states_shown.join(" "))?;
+        }
+
+        for (name, row) in &self.rows {
+            let mut lines = vec![format!("{:<30}{}", name, row.version)];
+            lines.extend(row.detail_lines(
+                name,
+                self.show_blocking,
+                self.show_flags,
+                self.show_meta,
+            ));
+
+            writeln!(f, "{0:-<80}\n{1}", "", lines.join(&format!("\n{:<30}", "")))?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<'a> ReleaseWorkspace<'a> {
     const README_FILENAME: &'a str = "README.md";
+    const LICENSE_FILENAMES: &'a [&'a str] = &["LICENSE", "LICENSE-APACHE", "LICENSE-MIT"];
+    const METADATA_KEY: &'a str = "release-automation";
     const GIT_CONFIG_NAME: &'a str = "Holochain Core Dev Team";
     const GIT_CONFIG_EMAIL: &'a str = "devcore@holochain.org";
+    pub(crate) const GIT_TOKEN_ENV_VAR: &'a str = "RELEASE_AUTOMATION_GIT_TOKEN";
 
     pub fn try_new_with_criteria(
         root_path: PathBuf,
@@ -699,31 +2329,141 @@ impl<'a> ReleaseWorkspace<'a> {
         })
     }
 
-    /// Reset all cached state which will cause a reload the next time any method is called.
+    /// Overrides the git identity that is used for commits and tags.
+    pub fn with_git_options(self, git_options: GitOptions) -> ReleaseWorkspace<'a> {
+        Self {
+            git_options,
+            ..self
+        }
+    }
+
+    /// Overrides the index that is used to look up the published versions of crates.
+    pub fn with_crates_index(self, crates_index: Box<dyn CratesIndex>) -> ReleaseWorkspace<'a> {
+        Self {
+            crates_index: Some(crates_index),
+            ..self
+        }
+    }
+
+    /// Overrides how external commands are run by `publish`.
+    pub fn with_command_runner(
+        self,
+        command_runner: Box<dyn CommandRunner>,
+    ) -> ReleaseWorkspace<'a> {
+        Self {
+            command_runner,
+            ..self
+        }
+    }
+
+    /// Overrides how HTTP requests to the crates.io API and the sparse index of the registry are performed.
+    pub fn with_http_client(self, http_client: Box<dyn HttpClient>) -> ReleaseWorkspace<'a> {
+        Self {
+            http_client,
+            ..self
+        }
+    }
+
+    /// Overrides how the progress of the analysis, the version bumps and publishing is reported.
+    pub fn with_progress_reporter(
+        self,
+        progress_reporter: Box<dyn ProgressReporter>,
+    ) -> ReleaseWorkspace<'a> {
+        Self {
+            progress_reporter,
+            ..self
+        }
+    }
+
+    /// The reporter that is set by `with_progress_reporter`, for steps that are driven from outside the workspace.
+    pub fn progress_reporter(&self) -> &dyn ProgressReporter {
+        self.progress_reporter.as_ref()
+    }
+
+    /// Records all branches, commits and tags that are created in the release journal so they can be rolled back.
+    pub fn with_journal(self) -> ReleaseWorkspace<'a> {
+        Self {
+            journal_enabled: true,
+            ..self
+        }
+    }
+
+    /// Clears everything that was computed or read from the workspace so it's reloaded on the next call,
+    /// e.g. after a phase modified the manifests or changelogs.
+    ///
+    /// The members are rebuilt, which also clears what each crate cached.
+    /// The configuration and the records of this run, i.e. the counters and the created tags, are kept.
     pub fn reset_state(&mut self) {
-        self.cargo_workspace = Default::default();
+        self.changelog = Self::workspace_changelog(&self.root_path);
         self.cargo_workspace = Default::default();
         self.members_unsorted = Default::default();
         self.members_sorted = Default::default();
+        self.members_matched = Default::default();
         self.members_states = Default::default();
+        self.excluded_crates = Default::default();
+        self.release_metadata = Default::default();
+        self.changed_files_cache = Default::default();
+        self.tag_index = Default::default();
+        self.published_versions_cache = Default::default();
+        self.reverse_dependency_index = Default::default();
+        self.semver_checks = Default::default();
     }
 
-    pub fn try_new(root_path: PathBuf) -> Fallible<ReleaseWorkspace<'a>> {
-        let changelog = {
-            let changelog_path = root_path.join("CHANGELOG.md");
-            if changelog_path.exists() {
-                Some(ChangelogT::<WorkspaceChangelog>::at_path(&changelog_path))
-            } else {
-                None
+    fn workspace_changelog(root_path: &Path) -> Option<ChangelogT<'a, WorkspaceChangelog>> {
+        let changelog_path = root_path.join("CHANGELOG.md");
+        if changelog_path.exists() {
+            Some(ChangelogT::<WorkspaceChangelog>::at_path(&changelog_path))
+        } else {
+            None
+        }
+    }
+
+    /// Finds the root of the workspace which contains the given path by walking upwards to the first manifest with a `[workspace]` table.
+    /// Manifests of the members on the way are skipped.
+    pub fn discover_root(start_path: &Path) -> Fallible<PathBuf> {
+        let start_path = start_path
+            .canonicalize()
+            .context(format!("resolving {}", start_path.display()))?;
+
+        for dir in start_path.ancestors() {
+            let manifest_path = dir.join("Cargo.toml");
+            if !manifest_path.is_file() {
+                continue;
             }
-        };
+
+            let manifest: toml_edit::Document = crate::common::load_from_file(&manifest_path)?
+                .parse()
+                .context(format!("parsing {}", manifest_path.display()))?;
+            if manifest.as_table().contains_key("workspace") {
+                info!(
+                    "resolved the workspace root {} from {}",
+                    dir.display(),
+                    start_path.display()
+                );
+                return Ok(dir.to_path_buf());
+            }
+        }
+
+        bail!(
+            "couldn't find a workspace manifest in {} or any of its parent directories",
+            start_path.display()
+        )
+    }
+
+    /// Like `try_new` but accepts any path within the workspace, see `discover_root`.
+    pub fn try_discover(start_path: &Path) -> Fallible<ReleaseWorkspace<'a>> {
+        Self::try_new(Self::discover_root(start_path)?)
+    }
+
+    pub fn try_new(root_path: PathBuf) -> Fallible<ReleaseWorkspace<'a>> {
+        let changelog = Self::workspace_changelog(&root_path);
 
         let new = Self {
             // initialised: false,
-            git_repo: git2::Repository::open(&root_path)?,
+            // discovering supports linked worktrees and workspaces below the top level of the repository
+            git_repo: git2::Repository::discover(&root_path)?,
 
-            git_config_name: Self::GIT_CONFIG_NAME.to_string(),
-            git_config_email: Self::GIT_CONFIG_EMAIL.to_string(),
+            git_options: Default::default(),
 
             root_path,
             criteria: Default::default(),
@@ -735,6 +2475,25 @@ impl<'a> ReleaseWorkspace<'a> {
             members_sorted: Default::default(),
             members_matched: Default::default(),
             members_states: Default::default(),
+            excluded_crates: Default::default(),
+            target_cfgs: Default::default(),
+            release_metadata: Default::default(),
+            changed_files_cache: Default::default(),
+            changed_files_computations: Default::default(),
+            tag_index: Default::default(),
+            tag_index_builds: Default::default(),
+            crates_index: Default::default(),
+            crates_index_url: Default::default(),
+            published_versions_cache: Default::default(),
+            crates_index_queries: Default::default(),
+            command_runner: Box::new(SystemCommandRunner),
+            http_client: Box::new(CurlHttpClient),
+            progress_reporter: Box::new(NoopProgressReporter),
+            reverse_dependency_index: Default::default(),
+            semver_checks: Default::default(),
+            reverse_dependency_index_builds: Default::default(),
+            created_tags: Default::default(),
+            journal_enabled: false,
         };
 
         // todo(optimization): eagerly ensure that the workspace is valid, but the following fails lifetime checks
@@ -745,492 +2504,3454 @@ impl<'a> ReleaseWorkspace<'a> {
 
     fn members_states(&'a self) -> Fallible<&MemberStates> {
         self.members_states.get_or_try_init(|| {
-            let mut members_states = MemberStates::new();
+            // the spans are on the error level so their fields are attached to the warnings at the default log level
+            let _phase_span = error_span!("phase", phase = ?ReleasePhase::Selection).entered();
 
-            let criteria = &self.criteria;
-            let initial_state = CrateState {
-                allowed_dev_dependency_blockers: criteria.allowed_dev_dependency_blockers,
-                allowed_selection_blockers: criteria.allowed_selection_blockers,
+            if let Some(remote_name) = &self.criteria.fetch_tags_from_remote {
+                self.git_fetch_tags(remote_name)?;
+            }
 
-                ..Default::default()
-            };
+            let initial_state = self.initial_crate_state();
+            let (match_filter, exclusion_filter) = self.selection_filters()?;
 
-            let keyword_validation_re = Regex::new("^[a-zA-Z][a-zA-Z_\\-0-9]+$").unwrap();
+            self.warn_about_never_release_drift()?;
+            self.ensure_selection_names_are_members()?;
 
-            for member in self.members()? {
+            let members = self.members()?;
+            self.progress_reporter
+                .on_phase_started(ReleasePhase::Selection, members.len());
 
-                // helper macros to access the desired state
-                macro_rules! get_state {
-                    ( $i:expr ) => {
-                        members_states.entry($i).or_insert(initial_state.clone())
-                    };
-                }
-                macro_rules! insert_state {
-                    ( $flag:expr ) => {
-                        insert_state!($flag, member.name())
-                    };
-                    ( $flag:expr, $i:expr ) => {
-                        get_state!($i).insert($flag)
-                    };
-                }
+            // the states depend on the ones of the members before, so the analyses are folded into them in order
+            let analyses = self.analyze_members(members)?;
 
-                // manifest metadata validation
-                {
-                    let metadata = member.package().manifest().metadata();
-                    if !(metadata.license.is_some() || metadata.license_file.is_some()) {
-                        insert_state!(CrateStateFlags::MissingLicense);
+            let mut members_states = MemberStates::new();
+            for (index, (&member, analysis)) in members.iter().zip(&analyses).enumerate() {
+                let _member_span = error_span!("member", "crate" = %member.name()).entered();
+
+                let state = members_states
+                    .entry(member.name())
+                    .or_insert_with(|| initial_state.clone());
+                Self::validate_manifest_metadata(member, state);
+                self.match_member(member, state, &match_filter, exclusion_filter.as_ref())?;
+                self.check_version_requirements(member, state);
+                self.apply_member_analysis(member, analysis, state)?;
+
+                // set DependencyChanged in dependants if this crate changed
+                if state.changed() {
+                    for dependant in member.dependants_in_workspace(DependentsScope::Transitive)? {
+                        members_states
+                            .entry(dependant.name())
+                            .or_insert_with(|| initial_state.clone())
+                            .insert(CrateStateFlags::DependencyChanged);
                     }
+                }
 
-                    if metadata.description.is_none() {
-                        insert_state!(CrateStateFlags::MissingDescription);
-                    }
+                self.progress_reporter
+                    .on_member_analyzed(&member.name(), index, members.len());
+            }
 
-                    // see https://doc.rust-lang.org/cargo/reference/manifest.html?highlight=keywords#the-keywords-field
-                    // Note: crates.io has a maximum of 5 keywords. Each keyword must be ASCII text, start with a letter, and only contain letters, numbers, _ or -, and have at most 20 characters.
-                    if metadata.keywords.iter().any(|keyword| keyword.len() > 20) {
-                        insert_state!(CrateStateFlags::ManifestKeywordExceeds20Chars);
-                    }
-                    if metadata.keywords.iter().any(|keyword| !keyword_validation_re.is_match(keyword)) {
-                        insert_state!(CrateStateFlags::ManifestKeywordContainsInvalidChar);
-                    }
-                    if metadata.keywords.len() > 5 {
-                        insert_state!(CrateStateFlags::ManifestKeywordsMoreThan5);
-                    }
-                }
+            if self.criteria.include_dependents {
+                self.select_dependents(&initial_state, &mut members_states)?;
+            }
+            self.select_dependencies(&initial_state, &mut members_states)?;
 
-                // regex matching state
-                if criteria.match_filter.is_match(&member.name())? {
-                    insert_state!(CrateStateFlags::Matched);
+            let rust_versions = members
+                .iter()
+                .map(|member| (member.name(), member.rust_version()))
+                .collect::<HashMap<_, _>>();
+            for member in members {
+                if let Some(state) = members_states
+                    .get_mut(&member.name())
+                    .filter(|state| state.selected())
+                {
+                    self.check_publishability(member, state, &rust_versions)?;
                 }
+            }
 
-                // version requirements
-                {
-                    let version = member.version();
+            // the escalations depend on the next versions of all selected dependencies, so they're determined last
+            let release_selection = members
+                .iter()
+                .filter(|member| {
+                    matches!(members_states.get(&member.name()), Some(state) if state.release_selection())
+                })
+                .copied()
+                .collect::<Vec<_>>();
+            for (name, chain) in self.version_bump_cascades(&release_selection, &members_states)? {
+                let state = members_states.get_mut(&name).expect("all members have a state");
+                state.insert(CrateStateFlags::CascadeBumped);
+                state.cascade_chain = chain;
+            }
 
-                    criteria
-                        .enforced_version_reqs
-                        .iter()
-                        .filter(|enforced_version_req| !enforced_version_req.matches(&version))
-                        .take(1)
-                        .for_each(|enforced_version_req| {
-                            warn!(
-                                "'{}' version '{}' doesn't meet the enforced requirement '{}'",
-                                member.name(),
-                                version,
-                                enforced_version_req
-                            );
-                            insert_state!(CrateStateFlags::EnforcedVersionReqViolated);
-                        });
+            self.warn_about_shallow_repository(&members_states);
 
-                    criteria
-                        .disallowed_version_reqs
-                        .iter()
-                        .filter(|disallowed_version_req| disallowed_version_req.matches(&version))
-                        .take(1)
-                        .for_each(|disallowed_version_req| {
-                            warn!(
-                                "'{}' version '{}' matches the disallowed requirement '{}'",
-                                member.name(),
-                                version,
-                                disallowed_version_req
-                            );
-                            insert_state!(CrateStateFlags::DisallowedVersionReqViolated);
-                        });
+            self.progress_reporter.on_phase_finished(ReleasePhase::Selection);
 
-                    if !std::path::Path::new(&member.root().join(Self::README_FILENAME)).exists() {
-                        insert_state!(CrateStateFlags::MissingReadme);
-                    }
+            Ok(members_states)
+        })
+    }
 
-                    // change related state
-                    match member.changelog() {
-                        None => {
-                            warn!("'{}' is missing the changelog", member.name());
-                            insert_state!(CrateStateFlags::MissingChangelog);
-                        }
+    /// The state every member starts out with, which carries the allowed and blocking flags of the criteria.
+    fn initial_crate_state(&self) -> CrateState {
+        let criteria = &self.criteria;
 
-                        Some(changelog) => {
-                            if let Some(front_matter) = changelog.front_matter().context(
-                                format!("when parsing front matter of crate '{}'", member.name()),
-                            )? {
-                                if front_matter.unreleasable() {
-                                    warn!("'{}' has unreleasable defined via the changelog frontmatter", member.name());
-                                    insert_state!(
-                                        CrateStateFlags::UnreleasableViaChangelogFrontmatter
-                                    );
-                                }
-                            }
-
-                            if let Some(changelog::ReleaseChange::CrateReleaseChange(previous_release_version)) =
-                                changelog
-                                    .changes()
-                                    .ok()
-                                    .iter()
-                                    .flatten()
-                                    .filter_map(|r| {
-                                        if let ChangeT::Release(r) = r {
-                                            Some(r)
-                                        } else {
-                                            None
-                                        }
-                                    })
-                                    .take(1)
-                                    .next()
-                            {
+        CrateState {
+            allowed_dev_dependency_blockers: criteria.allowed_dev_dependency_blockers,
+            allowed_selection_blockers: criteria.allowed_selection_blockers,
+            blocking_states: if criteria.block_on_version_changelog_mismatch {
+                criteria.blocking_states | CrateStateFlags::VersionChangelogMismatch
+            } else {
+                criteria.blocking_states
+            },
 
-                                // todo: derive the tagname from a function?
-                                // lookup the git tag for the previous release
-                                let maybe_git_tag =
-                                        git_lookup_tag(&self.git_repo, format!("{}-{}", &member.name(), previous_release_version).as_str());
+            ..Default::default()
+        }
+    }
 
-                                log::debug!("[{}] previous release: {}, previous git tag {:?}", member.name(), previous_release_version, maybe_git_tag);
+    /// The match and exclusion filters of the criteria as applied in their selection filter mode.
+    fn selection_filters(&self) -> Fallible<(fancy_regex::Regex, Option<fancy_regex::Regex>)> {
+        let criteria = &self.criteria;
+
+        let match_filter = criteria
+            .selection_filter_mode
+            .apply(&criteria.match_filter)?;
+        let exclusion_filter = criteria
+            .exclusion_filter
+            .as_ref()
+            .map(|exclusion_filter| criteria.selection_filter_mode.apply(exclusion_filter))
+            .transpose()?;
+        trace!(
+            "applying the selection filters in {:?} mode: match filter '{}', exclusion filter {:?}",
+            criteria.selection_filter_mode,
+            match_filter.as_str(),
+            exclusion_filter.as_ref().map(fancy_regex::Regex::as_str),
+        );
 
-                                if let Some(git_tag) = maybe_git_tag {
+        Ok((match_filter, exclusion_filter))
+    }
 
-                                    insert_state!(CrateStateFlags::HasPreviousRelease);
+    fn warn_about_never_release_drift(&'a self) -> Fallible<()> {
+        let drift = self.never_release_drift()?;
+        for name in &drift.unknown {
+            warn!(
+                "'{}' is listed in never_release but is not a member of the workspace",
+                name
+            );
+        }
+        for name in &drift.unlisted_unpublishable {
+            warn!(
+                "'{}' sets `publish = false` but is not listed in never_release, consider adding it",
+                name
+            );
+        }
 
-                                    // todo: make comparison ref configurable
-                                    if !changed_files(member.package.root(), &git_tag, "HEAD")?
-                                        .is_empty()
-                                    {
-                                        insert_state!(CrateStateFlags::ChangedSincePreviousRelease)
-                                    }
-                                } else {
-                                    insert_state!(CrateStateFlags::MissingReleaseTag);
-                                }
-                            } else {
-                                    insert_state!(CrateStateFlags::NoPreviousRelease);
-                            }
-                        }
-                    }
+        Ok(())
+    }
 
-                    // dependency state
-                    // only dependencies of explicitly matched packages are considered here.
-                    //
-                    // note(steveej):
-                    // while trying to signal the inclusion of reverse dependencies it eventually occurred to me
-                    // that only considering the crates in the dependency trees that start with a selected package is preferred.
-                    // even if a reverse dependency of a matched package is changed during the release (by having its dependency version updated),
-                    // its not relevant to the release if it hasn't been requested for release excplicitly or as a dependency of one that has been, in which case it is already considered.
-                    // if get_state!(member.name()).is_matched() && !get_state!(member.name()).blocked()
-                    if get_state!(member.name()).is_matched()
-                        && get_state!(member.name()).changed()
-                        && !get_state!(member.name()).blocked()
-                    {
-                        for (_, deps) in member.dependencies_in_workspace()? {
-                            for dep in deps {
-                                insert_state!(
-                                    match dep.kind() {
-                                        CargoDepKind::Development => CrateStateFlags::IsWorkspaceDevDependency,
-                                        _ => CrateStateFlags::IsWorkspaceDependency,
-                                    },
-                                    dep.package_name().to_string()
-                                );
-                            }
-                        }
+    fn ensure_selection_names_are_members(&'a self) -> Fallible<()> {
+        let member_names = self
+            .members()?
+            .iter()
+            .map(|member| member.name())
+            .collect::<HashSet<_>>();
+        let unknown_selection_names = self
+            .criteria
+            .selection_names
+            .iter()
+            .filter(|name| !member_names.contains(*name))
+            .map(|name| format!("\n- {}", name))
+            .collect::<String>();
+        if !unknown_selection_names.is_empty() {
+            bail!(
+                "the following crates were selected by name but are not members of the workspace: {}",
+                unknown_selection_names
+            );
+        }
 
-                        for dep in member.package().dependencies() {
-                            if dep.version_req().to_string().contains('*') {
-                                insert_state!(match dep.kind() {
-                                    CargoDepKind::Normal | CargoDepKind::Build => CrateStateFlags::HasWildcardDependency,
-                                    CargoDepKind::Development => CrateStateFlags::HasWildcardDevDependency,
-                                });
-                            }
-                        }
-                    }
+        Ok(())
+    }
 
-                    // set DependencyChanged in dependants if this crate changed
-                    if get_state!(member.name()).changed() {
-                        for dependant in member.dependants_in_workspace()? {
-                            insert_state!(
-                                CrateStateFlags::DependencyChanged,
-                                dependant.name()
-                            );
-                        }
-                    }
-                }
+    /// Checks the metadata crates.io requires, and the limits it imposes on the keywords and categories.
+    fn validate_manifest_metadata(member: &Crate<'a>, state: &mut CrateState) {
+        static KEYWORD_VALIDATION_RE: once_cell::sync::OnceCell<Regex> =
+            once_cell::sync::OnceCell::new();
+
+        let metadata = member.package().manifest().metadata();
+        let missing_publish_metadata = [
+            ("description", metadata.description.is_some()),
+            (
+                "license",
+                metadata.license.is_some() || metadata.license_file.is_some(),
+            ),
+            ("repository", metadata.repository.is_some()),
+        ]
+        .iter()
+        .filter(|(_, present)| !present)
+        .map(|(field, _)| field.to_string())
+        .collect::<Vec<_>>();
+        if !missing_publish_metadata.is_empty() {
+            warn!(
+                "'{}' is missing publish metadata in its manifest: {}",
+                member.name(),
+                missing_publish_metadata.join(", ")
+            );
+            state.missing_publish_metadata = missing_publish_metadata;
+            state.insert(CrateStateFlags::MissingPublishMetadata);
+        }
 
+        // see https://doc.rust-lang.org/cargo/reference/manifest.html?highlight=keywords#the-keywords-field
+        // Note: crates.io has a maximum of 5 keywords. Each keyword must be ASCII text, start with a letter, and only contain letters, numbers, _ or -, and have at most 20 characters.
+        // The same maximum applies to the categories.
+        let keyword_validation_re = KEYWORD_VALIDATION_RE.get_or_init(|| {
+            Regex::new("^[a-zA-Z][a-zA-Z_\\-0-9]+$").expect("regex should compile")
+        });
+        let mut invalid_keywords_or_categories = Vec::new();
+        if metadata.keywords.len() > 5 {
+            invalid_keywords_or_categories.push(format!(
+                "more than 5 keywords: {}",
+                metadata.keywords.join(", ")
+            ));
+        }
+        if metadata.categories.len() > 5 {
+            invalid_keywords_or_categories.push(format!(
+                "more than 5 categories: {}",
+                metadata.categories.join(", ")
+            ));
+        }
+        for keyword in &metadata.keywords {
+            if keyword.len() > 20 {
+                invalid_keywords_or_categories
+                    .push(format!("keyword '{}' exceeds 20 characters", keyword));
             }
-
-            Ok(members_states)
-        })
+            if !keyword_validation_re.is_match(keyword) {
+                invalid_keywords_or_categories
+                    .push(format!("keyword '{}' contains invalid characters", keyword));
+            }
+        }
+        if !invalid_keywords_or_categories.is_empty() {
+            warn!(
+                "'{}' has invalid keywords or categories in its manifest: {}",
+                member.name(),
+                invalid_keywords_or_categories
+                    .iter()
+                    .map(|entry| format!("\n- {}", entry))
+                    .collect::<String>()
+            );
+            state.insert(CrateStateFlags::InvalidKeywordsOrCategories);
+        }
     }
 
-    fn cargo_workspace(&'a self) -> Fallible<&'a CargoWorkspace> {
-        self.cargo_workspace.get_or_try_init(|| {
-            CargoWorkspace::new(&self.root_path.join("Cargo.toml"), &self.cargo_config)
-        })
-    }
+    /// Matches the member against the selection criteria, unless it's never released, unpublishable, skipped or excluded.
+    /// Also applies the blockers its manifest metadata allows and the registries its manifest allows.
+    fn match_member(
+        &'a self,
+        member: &'a Crate<'a>,
+        state: &mut CrateState,
+        match_filter: &fancy_regex::Regex,
+        exclusion_filter: Option<&fancy_regex::Regex>,
+    ) -> Fallible<()> {
+        let criteria = &self.criteria;
 
-    /// Returns the crates that are going to be processed for release.
-    pub fn release_selection(&'a self) -> Fallible<Vec<&'a Crate>> {
-        let members = self.members()?;
+        let excluded = criteria.excluded_names.contains(&member.name())
+            || match exclusion_filter {
+                Some(exclusion_filter) => exclusion_filter.is_match(&member.name())?,
+                None => false,
+            };
 
-        let all_crates_states_iter = members.iter().map(|member| (member.name(), member.state()));
-        let all_crates_states = all_crates_states_iter.clone().collect::<Vec<_>>();
-        trace!(
-            "{}",
-            CrateState::format_crates_states(&all_crates_states, "ALL CRATES", true, true, true,)
-        );
-        let blocked_crates_states = all_crates_states_iter
-            .clone()
-            .filter(|(_, state)| state.selected() && !state.allowed())
-            .collect::<Vec<_>>();
+        let metadata_config = member.metadata_config()?;
+        state.allow_blockers(metadata_config.allowed_blockers());
 
-        // indicate an error if any unreleasable crates block the release
-        if !blocked_crates_states.is_empty() {
-            bail!(
-                "the following crates are blocked but required for the release: \n{}",
-                CrateState::format_crates_states(
-                    &blocked_crates_states,
-                    "DISALLOWED BLOCKING CRATES",
-                    true,
-                    false,
-                    false,
-                )
-            )
+        // regex matching state
+        if self
+            .release_metadata()?
+            .never_release
+            .contains(&member.name())
+        {
+            state.insert(CrateStateFlags::NeverRelease);
+        } else if !member.publishable() {
+            trace!(
+                "[{}] not matching because of `publish = false`",
+                member.name()
+            );
+            state.insert(CrateStateFlags::ManifestPublishFalse);
+        } else if metadata_config.skip {
+            trace!("[{}] skipped via the manifest metadata", member.name());
+        } else if excluded {
+            trace!("[{}] excluded from the selection", member.name());
+            if !criteria.allow_excluded_as_dependency {
+                state.insert(CrateStateFlags::Excluded);
+            }
+        } else if match_filter.is_match(&member.name())?
+            || criteria.selection_names.contains(&member.name())
+            || self.matches_selection_path_globs(member)
+        {
+            state.insert(CrateStateFlags::Matched);
         }
 
-        let release_selection = members
-            .iter()
-            .filter(|member| {
-                let release = member.state().release_selection();
-
-                trace!(
-                    "{} release indicator: {}, blocked: {:?}, state: {:#?}",
+        // registry restrictions
+        if let Some(registries) = member.package().publish() {
+            let registry = self.registry().to_string();
+            if !registries.is_empty() && !registries.contains(&registry) {
+                warn!(
+                    "'{}' may only be published to {:?} according to its manifest, but the release targets '{}'",
                     member.name(),
-                    release,
-                    member.state().blocked(),
-                    member.state(),
+                    registries,
+                    registry
                 );
+                state.insert(CrateStateFlags::RegistryNotAllowed);
+            }
+        }
 
-                release
-            })
-            .cloned()
-            .collect::<Vec<_>>();
-
-        Ok(release_selection)
+        Ok(())
     }
 
-    fn members_unsorted(&'a self) -> Fallible<&'a Vec<Crate<'a>>> {
-        self.members_unsorted.get_or_try_init(|| {
-            let mut members = vec![];
+    /// Checks the member's version against the enforced and disallowed requirements of the criteria.
+    fn check_version_requirements(&self, member: &Crate<'a>, state: &mut CrateState) {
+        let version = member.version();
 
-            for package in self.cargo_workspace()?.members() {
-                members.push(Crate::with_cargo_package(package.to_owned(), self)?);
-            }
+        if let Some(enforced_version_req) = self
+            .criteria
+            .enforced_version_reqs
+            .iter()
+            .find(|enforced_version_req| !version_req_matches(enforced_version_req, &version))
+        {
+            warn!(
+                "'{}' version '{}' doesn't meet the enforced requirement '{}'",
+                member.name(),
+                version,
+                enforced_version_req
+            );
+            state.insert(CrateStateFlags::EnforcedVersionReqViolated);
+        }
 
-            Ok(members)
-        })
+        if let Some(disallowed_version_req) = self
+            .criteria
+            .disallowed_version_reqs
+            .iter()
+            .find(|disallowed_version_req| version_req_matches(disallowed_version_req, &version))
+        {
+            warn!(
+                "'{}' version '{}' matches the disallowed requirement '{}'",
+                member.name(),
+                version,
+                disallowed_version_req
+            );
+            state.insert(CrateStateFlags::DisallowedVersionReqViolated);
+        }
     }
 
-    /// Return all member crates matched by `SelectionCriteria::match_filter`
-    pub fn members_matched(&'a self) -> Fallible<&'a Vec<&'a Crate<'a>>> {
-        self.members_matched.get_or_try_init(|| {
-            let states = self.members_states()?;
+    /// Applies the facts of the member's analysis to its state, i.e. the presence of its readme, license file and changelog,
+    /// and the changes since its previous release.
+    fn apply_member_analysis(
+        &self,
+        member: &Crate<'a>,
+        analysis: &MemberAnalysis,
+        state: &mut CrateState,
+    ) -> Fallible<()> {
+        let criteria = &self.criteria;
 
-            self.members().map(|members| {
-                members
-                    .into_iter()
-                    .filter(|crt| {
-                        states
-                            .get(&crt.name())
-                            .cloned()
-                            .unwrap_or_else(|| {
-                                warn!(
-                                    "cannot get CrateState for {}, using default state",
-                                    crt.name()
-                                );
-                                CrateState::default()
-                            })
-                            .contains(CrateStateFlags::Matched)
-                    })
-                    .map(|crt| *crt)
-                    .collect::<Vec<_>>()
-            })
-        })
-    }
+        if !analysis.readme_present {
+            state.insert(CrateStateFlags::MissingReadme);
+        }
 
-    /// Returns all non-excluded workspace members.
-    /// Members are sorted according to their dependency tree from most independent to most dependent.
-    pub fn members(&'a self) -> Fallible<&'a Vec<&'a Crate<'a>>> {
-        self.members_sorted.get_or_try_init(|| -> Fallible<_> {
-            let mut members = self
-                .members_unsorted()?
-                .iter()
-                .enumerate()
+        match member.license_file() {
+            None => {
+                warn!("'{}' is missing a license file", member.name());
+                state.insert(CrateStateFlags::MissingLicenseFile);
+            }
+            Some(license_file) => {
+                if !license_file
+                    .canonicalize()?
+                    .starts_with(member.root().canonicalize()?)
+                {
+                    warn!(
+                        "'{}' ships the license file {:?} from outside of the crate",
+                        member.name(),
+                        license_file
+                    );
+                }
+            }
+        }
+
+        // change related state
+        let changelog = match &analysis.changelog {
+            None => {
+                warn!("'{}' is missing the changelog", member.name());
+                state.insert(CrateStateFlags::MissingChangelog);
+                return Ok(());
+            }
+            Some(changelog) => changelog,
+        };
+
+        if let Some(err) = &changelog.parse_error {
+            warn!("'{}' has an unparseable changelog: {}", member.name(), err);
+            state.insert(CrateStateFlags::ChangelogParseError);
+            state.changelog_parse_error = Some(err.clone());
+        }
+
+        if changelog.unreleasable {
+            if changelog.unreleasable_via_workspace_default {
+                warn!(
+                    "'{}' is unreleasable by default via the workspace changelog frontmatter",
+                    member.name()
+                );
+            } else {
+                warn!(
+                    "'{}' has unreleasable defined via its changelog frontmatter",
+                    member.name()
+                );
+            }
+            state.insert(CrateStateFlags::UnreleasableViaChangelogFrontmatter);
+        }
+
+        let missing_release_flag = if let Some((previous_release_version, parsed_version)) =
+            &changelog.previous_release
+        {
+            match parsed_version {
+                Ok(version) => {
+                    if member.version() < *version {
+                        warn!(
+                            "[{}] manifest version {} is older than the latest release {} in the changelog",
+                            member.name(),
+                            member.version(),
+                            version
+                        );
+                        state.insert(CrateStateFlags::VersionChangelogMismatch);
+                    }
+
+                    if criteria.promote && version.is_prerelease() {
+                        debug!(
+                            "[{}] promoting the pre-release {} to {}",
+                            member.name(),
+                            version,
+                            member.stable_version()
+                        );
+                        state.insert(CrateStateFlags::PendingStablePromotion);
+                    }
+                }
+                Err(err) => {
+                    warn!(
+                        "[{}] previous release '{}' is not a valid version: {}",
+                        member.name(),
+                        previous_release_version,
+                        err
+                    );
+                }
+            }
+
+            debug!(
+                "[{}] previous release: {}, previous git tag {:?}",
+                member.name(),
+                previous_release_version,
+                changelog.previous_release_tag
+            );
+
+            CrateStateFlags::MissingReleaseTag
+        } else {
+            CrateStateFlags::NoPreviousRelease
+        };
+
+        // the changelog may lag behind crates.io, in which case the commit that set the published version takes the place of the tag
+        if let Some(commit) = &changelog.untagged_release_commit {
+            state.insert(CrateStateFlags::PreviousReleaseUntagged);
+            state.untagged_release_commit = Some(commit.clone());
+        }
+
+        let git_tag = match changelog.previous_release_rev() {
+            Some(git_tag) => git_tag,
+            None => {
+                state.insert(missing_release_flag);
+                return Ok(());
+            }
+        };
+
+        state.insert(CrateStateFlags::HasPreviousRelease);
+
+        // todo: make comparison ref configurable
+        let changed_files = analysis
+            .changed_files
+            .iter()
+            .filter_map(|path| path_relative_to(path, member.package.root()))
+            .filter(|path| {
+                !criteria
+                    .change_detection_ignore_globs
+                    .iter()
+                    .any(|glob| glob.matches_path(path))
+            })
+            .collect::<Vec<_>>();
+        let change_classes = changed_files
+            .iter()
+            .map(|path| ChangeClass::classify(path))
+            .collect::<BitFlags<_>>();
+
+        state.change_classes = change_classes;
+        state.previous_release_rev = Some(git_tag.to_string());
+        state.changed_files = changed_files;
+
+        if change_classes.intersects(criteria.change_classes_triggering_release) {
+            state.insert(CrateStateFlags::ChangedSincePreviousRelease)
+        }
+
+        if analysis.workspace_files_changed {
+            state.insert(CrateStateFlags::ChangedSincePreviousRelease);
+            state.insert(CrateStateFlags::WorkspaceFilesChanged);
+        }
+
+        Ok(())
+    }
+
+    /// Selects the dependents of the matched crates transitively.
+    fn select_dependents(
+        &'a self,
+        initial_state: &CrateState,
+        members_states: &mut MemberStates,
+    ) -> Fallible<()> {
+        for member in self.members()? {
+            let member_changed = match members_states.get(&member.name()) {
+                Some(state) if state.is_matched() => state.changed(),
+                _ => continue,
+            };
+
+            // the transitive dependants already include the dependants of the dependants
+            for dependent in member.dependants_in_workspace(DependentsScope::Transitive)? {
+                let state = members_states
+                    .entry(dependent.name())
+                    .or_insert_with(|| initial_state.clone());
+                if !state.is_matched() {
+                    state.dependent_of.insert(member.name());
+                    state.insert(CrateStateFlags::IsDependentOfMatched);
+
+                    // the dependent needs to be released with the updated dependency
+                    if member_changed {
+                        state.insert(CrateStateFlags::DependencyChanged);
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Marks the workspace dependencies of the changed matched crates and their included dependents.
+    ///
+    /// note(steveej):
+    /// while trying to signal the inclusion of reverse dependencies it eventually occurred to me
+    /// that only considering the crates in the dependency trees that start with a selected package is preferred.
+    /// even if a reverse dependency of a matched package is changed during the release (by having its dependency version updated),
+    /// its not relevant to the release if it hasn't been requested for release excplicitly or as a dependency of one that has been, in which case it is already considered.
+    fn select_dependencies(
+        &'a self,
+        initial_state: &CrateState,
+        members_states: &mut MemberStates,
+    ) -> Fallible<()> {
+        for member in self.members()? {
+            let walk_dependencies = match members_states.get(&member.name()) {
+                Some(state) if state.blocked() => false,
+                Some(state) if state.is_matched() => state.changed(),
+                Some(state) if state.contains(CrateStateFlags::IsDependentOfMatched) => {
+                    state.changed() || state.dependency_changed()
+                }
+                _ => false,
+            };
+            if !walk_dependencies {
+                continue;
+            }
+
+            let _member_span = error_span!("member", "crate" = %member.name()).entered();
+
+            let dependencies = member.dependencies_in_workspace()?;
+            for dep_crate in member.workspace_dependency_crates()? {
+                let dep_name = dep_crate.name();
+                if let Some(max_depth) = self.criteria.max_dependency_depth {
+                    if matches!(member.dependency_depth(&dep_name)?, Some(depth) if depth > max_depth)
+                    {
+                        trace!(
+                            "[{}] not selecting '{}' beyond the maximum dependency depth of {}",
+                            member.name(),
+                            dep_name,
+                            max_depth
+                        );
+                        continue;
+                    }
+                }
+
+                // the dependencies are transitive, so the matched crate is the root of the chain
+                let dep_state = members_states
+                    .entry(dep_name.clone())
+                    .or_insert_with(|| initial_state.clone());
+                dep_state.required_by.insert(member.name());
+                for dep in dependencies.get(&dep_name).into_iter().flatten() {
+                    dep_state.insert(match dep.kind() {
+                        CargoDepKind::Development => CrateStateFlags::IsWorkspaceDevDependency,
+                        _ => CrateStateFlags::IsWorkspaceDependency,
+                    });
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Checks whether the selected member can be published as it is, see the individual checks.
+    fn check_publishability(
+        &self,
+        member: &Crate<'a>,
+        state: &mut CrateState,
+        rust_versions: &HashMap<String, Option<&Version>>,
+    ) -> Fallible<()> {
+        self.check_dependency_sources(member, state);
+        self.check_msrv(member, state, rust_versions);
+
+        if self.criteria.check_crates_io
+            && !state.contains(CrateStateFlags::ManifestPublishFalse)
+            && (state.changed() || state.dependency_changed())
+        {
+            self.check_unpublished_version(member, state)?;
+        }
+
+        if self.criteria.verify_package_contents
+            && !state.contains(CrateStateFlags::ManifestPublishFalse)
+        {
+            self.check_package_size(member, state)?;
+        }
+
+        Ok(())
+    }
+
+    /// crates.io rejects wildcard requirements and git dependencies.
+    /// dev-dependencies are stripped on publish, so wildcards are tolerated there and git sources can be allowed.
+    fn check_dependency_sources(&self, member: &Crate<'a>, state: &mut CrateState) {
+        let (wildcard_dev_deps, wildcard_deps): (Vec<_>, Vec<_>) = member
+            .package()
+            .dependencies()
+            .iter()
+            // unversioned path dependencies are covered by `PathDepMissingVersion` below
+            .filter(|dep| dep.specified_req() && dep.version_req().to_string().contains('*'))
+            .partition(|dep| dep.kind() == CargoDepKind::Development);
+
+        if !wildcard_deps.is_empty() {
+            warn!(
+                "'{}' has wildcard requirements on the following dependencies: {}",
+                member.name(),
+                wildcard_deps
+                    .iter()
+                    .map(|dep| dep.package_name().to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            );
+            state.insert(CrateStateFlags::HasWildcardDependency);
+        }
+
+        if !wildcard_dev_deps.is_empty() {
+            debug!(
+                "'{}' has wildcard requirements on the following dev-dependencies: {}",
+                member.name(),
+                wildcard_dev_deps
+                    .iter()
+                    .map(|dep| dep.package_name().to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            );
+            state.insert(CrateStateFlags::HasWildcardDevDependency);
+        }
+
+        let git_deps = member
+            .package()
+            .dependencies()
+            .iter()
+            .filter(|dep| dep.source_id().is_git())
+            .filter(|dep| {
+                !(self.criteria.allow_git_dev_dependencies
+                    && dep.kind() == CargoDepKind::Development)
+            })
+            .map(|dep| format!("\n- {} ({})", dep.package_name(), dep.source_id().url()))
+            .collect::<String>();
+
+        if !git_deps.is_empty() {
+            warn!(
+                "'{}' has the following git dependencies: {}",
+                member.name(),
+                git_deps
+            );
+            state.insert(CrateStateFlags::HasGitDependency);
+        }
+
+        // cargo only strips the path of dependencies that also specify a version
+        if !state.contains(CrateStateFlags::ManifestPublishFalse) {
+            let path_deps_missing_version = member
+                .package()
+                .dependencies()
+                .iter()
+                .filter(|dep| dep.source_id().is_path() && dep.kind() != CargoDepKind::Development)
+                .filter(|dep| !dep.specified_req())
+                .map(|dep| dep.package_name().to_string())
                 .collect::<Vec<_>>();
 
-            let workspace_dependencies = self.members_unsorted()?.iter().try_fold(
-                LinkedHashMap::<String, LinkedHashSet<String>>::new(),
-                |mut acc, elem| -> Fallible<_> {
-                    acc.insert(
-                        elem.name(),
-                        elem.dependencies_in_workspace()?
-                            .into_iter()
-                            .filter_map(|(dep_name, deps)| {
-                                deps.into_iter()
-                                    .find(|dep| {
-                                        dep.specified_req() && dep.version_req().to_string() != "*"
-                                    })
-                                    .map(|_| dep_name.clone())
-                            })
-                            .collect(),
+            if !path_deps_missing_version.is_empty() {
+                warn!(
+                    "'{}' has the following path dependencies without a version requirement: {}",
+                    member.name(),
+                    path_deps_missing_version.join(", ")
+                );
+                state.insert(CrateStateFlags::PathDepMissingVersion);
+            }
+        }
+    }
+
+    /// A crate can't be built with its declared MSRV if it exceeds the policy or that of a dependency.
+    fn check_msrv(
+        &self,
+        member: &Crate<'a>,
+        state: &mut CrateState,
+        rust_versions: &HashMap<String, Option<&Version>>,
+    ) {
+        let rust_version = match member.rust_version() {
+            Some(rust_version) => rust_version,
+            None => return,
+        };
+
+        let mut msrv_violations = Vec::new();
+
+        if let Some(max_msrv) = &self.criteria.max_msrv {
+            if rust_version > max_msrv {
+                msrv_violations.push(format!(
+                    "rust-version {} exceeds the maximum of {}",
+                    rust_version, max_msrv
+                ));
+            }
+        }
+
+        for dep in member.package().dependencies() {
+            if dep.kind() == CargoDepKind::Development {
+                continue;
+            }
+
+            let dep_name = dep.package_name().to_string();
+            if let Some(Some(dep_rust_version)) = rust_versions.get(&dep_name) {
+                if *dep_rust_version > rust_version {
+                    msrv_violations.push(format!(
+                        "rust-version {} is lower than rust-version {} of its dependency '{}'",
+                        rust_version, dep_rust_version, dep_name
+                    ));
+                }
+            }
+        }
+
+        if !msrv_violations.is_empty() {
+            warn!(
+                "'{}' violates the MSRV policy: {}",
+                member.name(),
+                msrv_violations
+                    .iter()
+                    .map(|violation| format!("\n- {}", violation))
+                    .collect::<String>()
+            );
+            state.insert(CrateStateFlags::MsrvViolation);
+        }
+    }
+
+    /// Looks up whether the member's version is already published in the registry.
+    /// Lookup failures are only warned about unless the criteria are strict.
+    fn check_unpublished_version(
+        &self,
+        member: &Crate<'a>,
+        state: &mut CrateState,
+    ) -> Fallible<()> {
+        match self.published_versions(&member.name()) {
+            Ok(published_versions) => {
+                if published_versions.contains(&member.version()) {
+                    warn!(
+                        "'{}' version {} is already published in the registry",
+                        member.name(),
+                        member.version()
                     );
+                    state.insert(CrateStateFlags::VersionAlreadyPublished);
+                }
+            }
+            Err(err) if self.criteria.strict => {
+                return Err(err.context(format!(
+                    "looking up the published versions of '{}'",
+                    member.name()
+                )));
+            }
+            Err(err) => {
+                warn!(
+                    "[{}] couldn't look up the published versions, continuing without: {:?}",
+                    member.name(),
+                    err
+                );
+            }
+        }
 
-                    Ok(acc)
-                },
-            )?;
+        Ok(())
+    }
+
+    /// Checks the size of the files that would be packaged against the maximum of the criteria.
+    fn check_package_size(&self, member: &Crate<'a>, state: &mut CrateState) -> Fallible<()> {
+        let package_files = member.package_files()?;
+        let package_size = package_files.iter().map(|(_, size)| size).sum::<u64>();
+        debug!(
+            "[{}] the package contains {} files with {} bytes",
+            member.name(),
+            package_files.len(),
+            package_size
+        );
+
+        if package_size > self.criteria.max_package_size {
+            state.largest_package_files = package_files
+                .into_iter()
+                .take(5)
+                .map(|(path, size)| {
+                    (
+                        normalized_path(path.strip_prefix(member.root()).unwrap_or(&path)),
+                        size,
+                    )
+                })
+                .collect();
+            warn!(
+                "'{}' would package {} bytes which exceeds the maximum of {} bytes. the largest files are: {}",
+                member.name(),
+                package_size,
+                self.criteria.max_package_size,
+                state
+                    .largest_package_files
+                    .iter()
+                    .map(|(path, size)| format!("\n- {} ({} bytes)", path, size))
+                    .collect::<String>()
+            );
+            state.insert(CrateStateFlags::PackageTooLarge);
+        }
+
+        Ok(())
+    }
+
+    fn warn_about_shallow_repository(&self, members_states: &MemberStates) {
+        if self.git_repo.is_shallow()
+            && members_states
+                .values()
+                .any(|state| state.contains(CrateStateFlags::MissingReleaseTag))
+            && !members_states
+                .values()
+                .any(|state| state.contains(CrateStateFlags::HasPreviousRelease))
+        {
+            warn!(
+                "the repository is shallow and none of the previous release tags could be found. \
+                all crates will be considered as changed. consider fetching the full history and tags."
+            );
+        }
+    }
+
+    /// Gathers the facts about the given members that don't depend on the states of the other members.
+    ///
+    /// The changed files, which are the most expensive part, are computed in parallel, see `changed_files_in_parallel`.
+    /// The rest is gathered serially as cargo's packages can't be shared between threads.
+    fn analyze_members(&'a self, members: &[&'a Crate<'a>]) -> Fallible<Vec<MemberAnalysis>> {
+        let mut analyses = Vec::with_capacity(members.len());
+        for member in members {
+            let _member_span = error_span!("member", "crate" = %member.name()).entered();
+
+            analyses.push(MemberAnalysis {
+                readme_present: member.root().join(Self::README_FILENAME).exists(),
+                changelog: self.analyze_changelog(member)?,
+                ..Default::default()
+            });
+        }
+
+        let check_workspace_files = !self.criteria.workspace_change_paths.is_empty();
+        let mut requests = vec![];
+        for (member, analysis) in members.iter().zip(&analyses) {
+            if let Some(rev) = analysis
+                .changelog
+                .as_ref()
+                .and_then(ChangelogAnalysis::previous_release_rev)
+            {
+                requests.push((member.package.root().to_path_buf(), rev.to_string()));
+                if check_workspace_files {
+                    requests.push((self.root_path.clone(), rev.to_string()));
+                }
+            }
+        }
+
+        // the results are in the order of the requests
+        let mut changed_files = self.changed_files_in_parallel(&requests)?.into_iter();
+        for (member, analysis) in members.iter().zip(&mut analyses) {
+            if analysis
+                .changelog
+                .as_ref()
+                .and_then(ChangelogAnalysis::previous_release_rev)
+                .is_none()
+            {
+                continue;
+            }
+
+            analysis.changed_files = self
+                .without_excluded_crate_files(member, changed_files.next().unwrap_or_default())?;
+            if check_workspace_files {
+                analysis.workspace_files_changed =
+                    self.workspace_files_changed_in(&changed_files.next().unwrap_or_default());
+            }
+        }
+
+        Ok(analyses)
+    }
+
+    /// Looks up the previous release of the given member according to its changelog, if it has one.
+    fn analyze_changelog(&'a self, member: &'a Crate<'a>) -> Fallible<Option<ChangelogAnalysis>> {
+        let changelog = match member.changelog() {
+            Some(changelog) => changelog,
+            None => return Ok(None),
+        };
+
+        let front_matter = changelog.front_matter().context(format!(
+            "when parsing front matter of crate '{}'",
+            member.name()
+        ))?;
+        let declared_unreleasable = front_matter
+            .as_ref()
+            .and_then(|front_matter| front_matter.declared_unreleasable());
+        let (unreleasable, unreleasable_via_workspace_default) = match declared_unreleasable {
+            Some(unreleasable) => (unreleasable, false),
+            None => (self.default_unreleasable()?, true),
+        };
+
+        let mut parse_error = None;
+        let changes = match changelog.changes().context(format!(
+            "parsing the changelog of '{}' at {:?}",
+            member.name(),
+            changelog.path()
+        )) {
+            Ok(changes) => changes,
+            Err(err) if self.criteria.tolerate_changelog_parse_errors => {
+                parse_error = Some(format!("{:#}", err));
+                vec![]
+            }
+            Err(err) => return Err(err),
+        };
+
+        let previous_release = changes
+            .iter()
+            .find_map(|change| match change {
+                ChangeT::Release(release) => Some(release),
+                _ => None,
+            })
+            .and_then(|release| match release {
+                changelog::ReleaseChange::CrateReleaseChange(previous_release_version) => Some((
+                    previous_release_version.clone(),
+                    Version::parse(previous_release_version).map_err(|err| err.to_string()),
+                )),
+                _ => None,
+            });
+
+        // lookup the git tag for the previous release
+        let previous_release_tag = match &previous_release {
+            Some((_, Ok(version))) => {
+                let git_tag_name = self.release_tag_name(&member.name(), version)?;
+                self.tag_index()?.get(&git_tag_name).map(|_| git_tag_name)
+            }
+            _ => None,
+        };
+        let previous_release_tag = match previous_release_tag {
+            Some(git_tag_name) => Some(git_tag_name),
+            None => self.previous_release_tag_override(member, front_matter.as_ref())?,
+        };
+
+        let untagged_release_commit =
+            if previous_release_tag.is_none() && self.criteria.reconcile_untagged_releases {
+                self.reconcile_untagged_release(member)?
+                    .map(|commit| commit.to_string())
+            } else {
+                None
+            };
+
+        Ok(Some(ChangelogAnalysis {
+            unreleasable,
+            unreleasable_via_workspace_default,
+            previous_release,
+            previous_release_tag,
+            untagged_release_commit,
+            parse_error,
+        }))
+    }
+
+    /// The `previous_release_tag` of the given changelog frontmatter if the tag exists.
+    /// Warns if it doesn't, in which case the previous release is looked up as if there was no override.
+    fn previous_release_tag_override(
+        &'a self,
+        member: &Crate<'a>,
+        front_matter: Option<&Frontmatter>,
+    ) -> Fallible<Option<String>> {
+        let git_tag_name = match front_matter.and_then(Frontmatter::previous_release_tag) {
+            Some(git_tag_name) => git_tag_name,
+            None => return Ok(None),
+        };
+
+        if self.tag_index()?.contains_key(git_tag_name) {
+            debug!(
+                "[{}] using the previous release tag '{}' of the changelog frontmatter",
+                member.name(),
+                git_tag_name
+            );
+            Ok(Some(git_tag_name.to_string()))
+        } else {
+            warn!(
+                "[{}] the previous release tag '{}' of the changelog frontmatter doesn't exist, ignoring it",
+                member.name(),
+                git_tag_name
+            );
+            Ok(None)
+        }
+    }
+
+    /// The `default_unreleasable` of the workspace changelog frontmatter, which defaults to false.
+    fn default_unreleasable(&'a self) -> Fallible<bool> {
+        Ok(self
+            .changelog
+            .as_ref()
+            .map(|changelog| {
+                changelog
+                    .front_matter()
+                    .context("when parsing front matter of the workspace changelog")
+            })
+            .transpose()?
+            .flatten()
+            .and_then(|front_matter| front_matter.default_unreleasable())
+            .unwrap_or_default())
+    }
+
+    /// Whether the optional dependencies of the given crate are excluded, considering the per-crate overrides first.
+    fn excludes_optional_deps_of(&self, crate_name: &str) -> bool {
+        self.criteria
+            .optional_deps_overrides
+            .get(crate_name)
+            .copied()
+            .unwrap_or(self.criteria.exclude_optional_deps)
+    }
+
+    /// Whether the dependency applies to the target of `SelectionCriteria::dependency_platform_filter`.
+    /// Dependencies without a platform apply to every target, and all dependencies apply without the filter.
+    fn dependency_applies_to_platform(&self, dep: &Dependency) -> Fallible<bool> {
+        match (&self.criteria.dependency_platform_filter, dep.platform()) {
+            (Some(target), Some(platform)) => {
+                Ok(platform.matches(target, self.target_cfgs(target)?))
+            }
+            _ => Ok(true),
+        }
+    }
+
+    /// Returns the cfg values of the given target as reported by `rustc --print cfg`.
+    fn target_cfgs(&self, target: &str) -> Fallible<&[cargo_platform::Cfg]> {
+        self.target_cfgs
+            .get_or_try_init(|| {
+                let mut cmd = Command::new("rustc");
+                cmd.args(["--print", "cfg", "--target", target]);
+                debug!("running command: {:?}", cmd);
+
+                let output = self.command_runner.run(&mut cmd)?;
+                if !output.success {
+                    bail!(
+                        "couldn't determine the cfg values of the target '{}': {}",
+                        target,
+                        first_error_line(&output.stderr)
+                    );
+                }
+
+                output
+                    .stdout
+                    .lines()
+                    .filter(|line| !line.trim().is_empty())
+                    .map(|line| {
+                        line.parse::<cargo_platform::Cfg>()
+                            .context(format!("parsing cfg value '{}' of '{}'", line, target))
+                    })
+                    .collect()
+            })
+            .map(Vec::as_slice)
+    }
+
+    /// Returns the direct dependencies of the given package on other workspace members,
+    /// leaving out the ones that are excluded by their kind, optionality, platform or requirement.
+    fn workspace_dependencies_of<'p>(
+        &self,
+        package: &'p CargoPackage,
+        ws_members: &HashMap<String, &CargoPackage>,
+    ) -> Fallible<Vec<&'p Dependency>> {
+        let mut dependencies = vec![];
+
+        for dep in package.dependencies() {
+            let dep_name = dep.package_name().to_string();
+
+            if dep.is_optional() && self.excludes_optional_deps_of(&package.name()) {
+                trace!(
+                    "[{}] excluding optional dependency '{}'",
+                    package.name(),
+                    dep_name,
+                );
+
+                continue;
+            }
+
+            let (excluded_dep_kinds, source) = self.excluded_dep_kinds_of(&package.name());
+            if excluded_dep_kinds.contains(&dep.kind()) {
+                debug!(
+                    "[{}] excluding {:?} dependency '{}' due to the {}",
+                    package.name(),
+                    dep.kind(),
+                    dep_name,
+                    source,
+                );
+
+                continue;
+            } else if self.criteria.exclude_dep_kinds.contains(&dep.kind()) {
+                debug!(
+                    "[{}] including {:?} dependency '{}' due to the {}",
+                    package.name(),
+                    dep.kind(),
+                    dep_name,
+                    source,
+                );
+            }
+
+            if !self.dependency_applies_to_platform(dep)? {
+                debug!(
+                    "[{}] excluding dependency '{}' as {:?} doesn't apply to {:?}",
+                    package.name(),
+                    dep_name,
+                    dep.platform().map(ToString::to_string),
+                    self.criteria.dependency_platform_filter,
+                );
+
+                continue;
+            }
+
+            // only consider workspace members
+            if !ws_members.contains_key(&dep_name) {
+                continue;
+            }
+
+            // only consider non-star version requirements
+            if !dep.specified_req() || dep.version_req().to_string() == "*" {
+                continue;
+            }
+
+            // don't add this package to its own dependencies
+            if dep_name == package.name().as_str() {
+                warn!("{:?} depends on itself", package.name());
+                continue;
+            }
+
+            dependencies.push(dep);
+        }
+
+        Ok(dependencies)
+    }
+
+    /// The dependency kinds that are excluded for the given crate, along with the source of that decision.
+    fn excluded_dep_kinds_of(&self, crate_name: &str) -> (&HashSet<CargoDepKind>, &'static str) {
+        match self.criteria.dep_kind_overrides.get(crate_name) {
+            Some(dep_kinds) => (dep_kinds, "per-crate override"),
+            None => (&self.criteria.exclude_dep_kinds, "global setting"),
+        }
+    }
+
+    fn matches_selection_path_globs(&self, member: &Crate) -> bool {
+        if self.criteria.selection_path_globs.is_empty() {
+            return false;
+        }
+
+        let relative_root = match path_relative_to(member.root(), &self.root_path) {
+            Some(path) => normalized_path(&path),
+            None => return false,
+        };
+
+        let match_options = glob::MatchOptions {
+            require_literal_separator: true,
+            ..Default::default()
+        };
+
+        self.criteria
+            .selection_path_globs
+            .iter()
+            .any(|glob| glob.matches_with(&relative_root, match_options))
+    }
+
+    fn cargo_workspace(&'a self) -> Fallible<&'a CargoWorkspace> {
+        self.cargo_workspace.get_or_try_init(|| {
+            CargoWorkspace::new(&self.root_path.join("Cargo.toml"), &self.cargo_config)
+        })
+    }
+
+    /// Returns the next version of the crate according to its declared increment mode, which is taken from the overrides of the criteria,
+    /// the crate's manifest metadata, its changelog frontmatter, or the default mode of the criteria, in this order of precedence.
+    ///
+    /// For `Pre` the next version continues the pre-release series of the crate's release tags, see `next_pre_release_version`.
+    /// The other modes increment the previous release, and a crate without one keeps its manifest version, see `Crate::next_version`.
+    pub fn declared_next_version(&'a self, crt: &'a Crate<'a>) -> Fallible<Version> {
+        // not even a pre-release series is started for the first release
+        if !crt.version().is_prerelease() && crt.previous_release_version()?.is_none() {
+            return Ok(crt.version());
+        }
+
+        let modes = self.declared_increment_modes(crt)?;
+
+        match &modes[0] {
+            SemverIncrementMode::Pre(identifier) => {
+                // the first of the lower precedence modes that increments the version itself
+                let base_mode = modes
+                    .iter()
+                    .skip(1)
+                    .find(|mode| {
+                        matches!(
+                            mode,
+                            SemverIncrementMode::Major
+                                | SemverIncrementMode::Minor
+                                | SemverIncrementMode::Patch
+                                | SemverIncrementMode::Breaking
+                                | SemverIncrementMode::Feature
+                                | SemverIncrementMode::Fix
+                        )
+                    })
+                    .cloned()
+                    .unwrap_or_default();
+
+                self.next_pre_release_version(crt, identifier, base_mode)
+            }
+            mode => crt.next_version(mode.clone()),
+        }
+    }
+
+    /// Returns the increment mode that takes precedence for the crate, see `declared_next_version`.
+    pub fn declared_increment_mode(&'a self, crt: &'a Crate<'a>) -> Fallible<SemverIncrementMode> {
+        Ok(self.declared_increment_modes(crt)?.remove(0))
+    }
+
+    /// Returns all increment modes that are declared for the crate in the order of their precedence,
+    /// ending with the default mode of the criteria.
+    fn declared_increment_modes(
+        &'a self,
+        crt: &'a Crate<'a>,
+    ) -> Fallible<Vec<SemverIncrementMode>> {
+        let mut modes = vec![];
+        modes.extend(
+            self.criteria
+                .semver_increment_overrides
+                .get(&crt.name())
+                .cloned(),
+        );
+        modes.extend(crt.metadata_config()?.semver_increment.clone());
+        if let Some(changelog) = crt.changelog() {
+            modes.extend(
+                changelog
+                    .front_matter()
+                    .context(format!(
+                        "parsing the changelog frontmatter of crate '{}'",
+                        crt.name()
+                    ))?
+                    .and_then(|front_matter| front_matter.declared_semver_increment_mode()),
+            );
+        }
+        modes.push(self.criteria.semver_increment_mode.clone());
+        trace!("[{}] semver increment modes: {:?}", crt.name(), modes);
+
+        Ok(modes)
+    }
+
+    /// Runs `cargo semver-checks check-release` for the crate against the given baseline revision, e.g. its previous release tag.
+    /// Returns `None` if cargo-semver-checks is not installed.
+    fn run_semver_checks(
+        &'a self,
+        crt: &Crate,
+        baseline_rev: &str,
+    ) -> Fallible<Option<SemverChecksReport>> {
+        let mut cmd = Command::new("cargo");
+        cmd.current_dir(self.root())
+            .args(["semver-checks", "check-release"])
+            .arg(format!(
+                "--manifest-path={}",
+                crt.manifest_path().to_string_lossy()
+            ))
+            .arg(format!("--baseline-rev={}", baseline_rev));
+        debug!("running command: {:?}", cmd);
+
+        let output = self.command_runner.run(&mut cmd)?;
+        if output.stderr.contains("no such command") || output.stderr.contains("no such subcommand")
+        {
+            return Ok(None);
+        }
+
+        let report = parse_semver_checks_output(
+            baseline_rev,
+            &format!("{}\n{}", output.stdout, output.stderr),
+        );
+        if !output.success && !report.changes_detected() {
+            bail!(
+                "cargo semver-checks failed for '{}': {}",
+                crt.name(),
+                first_error_line(&output.stderr)
+            );
+        }
+
+        Ok(Some(report))
+    }
+
+    /// Runs `cargo semver-checks` against the previous release of every changed crate in the release selection.
+    /// It builds every crate twice, which is why it's a separate step instead of a part of the selection.
+    ///
+    /// Returns the states of the checked crates including the reports. The crates whose declared semver increment is too weak
+    /// for the detected changes are flagged with `SemverIncrementTooWeak`.
+    /// Skipped with a warning if cargo-semver-checks is not installed.
+    pub fn semver_checks(&'a self) -> Fallible<&'a BTreeMap<String, CrateStateReport>> {
+        self.semver_checks.get_or_try_init(|| {
+            let mut reports = BTreeMap::new();
+
+            for member in self.members()? {
+                let mut state = member.state()?;
+                let baseline_rev = match state.previous_release_rev() {
+                    Some(baseline_rev) if state.release_selection() && state.changed() => {
+                        baseline_rev.to_string()
+                    }
+                    _ => continue,
+                };
+
+                let report = match self.run_semver_checks(member, &baseline_rev) {
+                    Ok(None) => {
+                        warn!("cargo-semver-checks is not installed, continuing without the semver checks");
+                        return Ok(BTreeMap::new());
+                    }
+                    Err(err) if self.criteria.strict => {
+                        return Err(err.context(format!(
+                            "running the semver checks of '{}'",
+                            member.name()
+                        )));
+                    }
+                    Err(err) => {
+                        warn!(
+                            "[{}] couldn't run the semver checks, continuing without: {:?}",
+                            member.name(),
+                            err
+                        );
+                        continue;
+                    }
+                    Ok(Some(report)) => report,
+                };
+
+                if let Some(required_increment) = &report.required_increment {
+                    let required_version = member.next_version(required_increment.clone())?;
+                    let declared_version = self.declared_next_version(member)?;
+
+                    // a pre-release of the required version is sufficient
+                    if Version::new(
+                        declared_version.major,
+                        declared_version.minor,
+                        declared_version.patch,
+                    ) < required_version
+                    {
+                        warn!(
+                            "'{}' is declared to be released as {} but the changes since {} require at least {}: {}",
+                            member.name(),
+                            declared_version,
+                            baseline_rev,
+                            required_version,
+                            report
+                                .findings
+                                .iter()
+                                .map(|finding| format!("\n- {}", finding))
+                                .collect::<String>()
+                        );
+                        state.insert(CrateStateFlags::SemverIncrementTooWeak);
+                    }
+                }
+
+                state.semver_checks = Some(report);
+                reports.insert(
+                    member.name(),
+                    CrateStateReport::new(&member.version(), &state),
+                );
+            }
+
+            Ok(reports)
+        })
+    }
+
+    /// Returns the current and the next version of every crate in the release selection, in the order of the selection.
+    /// The next version is the declared one, see `declared_next_version`.
+    /// Crates that are pending their promotion to a stable release get their pre-release identifier stripped instead.
+    ///
+    /// The bumps of the crates that are flagged with `CascadeBumped` are escalated to breaking ones. These crates depend on a crate
+    /// whose next version is semver incompatible with its previous release and falls outside the requirement on it,
+    /// and their APIs are likely to expose the dependency. Exact requirements are rewritten instead and don't escalate.
+    /// Crates that are bumped to a pre-release aren't escalated.
+    pub fn compute_version_bumps(&'a self) -> Fallible<LinkedHashMap<String, (Version, Version)>> {
+        let release_selection = self.release_selection()?;
+
+        let members_states = self.members_states()?;
+
+        let mut bumps = self.declared_version_bumps(&release_selection, members_states)?;
+        for crt in &release_selection {
+            if matches!(
+                members_states.get(&crt.name()),
+                Some(state) if state.contains(CrateStateFlags::CascadeBumped)
+            ) {
+                let (_, next_version) = bumps
+                    .get_mut(&crt.name())
+                    .expect("all crates of the selection are bumped");
+                *next_version = crt.next_version(SemverIncrementMode::Breaking)?;
+            }
+        }
+
+        Ok(bumps)
+    }
+
+    /// Returns the current and the declared next version of the given crates, see `compute_version_bumps`.
+    fn declared_version_bumps(
+        &'a self,
+        crates: &[&'a Crate<'a>],
+        members_states: &MemberStates,
+    ) -> Fallible<LinkedHashMap<String, (Version, Version)>> {
+        crates
+            .iter()
+            .map(|crt| {
+                if matches!(
+                    members_states.get(&crt.name()),
+                    Some(state) if state.contains(CrateStateFlags::PendingStablePromotion)
+                ) {
+                    return Ok((crt.name(), (crt.version(), crt.stable_version())));
+                }
+
+                Ok((
+                    crt.name(),
+                    (crt.version(), self.declared_next_version(crt)?),
+                ))
+            })
+            .collect()
+    }
+
+    /// Returns the crates among the given ones whose version bump needs to be escalated to a breaking one,
+    /// together with the chain of dependencies that caused the escalation, see `compute_version_bumps`.
+    fn version_bump_cascades(
+        &'a self,
+        crates: &[&'a Crate<'a>],
+        members_states: &MemberStates,
+    ) -> Fallible<HashMap<String, Vec<String>>> {
+        // a crate whose next version can't be determined, e.g. as its changelog is ahead of its manifest,
+        // fails the version bumps later on and is left out of the escalations
+        let mut bumps = LinkedHashMap::new();
+        for crt in crates {
+            match self.declared_version_bumps(&[*crt], members_states) {
+                Ok(crate_bumps) => bumps.extend(crate_bumps),
+                Err(err) => debug!(
+                    "[{}] not considering the crate for escalations: {:#}",
+                    crt.name(),
+                    err
+                ),
+            }
+        }
+
+        let ws_members = self
+            .members_unsorted()?
+            .iter()
+            .map(|member| (member.name(), &member.package))
+            .collect::<HashMap<_, _>>();
+
+        // the crates are ordered by dependencies, so the escalations reach the dependants before they're visited
+        let mut cascades = HashMap::<String, Vec<String>>::new();
+        for crt in crates {
+            if !bumps.contains_key(&crt.name()) {
+                continue;
+            }
+
+            // only the crate's own requirements matter, the transitive ones belong to the intermediate crates
+            let mut direct_deps = LinkedHashMap::<String, Vec<&Dependency>>::new();
+            for dep in self.workspace_dependencies_of(crt.package(), &ws_members)? {
+                direct_deps
+                    .entry(dep.package_name().to_string())
+                    .or_insert_with(Vec::new)
+                    .push(dep);
+            }
+
+            for (dep_name, deps) in &direct_deps {
+                let (dep_current_version, dep_next_version) = match bumps.get(dep_name) {
+                    Some(versions) => versions.clone(),
+                    None => continue,
+                };
+
+                // only a bump that is incompatible with the dependency's previous release can break the dependant's API
+                let dep_previous_version = match crates.iter().find(|c| &c.name() == dep_name) {
+                    Some(dep_crt) => dep_crt
+                        .previous_release_version()?
+                        .unwrap_or(dep_current_version),
+                    None => dep_current_version,
+                };
+                if is_semver_compatible(&dep_previous_version, &dep_next_version) {
+                    continue;
+                }
+
+                let mut violated_reqs = vec![];
+                for dep in deps {
+                    // dev-dependencies don't affect the published crate
+                    if dep.kind() == CargoDepKind::Development {
+                        continue;
+                    }
+
+                    // exact pins are rewritten to the new version and don't cause an escalation on their own
+                    let version_req = VersionReq::parse(&dep.version_req().to_string())?;
+                    if is_exact_version_req(&version_req) {
+                        continue;
+                    }
+
+                    if !version_req_matches(&version_req, &dep_next_version) {
+                        violated_reqs.push(version_req);
+                    }
+                }
+                if violated_reqs.is_empty() {
+                    continue;
+                }
+
+                let (_, next_version) = bumps
+                    .get_mut(&crt.name())
+                    .expect("all crates of the selection are bumped");
+                // pre-releases make no compatibility promises, escalating would end the series
+                if next_version.is_prerelease() {
+                    continue;
+                }
+
+                let escalated_version = crt.next_version(SemverIncrementMode::Breaking)?;
+                if *next_version >= escalated_version {
+                    continue;
+                }
+
+                info!(
+                    "[{}] escalating the version bump from {} to {} because '{}' {} is incompatible with its previous release {} and doesn't meet the requirement {}",
+                    crt.name(),
+                    next_version,
+                    escalated_version,
+                    dep_name,
+                    dep_next_version,
+                    dep_previous_version,
+                    violated_reqs[0]
+                );
+                *next_version = escalated_version;
+
+                let mut chain = cascades.get(dep_name).cloned().unwrap_or_default();
+                chain.push(dep_name.clone());
+                cascades.entry(crt.name()).or_insert(chain);
+            }
+        }
+
+        Ok(cascades)
+    }
+
+    /// Returns the path of the workspace's root manifest.
+    pub fn root_manifest_path(&self) -> PathBuf {
+        self.root_path.join("Cargo.toml")
+    }
+
+    /// Writes the given version to the `[workspace.package]` table of the root manifest,
+    /// preserving the formatting like `Crate::set_manifest_version`.
+    pub fn set_workspace_package_version(&self, version: &Version) -> Fallible<()> {
+        let manifest_path = self.root_manifest_path();
+        let mut manifest: toml_edit::Document = crate::common::load_from_file(&manifest_path)?
+            .parse()
+            .context(format!("parsing manifest at {:?}", manifest_path))?;
+
+        if manifest["workspace"]["package"]["version"]
+            .as_str()
+            .is_none()
+        {
+            bail!(
+                "expected a version string in the [workspace.package] table of {:?}",
+                manifest_path
+            );
+        }
+        replace_str_value(
+            &mut manifest["workspace"]["package"]["version"],
+            version.to_string(),
+        );
+
+        std::fs::write(&manifest_path, manifest.to_string_in_original_order())?;
+
+        Ok(())
+    }
+
+    /// Writes the next versions of `compute_version_bumps` to the manifests of the selected crates,
+    /// see `apply_planned_version_bumps`.
+    pub fn apply_version_bumps(&'a self) -> Fallible<Vec<PathBuf>> {
+        self.apply_planned_version_bumps(&self.compute_plan()?)
+    }
+
+    /// Writes the planned next versions to the manifests, and returns the paths of the manifests that were modified
+    /// for inclusion in the release commit.
+    ///
+    /// The next version of crates that inherit their version from the workspace is written to the root manifest instead.
+    /// As they share the version, their next versions must not differ unless `lockstep` is set in the criteria,
+    /// in which case the highest one is used.
+    ///
+    /// Bumps whose next version is already in the manifest are skipped, so applying the same plan again doesn't bump twice.
+    /// Fails if a manifest contains neither the planned current nor the planned next version.
+    pub fn apply_planned_version_bumps(&'a self, plan: &ReleasePlan) -> Fallible<Vec<PathBuf>> {
+        let members = self.members()?;
+
+        let mut explicit_bumps = vec![];
+        let mut inherited_bumps = LinkedHashMap::new();
+        for (name, (current_version, next_version)) in plan.version_bumps()? {
+            if current_version == next_version {
+                continue;
+            }
+
+            let crt = *members
+                .iter()
+                .find(|member| member.name() == name)
+                .ok_or_else(|| {
+                    anyhow!(
+                        "the planned crate '{}' is not a member of the workspace",
+                        name
+                    )
+                })?;
+
+            if crt.inherits_version()? {
+                inherited_bumps.insert(name, (crt, current_version, next_version));
+            } else {
+                explicit_bumps.push((crt, current_version, next_version));
+            }
+        }
+
+        let inherited_next_versions = inherited_bumps
+            .values()
+            .map(|(_, _, next_version)| next_version)
+            .collect::<BTreeSet<_>>();
+        if inherited_next_versions.len() > 1 && !self.criteria.lockstep {
+            bail!(
+                "the following crates inherit their version from the workspace but would need different next versions, \
+                which is only possible in lockstep mode:{}",
+                inherited_bumps
+                    .iter()
+                    .map(|(name, (_, _, next_version))| format!("\n- {}: {}", name, next_version))
+                    .collect::<String>()
+            );
+        }
+
+        let total = explicit_bumps.len() + inherited_bumps.len();
+        self.progress_reporter
+            .on_phase_started(ReleasePhase::VersionBump, total);
+
+        let mut modified_manifests = vec![];
+        for (index, (crt, current_version, next_version)) in explicit_bumps.iter().enumerate() {
+            if ensure_bump_pending(
+                &crt.name(),
+                &crt.manifest_version()?,
+                current_version,
+                next_version,
+            )? {
+                debug!("[{}] bumping version to {}", crt.name(), next_version);
+                crt.set_manifest_version(next_version)?;
+                modified_manifests.push(crt.manifest_path().to_path_buf());
+            }
+
+            self.progress_reporter.on_crate_processed(
+                ReleasePhase::VersionBump,
+                &crt.name(),
+                index,
+                total,
+            );
+        }
+
+        if let (Some(next_version), Some((_, (crt, current_version, _)))) = (
+            inherited_next_versions.iter().next_back(),
+            inherited_bumps.iter().next(),
+        ) {
+            if ensure_bump_pending(
+                "workspace",
+                &crt.manifest_version()?,
+                current_version,
+                next_version,
+            )? {
+                debug!(
+                    "bumping the workspace package version to {} for {:?}",
+                    next_version,
+                    inherited_bumps.keys().collect::<Vec<_>>()
+                );
+                self.set_workspace_package_version(next_version)?;
+                modified_manifests.push(self.root_manifest_path());
+            }
+        }
+
+        for (index, name) in inherited_bumps.keys().enumerate() {
+            self.progress_reporter.on_crate_processed(
+                ReleasePhase::VersionBump,
+                name,
+                explicit_bumps.len() + index,
+                total,
+            );
+        }
+        self.progress_reporter
+            .on_phase_finished(ReleasePhase::VersionBump);
+
+        Ok(modified_manifests)
+    }
+
+    /// Rewrites the requirements of the workspace dependants of the selected crates that don't accommodate the next versions
+    /// of `compute_version_bumps`, see `update_planned_dependent_requirements`.
+    pub fn update_dependent_requirements(&'a self) -> Fallible<Vec<PathBuf>> {
+        self.update_planned_dependent_requirements(&self.compute_plan()?)
+    }
+
+    /// Rewrites the requirements of the workspace dependants of the planned crates that don't accommodate the planned next versions,
+    /// and returns the paths of the manifests that were modified for inclusion in the release commit.
+    ///
+    /// Dependants that inherit the dependency via `{ workspace = true }` are covered by rewriting the requirement
+    /// in the `[workspace.dependencies]` table of the root manifest, which includes it in the modified manifests.
+    ///
+    /// Requirements that already accommodate the next versions are left untouched,
+    /// which completes the rewrites that are missing if the plan was applied partially before.
+    pub fn update_planned_dependent_requirements(
+        &'a self,
+        plan: &ReleasePlan,
+    ) -> Fallible<Vec<PathBuf>> {
+        let members = self.members()?;
+
+        let mut modified_manifests = LinkedHashSet::new();
+        for (name, (_, next_version)) in plan.version_bumps()? {
+            let crt = members
+                .iter()
+                .find(|member| member.name() == name)
+                .ok_or_else(|| {
+                    anyhow!(
+                        "the planned crate '{}' is not a member of the workspace",
+                        name
+                    )
+                })?;
+
+            if self.update_workspace_dependency_requirement(&name, &next_version)? {
+                modified_manifests.insert(self.root_manifest_path());
+            }
+
+            for dependant in crt.dependants_in_workspace(DependentsScope::Transitive)? {
+                if dependant.update_dependency_requirement(&name, &next_version)? {
+                    modified_manifests.insert(dependant.manifest_path().to_path_buf());
+                } else {
+                    trace!(
+                        "[{}] the requirement on {} already accommodates {}",
+                        dependant.name(),
+                        name,
+                        next_version
+                    );
+                }
+            }
+        }
+
+        Ok(modified_manifests.into_iter().collect())
+    }
+
+    /// Updates the entries of the workspace members in the lockfile to their current versions,
+    /// leaving the versions of all other dependencies untouched.
+    /// Returns the path of the lockfile for inclusion in the release commit.
+    pub fn refresh_lockfile(&'a self) -> Fallible<PathBuf> {
+        let mut cmd = Command::new("cargo");
+        cmd.current_dir(self.root())
+            .args(["update", "--workspace", "--offline"])
+            .arg(format!(
+                "--manifest-path={}",
+                self.root_manifest_path().to_string_lossy()
+            ));
+        debug!("running command: {:?}", cmd);
+
+        let output = self.command_runner.run(&mut cmd)?;
+        if !output.success {
+            bail!(
+                "updating the lockfile failed: {}",
+                first_error_line(&output.stderr)
+            );
+        }
+
+        Ok(self.root().join("Cargo.lock"))
+    }
+
+    /// Applies the version bumps, rewrites the requirements of the dependants accordingly and refreshes the lockfile.
+    /// Returns the paths of all modified files for inclusion in the release commit.
+    pub fn write_release_versions(&'a self) -> Fallible<Vec<PathBuf>> {
+        self.write_planned_versions(&self.compute_plan()?)
+    }
+
+    /// Like `write_release_versions` for the given plan, which can be applied repeatedly without bumping any version twice.
+    pub fn write_planned_versions(&'a self, plan: &ReleasePlan) -> Fallible<Vec<PathBuf>> {
+        let mut modified_files = LinkedHashSet::new();
+        modified_files.extend(self.apply_planned_version_bumps(plan)?);
+        modified_files.extend(self.update_planned_dependent_requirements(plan)?);
+        modified_files.insert(self.refresh_lockfile()?);
+
+        Ok(modified_files.into_iter().collect())
+    }
+
+    /// Rewrites the requirement on the given dependency in the `[workspace.dependencies]` table of the root manifest
+    /// if it doesn't accommodate the new version, see `Crate::update_dependency_requirement`.
+    ///
+    /// Returns whether the manifest was modified.
+    pub fn update_workspace_dependency_requirement(
+        &self,
+        name: &str,
+        version: &Version,
+    ) -> Fallible<bool> {
+        let manifest_path = self.root_manifest_path();
+        let mut manifest: toml_edit::Document = crate::common::load_from_file(&manifest_path)?
+            .parse()
+            .context(format!("parsing manifest at {:?}", manifest_path))?;
+
+        let modified = match manifest
+            .as_table_mut()
+            .get_mut("workspace")
+            .and_then(toml_edit::Item::as_table_mut)
+        {
+            Some(workspace) => update_dependency_requirements_in(workspace, name, version)?,
+            None => false,
+        };
+
+        if modified {
+            debug!(
+                "updated the workspace requirement on {} to accommodate {}",
+                name, version
+            );
+            std::fs::write(&manifest_path, manifest.to_string_in_original_order())?;
+        }
+
+        Ok(modified)
+    }
+
+    /// Returns the next pre-release version of the crate with the given identifier, e.g. `0.2.0-rc.2` if `0.2.0-rc.1` is tagged.
+    ///
+    /// The pre-release series of the current version is continued if it's a pre-release,
+    /// otherwise the previous release is incremented by the given base mode first and the series starts at 1.
+    pub fn next_pre_release_version(
+        &'a self,
+        crt: &'a Crate<'a>,
+        identifier: &str,
+        base_mode: SemverIncrementMode,
+    ) -> Fallible<Version> {
+        let current_version = crt.version();
+
+        let mut base = if current_version.is_prerelease() {
+            let mut base = current_version.clone();
+            base.pre = semver::Prerelease::EMPTY;
+            base
+        } else {
+            crt.next_version(base_mode)?
+        };
+        base.build = semver::BuildMetadata::EMPTY;
+
+        let counter = self
+            .released_versions(&crt.name())?
+            .iter()
+            .chain(std::iter::once(&current_version))
+            .filter(|version| {
+                (version.major, version.minor, version.patch)
+                    == (base.major, base.minor, base.patch)
+            })
+            .filter_map(|version| {
+                version
+                    .pre
+                    .strip_prefix(identifier)?
+                    .strip_prefix('.')?
+                    .parse::<u64>()
+                    .ok()
+            })
+            .max()
+            .map(|counter| counter + 1)
+            .unwrap_or(1);
+
+        base.pre = semver::Prerelease::new(&format!("{}.{}", identifier, counter))?;
+        trace!(
+            "[{}] next pre-release version after {}: {}",
+            crt.name(),
+            current_version,
+            base
+        );
+
+        Ok(base)
+    }
+
+    /// Returns the crates that are going to be processed for release.
+    pub fn release_selection(&'a self) -> Fallible<Vec<&'a Crate>> {
+        let members = self.members()?;
+
+        let all_crates_states = members
+            .iter()
+            .map(|member| Ok((member.name(), member.state()?)))
+            .collect::<Fallible<Vec<_>>>()?;
+        let rows = members
+            .iter()
+            .zip(all_crates_states.iter())
+            .map(|(member, (name, state))| {
+                (
+                    name.clone(),
+                    CrateStateReport::new(&member.version(), state),
+                )
+            })
+            .collect::<Vec<_>>();
+        trace!(
+            "{}",
+            CratesStateReport::new("ALL CRATES", rows.clone()).showing(true, true, true)
+        );
+        let blocked_report = CratesStateReport::new(
+            "DISALLOWED BLOCKING CRATES",
+            rows.into_iter()
+                .zip(all_crates_states.iter())
+                .filter(|(_, (_, state))| state.selected() && !state.allowed())
+                .map(|(row, _)| row)
+                .collect(),
+        )
+        .showing(true, false, false);
+
+        // indicate an error if any unreleasable crates block the release
+        if !blocked_report.is_empty() {
+            let required_via = |flag| {
+                blocked_report
+                    .rows
+                    .iter()
+                    .filter(|(_, row)| row.flags.contains(flag))
+                    .map(|(name, _)| -> Fallible<_> {
+                        Ok(self
+                            .dependency_chain(name)?
+                            .map(|chain| chain.join(" -> "))
+                            .unwrap_or_else(|| name.clone()))
+                    })
+                    .collect::<Fallible<Vec<_>>>()
+            };
+
+            bail!(BlockedSelectionError {
+                never_release: required_via(CrateStateFlags::NeverRelease)?,
+                excluded: required_via(CrateStateFlags::Excluded)?,
+                publish_false: required_via(CrateStateFlags::ManifestPublishFalse)?,
+                report: blocked_report,
+            })
+        }
+
+        let release_selection = members
+            .iter()
+            .zip(all_crates_states.iter())
+            .filter(|(member, (_, state))| {
+                let release = state.release_selection();
+
+                trace!(
+                    "{} release indicator: {}, blocked: {:?}, state: {:#?}",
+                    member.name(),
+                    release,
+                    state.blocked(),
+                    state,
+                );
+
+                release
+            })
+            .map(|(member, _)| *member)
+            .collect::<Vec<_>>();
+
+        // dependencies beyond the depth limit which need a release would render the selection unpublishable
+        if let Some(max_depth) = self.criteria.max_dependency_depth {
+            let selected_names = release_selection
+                .iter()
+                .map(|member| member.name())
+                .collect::<HashSet<_>>();
+            let states = all_crates_states.iter().cloned().collect::<HashMap<_, _>>();
+
+            let missing_dependencies = release_selection
+                .iter()
+                .flat_map(|member| {
+                    member
+                        .package()
+                        .dependencies()
+                        .iter()
+                        .filter(|dep| dep.kind() != CargoDepKind::Development)
+                        .map(move |dep| (member.name(), dep.package_name().to_string()))
+                })
+                .filter(|(_, dep_name)| {
+                    !selected_names.contains(dep_name)
+                        && matches!(states.get(dep_name), Some(state) if state.changed())
+                })
+                .map(|(name, dep_name)| format!("\n- {} -> {}", name, dep_name))
+                .collect::<LinkedHashSet<_>>();
+
+            if !missing_dependencies.is_empty() {
+                bail!(
+                    "the following dependencies are beyond the maximum dependency depth of {} but need to be released for the selected crates to be publishable: {}",
+                    max_depth,
+                    missing_dependencies.into_iter().collect::<String>()
+                );
+            }
+        }
+
+        Ok(release_selection)
+    }
+
+    /// Returns the changed files in the given directory between the given revisions.
+    ///
+    /// The results are cached by the resolved revisions unless disabled by the selection criteria.
+    fn changed_files_cached(
+        &self,
+        dir: &Path,
+        from_rev: &str,
+        to_rev: &str,
+    ) -> Fallible<Vec<PathBuf>> {
+        if self.criteria.no_change_detection_cache {
+            self.changed_files_computations
+                .fetch_add(1, Ordering::Relaxed);
+            return changed_files(dir, from_rev, to_rev);
+        }
+
+        let key = (
+            dir.to_path_buf(),
+            self.git_repo.revparse_single(from_rev)?.id(),
+            self.git_repo.revparse_single(to_rev)?.id(),
+        );
+
+        if let Some(paths) = lock_cache(&self.changed_files_cache).get(&key) {
+            return Ok(paths.clone());
+        }
+
+        let paths = changed_files(dir, from_rev, to_rev)?;
+        self.changed_files_computations
+            .fetch_add(1, Ordering::Relaxed);
+        lock_cache(&self.changed_files_cache).insert(key, paths.clone());
+
+        Ok(paths)
+    }
+
+    /// Like `changed_files_cached` for each of the given directories between the given revisions and HEAD.
+    ///
+    /// The ones that aren't cached are computed in parallel on `analysis_threads` threads,
+    /// each of which opens its own handle to the repository.
+    fn changed_files_in_parallel(
+        &self,
+        requests: &[(PathBuf, String)],
+    ) -> Fallible<Vec<Vec<PathBuf>>> {
+        // the revisions are resolved upfront as the repository handle can't be shared between threads
+        let head = self.git_repo.revparse_single("HEAD")?.id();
+        let keys = requests
+            .iter()
+            .map(|(dir, rev)| Ok((dir.clone(), self.git_repo.revparse_single(rev)?.id(), head)))
+            .collect::<Fallible<Vec<_>>>()?;
+
+        let pending = keys
+            .iter()
+            .filter(|key| {
+                self.criteria.no_change_detection_cache
+                    || !lock_cache(&self.changed_files_cache).contains_key(*key)
+            })
+            .cloned()
+            .collect::<LinkedHashSet<_>>()
+            .into_iter()
+            .collect::<Vec<_>>();
+
+        let compute = || {
+            pending
+                .par_iter()
+                .map(|(dir, from, to)| changed_files(dir, &from.to_string(), &to.to_string()))
+                .collect::<Fallible<Vec<_>>>()
+        };
+        let computed = match self.criteria.analysis_threads {
+            Some(threads) => rayon::ThreadPoolBuilder::new()
+                .num_threads(threads)
+                .build()?
+                .install(compute)?,
+            None => compute()?,
+        };
+
+        self.changed_files_computations
+            .fetch_add(pending.len(), Ordering::Relaxed);
+        let computed = pending.into_iter().zip(computed).collect::<HashMap<_, _>>();
+        if !self.criteria.no_change_detection_cache {
+            lock_cache(&self.changed_files_cache).extend(computed.clone());
+        }
+
+        Ok(keys
+            .iter()
+            .map(|key| {
+                computed
+                    .get(key)
+                    .cloned()
+                    .or_else(|| lock_cache(&self.changed_files_cache).get(key).cloned())
+                    .unwrap_or_default()
+            })
+            .collect())
+    }
+
+    /// Drops the files that belong to excluded crates which are nested within the given member's directory.
+    fn without_excluded_crate_files(
+        &'a self,
+        member: &Crate<'a>,
+        changed_files: Vec<PathBuf>,
+    ) -> Fallible<Vec<PathBuf>> {
+        let nested_dirs = self
+            .excluded_crates()?
+            .iter()
+            .map(|excluded| self.root_path.join(&excluded.path))
+            .filter(|dir| path_relative_to(dir, member.root()).is_some())
+            .collect::<Vec<_>>();
+
+        Ok(changed_files
+            .into_iter()
+            .filter(|path| {
+                let nested = nested_dirs
+                    .iter()
+                    .any(|dir| path_relative_to(path, dir).is_some());
+                if nested {
+                    trace!(
+                        "[{}] {:?} belongs to an excluded crate",
+                        member.name(),
+                        path
+                    );
+                }
+                !nested
+            })
+            .collect())
+    }
+
+    /// Whether any of the configured workspace change paths are among the given changed files of the workspace root.
+    fn workspace_files_changed_in(&self, changed_files: &[PathBuf]) -> bool {
+        changed_files
+            .iter()
+            .filter_map(|path| path_relative_to(path, &self.root_path))
+            .any(|path| {
+                self.criteria
+                    .workspace_change_paths
+                    .iter()
+                    .any(|change_path| &path == change_path)
+            })
+    }
+
+    /// Renders the workspace dependency graph in Graphviz DOT format.
+    ///
+    /// Nodes are colored by their meta state and edges are labeled with the dependency kind.
+    pub fn dependency_graph_dot(&'a self) -> Fallible<String> {
+        let mut dot = String::from("digraph workspace {\n");
+
+        for member in self.members()? {
+            let state = member.state()?;
+            let color = if state.meta_flags.contains(MetaCrateStateFlags::Blocked) {
+                "red"
+            } else if state.meta_flags.contains(MetaCrateStateFlags::Selected) {
+                "green"
+            } else if state.meta_flags.contains(MetaCrateStateFlags::Changed) {
+                "yellow"
+            } else {
+                "grey"
+            };
+
+            dot += &format!(
+                "    \"{}\" [label=\"{}\\n{}\", style=filled, fillcolor={}];\n",
+                member.name(),
+                member.name(),
+                member.version(),
+                color
+            );
+        }
+
+        for member in self.members()? {
+            let dependencies_in_workspace = member.dependencies_in_workspace()?;
+
+            // only render the direct dependencies to keep the graph readable
+            for dep in member.package().dependencies() {
+                let dep_name = dep.package_name().to_string();
+                if dep_name == member.name() || !dependencies_in_workspace.contains_key(&dep_name) {
+                    continue;
+                }
+
+                dot += &format!(
+                    "    \"{}\" -> \"{}\" [label=\"{:?}\"];\n",
+                    member.name(),
+                    dep_name,
+                    dep.kind()
+                );
+            }
+        }
+
+        dot += "}\n";
+
+        Ok(dot)
+    }
+
+    /// Returns a serializable report of all members' states, keyed by crate name, and of the excluded crates.
+    ///
+    /// Unlike `release_selection()` this doesn't fail if blocked crates are selected.
+    pub fn states_report(&'a self) -> Fallible<StatesReport> {
+        let crates = self
+            .members()?
+            .iter()
+            .map(|member| {
+                let state = member.state()?;
+
+                Ok((
+                    member.name(),
+                    CrateStateReport::new(&member.version(), &state),
+                ))
+            })
+            .collect::<Fallible<_>>()?;
+
+        Ok(StatesReport {
+            crates,
+            excluded_crates: self.excluded_crates()?.clone(),
+        })
+    }
+
+    /// Explains why the crate with the given name is or isn't part of the release selection.
+    pub fn explain(&'a self, crate_name: &str) -> Fallible<SelectionExplanation> {
+        let member = self
+            .members()?
+            .iter()
+            .find(|member| member.name() == crate_name)
+            .ok_or_else(|| anyhow!("'{}' is not a member of the workspace", crate_name))?;
+        let state = member.state()?;
+        let criteria = &self.criteria;
+
+        let filter_matched = criteria
+            .selection_filter_mode
+            .apply(&criteria.match_filter)?
+            .is_match(crate_name)?;
+
+        let dependency_path = if state.is_dependency() || state.is_dev_dependency() {
+            self.dependency_chain(crate_name)?
+        } else {
+            None
+        };
+
+        // the files that changed but don't trigger a release are left out
+        let changed_files = state
+            .changed_files()
+            .iter()
+            .filter(|path| {
+                BitFlags::from(ChangeClass::classify(path))
+                    .intersects(criteria.change_classes_triggering_release)
+            })
+            .cloned()
+            .collect();
+
+        Ok(SelectionExplanation {
+            name: crate_name.to_string(),
+            filter_matched,
+            matched: state.is_matched(),
+            dependency_path,
+            dependent_of: state.dependent_of.clone(),
+            blocking_flags: state.blocked_by(),
+            allowed_blockers: state.allowed_blockers(),
+            previous_release: state.previous_release_rev().map(str::to_string),
+            changed: state.changed(),
+            changed_files,
+            workspace_files_changed: state.contains(CrateStateFlags::WorkspaceFilesChanged),
+            dependency_changed: state.dependency_changed(),
+            release_selection: state.release_selection(),
+        })
+    }
+
+    /// Returns the release configuration of the workspace manifest, or the defaults if there is none.
+    pub fn release_metadata(&'a self) -> Fallible<&'a WorkspaceReleaseMetadata> {
+        self.release_metadata.get_or_try_init(|| {
+            let metadata: WorkspaceReleaseMetadata = match self
+                .cargo_workspace()?
+                .custom_metadata()
+                .and_then(|metadata| metadata.get(Self::METADATA_KEY))
+            {
+                Some(value) => value.clone().try_into().context(format!(
+                    "parsing workspace.metadata.{} in {:?}",
+                    Self::METADATA_KEY,
+                    self.root_path.join("Cargo.toml")
+                ))?,
+                None => Default::default(),
+            };
+
+            Ok(metadata)
+        })
+    }
+
+    /// The paths listed in `workspace.exclude` of the workspace manifest, relative to the workspace root.
+    pub fn excluded_paths(&self) -> Fallible<Vec<PathBuf>> {
+        let manifest_path = self.root_path.join("Cargo.toml");
+        let manifest: toml_edit::Document = crate::common::load_from_file(&manifest_path)?
+            .parse()
+            .context(format!("parsing {}", manifest_path.display()))?;
+
+        Ok(manifest["workspace"]["exclude"]
+            .as_array()
+            .into_iter()
+            .flat_map(|paths| paths.iter())
+            .filter_map(|path| path.as_str())
+            .map(PathBuf::from)
+            .collect())
+    }
+
+    /// Returns the crates within the workspace directory that aren't members,
+    /// either because they're listed in `workspace.exclude` or because `workspace.members` doesn't cover them.
+    ///
+    /// Only crates whose manifest is tracked by git are found, as only those can show up in the changed files.
+    pub fn excluded_crates(&'a self) -> Fallible<&'a Vec<ExcludedCrate>> {
+        self.excluded_crates.get_or_try_init(|| {
+            let workdir = self.git_repo.workdir().ok_or_else(|| {
+                anyhow!(
+                    "repository at {:?} has no working directory",
+                    self.git_repo.path()
+                )
+            })?;
+            let excluded_paths = self.excluded_paths()?;
+            let member_paths = self
+                .members_unsorted()?
+                .iter()
+                .filter_map(|member| path_relative_to(member.root(), &self.root_path))
+                .collect::<HashSet<_>>();
+
+            let mut excluded_crates = vec![];
+            for entry in self.git_repo.index()?.iter() {
+                let manifest_path = workdir.join(git_path_to_native(&entry.path)?);
+                if manifest_path.file_name() != Some(std::ffi::OsStr::new("Cargo.toml"))
+                    || !manifest_path.is_file()
+                {
+                    continue;
+                }
+
+                let path = match manifest_path
+                    .parent()
+                    .and_then(|dir| path_relative_to(dir, &self.root_path))
+                {
+                    // the workspace manifest itself
+                    Some(path) if path.as_os_str().is_empty() => continue,
+                    Some(path) if member_paths.contains(&path) => continue,
+                    Some(path) => path,
+                    None => continue,
+                };
+
+                let manifest: toml_edit::Document = crate::common::load_from_file(&manifest_path)?
+                    .parse()
+                    .context(format!("parsing {}", manifest_path.display()))?;
+
+                excluded_crates.push(ExcludedCrate {
+                    name: manifest["package"]["name"].as_str().map(str::to_string),
+                    listed_in_exclude: excluded_paths
+                        .iter()
+                        .any(|excluded_path| path.starts_with(excluded_path)),
+                    path,
+                });
+            }
+
+            debug!("excluded crates: {:?}", excluded_crates);
+
+            Ok(excluded_crates)
+        })
+    }
+
+    /// Compares the `never_release` list of the workspace manifest against the workspace members.
+    pub fn never_release_drift(&'a self) -> Fallible<NeverReleaseDrift> {
+        let never_release = &self.release_metadata()?.never_release;
+        let members = self.members_unsorted()?;
+        let member_names = members.iter().map(Crate::name).collect::<HashSet<_>>();
+
+        Ok(NeverReleaseDrift {
+            unknown: never_release
+                .iter()
+                .filter(|name| !member_names.contains(*name))
+                .cloned()
+                .collect(),
+            unlisted_unpublishable: members
+                .iter()
+                .filter(|member| !member.publishable() && !never_release.contains(&member.name()))
+                .map(Crate::name)
+                .collect(),
+        })
+    }
+
+    /// Finds the shortest chain of workspace dependencies that leads from a matched crate to the crate with the given name.
+    fn dependency_chain(&'a self, name: &str) -> Fallible<Option<Vec<String>>> {
+        let members = self
+            .members_unsorted()?
+            .iter()
+            .map(|member| (member.name(), member))
+            .collect::<HashMap<_, _>>();
+
+        for matched in self.members_matched()? {
+            let mut queue = std::collections::VecDeque::from(vec![vec![matched.name()]]);
+            let mut seen = HashSet::new();
+
+            while let Some(chain) = queue.pop_front() {
+                let last = chain.last().expect("chains are never empty").clone();
+                if last == name {
+                    return Ok(Some(chain));
+                }
+
+                if !seen.insert(last.clone()) {
+                    continue;
+                }
+
+                let member = if let Some(member) = members.get(&last) {
+                    member
+                } else {
+                    continue;
+                };
+
+                for dep in member.package().dependencies() {
+                    if dep.is_optional() && self.criteria.exclude_optional_deps {
+                        continue;
+                    }
+
+                    let dep_name = dep.package_name().to_string();
+                    if members.contains_key(&dep_name)
+                        && dep.specified_req()
+                        && dep.version_req().to_string() != "*"
+                        && !seen.contains(&dep_name)
+                    {
+                        queue.push_back([chain.clone(), vec![dep_name]].concat());
+                    }
+                }
+            }
+        }
+
+        Ok(None)
+    }
+
+    fn members_unsorted(&'a self) -> Fallible<&'a Vec<Crate<'a>>> {
+        self.members_unsorted.get_or_try_init(|| {
+            let mut members = vec![];
+
+            for package in self.cargo_workspace()?.members() {
+                members.push(Crate::with_cargo_package(package.to_owned(), self)?);
+            }
+
+            Ok(members)
+        })
+    }
+
+    /// Returns the names of the members that depend directly on each member, which is built once for all members.
+    pub fn reverse_dependency_index(&'a self) -> Fallible<&'a ReverseDependencyIndex> {
+        self.reverse_dependency_index.get_or_try_init(|| {
+            self.reverse_dependency_index_builds
+                .fetch_add(1, Ordering::Relaxed);
+
+            let ws_members = self
+                .members_unsorted()?
+                .iter()
+                .map(|member| (member.name(), &member.package))
+                .collect::<HashMap<_, _>>();
+
+            let mut index = ReverseDependencyIndex::new();
+            for member in self.members_unsorted()? {
+                for dep in self.workspace_dependencies_of(&member.package, &ws_members)? {
+                    index
+                        .entry(dep.package_name().to_string())
+                        .or_default()
+                        .insert(member.name());
+                }
+            }
+
+            Ok(index)
+        })
+    }
+
+    /// Return all member crates matched by `SelectionCriteria::match_filter`
+    pub fn members_matched(&'a self) -> Fallible<&'a Vec<&'a Crate<'a>>> {
+        self.members_matched.get_or_try_init(|| {
+            let states = self.members_states()?;
+
+            self.members().map(|members| {
+                members
+                    .into_iter()
+                    .filter(|crt| {
+                        states
+                            .get(&crt.name())
+                            .cloned()
+                            .unwrap_or_else(|| {
+                                warn!(
+                                    "cannot get CrateState for {}, using default state",
+                                    crt.name()
+                                );
+                                CrateState::default()
+                            })
+                            .contains(CrateStateFlags::Matched)
+                    })
+                    .map(|crt| *crt)
+                    .collect::<Vec<_>>()
+            })
+        })
+    }
+
+    /// Returns all non-excluded workspace members.
+    /// Members are sorted according to their dependency tree from most independent to most dependent,
+    /// and by name where that leaves a choice.
+    pub fn members(&'a self) -> Fallible<&'a Vec<&'a Crate<'a>>> {
+        self.members_sorted
+            .get_or_try_init(|| sort_members(&self.members_unsorted()?.iter().collect::<Vec<_>>()))
+    }
+
+    /// Return the root path of the workspace.
+    pub fn root(&'a self) -> &Path {
+        &self.root_path
+    }
+
+    pub fn git_repo(&'a self) -> &git2::Repository {
+        &self.git_repo
+    }
+
+    /// Tries to resolve the git HEAD to its corresponding branch.
+    pub fn git_head_branch(&'a self) -> Fallible<HeadState> {
+        if self.git_repo.head_detached()? {
+            let oid = self
+                .git_repo
+                .head()?
+                .target()
+                .ok_or_else(|| anyhow::anyhow!("repo head doesn't have a target"))?;
+
+            return Ok(HeadState::Detached(oid));
+        }
+
+        for branch in self.git_repo.branches(None)? {
+            let (branch, branch_type) = branch?;
+            if branch.is_head() {
+                return Ok(HeadState::Branch(branch, branch_type));
+            }
+        }
+
+        bail!("head branch not found")
+    }
+
+    /// Calls Self::git_head_branch and tries to resolve its name to String.
+    ///
+    /// If HEAD is detached this returns the configured placeholder or the short commit id.
+    pub fn git_head_branch_name(&'a self) -> Fallible<String> {
+        match self.git_head_branch()? {
+            HeadState::Branch(branch, _) => branch
+                .name()?
+                .map(String::from)
+                .ok_or_else(|| anyhow::anyhow!("the current git branch has no name")),
+            HeadState::Detached(oid) => match &self.git_options.detached_head_placeholder {
+                Some(placeholder) => Ok(placeholder.clone()),
+                None => self
+                    .git_repo
+                    .find_object(oid, None)?
+                    .short_id()?
+                    .as_str()
+                    .map(String::from)
+                    .ok_or_else(|| anyhow::anyhow!("short id of {} is not valid UTF-8", oid)),
+            },
+        }
+    }
+
+    /// Like Self::git_head_branch_name but fails with `GitHeadError::Detached` if HEAD is detached.
+    pub fn git_head_branch_name_required(&'a self) -> Fallible<String> {
+        if let HeadState::Detached(oid) = self.git_head_branch()? {
+            bail!(GitHeadError::Detached(oid));
+        }
+
+        self.git_head_branch_name()
+    }
+
+    /// Creates a git branch with the given name off of the current HEAD, optionally overwriting the branch if it exists.
+    ///
+    /// Refuses to check out the branch over local modifications, unless `force` is set in which case they're discarded.
+    pub fn git_checkout_branch(&'a self, name: &str, force: bool) -> Fallible<git2::Branch> {
+        if !force {
+            self.git_ensure_clean_worktree()?;
+        }
+
+        self.journal_begin()?;
+
+        let head_commit = self.git_repo.head()?.peel_to_commit()?;
+
+        let previous_target = self
+            .git_repo
+            .find_branch(name, git2::BranchType::Local)
+            .ok()
+            .and_then(|branch| branch.get().target());
+
+        let new_branch = self.git_repo.branch(name, &head_commit, force)?;
+        self.journal_record(JournalEntry::Branch {
+            name: name.to_string(),
+            previous_target: previous_target.map(|oid| oid.to_string()),
+        })?;
+
+        self.git_checkout_reference(name, force)?;
+
+        Ok(new_branch)
+    }
+
+    /// Creates a new git branch with the given name off of the current HEAD and fails if it already exists.
+    ///
+    /// Refuses to check out the branch over local modifications, unless `force` is set in which case they're discarded.
+    pub fn git_checkout_new_branch(&'a self, name: &str, force: bool) -> Fallible<git2::Branch> {
+        self.git_checkout_new_branch_with_policy(name, BranchPolicy::ErrorIfExists, force)
+    }
+
+    /// Creates a new git branch with the given name off of the current HEAD.
+    /// The `policy` determines what happens if the branch already exists.
+    ///
+    /// Refuses to check out the branch over local modifications, unless `force` is set in which case they're discarded.
+    pub fn git_checkout_new_branch_with_policy(
+        &'a self,
+        name: &str,
+        policy: BranchPolicy,
+        force: bool,
+    ) -> Fallible<git2::Branch<'a>> {
+        if !force {
+            self.git_ensure_clean_worktree()?;
+        }
+
+        self.journal_begin()?;
+
+        let head_commit = self.git_repo.head()?.peel_to_commit()?;
+
+        let branch = match (
+            self.git_repo
+                .find_branch(name, git2::BranchType::Local)
+                .ok(),
+            policy,
+        ) {
+            (None, _) => {
+                let branch = self.git_repo.branch(name, &head_commit, false)?;
+                self.journal_record(JournalEntry::Branch {
+                    name: name.to_string(),
+                    previous_target: None,
+                })?;
+                branch
+            }
+
+            (Some(_), BranchPolicy::ErrorIfExists) => bail!(
+                "branch '{}' already exists. delete it or choose a different branch policy",
+                name
+            ),
+
+            (Some(existing), BranchPolicy::ReuseExisting) => {
+                debug!("reusing existing branch '{}'", name);
+                existing
+            }
+
+            (Some(existing), BranchPolicy::Recreate) if existing.is_head() => {
+                debug!(
+                    "branch '{}' is already checked out, there's nothing to recreate",
+                    name
+                );
+                existing
+            }
+
+            (Some(mut existing), BranchPolicy::Recreate) => {
+                debug!("recreating branch '{}' from HEAD", name);
+                let previous_target = existing.get().target();
+                existing.delete()?;
+                let branch = self.git_repo.branch(name, &head_commit, false)?;
+                self.journal_record(JournalEntry::Branch {
+                    name: name.to_string(),
+                    previous_target: previous_target.map(|oid| oid.to_string()),
+                })?;
+                branch
+            }
+        };
+
+        self.git_checkout_reference(name, force)?;
+
+        Ok(branch)
+    }
+
+    /// Returns the paths of all tracked files which have been modified in the index or the working tree.
+    pub fn git_dirty_paths(&self) -> Fallible<Vec<String>> {
+        let statuses = self.git_repo.statuses(Some(
+            git2::StatusOptions::new()
+                .include_untracked(false)
+                .include_ignored(false),
+        ))?;
+
+        Ok(statuses
+            .iter()
+            .filter(|entry| entry.status() != git2::Status::CURRENT)
+            .map(|entry| String::from_utf8_lossy(entry.path_bytes()).to_string())
+            .collect())
+    }
+
+    /// Errors with a `DirtyWorktreeError` if there are modified tracked files or untracked files, unless `allow_dirty` is set in the criteria.
+    pub fn ensure_clean_worktree(&self) -> Fallible<()> {
+        if self.criteria.allow_dirty {
+            return Ok(());
+        }
+
+        let statuses = self.git_repo.statuses(Some(
+            git2::StatusOptions::new()
+                .include_untracked(true)
+                .include_ignored(false),
+        ))?;
+
+        let (untracked, modified): (Vec<_>, Vec<_>) = statuses
+            .iter()
+            .filter(|entry| entry.status() != git2::Status::CURRENT)
+            .partition(|entry| entry.status() == git2::Status::WT_NEW);
+
+        let to_paths = |entries: Vec<git2::StatusEntry>| {
+            entries
+                .iter()
+                .map(|entry| String::from_utf8_lossy(entry.path_bytes()).to_string())
+                .collect::<Vec<_>>()
+        };
+
+        let error = DirtyWorktreeError {
+            modified: to_paths(modified),
+            untracked: to_paths(untracked),
+        };
+        if !error.modified.is_empty() || !error.untracked.is_empty() {
+            bail!(error);
+        }
+
+        Ok(())
+    }
+
+    /// Errors if the current branch doesn't match any of the allowed branch patterns in the criteria.
+    ///
+    /// A detached HEAD is only allowed if `allow_detached_head` is set in the criteria.
+    pub fn ensure_allowed_branch(&'a self) -> Fallible<()> {
+        let branch_name = match self.git_head_branch()? {
+            HeadState::Detached(_) if self.criteria.allow_detached_head => return Ok(()),
+            HeadState::Detached(oid) => bail!(GitHeadError::Detached(oid)),
+            HeadState::Branch(branch, _) => branch
+                .name()?
+                .ok_or_else(|| anyhow::anyhow!("could not get branch name"))?
+                .to_string(),
+        };
+
+        let patterns = &self.criteria.allowed_branch_patterns;
+        if patterns.is_empty() {
+            return Ok(());
+        }
+
+        for pattern in patterns {
+            if pattern.is_match(&branch_name)? {
+                return Ok(());
+            }
+        }
+
+        bail!(DisallowedBranchError {
+            branch: branch_name,
+            patterns: patterns
+                .iter()
+                .map(|pattern| pattern.as_str().to_string())
+                .collect(),
+        })
+    }
+
+    /// Errors if there are any local modifications to tracked files.
+    fn git_ensure_clean_worktree(&self) -> Fallible<()> {
+        let dirty_paths = self.git_dirty_paths()?;
+        if !dirty_paths.is_empty() {
+            bail!(
+                "refusing to check out over local modifications in {} path(s):\n{}",
+                dirty_paths.len(),
+                dirty_paths
+                    .iter()
+                    .map(|path| format!("- {}\n", path))
+                    .collect::<String>()
+            );
+        }
+
+        Ok(())
+    }
+
+    /// Checks out the given reference and points HEAD to it.
+    ///
+    /// Without `force` the working tree is only touched if the target tree differs from the one of HEAD.
+    fn git_checkout_reference(&self, name: &str, force: bool) -> Fallible<()> {
+        let (object, reference) = self.git_repo.revparse_ext(name)?;
+
+        let head_tree_id = self.git_repo.head()?.peel_to_tree()?.id();
+        let target_tree_id = object.peel_to_tree()?.id();
+
+        if force {
+            self.git_repo
+                .checkout_tree(&object, Some(git2::build::CheckoutBuilder::new().force()))?;
+        } else if head_tree_id != target_tree_id {
+            self.git_repo
+                .checkout_tree(&object, Some(git2::build::CheckoutBuilder::new().safe()))?;
+        } else {
+            trace!("'{}' has the same tree as HEAD, skipping checkout", name);
+        }
+
+        let reference_name = reference
+            .ok_or_else(|| anyhow::anyhow!("couldn't parse branch new branch to reference"))?
+            .name()
+            .ok_or_else(|| anyhow::anyhow!("couldn't get reference name"))?
+            .to_owned();
+
+        self.git_repo.set_head(&reference_name)?;
+
+        Ok(())
+    }
+
+    // todo: make this configurable?
+    fn git_signature(&self) -> Fallible<git2::Signature> {
+        let (name, email) = self.git_identity()?;
+
+        Ok(git2::Signature::now(&name, &email)?)
+    }
+
+    /// Returns the name and email used for commits and tags.
+    pub fn git_identity(&self) -> Fallible<(String, String)> {
+        Ok(Self::resolve_git_identity(
+            &self.git_options,
+            &self.git_repo.config()?,
+        ))
+    }
+
+    /// Returns the signing key if signing is requested.
+    ///
+    /// Fails if signing is requested but no secret key is available for signing.
+    pub fn git_signing_key(&self) -> Fallible<Option<String>> {
+        if !self.git_options.sign {
+            return Ok(None);
+        }
+
+        let signing_key = match self.git_options.signing_key.clone().or_else(|| {
+            self.git_repo
+                .config()
+                .ok()?
+                .get_string("user.signingkey")
+                .ok()
+        }) {
+            Some(signing_key) => signing_key,
+            None => bail!(
+                "signing was requested but no key was given and user.signingkey is not set in the git config"
+            ),
+        };
+
+        let mut cmd = Command::new("gpg");
+        if let Some(gnupg_home) = &self.git_options.gnupg_home {
+            cmd.env("GNUPGHOME", gnupg_home);
+        }
+        let output = cmd
+            .arg("--batch")
+            .arg("--list-secret-keys")
+            .arg(&signing_key)
+            .output()
+            .context("signing was requested but gpg could not be run")?;
+        if !output.status.success() {
+            bail!(
+                "signing was requested but no secret key is available for '{}'",
+                signing_key
+            );
+        }
+
+        Ok(Some(signing_key))
+    }
+
+    /// Returns a `git` command in the workspace root that uses the resolved git identity.
+    fn git_command_with_identity(&self) -> Fallible<Command> {
+        let (name, email) = self.git_identity()?;
+
+        let mut cmd = Command::new("git");
+        cmd.current_dir(&self.root_path)
+            .arg("-c")
+            .arg(format!("user.name={}", name))
+            .arg("-c")
+            .arg(format!("user.email={}", email));
+        if let Some(gnupg_home) = &self.git_options.gnupg_home {
+            cmd.env("GNUPGHOME", gnupg_home);
+        }
+
+        Ok(cmd)
+    }
+
+    /// Resolves the git identity in the order of precedence: explicit options, git config, defaults.
+    fn resolve_git_identity(git_options: &GitOptions, config: &git2::Config) -> (String, String) {
+        let resolve = |explicit: &Option<String>, key: &str, default: &str| {
+            explicit
+                .clone()
+                .or_else(|| config.get_string(key).ok())
+                .unwrap_or_else(|| default.to_string())
+        };
+
+        (
+            resolve(&git_options.name, "user.name", Self::GIT_CONFIG_NAME),
+            resolve(&git_options.email, "user.email", Self::GIT_CONFIG_EMAIL),
+        )
+    }
+
+    /// Add the given files and create a commit.
+    ///
+    /// If the resulting tree is identical to the one of HEAD no commit is created and HEAD is returned,
+    /// unless `allow_empty` is set.
+    pub fn git_add_all_and_commit(
+        &'a self,
+        msg: &str,
+        path_filter: Option<&mut git2::IndexMatchedPath<'_>>,
+        allow_empty: bool,
+    ) -> Fallible<git2::Oid> {
+        let mut index = self.git_repo().index()?;
+        index.add_all(["*"].iter(), git2::IndexAddOption::DEFAULT, path_filter)?;
+        index.write()?;
+
+        self.git_commit_index(msg, allow_empty)
+    }
+
+    /// Stage exactly the given paths and create a commit, leaving all other changes untouched.
+    ///
+    /// The paths are expected to be relative to the workspace root; absolute paths within the workspace are accepted as well.
+    /// Paths which don't exist in the working tree anymore are removed from the index, untracked ignored paths are skipped.
+    /// If the resulting tree is identical to the one of HEAD no commit is created and HEAD is returned.
+    pub fn git_commit_paths(&'a self, msg: &str, paths: &[&Path]) -> Fallible<git2::Oid> {
+        let mut index = self.git_repo().index()?;
+
+        for path in paths {
+            let relative_path = if path.is_absolute() {
+                path_relative_to(path, self.root()).ok_or_else(|| {
+                    anyhow::anyhow!(
+                        "path {} is not within the workspace at {}",
+                        path.display(),
+                        self.root().display()
+                    )
+                })?
+            } else {
+                path.to_path_buf()
+            };
+
+            if self.root().join(&relative_path).exists() {
+                if index.get_path(&relative_path, 0).is_none()
+                    && self.git_repo().is_path_ignored(&relative_path)?
+                {
+                    debug!("not adding ignored path {:?}", relative_path);
+                    continue;
+                }
+                index.add_path(&relative_path)?;
+            } else {
+                index.remove_path(&relative_path)?;
+            }
+        }
+        index.write()?;
+
+        self.git_commit_index(msg, false)
+    }
+
+    /// Create a commit from the current state of the index.
+    fn git_commit_index(&'a self, msg: &str, allow_empty: bool) -> Fallible<git2::Oid> {
+        let repo = self.git_repo();
+
+        let tree_id = repo.index()?.write_tree()?;
+
+        // an unborn HEAD results in a root commit without parents
+        let mut parents = Vec::new();
+        if let Some(parent) = repo.head().ok().and_then(|head| head.target()) {
+            parents.push(repo.find_commit(parent)?)
+        }
+
+        if !allow_empty {
+            if let Some(head_commit) = parents.first() {
+                if head_commit.tree_id() == tree_id {
+                    debug!("nothing to commit, skipping commit '{}'", msg);
+                    return Ok(head_commit.id());
+                }
+            }
+        }
+
+        self.journal_begin()?;
+        let parent = parents.first().map(|parent| parent.id().to_string());
+
+        let commit_oid = if let Some(signing_key) = self.git_signing_key()? {
+            run_checked(
+                self.git_command_with_identity()?
+                    .arg("commit")
+                    .arg("--allow-empty")
+                    .arg("--no-verify")
+                    .arg(format!("--gpg-sign={}", signing_key))
+                    .arg("-m")
+                    .arg(msg),
+            )?;
+
+            repo.head()?
+                .target()
+                .ok_or_else(|| anyhow::anyhow!("repo head doesn't have a target"))?
+        } else {
+            let sig = self.git_signature()?;
+            let parents = parents.iter().collect::<Vec<_>>();
+            repo.commit(
+                Some("HEAD"),
+                &sig,
+                &sig,
+                msg,
+                &repo.find_tree(tree_id)?,
+                &parents,
+            )?
+        };
+
+        self.journal_record(JournalEntry::Commit {
+            reference: repo
+                .head()?
+                .name()
+                .filter(|name| name.starts_with("refs/heads/"))
+                .map(String::from),
+            oid: commit_oid.to_string(),
+            parent,
+        })?;
+
+        Ok(commit_oid)
+    }
+
+    /// Create a new git tag of the given kind from HEAD.
+    ///
+    /// Only annotated tags can be signed.
+    pub fn git_tag(&self, name: &str, kind: TagKind, force: bool) -> Fallible<CreatedTag> {
+        self.git_tag_at(name, None, kind, force)
+    }
+
+    /// Create a new git tag of the given kind at the given commit, or HEAD if none is given.
+    ///
+    /// Only annotated tags can be signed.
+    pub fn git_tag_at(
+        &self,
+        name: &str,
+        commit: Option<git2::Oid>,
+        kind: TagKind,
+        force: bool,
+    ) -> Fallible<CreatedTag> {
+        self.journal_begin()?;
+
+        if let Some(signing_key) = self.git_signing_key()? {
+            let message = match &kind {
+                TagKind::Annotated { message } => message,
+                TagKind::Lightweight => bail!(
+                    "cannot sign the lightweight tag '{}', only annotated tags can be signed",
+                    name
+                ),
+            };
+
+            let mut cmd = self.git_command_with_identity()?;
+            cmd.arg("tag")
+                .arg(format!("--local-user={}", signing_key))
+                .arg("--sign")
+                .arg("-m")
+                .arg(message);
+            if force {
+                cmd.arg("--force");
+            }
+            cmd.arg(name);
+            if let Some(commit) = commit {
+                cmd.arg(commit.to_string());
+            }
+            run_checked(&mut cmd).context(format!("creating signed tag '{}'", name))?;
+
+            let tag_oid = self
+                .git_repo
+                .refname_to_id(&format!("refs/tags/{}", name))?;
+
+            return self.tag_created(name, tag_oid);
+        }
+
+        let target = match commit {
+            Some(commit) => commit,
+            None => match self.git_repo.head() {
+                Ok(head) => head,
+                Err(err) if err.code() == git2::ErrorCode::UnbornBranch => bail!(
+                    "cannot create tag '{}' because the repository has no commits yet",
+                    name
+                ),
+                Err(err) => return Err(err.into()),
+            }
+            .target()
+            .ok_or_else(|| anyhow::anyhow!("repo head doesn't have a target"))?,
+        };
+        let target = self.git_repo.find_object(target, None)?;
+
+        let tag_oid = match &kind {
+            TagKind::Annotated { message } => {
+                self.git_repo
+                    .tag(name, &target, &self.git_signature()?, message, force)
+            }
+            TagKind::Lightweight => self.git_repo.tag_lightweight(name, &target, force),
+        }
+        .context(format!("creating tag '{}'", name))?;
+
+        self.tag_created(name, tag_oid)
+    }
+
+    /// Create the given tags from HEAD and handle already existing tags according to the policy.
+    ///
+    /// All existing tags are checked before any tag is created.
+    pub fn create_release_tags(
+        &self,
+        tags: Vec<(String, TagKind)>,
+        policy: ExistingTagPolicy,
+        dry_run: bool,
+    ) -> Fallible<ReleaseTagsReport> {
+        let head_tree_id = self.git_repo.head()?.peel_to_tree()?.id();
+
+        let mut report = ReleaseTagsReport::default();
+        let mut to_create = vec![];
+        let mut existing_tags = vec![];
+
+        for (name, kind) in tags {
+            let existing_oid = self.tag_index()?.get(&name).cloned();
+
+            match (existing_oid, policy) {
+                (None, _) => to_create.push((name, kind, false)),
+                (Some(_), ExistingTagPolicy::Error) => existing_tags.push(name),
+                (Some(_), ExistingTagPolicy::Skip) => {
+                    info!("tag '{}' already exists, skipping", name);
+                    report.skipped.push(name);
+                }
+                (Some(oid), ExistingTagPolicy::Force) => {
+                    let tagged_tree_id = self.git_repo.find_object(oid, None)?.peel_to_tree()?.id();
+                    if tagged_tree_id != head_tree_id {
+                        bail!(
+                            "refusing to recreate tag '{}' because it points at a different tree than HEAD",
+                            name
+                        );
+                    }
+                    to_create.push((name, kind, true));
+                }
+            }
+        }
+
+        if !existing_tags.is_empty() {
+            bail!(
+                "the following tags already exist: {}",
+                existing_tags
+                    .iter()
+                    .map(|tag| format!("\n- {}", tag))
+                    .collect::<String>()
+            );
+        }
+
+        for (name, kind, force) in to_create {
+            debug!("creating tag '{}': {:?}", name, kind);
+            if !dry_run {
+                self.git_tag(&name, kind, force)?;
+            }
+            report.created.push(name);
+        }
+
+        Ok(report)
+    }
+
+    /// Fetch all tags from the given remote and invalidate the tag index.
+    pub fn git_fetch_tags(&self, remote_name: &str) -> Fallible<()> {
+        let mut remote = self
+            .git_repo
+            .find_remote(remote_name)
+            .context(format!("looking up remote '{}'", remote_name))?;
+
+        remote
+            .fetch(&["refs/tags/*:refs/tags/*"], None, None)
+            .context(format!("fetching tags from remote '{}'", remote_name))?;
+
+        *lock_cache(&self.tag_index) = None;
+
+        Ok(())
+    }
+
+    /// Derives the release tag name for the given crate version from the configured template.
+    /// The template configured in the crate's manifest metadata takes precedence.
+    pub fn release_tag_name(&'a self, crate_name: &str, version: &Version) -> Fallible<String> {
+        Ok(format_release_tag_name(
+            &self.crate_release_tag_template(crate_name)?,
+            crate_name,
+            version,
+        ))
+    }
+
+    /// Returns the versions of the given crate which have a release tag, in ascending order.
+    pub fn released_versions(&'a self, crate_name: &str) -> Fallible<Vec<Version>> {
+        let template = self.crate_release_tag_template(crate_name)?;
+
+        let mut versions = self
+            .tag_index()?
+            .keys()
+            .filter_map(
+                |tag_name| match parse_release_tag_name(&template, tag_name)? {
+                    (Some(name), version) if name == crate_name => Some(version),
+                    // the crate's own template doesn't need to contain its name
+                    (None, version) => Some(version),
+                    _ => None,
+                },
+            )
+            .collect::<Vec<_>>();
+        versions.sort();
+
+        Ok(versions)
+    }
+
+    /// The release tag template configured in the crate's manifest metadata, or the one of the criteria.
+    fn crate_release_tag_template(&'a self, crate_name: &str) -> Fallible<String> {
+        let crate_template = match self
+            .members_unsorted()?
+            .iter()
+            .find(|member| member.name() == crate_name)
+        {
+            Some(member) => member.metadata_config()?.tag_template.clone(),
+            None => None,
+        };
+
+        Ok(crate_template.unwrap_or_else(|| self.release_tag_template().to_string()))
+    }
+
+    /// Parses a release tag name that was derived from the configured template back into the crate name and version.
+    pub fn parse_release_tag_name(&self, tag_name: &str) -> Option<(Option<String>, Version)> {
+        parse_release_tag_name(self.release_tag_template(), tag_name)
+    }
+
+    fn release_tag_template(&self) -> &str {
+        self.criteria
+            .release_tag_template
+            .as_deref()
+            .unwrap_or(DEFAULT_RELEASE_TAG_TEMPLATE)
+    }
+
+    /// Returns the names of all tags that were created via this workspace instance.
+    pub fn created_tags(&self) -> Vec<String> {
+        lock_cache(&self.created_tags).clone()
+    }
+
+    /// The name of the registry to release to, as given to `cargo publish --registry`.
+    pub fn registry(&self) -> &str {
+        self.criteria
+            .registry
+            .as_deref()
+            .unwrap_or(CRATES_IO_REGISTRY_NAME)
+    }
+
+    /// The index of the registry to release to, which is queried with the workspace's HTTP client unless overridden.
+    fn crates_index(&self) -> Fallible<Box<dyn CratesIndex + '_>> {
+        if let Some(crates_index) = &self.crates_index {
+            return Ok(Box::new(crates_index.as_ref()));
+        }
+
+        let url = self
+            .crates_index_url
+            .get_or_try_init(|| match &self.criteria.registry {
+                None => Ok(CRATES_IO_SPARSE_INDEX_URL.to_string()),
+                Some(registry) => {
+                    crate::crates_index::registry_index_url(&self.root_path, registry)
+                }
+            })?;
+
+        Ok(Box::new(SparseIndex::new(url, self.http_client.as_ref())))
+    }
+
+    /// Returns the versions of the given crate that are published according to the crates index.
+    /// Successful responses are cached for the lifetime of the workspace.
+    pub fn published_versions(&self, name: &str) -> Fallible<Vec<Version>> {
+        if let Some(versions) = lock_cache(&self.published_versions_cache).get(name) {
+            return Ok(versions.clone());
+        }
 
-            // ensure members are ordered respecting their dependency tree
-            members.sort_unstable_by(move |(a_i, a), (b_i, b)| {
-                use std::cmp::Ordering::{Equal, Greater, Less};
-
-                let a_deps = workspace_dependencies
-                    .get(&a.name())
-                    .unwrap_or_else(|| panic!("dependencies for {} not found", a.name()));
-                let b_deps = workspace_dependencies
-                    .get(&b.name())
-                    .unwrap_or_else(|| panic!("dependencies for {} not found", b.name()));
-
-                // understand whether one is a direct dependency of the other
-                let comparison = (a_deps.contains(&b.name()), b_deps.contains(&a.name()));
-                let result = match comparison {
-                    (true, true) => {
-                        panic!("cyclic dependency between {} and {}", a.name(), b.name())
-                    }
-                    (true, false) => Greater,
-                    (false, true) => Less,
-                    (false, false) => a_i.cmp(b_i),
-                };
+        self.crates_index_queries.fetch_add(1, Ordering::Relaxed);
+        let versions = self.crates_index()?.published_versions(name)?;
+        trace!("[{}] published versions: {:?}", name, versions);
 
-                trace!(
-                    "comparing \n{} ({:?}) with \n{} ({:?})\n{:?} => {:?}",
-                    a.name(),
-                    a_deps,
-                    b.name(),
-                    b_deps,
-                    comparison,
-                    result
-                );
-                result
-            });
+        lock_cache(&self.published_versions_cache).insert(name.to_string(), versions.clone());
 
-            Ok(members.into_iter().map(|(_, member)| member).collect())
-        })
+        Ok(versions)
     }
 
-    /// Return the root path of the workspace.
-    pub fn root(&'a self) -> &Path {
-        &self.root_path
-    }
+    /// Waits until the given version of the crate is visible in the crates index, see `crates_index::wait_for_index_availability`.
+    pub fn wait_for_index_availability(
+        &self,
+        crate_name: &str,
+        version: &Version,
+        timeout: std::time::Duration,
+    ) -> Fallible<()> {
+        crate::crates_index::wait_for_index_availability(
+            self.crates_index()?.as_ref(),
+            crate_name,
+            version,
+            timeout,
+        )?;
 
-    pub fn git_repo(&'a self) -> &git2::Repository {
-        &self.git_repo
+        // the cached versions predate the publish
+        lock_cache(&self.published_versions_cache).remove(crate_name);
+
+        Ok(())
     }
 
-    /// Tries to resolve the git HEAD to its corresponding branch.
-    pub fn git_head_branch(&'a self) -> Fallible<(git2::Branch, git2::BranchType)> {
-        for branch in self.git_repo.branches(None)? {
-            let branch = branch?;
-            if branch.0.is_head() {
-                return Ok(branch);
+    /// Looks for a release of the given crate's manifest version that is published in the registry but lacks its release tag.
+    ///
+    /// Returns the commit that last set the manifest version, see `create_missing_release_tags`.
+    fn reconcile_untagged_release(&'a self, member: &Crate<'a>) -> Fallible<Option<git2::Oid>> {
+        if !member.publishable() {
+            return Ok(None);
+        }
+
+        let version = member.version();
+        let tag_name = self.release_tag_name(&member.name(), &version)?;
+        if self.tag_index()?.contains_key(&tag_name) {
+            return Ok(None);
+        }
+
+        match self.published_versions(&member.name()) {
+            Ok(published_versions) if published_versions.contains(&version) => {}
+            Ok(_) => return Ok(None),
+            Err(err) if self.criteria.strict => {
+                return Err(err.context(format!(
+                    "looking up the published versions of '{}'",
+                    member.name()
+                )));
+            }
+            Err(err) => {
+                warn!(
+                    "[{}] couldn't look up the published versions, not reconciling the missing release tag: {:?}",
+                    member.name(),
+                    err
+                );
+                return Ok(None);
             }
         }
 
-        bail!("head branch not found")
-    }
+        let commit = match self.git_commit_setting_version(member, &version)? {
+            Some(commit) => commit,
+            None => {
+                warn!(
+                    "'{}' version {} is published in the registry but the commit that set it in the manifest couldn't be found",
+                    member.name(),
+                    version
+                );
+                return Ok(None);
+            }
+        };
 
-    /// Calls Self::git_head_branch and tries to resolve its name to String.
-    pub fn git_head_branch_name(&'a self) -> Fallible<String> {
-        self.git_head_branch().map(|(branch, _)| {
-            branch
-                .name()?
-                .map(String::from)
-                .ok_or_else(|| anyhow::anyhow!("the current git branch has no name"))
-        })?
-    }
+        warn!(
+            "'{}' version {} is published in the registry but the tag '{}' is missing, treating commit {} as its release",
+            member.name(),
+            version,
+            tag_name,
+            commit
+        );
 
-    /// Creates a git branch with the given name off of the current HEAD, optionally overwriting the branch if it exists.
-    pub fn git_checkout_branch(&'a self, name: &str, force: bool) -> Fallible<git2::Branch> {
-        let head_commit = self.git_repo.head()?.peel_to_commit()?;
+        Ok(Some(commit))
+    }
 
-        let new_branch = self.git_repo.branch(name, &head_commit, force)?;
+    /// Returns the most recent commit in the first-parent history of HEAD that set the given crate's manifest version
+    /// to the given one, i.e. whose first parent has another version or none at all.
+    ///
+    /// The versions are read from the `[package]` table of each commit's manifest, so requirements on dependencies
+    /// with the same version aren't mistaken for it. An inherited version is read from the root manifest of the commit.
+    fn git_commit_setting_version(
+        &'a self,
+        member: &Crate<'a>,
+        version: &Version,
+    ) -> Fallible<Option<git2::Oid>> {
+        let workdir = self
+            .git_repo
+            .workdir()
+            .ok_or_else(|| anyhow!("the repository doesn't have a working directory"))?;
+        let relative_path = |path: &Path| {
+            path_relative_to(path, workdir)
+                .ok_or_else(|| anyhow!("{:?} is not within the repository at {:?}", path, workdir))
+        };
+        let manifest_path = relative_path(member.manifest_path())?;
+        let root_manifest_path = relative_path(&self.root_manifest_path())?;
+
+        let version_at = |commit: &git2::Commit| -> Fallible<Option<Version>> {
+            let tree = commit.tree()?;
+            let manifest = match git_manifest_at(&self.git_repo, &tree, &manifest_path)? {
+                Some(manifest) => manifest,
+                None => return Ok(None),
+            };
 
-        let (object, reference) = self.git_repo.revparse_ext(name)?;
+            let version = if is_workspace_inherited(&manifest["package"]["version"]) {
+                git_manifest_at(&self.git_repo, &tree, &root_manifest_path)?.and_then(
+                    |root_manifest| {
+                        root_manifest["workspace"]["package"]["version"]
+                            .as_str()
+                            .map(str::to_string)
+                    },
+                )
+            } else {
+                manifest["package"]["version"].as_str().map(str::to_string)
+            };
 
-        self.git_repo.checkout_tree(&object, None)?;
+            Ok(version.and_then(|version| Version::parse(&version).ok()))
+        };
 
-        let reference_name = reference
-            .ok_or_else(|| anyhow::anyhow!("couldn't parse branch new branch to reference"))?
-            .name()
-            .ok_or_else(|| anyhow::anyhow!("couldn't get reference name"))?
-            .to_owned();
+        let mut revwalk = self.git_repo.revwalk()?;
+        revwalk.push_head()?;
+        revwalk.simplify_first_parent()?;
+        for oid in revwalk {
+            let commit = self.git_repo.find_commit(oid?)?;
+            if version_at(&commit)?.as_ref() != Some(version) {
+                continue;
+            }
 
-        self.git_repo.set_head(&reference_name)?;
+            let parent_version = match commit.parents().next() {
+                Some(parent) => version_at(&parent)?,
+                None => None,
+            };
+            if parent_version.as_ref() != Some(version) {
+                return Ok(Some(commit.id()));
+            }
+        }
 
-        Ok(new_branch)
+        Ok(None)
     }
 
-    /// Creates a new git branch with the given name off of the current HEAD.
-    pub fn git_checkout_new_branch(&'a self, name: &str) -> Fallible<git2::Branch> {
-        self.git_checkout_branch(name, false)
-    }
+    /// Creates the missing release tags of the crates whose previous release was reconciled, see `reconcile_untagged_release`.
+    /// Returns the names of the tags, which are only logged in a dry run.
+    pub fn create_missing_release_tags(&'a self, dry_run: bool) -> Fallible<Vec<String>> {
+        let mut tag_names = vec![];
 
-    // todo: make this configurable?
-    fn git_signature(&self) -> Fallible<git2::Signature> {
-        Ok(git2::Signature::now(
-            &self.git_config_name,
-            &self.git_config_email,
-        )?)
-    }
+        for member in self.members()? {
+            let state = member.state()?;
+            let commit = match state.untagged_release_commit() {
+                Some(commit) => git2::Oid::from_str(commit)?,
+                None => continue,
+            };
+            let tag_name = self.release_tag_name(&member.name(), &member.version())?;
 
-    /// Add the given files and create a commit.
-    pub fn git_add_all_and_commit(
-        &'a self,
-        msg: &str,
-        path_filter: Option<&mut git2::IndexMatchedPath<'_>>,
-    ) -> Fallible<git2::Oid> {
-        let repo = self.git_repo();
+            if dry_run {
+                info!(
+                    "[dry-run] would create the missing tag '{}' at commit {}",
+                    tag_name, commit
+                );
+            } else {
+                self.git_tag_at(
+                    &tag_name,
+                    Some(commit),
+                    TagKind::default_annotated(&tag_name),
+                    false,
+                )?;
+                info!(
+                    "created the missing tag '{}' at commit {}",
+                    tag_name, commit
+                );
+            }
 
-        let mut index = repo.index()?;
-        index.add_all(["*"].iter(), git2::IndexAddOption::DEFAULT, path_filter)?;
-        index.write()?;
+            tag_names.push(tag_name);
+        }
 
-        let tree_id = repo.index()?.write_tree()?;
-        let sig = self.git_signature()?;
-        let mut parents = Vec::new();
+        Ok(tag_names)
+    }
 
-        if let Some(parent) = repo.head().ok().map(|h| h.target().unwrap()) {
-            parents.push(repo.find_commit(parent)?)
+    /// Returns an index of all git tags in the repository, which is built once and then cached.
+    /// The index stays locked while it's borrowed.
+    pub fn tag_index(&self) -> Fallible<TagIndexGuard<'_>> {
+        let mut tag_index = lock_cache(&self.tag_index);
+        if tag_index.is_none() {
+            self.tag_index_builds.fetch_add(1, Ordering::Relaxed);
+
+            let mut built_index = TagIndex::new();
+            for name in self.git_repo.tag_names(None)?.iter().flatten() {
+                let oid = self
+                    .git_repo
+                    .refname_to_id(&format!("refs/tags/{}", name))?;
+                built_index.insert(name.to_string(), oid);
+            }
+
+            trace!("indexed {} git tags", built_index.len());
+            *tag_index = Some(built_index);
         }
-        let parents = parents.iter().collect::<Vec<_>>();
-        repo.commit(
-            Some("HEAD"),
-            &sig,
-            &sig,
-            msg,
-            &repo.find_tree(tree_id)?,
-            &parents,
-        )
-        .map_err(anyhow::Error::from)
+
+        Ok(TagIndexGuard(tag_index))
     }
 
-    /// Create a new git tag from HEAD
-    pub fn git_tag(&self, name: &str, force: bool) -> Fallible<git2::Oid> {
-        let head = self
-            .git_repo
-            .head()?
-            .target()
-            .ok_or_else(|| anyhow::anyhow!("repo head doesn't have a target"))?;
-        self.git_repo
-            .tag(
-                name,
-                &self.git_repo.find_object(head, None)?,
-                &self.git_signature()?,
-                &format!("tag for release {}", name),
-                force,
-            )
-            .context(format!("creating tag '{}'", name))
+    /// Keeps track of a newly created tag.
+    fn tag_created(&self, name: &str, oid: git2::Oid) -> Fallible<CreatedTag> {
+        self.insert_into_tag_index(name, oid);
+        lock_cache(&self.created_tags).push(name.to_string());
+
+        self.journal_record(JournalEntry::Tag {
+            name: name.to_string(),
+            oid: oid.to_string(),
+        })?;
+
+        Ok(CreatedTag {
+            name: name.to_string(),
+            oid,
+        })
+    }
+
+    /// Keeps an already built tag index up to date with newly created tags.
+    fn insert_into_tag_index(&self, name: &str, oid: git2::Oid) {
+        if let Some(tag_index) = lock_cache(&self.tag_index).as_mut() {
+            tag_index.insert(name.to_string(), oid);
+        }
     }
 
     pub fn changelog(&'a self) -> Option<&'a ChangelogT<'a, WorkspaceChangelog>> {
@@ -1341,44 +6062,587 @@ impl<'a> ReleaseWorkspace<'a> {
     }
 }
 
-/// Use the `git` shell command to detect changed files in the given directory between the given revisions.
+/// Parses the manifest at the given path in the tree of a commit, if it exists.
+fn git_manifest_at(
+    git_repo: &git2::Repository,
+    tree: &git2::Tree,
+    path: &Path,
+) -> Fallible<Option<toml_edit::Document>> {
+    let entry = match tree.get_path(path) {
+        Ok(entry) => entry,
+        Err(err) if err.code() == git2::ErrorCode::NotFound => return Ok(None),
+        Err(err) => return Err(err.into()),
+    };
+    let blob = entry.to_object(git_repo)?.peel_to_blob()?;
+
+    Ok(Some(
+        std::str::from_utf8(blob.content())?
+            .parse()
+            .context(format!("parsing manifest at {:?}", path))?,
+    ))
+}
+
+/// Extracts the failed checks and the required increment from the human readable output of `cargo semver-checks`, e.g.
 ///
-/// Inspired by: https://github.com/sunng87/cargo-release/blob/master/src/git.rs
+/// ```text
+/// --- failure function_missing: pub fn removed or renamed ---
+/// ...
+///      Summary semver requires new major version: 1 major and 0 minor checks failed
+/// ```
+fn parse_semver_checks_output(baseline_rev: &str, output: &str) -> SemverChecksReport {
+    let findings = output
+        .lines()
+        .filter_map(|line| {
+            line.trim()
+                .strip_prefix("--- failure ")
+                .map(|finding| finding.trim_end_matches('-').trim().to_string())
+        })
+        .collect::<Vec<_>>();
+
+    let required_increment = if output.contains("requires new major version") {
+        Some(SemverIncrementMode::Major)
+    } else if output.contains("requires new minor version") {
+        Some(SemverIncrementMode::Minor)
+    } else if !findings.is_empty() {
+        Some(SemverIncrementMode::Breaking)
+    } else {
+        None
+    };
+
+    SemverChecksReport {
+        baseline_rev: baseline_rev.to_string(),
+        required_increment,
+        findings,
+    }
+}
+
+/// Whether the manifest value is inherited from the workspace, i.e. `{ workspace = true }`.
+fn is_workspace_inherited(item: &toml_edit::Item) -> bool {
+    item.as_inline_table()
+        .map(|table| table.contains_key("workspace"))
+        .or_else(|| item.as_table().map(|table| table.contains_key("workspace")))
+        .unwrap_or_default()
+}
+
+/// Returns whether the bump from the current to the next version still needs to be applied to the manifest version.
+/// Errors if the manifest version matches neither of them, e.g. because it was bumped by a different plan.
+fn ensure_bump_pending(
+    name: &str,
+    manifest_version: &Version,
+    current_version: &Version,
+    next_version: &Version,
+) -> Fallible<bool> {
+    if manifest_version == next_version {
+        info!(
+            "[{}] the bump from {} to {} is already applied, skipping it",
+            name, current_version, next_version
+        );
+        return Ok(false);
+    }
+
+    if manifest_version != current_version {
+        bail!(
+            "[{}] expected version {} or {} in the manifest according to the plan, found {}",
+            name,
+            current_version,
+            next_version,
+            manifest_version
+        );
+    }
+
+    Ok(true)
+}
+
+/// Replaces the string value of the item while keeping the whitespace and comments around it.
+fn replace_str_value(item: &mut toml_edit::Item, new_value: String) {
+    let (prefix, suffix) = item
+        .as_value()
+        .map(|value| {
+            (
+                value.decor().prefix().to_string(),
+                value.decor().suffix().to_string(),
+            )
+        })
+        .unwrap_or_else(|| (" ".to_string(), "".to_string()));
+
+    *item = toml_edit::Item::Value(toml_edit::decorated(
+        new_value.as_str().into(),
+        &prefix,
+        &suffix,
+    ));
+}
+
+/// Updates the requirements on the given dependency in the `dependencies`, `dev-dependencies` and `build-dependencies` tables
+/// of the given table, which is either the root of a manifest, a `target.{cfg}` table or the `workspace` table.
+/// Renamed dependencies are recognized by their `package` key.
+fn update_dependency_requirements_in(
+    parent: &mut toml_edit::Table,
+    name: &str,
+    version: &Version,
+) -> Fallible<bool> {
+    let mut modified = false;
+
+    for key in &["dependencies", "dev-dependencies", "build-dependencies"] {
+        let dependencies = match parent.get_mut(key).and_then(toml_edit::Item::as_table_mut) {
+            Some(dependencies) => dependencies,
+            None => continue,
+        };
+
+        let dependency_keys = dependencies
+            .iter()
+            .filter(|(dependency_key, dependency)| {
+                *dependency_key == name || dependency["package"].as_str() == Some(name)
+            })
+            .map(|(dependency_key, _)| dependency_key.to_string())
+            .collect::<Vec<_>>();
+
+        for dependency_key in dependency_keys {
+            let dependency = match dependencies.get_mut(&dependency_key) {
+                Some(dependency) => dependency,
+                None => continue,
+            };
+
+            if is_workspace_inherited(dependency) {
+                trace!("{} {} is inherited from the workspace", key, dependency_key);
+                continue;
+            }
+
+            // dependencies without a requirement, e.g. path-only dev-dependencies, are left alone
+            let existing_version_req = match dependency
+                .as_str()
+                .or_else(|| dependency["version"].as_str())
+            {
+                Some(existing_version_req) => existing_version_req.to_string(),
+                None => continue,
+            };
+
+            if version_req_matches(&VersionReq::parse(&existing_version_req)?, version) {
+                trace!(
+                    "{} {} requirement {} already accommodates {}",
+                    key,
+                    dependency_key,
+                    existing_version_req,
+                    version
+                );
+                continue;
+            }
+
+            let new_version_req = rewrite_version_req(&existing_version_req, version)?;
+            trace!(
+                "rewriting {} {} requirement {} to {}",
+                key,
+                dependency_key,
+                existing_version_req,
+                new_version_req
+            );
+            if dependency.as_str().is_some() {
+                replace_str_value(dependency, new_version_req);
+            } else {
+                replace_str_value(&mut dependency["version"], new_version_req);
+            }
+            modified = true;
+        }
+    }
+
+    Ok(modified)
+}
+
+/// Returns a requirement on the given version in the style of the existing one, i.e. with the same operator and precision,
+/// e.g. `0.1` becomes `0.2`, `~0.1.3` becomes `~0.2.0` and `=0.1.3` becomes `=0.2.0`.
+/// Falls back to the full version for requirements with multiple comparators or other operators,
+/// and to the full precision if the reduced one wouldn't match the version, e.g. for pre-releases.
+fn rewrite_version_req(existing: &str, version: &Version) -> Fallible<String> {
+    let existing_version_req = VersionReq::parse(existing)?;
+    let comparator = match &existing_version_req.comparators[..] {
+        [comparator]
+            if matches!(
+                comparator.op,
+                Op::Exact | Op::Tilde | Op::Caret | Op::Wildcard
+            ) =>
+        {
+            comparator
+        }
+        _ => return Ok(version.to_string()),
+    };
+
+    let mut new_comparator = Comparator {
+        op: comparator.op,
+        major: version.major,
+        minor: comparator.minor.map(|_| version.minor),
+        patch: comparator.patch.map(|_| version.patch),
+        pre: semver::Prerelease::EMPTY,
+    };
+    if comparator.patch.is_some() {
+        new_comparator.pre = version.pre.clone();
+    }
+
+    // cargo only selects a pre-release if the requirement mentions it
+    let reduced_precision_matches = VersionReq {
+        comparators: vec![new_comparator.clone()],
+    }
+    .matches(version);
+    if !reduced_precision_matches {
+        new_comparator = Comparator {
+            op: match comparator.op {
+                Op::Wildcard => Op::Caret,
+                op => op,
+            },
+            major: version.major,
+            minor: Some(version.minor),
+            patch: Some(version.patch),
+            pre: version.pre.clone(),
+        };
+    }
+
+    let new_version_req = new_comparator.to_string();
+
+    // `0.1` and `^0.1` are equivalent, keep whichever was used
+    match new_version_req.strip_prefix('^') {
+        Some(stripped) if !existing.trim_start().starts_with('^') => Ok(stripped.to_string()),
+        _ => Ok(new_version_req),
+    }
+}
+
+/// Returns whether the given version is semver compatible with the previous one, i.e. it's within the caret range `^previous`.
+fn is_semver_compatible(previous: &Version, version: &Version) -> bool {
+    VersionReq {
+        comparators: vec![Comparator {
+            op: Op::Caret,
+            major: previous.major,
+            minor: Some(previous.minor),
+            patch: Some(previous.patch),
+            pre: previous.pre.clone(),
+        }],
+    }
+    .matches(version)
+}
+
+/// Returns whether the given requirement pins a single version exactly, e.g. `=0.1.3`.
+fn is_exact_version_req(version_req: &VersionReq) -> bool {
+    matches!(
+        &version_req.comparators[..],
+        [comparator] if comparator.op == Op::Exact && comparator.patch.is_some()
+    )
+}
+
+/// Returns the first line of cargo's output that reports an error, or the last line if there's none.
+pub(crate) fn first_error_line(cargo_output: &str) -> String {
+    cargo_output
+        .lines()
+        .map(str::trim)
+        .find(|line| line.starts_with("error"))
+        .or_else(|| {
+            cargo_output
+                .lines()
+                .map(str::trim)
+                .rfind(|line| !line.is_empty())
+        })
+        .unwrap_or_default()
+        .to_string()
+}
+
+/// Runs the given command to completion and fails with its stderr if it doesn't succeed.
+fn run_checked(cmd: &mut Command) -> Fallible<()> {
+    debug!("running command: {:?}", cmd);
+
+    let output = cmd.output()?;
+    if !output.status.success() {
+        bail!(
+            "running {:?} failed: \n{}",
+            cmd,
+            String::from_utf8_lossy(&output.stderr)
+        );
+    }
+
+    Ok(())
+}
+
+/// Detect changed files in the given directory between the given revisions.
+///
+/// Uses libgit2 unless the `changed-files-git-cli` feature is enabled.
 fn changed_files(dir: &Path, from_rev: &str, to_rev: &str) -> Fallible<Vec<PathBuf>> {
-    use bstr::ByteSlice;
+    if cfg!(feature = "changed-files-git-cli") {
+        #[allow(deprecated)]
+        return changed_files_git_cli(dir, from_rev, to_rev);
+    }
+
+    let repo = git2::Repository::discover(dir)?;
+    let workdir = repo
+        .workdir()
+        .ok_or_else(|| anyhow!("repository at {:?} has no working directory", repo.path()))?;
+
+    // the diff paths are relative to the repository root
+    let dir_relative = dir
+        .canonicalize()?
+        .strip_prefix(workdir.canonicalize()?)?
+        .to_path_buf();
+
+    // the whole tree is diffed so renames across the directory boundary can be detected
+    let from_tree = repo.revparse_single(from_rev)?.peel_to_tree()?;
+    let to_tree = repo.revparse_single(to_rev)?.peel_to_tree()?;
+    let mut diff = repo.diff_tree_to_tree(Some(&from_tree), Some(&to_tree), None)?;
+    diff.find_similar(Some(git2::DiffFindOptions::new().renames(true)))?;
+
+    // both sides of a rename are reported if they're within the directory
+    let mut paths = LinkedHashSet::new();
+    for delta in diff.deltas() {
+        for path_bytes in [delta.old_file().path_bytes(), delta.new_file().path_bytes()]
+            .iter()
+            .flatten()
+        {
+            if let Ok(path) = git_path_to_native(path_bytes)?.strip_prefix(&dir_relative) {
+                paths.insert(dir.join(path));
+            }
+        }
+    }
 
+    Ok(paths.into_iter().collect())
+}
+
+/// Use the `git` shell command to detect changed files in the given directory between the given revisions.
+///
+/// Inspired by: https://github.com/sunng87/cargo-release/blob/master/src/git.rs
+#[deprecated(note = "superseded by the libgit2 based implementation in `changed_files`")]
+fn changed_files_git_cli(dir: &Path, from_rev: &str, to_rev: &str) -> Fallible<Vec<PathBuf>> {
     let output = Command::new("git")
         .arg("diff")
         .arg(&format!("{}..{}", from_rev, to_rev))
         .arg("--name-only")
         .arg("--exit-code")
+        .arg("-z")
         .arg(".")
         .current_dir(dir)
         .output()?;
 
     match output.status.code() {
         Some(0) => Ok(Vec::new()),
-        Some(1) => {
-            let paths = output
-                .stdout
-                .lines()
-                .map(|l| dir.join(l.to_path_lossy()))
-                .collect();
-            Ok(paths)
-        }
+        Some(1) => output
+            .stdout
+            .split(|byte| *byte == 0)
+            .filter(|path_bytes| !path_bytes.is_empty())
+            .map(|path_bytes| Ok(dir.join(git_path_to_native(path_bytes)?)))
+            .collect(),
         code => Err(anyhow!("git exited with code: {:?}", code)),
     }
 }
 
-/// Find a git tag in a repository
+/// Converts a path as reported by git, i.e. raw bytes with `/` as separator, into a native path.
+#[cfg(unix)]
+fn git_path_to_native(path_bytes: &[u8]) -> Fallible<PathBuf> {
+    use std::os::unix::ffi::OsStrExt;
+
+    Ok(PathBuf::from(std::ffi::OsStr::from_bytes(path_bytes)))
+}
+
+/// Converts a path as reported by git, i.e. UTF-8 with `/` as separator, into a native path.
+#[cfg(not(unix))]
+fn git_path_to_native(path_bytes: &[u8]) -> Fallible<PathBuf> {
+    Ok(std::str::from_utf8(path_bytes)?.split('/').collect())
+}
+
+/// Returns the path relative to the given directory if it's located within it.
+///
+/// Falls back to comparing the canonicalized paths if they aren't directly comparable.
+pub(crate) fn path_relative_to(path: &Path, dir: &Path) -> Option<PathBuf> {
+    path.strip_prefix(dir)
+        .ok()
+        .map(Path::to_path_buf)
+        .or_else(|| {
+            path.canonicalize()
+                .ok()?
+                .strip_prefix(dir.canonicalize().ok()?)
+                .ok()
+                .map(Path::to_path_buf)
+        })
+}
+
+/// Sorts the members according to their dependency tree from most independent to most dependent.
+/// Crates that are ready at the same time are ordered by name, which makes the order independent of the given one.
+fn sort_members<'b>(members: &[&'b Crate<'b>]) -> Fallible<Vec<&'b Crate<'b>>> {
+    let indices = members
+        .iter()
+        .enumerate()
+        .map(|(i, member)| (member.name(), i))
+        .collect::<HashMap<_, _>>();
+
+    // build the dependency graph as adjacency lists from each crate to its dependants
+    let mut dependants = vec![Vec::<usize>::new(); members.len()];
+    let mut in_degrees = vec![0_usize; members.len()];
+
+    for (i, member) in members.iter().enumerate() {
+        for (dep_name, deps) in member.dependencies_in_workspace()? {
+            if !deps
+                .iter()
+                .any(|dep| dep.specified_req() && dep.version_req().to_string() != "*")
+            {
+                continue;
+            }
+
+            if let Some(&j) = indices.get(dep_name) {
+                if i != j {
+                    dependants[j].push(i);
+                    in_degrees[i] += 1;
+                }
+            }
+        }
+    }
+
+    // Kahn's algorithm. the crates that are ready to be emitted are ordered by their name
+    // as cargo's order of the members differs between its versions.
+    let mut ready = in_degrees
+        .iter()
+        .enumerate()
+        .filter(|(_, in_degree)| **in_degree == 0)
+        .map(|(i, _)| (members[i].name(), i))
+        .collect::<BTreeSet<_>>();
+    let mut sorted = Vec::with_capacity(members.len());
+
+    while let Some((name, i)) = ready.iter().next().cloned() {
+        ready.remove(&(name, i));
+        sorted.push(members[i]);
+
+        for &j in &dependants[i] {
+            in_degrees[j] -= 1;
+            if in_degrees[j] == 0 {
+                ready.insert((members[j].name(), j));
+            }
+        }
+
+        trace!(
+            "[{}] sorted at position {}",
+            members[i].name(),
+            sorted.len() - 1
+        );
+    }
+
+    if sorted.len() != members.len() {
+        bail!(
+            "cyclic dependencies between the following crates: {:?}",
+            in_degrees
+                .iter()
+                .enumerate()
+                .filter(|(_, in_degree)| **in_degree > 0)
+                .map(|(i, _)| members[i].name())
+                .collect::<Vec<_>>()
+        );
+    }
+
+    Ok(sorted)
+}
+
+/// Derives the anchor GitHub generates for a markdown heading with the given text, e.g. `011` for `0.1.1`.
+pub(crate) fn github_heading_anchor(heading: &str) -> String {
+    heading
+        .trim()
+        .to_lowercase()
+        .chars()
+        .filter_map(|c| match c {
+            ' ' => Some('-'),
+            c if c.is_alphanumeric() || c == '-' || c == '_' => Some(c),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Joins the path's components with `/` so that globs behave the same on all platforms.
+pub(crate) fn normalized_path(path: &Path) -> String {
+    path.components()
+        .map(|component| component.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// Parses a `rust-version` like "1.60" into a full semver version, e.g. "1.60.0".
+pub fn parse_rust_version(input: &str) -> Fallible<Version> {
+    let mut parts = input.trim().split('.').collect::<Vec<_>>();
+    if parts.is_empty() || parts.len() > 3 {
+        bail!("invalid rust version '{}'", input);
+    }
+    parts.resize(3, "0");
+
+    Version::parse(&parts.join(".")).context(format!("invalid rust version '{}'", input))
+}
+
+/// The maximum size of the packaged files in bytes that's used unless one is configured.
+pub const DEFAULT_MAX_PACKAGE_SIZE: u64 = 10 * 1024 * 1024;
+
+/// The template for release tag names that's used unless one is configured.
+pub const DEFAULT_RELEASE_TAG_TEMPLATE: &str = "{name}-{version}";
+
+/// Derives the release tag name for the given crate version from the default template.
+pub(crate) fn release_tag_name(crate_name: &str, version: &Version) -> String {
+    format_release_tag_name(DEFAULT_RELEASE_TAG_TEMPLATE, crate_name, version)
+}
+
+/// Derives the release tag name by replacing `{name}` and `{version}` in the template.
+pub(crate) fn format_release_tag_name(
+    template: &str,
+    crate_name: &str,
+    version: &Version,
+) -> String {
+    template
+        .replace("{name}", crate_name)
+        .replace("{version}", &version.to_string())
+}
+
+/// Parses a tag name that was derived from the given template back into the crate name and version.
+///
+/// The crate name is `None` if the template doesn't contain `{name}`.
+pub(crate) fn parse_release_tag_name(
+    template: &str,
+    tag_name: &str,
+) -> Option<(Option<String>, Version)> {
+    let pattern = template
+        .split("{version}")
+        .map(|part| {
+            part.split("{name}")
+                .map(regex::escape)
+                .collect::<Vec<_>>()
+                .join("(?P<name>.+)")
+        })
+        .collect::<Vec<_>>()
+        .join(r"(?P<version>\d+\.\d+\.\d+(?:-[0-9A-Za-z.-]+)?(?:\+[0-9A-Za-z.-]+)?)");
+
+    let captures = Regex::new(&format!("^{}$", pattern))
+        .ok()?
+        .captures(tag_name)?;
+
+    Some((
+        captures.name("name").map(|name| name.as_str().to_string()),
+        Version::parse(captures.name("version")?.as_str()).ok()?,
+    ))
+}
+
+/// Whether the version meets the requirement by precedence alone.
+///
+/// `VersionReq::matches` only lets a pre-release match if one of the comparators is a pre-release of the same version,
+/// so e.g. `0.2.0-rc.1` would neither match `>=0.1` nor `<0.3`. That's meant for dependency resolution,
+/// but the requirements on the crates' own versions need to apply to pre-releases as well.
+pub(crate) fn version_req_matches(req: &VersionReq, version: &Version) -> bool {
+    if version.pre.is_empty() {
+        return req.matches(version);
+    }
+
+    // the lowest possible pre-release of the version opts into pre-release matching without restricting the requirement
+    let mut req = req.clone();
+    req.comparators.push(Comparator {
+        op: Op::GreaterEq,
+        major: version.major,
+        minor: Some(version.minor),
+        patch: Some(version.patch),
+        pre: semver::Prerelease::new("0").expect("valid pre-release"),
+    });
+
+    req.matches(version)
+}
+
+/// Find a git tag in a repository, regardless of whether it's annotated or lightweight.
 // todo: refactor into common place module
 pub fn git_lookup_tag(git_repo: &git2::Repository, tag_name: &str) -> Option<String> {
     let tag = git_repo
-        .revparse_single(tag_name)
+        .find_reference(&format!("refs/tags/{}", tag_name))
         .ok()
-        .map(|obj| obj.as_tag().cloned())
-        .flatten()
-        .map(|tag| tag.name().unwrap_or_default().to_owned());
+        .map(|_| tag_name.to_owned());
 
     trace!("looking up tag '{}' -> {:?}", tag_name, tag);
 
@@ -1,16 +1,46 @@
 use super::*;
 
+use crate::crates_index::{CratesIndex, HttpClient, CRATES_IO_API_URL};
+use crate::phases::ReleasePhaseOptions;
+use crate::plan::PlannedCrate;
+use crate::progress::ProgressReporter;
+use crate::publish::unpublished_dependency;
+use crate::rollback::{PublishOutcome, ReleaseJournal};
 use crate::tests::workspace_mocker::{
     example_workspace_1, example_workspace_2, example_workspace_3,
+    example_workspace_broken_changelog, example_workspace_broken_manifest,
+    example_workspace_cascade, example_workspace_chain, example_workspace_chain_of_four,
+    example_workspace_crate_metadata, example_workspace_cycle, example_workspace_dep_kinds,
+    example_workspace_dependency_requirements, example_workspace_diamond,
+    example_workspace_external_dependency, example_workspace_formatted_manifest,
+    example_workspace_frontmatter_increments, example_workspace_generated,
+    example_workspace_git_dependency, example_workspace_inherited_dependencies,
+    example_workspace_inherited_versions, example_workspace_keywords_and_categories,
+    example_workspace_large_package, example_workspace_license_files,
+    example_workspace_malformed_changelogs, example_workspace_msrv, example_workspace_nested,
+    example_workspace_nested_excluded, example_workspace_never_release,
+    example_workspace_optional_dependency, example_workspace_overlapping_names,
+    example_workspace_path_dep_version, example_workspace_platform_dependencies,
+    example_workspace_prerelease_versions, example_workspace_promotion,
+    example_workspace_publish_false, example_workspace_publish_metadata,
+    example_workspace_release_candidate, example_workspace_released,
+    example_workspace_renamed_crate, example_workspace_semver_checks,
+    example_workspace_untagged_release,
+    example_workspace_untagged_release_with_matching_dependency,
+    example_workspace_version_changelog, example_workspace_wildcard, released_changelog,
+    MockProject, WorkspaceMocker,
 };
 use enumflags2::make_bitflags;
+use std::cell::RefCell;
+use std::rc::Rc;
 use std::str::FromStr;
+use test_case::test_case;
 
 #[ctor::ctor]
 fn init_logger() {
-    env_logger::builder()
-        .filter_level(log::LevelFilter::Trace)
-        .is_test(true)
+    tracing_subscriber::fmt()
+        .with_max_level(tracing::Level::TRACE)
+        .with_test_writer()
         .init();
 }
 
@@ -36,184 +66,6474 @@ fn detect_changed_files() {
 }
 
 #[test]
-fn workspace_members() {
+fn changed_files_implementations_agree() {
+    let workspace_mocker = example_workspace_1().unwrap();
+    let before = workspace_mocker.head().unwrap();
+
+    workspace_mocker.add_or_replace_file("ADDED", "added");
+    workspace_mocker.add_or_replace_file("crates/crate_a/README.md", "modified");
+    workspace_mocker
+        .remove_file("crates/crate_b/CHANGELOG.md")
+        .unwrap();
+    let after = workspace_mocker.commit(None);
+
+    let workspace = ReleaseWorkspace::try_new(workspace_mocker.root()).unwrap();
+
+    let mut changed = changed_files(workspace.root(), &before, &after).unwrap();
+    changed.sort();
+    #[allow(deprecated)]
+    let mut changed_git_cli = changed_files_git_cli(workspace.root(), &before, &after).unwrap();
+    changed_git_cli.sort();
+
+    let root = PathBuf::from(workspace.root());
+    assert_eq!(
+        vec![
+            root.join("ADDED"),
+            root.join("crates/crate_a/README.md"),
+            root.join("crates/crate_b/CHANGELOG.md"),
+        ],
+        changed
+    );
+    assert_eq!(changed, changed_git_cli);
+}
+
+#[test]
+fn changed_files_in_subdirectory() {
+    let workspace_mocker = example_workspace_1().unwrap();
+    let before = workspace_mocker.head().unwrap();
+
+    workspace_mocker.add_or_replace_file("ADDED", "added");
+    workspace_mocker.add_or_replace_file("crates/crate_a/README.md", "modified");
+    let after = workspace_mocker.commit(None);
+
+    let crate_dir = workspace_mocker.root().join("crates").join("crate_a");
+
+    assert_eq!(
+        vec![crate_dir.join("README.md")],
+        changed_files(&crate_dir, &before, &after).unwrap()
+    );
+}
+
+/// Moves the previous release tag of crate_a in example_workspace_1 to HEAD and commits the given changes on top.
+fn example_workspace_1_with_changes_since_crate_a_tag(
+    changes: &[(&str, &str)],
+) -> crate::tests::workspace_mocker::WorkspaceMocker {
+    let workspace_mocker = example_workspace_1().unwrap();
+
+    git2::Repository::open(workspace_mocker.root())
+        .unwrap()
+        .tag_delete("crate_a-0.0.1")
+        .unwrap();
+    workspace_mocker.tag("crate_a-0.0.1");
+
+    for (path, content) in changes {
+        workspace_mocker.add_or_replace_file(path, content);
+    }
+    workspace_mocker.commit(None);
+
+    workspace_mocker
+}
+
+#[test]
+fn change_detection_ignores_changelog_only_changes() {
+    let workspace_mocker = example_workspace_1_with_changes_since_crate_a_tag(&[(
+        "crates/crate_a/CHANGELOG.md",
+        "# Changelog\n\n## Unreleased\n\n## 0.0.1\n",
+    )]);
+    let workspace = ReleaseWorkspace::try_new(workspace_mocker.root()).unwrap();
+
+    let state = workspace.members_states().unwrap().get("crate_a").unwrap();
+    assert!(
+        state.contains(CrateStateFlags::HasPreviousRelease),
+        "{:#?}",
+        state
+    );
+    assert!(
+        !state.contains(CrateStateFlags::ChangedSincePreviousRelease),
+        "{:#?}",
+        state
+    );
+}
+
+#[test]
+fn change_detection_considers_non_ignored_changes() {
+    let workspace_mocker = example_workspace_1_with_changes_since_crate_a_tag(&[
+        (
+            "crates/crate_a/CHANGELOG.md",
+            "# Changelog\n\n## Unreleased\n\n## 0.0.1\n",
+        ),
+        ("crates/crate_a/src/main.rs", "fn main() { println!(); }"),
+    ]);
+    let workspace = ReleaseWorkspace::try_new(workspace_mocker.root()).unwrap();
+
+    let state = workspace.members_states().unwrap().get("crate_a").unwrap();
+    assert!(
+        state.contains(CrateStateFlags::ChangedSincePreviousRelease),
+        "{:#?}",
+        state
+    );
+}
+
+#[test]
+fn workspace_file_changes_affect_released_crates() {
+    let workspace_mocker = example_workspace_1_with_changes_since_crate_a_tag(&[]);
+    let root_manifest =
+        std::fs::read_to_string(workspace_mocker.root().join("Cargo.toml")).unwrap();
+    workspace_mocker.add_or_replace_file("Cargo.toml", &format!("{}\n# changed\n", root_manifest));
+    workspace_mocker.commit(None);
+
+    let workspace = ReleaseWorkspace::try_new(workspace_mocker.root()).unwrap();
+    let state = workspace.members_states().unwrap().get("crate_a").unwrap();
+    assert!(
+        !state.contains(CrateStateFlags::ChangedSincePreviousRelease),
+        "{:#?}",
+        state
+    );
+
+    let criteria = SelectionCriteria {
+        workspace_change_paths: vec![PathBuf::from("Cargo.toml")],
+        ..Default::default()
+    };
+    let workspace =
+        ReleaseWorkspace::try_new_with_criteria(workspace_mocker.root(), criteria).unwrap();
+    let members_states = workspace.members_states().unwrap();
+
+    let previously_released = members_states
+        .iter()
+        .filter(|(_, state)| state.contains(CrateStateFlags::HasPreviousRelease))
+        .collect::<Vec<_>>();
+    assert!(!previously_released.is_empty());
+    for (name, state) in previously_released {
+        assert!(
+            state.contains(CrateStateFlags::ChangedSincePreviousRelease)
+                && state.contains(CrateStateFlags::WorkspaceFilesChanged),
+            "{}: {:#?}",
+            name,
+            state
+        );
+    }
+}
+
+#[cfg(unix)]
+#[test]
+fn changed_files_with_non_utf8_name() {
+    use std::os::unix::ffi::OsStrExt;
+
+    let workspace_mocker = example_workspace_1().unwrap();
+    let before = workspace_mocker.head().unwrap();
+
+    let file_name = std::ffi::OsStr::from_bytes(b"invalid-\xff.txt");
+    std::fs::write(workspace_mocker.root().join(file_name), "content").unwrap();
+    let after = workspace_mocker.commit(None);
+
+    let expected = vec![workspace_mocker.root().join(file_name)];
+    assert_eq!(
+        expected,
+        changed_files(&workspace_mocker.root(), &before, &after).unwrap()
+    );
+    #[allow(deprecated)]
+    let changed_git_cli = changed_files_git_cli(&workspace_mocker.root(), &before, &after).unwrap();
+    assert_eq!(expected, changed_git_cli);
+}
+
+#[test]
+fn git_paths_are_converted_to_native_paths() {
+    assert_eq!(
+        ["src", "nested", "lib.rs"].iter().collect::<PathBuf>(),
+        git_path_to_native(b"src/nested/lib.rs").unwrap()
+    );
+}
+
+#[cfg(windows)]
+#[test]
+fn path_relative_to_with_mixed_separators() {
+    assert_eq!(
+        Some(PathBuf::from(r"src\lib.rs")),
+        path_relative_to(
+            Path::new(r"C:\workspace\crates/crate_a\src/lib.rs"),
+            Path::new(r"C:\workspace\crates\crate_a"),
+        )
+    );
+}
+
+#[test]
+fn changed_files_are_cached() {
+    let workspace_mocker = example_workspace_1().unwrap();
+    let root = workspace_mocker.root();
+    let mut workspace = ReleaseWorkspace::try_new(root.clone()).unwrap();
+
+    let uncached = workspace
+        .changed_files_cached(&root, "crate_a-0.0.1", "HEAD")
+        .unwrap();
+    // the cached lookup only resolves the revisions instead of diffing the trees, see `changed_files_cache_speedup`
+    let cached = workspace
+        .changed_files_cached(&root, "crate_a-0.0.1", "HEAD")
+        .unwrap();
+
+    assert_eq!(uncached, cached);
+    assert_eq!(
+        1,
+        workspace.changed_files_computations.load(Ordering::Relaxed)
+    );
+
+    workspace.reset_state();
+    workspace
+        .changed_files_cached(&root, "crate_a-0.0.1", "HEAD")
+        .unwrap();
+    assert_eq!(
+        2,
+        workspace.changed_files_computations.load(Ordering::Relaxed)
+    );
+}
+
+#[test]
+fn changed_files_cache_can_be_disabled() {
+    let workspace_mocker = example_workspace_1().unwrap();
+    let root = workspace_mocker.root();
+    let criteria = SelectionCriteria {
+        no_change_detection_cache: true,
+        ..Default::default()
+    };
+    let workspace = ReleaseWorkspace::try_new_with_criteria(root.clone(), criteria).unwrap();
+
+    for _ in 0..2 {
+        workspace
+            .changed_files_cached(&root, "crate_a-0.0.1", "HEAD")
+            .unwrap();
+    }
+    assert_eq!(
+        2,
+        workspace.changed_files_computations.load(Ordering::Relaxed)
+    );
+}
+
+/// Compares the durations of repeated change detections with and without the cache.
+/// Timings vary between machines, so this only runs on request with `cargo test -- --ignored --nocapture`.
+#[test]
+#[ignore = "measures timing"]
+fn changed_files_cache_speedup() {
+    const LOOKUPS: usize = 100;
+
+    let workspace_mocker = example_workspace_1().unwrap();
+    let root = workspace_mocker.root();
+
+    let measure = |no_change_detection_cache| {
+        let workspace = ReleaseWorkspace::try_new_with_criteria(
+            root.clone(),
+            SelectionCriteria {
+                no_change_detection_cache,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        let start = std::time::Instant::now();
+        for _ in 0..LOOKUPS {
+            workspace
+                .changed_files_cached(&root, "crate_a-0.0.1", "HEAD")
+                .unwrap();
+        }
+        start.elapsed()
+    };
+
+    let uncached = measure(true);
+    let cached = measure(false);
+    eprintln!(
+        "{} change detections took {:?} uncached and {:?} cached",
+        LOOKUPS, uncached, cached
+    );
+    assert!(cached < uncached);
+}
+
+#[test]
+fn lookup_annotated_and_lightweight_tags() {
+    let workspace_mocker = example_workspace_1().unwrap();
+    let repo = git2::Repository::open(workspace_mocker.root()).unwrap();
+
+    workspace_mocker.tag("annotated");
+    let head = repo.revparse_single("HEAD").unwrap();
+    repo.tag_lightweight("lightweight", &head, false).unwrap();
+
+    assert_eq!(
+        Some("annotated".to_string()),
+        git_lookup_tag(&repo, "annotated")
+    );
+    assert_eq!(
+        Some("lightweight".to_string()),
+        git_lookup_tag(&repo, "lightweight")
+    );
+    assert_eq!(None, git_lookup_tag(&repo, "missing"));
+}
+
+#[test]
+fn lightweight_tag_counts_as_previous_release() {
+    let workspace_mocker = example_workspace_1().unwrap();
+    let repo = git2::Repository::open(workspace_mocker.root()).unwrap();
+
+    repo.tag_delete("crate_a-0.0.1").unwrap();
+    let head = repo.revparse_single("HEAD").unwrap();
+    repo.tag_lightweight("crate_a-0.0.1", &head, false).unwrap();
+
+    let workspace = ReleaseWorkspace::try_new(workspace_mocker.root()).unwrap();
+    let state = workspace.members_states().unwrap().get("crate_a").unwrap();
+    assert!(
+        state.contains(CrateStateFlags::HasPreviousRelease),
+        "{:#?}",
+        state
+    );
+    assert!(
+        !state.contains(CrateStateFlags::MissingReleaseTag),
+        "{:#?}",
+        state
+    );
+}
+
+#[test]
+fn fetch_tags_from_remote() {
+    let workspace_mocker = example_workspace_1().unwrap();
+    let tmpdir = tempfile::tempdir().unwrap();
+    let bare = tmpdir.path().join("bare");
+    let clone = tmpdir.path().join("clone");
+
+    for args in &[
+        vec![
+            "clone".to_string(),
+            "--bare".to_string(),
+            workspace_mocker.root().display().to_string(),
+            bare.display().to_string(),
+        ],
+        vec![
+            "clone".to_string(),
+            "--no-tags".to_string(),
+            bare.display().to_string(),
+            clone.display().to_string(),
+        ],
+    ] {
+        let output = Command::new("git").args(args).output().unwrap();
+        assert!(output.status.success(), "{:?}", output);
+    }
+
+    let workspace = ReleaseWorkspace::try_new(clone.clone()).unwrap();
+    assert!(!workspace.tag_index().unwrap().contains_key("crate_a-0.0.1"));
+
+    let criteria = SelectionCriteria {
+        fetch_tags_from_remote: Some("origin".to_string()),
+        ..Default::default()
+    };
+    let workspace = ReleaseWorkspace::try_new_with_criteria(clone, criteria).unwrap();
+    let state = workspace.members_states().unwrap().get("crate_a").unwrap();
+
+    assert!(workspace.tag_index().unwrap().contains_key("crate_a-0.0.1"));
+    assert!(
+        state.contains(CrateStateFlags::HasPreviousRelease),
+        "{:#?}",
+        state
+    );
+}
+
+#[test]
+fn tag_index_is_built_once() {
+    let workspace_mocker = example_workspace_1().unwrap();
+    let workspace = ReleaseWorkspace::try_new(workspace_mocker.root()).unwrap();
+
+    // previously every member caused a separate repository lookup
+    let members_count = workspace.members().unwrap().len();
+    workspace.members_states().unwrap();
+    assert!(members_count > 1);
+    assert_eq!(1, workspace.tag_index_builds.load(Ordering::Relaxed));
+    assert!(workspace.tag_index().unwrap().contains_key("crate_a-0.0.1"));
+
+    // newly created tags are added to the existing index
+    workspace
+        .git_tag("new-tag", TagKind::default_annotated("new-tag"), false)
+        .unwrap();
+    assert!(workspace.tag_index().unwrap().contains_key("new-tag"));
+    assert_eq!(1, workspace.tag_index_builds.load(Ordering::Relaxed));
+}
+
+#[test]
+fn tag_index_is_reset() {
     let workspace_mocker = example_workspace_1().unwrap();
+    let mut workspace = ReleaseWorkspace::try_new(workspace_mocker.root()).unwrap();
+
+    assert!(!workspace.tag_index().unwrap().contains_key("new-tag"));
+    workspace_mocker.tag("new-tag");
+
+    workspace.reset_state();
+    assert!(workspace.tag_index().unwrap().contains_key("new-tag"));
+    assert_eq!(2, workspace.tag_index_builds.load(Ordering::Relaxed));
+}
+
+#[test]
+fn changelogs_are_reloaded_after_reset() {
+    let workspace_mocker = example_workspace_cascade().unwrap();
+    let mut workspace = ReleaseWorkspace::try_new(workspace_mocker.root()).unwrap();
+    workspace
+        .changed_files_cached(&workspace_mocker.root(), "HEAD", "HEAD")
+        .unwrap();
+    // the members borrow the workspace for its whole lifetime, so only the caches behind a plain `&self`
+    // can be populated before the reset; the previous states are observed through a second workspace
+    let previous_states = ReleaseWorkspace::try_new(workspace_mocker.root())
+        .unwrap()
+        .states_report()
+        .unwrap();
+    assert!(!previous_states["crate_b"]
+        .flags
+        .contains(CrateStateFlags::UnreleasableViaChangelogFrontmatter));
+
+    workspace_mocker.add_or_replace_file(
+        "crates/crate_b/CHANGELOG.md",
+        indoc::indoc! {r#"
+            ---
+            unreleasable: true
+            ---
+            # Changelog
+
+            ## [Unreleased]
+            Awesome changes!
+            "#
+        },
+    );
+
+    workspace.reset_state();
+    assert!(lock_cache(&workspace.changed_files_cache).is_empty());
+
+    let states = workspace.members_states().unwrap();
+    assert!(states["crate_b"].contains(CrateStateFlags::UnreleasableViaChangelogFrontmatter));
+    assert!(!states["crate_a"].contains(CrateStateFlags::UnreleasableViaChangelogFrontmatter));
+}
+
+#[test]
+fn renames_mark_both_crates_as_changed() {
+    let workspace_mocker = example_workspace_released().unwrap();
+    let before = workspace_mocker.head().unwrap();
+
+    let moved =
+        std::fs::read_to_string(workspace_mocker.root().join("crates/crate_a/src/shared.rs"))
+            .unwrap();
+    workspace_mocker
+        .remove_file("crates/crate_a/src/shared.rs")
+        .unwrap();
+    workspace_mocker.add_or_replace_file("crates/crate_b/src/shared.rs", &moved);
+    let after = workspace_mocker.commit(None);
+
+    let crate_a_dir = workspace_mocker.root().join("crates/crate_a");
+    assert_eq!(
+        vec![crate_a_dir.join("src/shared.rs")],
+        changed_files(&crate_a_dir, &before, &after).unwrap()
+    );
+
+    let workspace = ReleaseWorkspace::try_new(workspace_mocker.root()).unwrap();
+    let members_states = workspace.members_states().unwrap();
+    for name in &["crate_a", "crate_b"] {
+        let state = members_states.get(*name).unwrap();
+        assert!(
+            state.contains(CrateStateFlags::ChangedSincePreviousRelease),
+            "{}: {:#?}",
+            name,
+            state
+        );
+    }
+}
+
+#[test]
+fn change_classification() {
+    for (path, expected) in &[
+        ("src/lib.rs", ChangeClass::Source),
+        ("Cargo.toml", ChangeClass::Source),
+        ("build.rs", ChangeClass::Source),
+        ("tests/it.rs", ChangeClass::Tests),
+        ("benches/bench.rs", ChangeClass::Benches),
+        ("examples/example.rs", ChangeClass::Examples),
+        ("docs/guide.txt", ChangeClass::Docs),
+        ("README.md", ChangeClass::Docs),
+        ("rustfmt.toml", ChangeClass::Other),
+    ] {
+        assert_eq!(
+            *expected,
+            ChangeClass::classify(Path::new(path)),
+            "{}",
+            path
+        );
+    }
+}
+
+#[test]
+fn test_only_changes_dont_trigger_release() {
+    let workspace_mocker = example_workspace_1_with_changes_since_crate_a_tag(&[(
+        "crates/crate_a/tests/it.rs",
+        "#[test]\nfn it() {}\n",
+    )]);
+
     let workspace = ReleaseWorkspace::try_new(workspace_mocker.root()).unwrap();
+    let state = workspace.members_states().unwrap().get("crate_a").unwrap();
+    assert_eq!(make_bitflags!(ChangeClass::{Tests}), state.change_classes());
+    assert!(
+        state.contains(CrateStateFlags::ChangedSincePreviousRelease),
+        "{:#?}",
+        state
+    );
+
+    let criteria = SelectionCriteria {
+        change_classes_triggering_release: BitFlags::all() & !ChangeClass::Tests,
+        ..Default::default()
+    };
+    let workspace =
+        ReleaseWorkspace::try_new_with_criteria(workspace_mocker.root(), criteria).unwrap();
+    let state = workspace.members_states().unwrap().get("crate_a").unwrap();
+    assert_eq!(make_bitflags!(ChangeClass::{Tests}), state.change_classes());
+    assert!(
+        !state.contains(CrateStateFlags::ChangedSincePreviousRelease),
+        "{:#?}",
+        state
+    );
+}
+
+#[test]
+fn git_identity_precedence() {
+    let tmpdir = tempfile::tempdir().unwrap();
+    let config_path = tmpdir.path().join("gitconfig");
+    std::fs::write(&config_path, "").unwrap();
+    let mut config = git2::Config::open(&config_path).unwrap();
+
+    assert_eq!(
+        (
+            ReleaseWorkspace::GIT_CONFIG_NAME.to_string(),
+            ReleaseWorkspace::GIT_CONFIG_EMAIL.to_string()
+        ),
+        ReleaseWorkspace::resolve_git_identity(&Default::default(), &config)
+    );
+
+    config.set_str("user.name", "Config Name").unwrap();
+    config.set_str("user.email", "config@example.com").unwrap();
+    assert_eq!(
+        ("Config Name".to_string(), "config@example.com".to_string()),
+        ReleaseWorkspace::resolve_git_identity(&Default::default(), &config)
+    );
+
+    let git_options = GitOptions {
+        name: Some("Explicit Name".to_string()),
+        ..Default::default()
+    };
+    assert_eq!(
+        (
+            "Explicit Name".to_string(),
+            "config@example.com".to_string()
+        ),
+        ReleaseWorkspace::resolve_git_identity(&git_options, &config)
+    );
+}
+
+#[test]
+fn git_commit_and_tag_use_git_options() {
+    let workspace_mocker = example_workspace_1().unwrap();
+    let workspace = ReleaseWorkspace::try_new(workspace_mocker.root())
+        .unwrap()
+        .with_git_options(GitOptions {
+            name: Some("Release Bot".to_string()),
+            email: Some("release@example.com".to_string()),
+            ..Default::default()
+        });
+
+    workspace_mocker.add_or_replace_file("README", "# Example");
+    let commit_oid = workspace
+        .git_add_all_and_commit("test", None, false)
+        .unwrap();
+    let commit = workspace.git_repo().find_commit(commit_oid).unwrap();
+    assert_eq!(Some("Release Bot"), commit.author().name());
+    assert_eq!(Some("release@example.com"), commit.committer().email());
+
+    let created_tag = workspace
+        .git_tag("test-tag", TagKind::default_annotated("test-tag"), false)
+        .unwrap();
+    assert_eq!("test-tag", created_tag.name);
+    let tag = workspace.git_repo().find_tag(created_tag.oid).unwrap();
+    assert_eq!(Some("Release Bot"), tag.tagger().unwrap().name());
+}
+
+#[test]
+fn git_signing_without_key_fails() {
+    let workspace_mocker = example_workspace_1().unwrap();
+    let workspace = ReleaseWorkspace::try_new(workspace_mocker.root())
+        .unwrap()
+        .with_git_options(GitOptions {
+            sign: true,
+            signing_key: Some("nonexistent@example.com".to_string()),
+            ..Default::default()
+        });
+
+    assert!(workspace.git_signing_key().is_err());
+    assert!(workspace
+        .git_tag("test-tag", TagKind::default_annotated("test-tag"), false)
+        .is_err());
+}
+
+#[test]
+fn git_signed_commit_and_tag_verify() {
+    let gpg_available = Command::new("gpg")
+        .arg("--version")
+        .output()
+        .map(|output| output.status.success())
+        .unwrap_or_default();
+    if !gpg_available {
+        eprintln!("gpg is not available, skipping test");
+        return;
+    }
+
+    let gnupghome = tempfile::tempdir().unwrap();
+    let output = Command::new("gpg")
+        .env("GNUPGHOME", gnupghome.path())
+        .args([
+            "--batch",
+            "--pinentry-mode=loopback",
+            "--passphrase=",
+            "--quick-gen-key",
+            "Release Bot <release@example.com>",
+            "default",
+            "default",
+            "never",
+        ])
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "{:?}", output);
+
+    let workspace_mocker = example_workspace_1().unwrap();
+    let workspace = ReleaseWorkspace::try_new(workspace_mocker.root())
+        .unwrap()
+        .with_git_options(GitOptions {
+            name: Some("Release Bot".to_string()),
+            email: Some("release@example.com".to_string()),
+            sign: true,
+            signing_key: Some("release@example.com".to_string()),
+            gnupg_home: Some(gnupghome.path().to_path_buf()),
+            ..Default::default()
+        });
+
+    workspace_mocker.add_or_replace_file("README", "# Example");
+    workspace
+        .git_add_all_and_commit("test", None, false)
+        .unwrap();
+    workspace
+        .git_tag("test-tag", TagKind::default_annotated("test-tag"), false)
+        .unwrap();
+
+    for args in &[["verify-commit", "HEAD"], ["verify-tag", "test-tag"]] {
+        let output = Command::new("git")
+            .current_dir(workspace.root())
+            .env("GNUPGHOME", gnupghome.path())
+            .args(args)
+            .output()
+            .unwrap();
+        assert!(output.status.success(), "{:?}", output);
+    }
+}
+
+#[test]
+fn git_commit_and_tag_in_empty_repository() {
+    let tmpdir = tempfile::tempdir().unwrap();
+    git2::Repository::init(tmpdir.path()).unwrap();
+    let workspace = ReleaseWorkspace::try_new(tmpdir.path().to_path_buf()).unwrap();
+
+    let err = workspace
+        .git_tag("too-early", TagKind::default_annotated("too-early"), false)
+        .unwrap_err()
+        .to_string();
+    assert!(err.contains("no commits yet"), "{}", err);
+
+    std::fs::write(tmpdir.path().join("README.md"), "# Example").unwrap();
+    let commit_oid = workspace
+        .git_add_all_and_commit("initial commit", None, false)
+        .unwrap();
+    let commit = workspace.git_repo().find_commit(commit_oid).unwrap();
+    assert_eq!(0, commit.parent_count());
+
+    workspace
+        .git_tag("initial", TagKind::default_annotated("initial"), false)
+        .unwrap();
+}
+
+#[test]
+fn git_commit_skipped_without_changes() {
+    let workspace_mocker = example_workspace_1().unwrap();
+    let workspace = ReleaseWorkspace::try_new(workspace_mocker.root()).unwrap();
+    let head = workspace.git_repo().head().unwrap().target().unwrap();
+
+    assert_eq!(
+        head,
+        workspace
+            .git_add_all_and_commit("nothing changed", None, false)
+            .unwrap()
+    );
+    assert_eq!(head, workspace.git_repo().head().unwrap().target().unwrap());
+
+    let empty_commit_oid = workspace
+        .git_add_all_and_commit("empty marker", None, true)
+        .unwrap();
+    assert_ne!(head, empty_commit_oid);
+    let empty_commit = workspace.git_repo().find_commit(empty_commit_oid).unwrap();
+    assert_eq!(head, empty_commit.parent_id(0).unwrap());
+    assert_eq!(
+        workspace.git_repo().find_commit(head).unwrap().tree_id(),
+        empty_commit.tree_id()
+    );
+}
+
+#[test]
+fn git_commit_paths_leaves_other_files_untouched() {
+    let workspace_mocker = example_workspace_1().unwrap();
+    workspace_mocker.add_or_replace_file("crates/crate_a/README.md", "# Changed README");
+    workspace_mocker.add_or_replace_file("crates/crate_b/README.md", "# Unstaged change");
+    workspace_mocker.add_or_replace_file("scratch.txt", "local notes");
+
+    let workspace = ReleaseWorkspace::try_new(workspace_mocker.root()).unwrap();
+    let commit_oid = workspace
+        .git_commit_paths(
+            "commit crate_a's README",
+            &[Path::new("crates/crate_a/README.md")],
+        )
+        .unwrap();
+
+    let repo = workspace.git_repo();
+    let commit = repo.find_commit(commit_oid).unwrap();
+    let tree = commit.tree().unwrap();
+    let blob = tree
+        .get_path(Path::new("crates/crate_a/README.md"))
+        .unwrap()
+        .to_object(repo)
+        .unwrap()
+        .peel_to_blob()
+        .unwrap();
+    assert_eq!(b"# Changed README", blob.content());
+    assert!(tree.get_path(Path::new("scratch.txt")).is_err());
+
+    let status = |path: &str| repo.status_file(Path::new(path)).unwrap();
+    assert_eq!(git2::Status::WT_NEW, status("scratch.txt"));
+    assert_eq!(
+        git2::Status::WT_MODIFIED,
+        status("crates/crate_b/README.md")
+    );
+    assert_eq!(git2::Status::CURRENT, status("crates/crate_a/README.md"));
+}
+
+#[test]
+fn git_checkout_new_branch_refuses_dirty_worktree() {
+    let workspace_mocker = example_workspace_1().unwrap();
+    workspace_mocker.add_or_replace_file("crates/crate_a/README.md", "# Local change");
+    let workspace = ReleaseWorkspace::try_new(workspace_mocker.root()).unwrap();
+
+    let err = match workspace.git_checkout_new_branch("develop", false) {
+        Ok(_) => panic!("expected the checkout to be refused"),
+        Err(err) => err,
+    };
+    assert!(
+        err.to_string().contains("crates/crate_a/README.md"),
+        "{}",
+        err
+    );
+    assert!(workspace
+        .git_repo()
+        .find_branch("develop", git2::BranchType::Local)
+        .is_err());
+    assert_eq!(
+        "# Local change",
+        std::fs::read_to_string(workspace_mocker.root().join("crates/crate_a/README.md")).unwrap()
+    );
+}
+
+#[test]
+fn git_checkout_new_branch_on_clean_worktree() {
+    let workspace_mocker = example_workspace_1().unwrap();
+    workspace_mocker.add_or_replace_file("scratch.txt", "untracked files don't count");
+    let workspace = ReleaseWorkspace::try_new(workspace_mocker.root()).unwrap();
+    let head = workspace.git_repo().head().unwrap().target().unwrap();
+
+    workspace.git_checkout_new_branch("develop", false).unwrap();
+
+    assert_eq!("develop", workspace.git_head_branch_name().unwrap());
+    assert_eq!(head, workspace.git_repo().head().unwrap().target().unwrap());
+    assert!(workspace_mocker.root().join("scratch.txt").exists());
+}
+
+#[test]
+fn git_checkout_new_branch_force_discards_local_modifications() {
+    let workspace_mocker = example_workspace_1().unwrap();
+    let readme_path = workspace_mocker.root().join("crates/crate_a/README.md");
+    let readme_committed = std::fs::read_to_string(&readme_path).unwrap();
+    workspace_mocker.add_or_replace_file("crates/crate_a/README.md", "# Local change");
+    let workspace = ReleaseWorkspace::try_new(workspace_mocker.root()).unwrap();
+
+    workspace.git_checkout_new_branch("develop", true).unwrap();
+
+    assert_eq!("develop", workspace.git_head_branch_name().unwrap());
+    assert_eq!(
+        readme_committed,
+        std::fs::read_to_string(&readme_path).unwrap()
+    );
+    assert!(workspace.git_dirty_paths().unwrap().is_empty());
+}
+
+/// Creates a branch with an additional commit and switches back to the previous branch.
+/// Returns the ids of the branch's commit and the one of HEAD.
+fn with_existing_branch<'a>(
+    workspace_mocker: &WorkspaceMocker,
+    workspace: &'a ReleaseWorkspace<'a>,
+    name: &str,
+) -> (git2::Oid, git2::Oid) {
+    let repo = workspace.git_repo();
+    let previous_head = repo.head().unwrap();
+    let previous_head_name = previous_head.name().unwrap().to_string();
+    let previous_head_oid = previous_head.target().unwrap();
+
+    workspace.git_checkout_new_branch(name, false).unwrap();
+    workspace_mocker.add_or_replace_file("crates/crate_a/README.md", "# Changed on the branch");
+    let branch_oid = workspace
+        .git_add_all_and_commit("change on the branch", None, false)
+        .unwrap();
+
+    repo.set_head(&previous_head_name).unwrap();
+    repo.checkout_head(Some(git2::build::CheckoutBuilder::new().force()))
+        .unwrap();
+
+    (branch_oid, previous_head_oid)
+}
+
+#[test]
+fn branch_policy_error_if_exists() {
+    let workspace_mocker = example_workspace_1().unwrap();
+    let workspace = ReleaseWorkspace::try_new(workspace_mocker.root()).unwrap();
+    let (branch_oid, _) = with_existing_branch(&workspace_mocker, &workspace, "release-test");
+
+    let err = match workspace.git_checkout_new_branch_with_policy(
+        "release-test",
+        BranchPolicy::ErrorIfExists,
+        false,
+    ) {
+        Ok(_) => panic!("expected the checkout to be refused"),
+        Err(err) => err,
+    };
+    assert!(err.to_string().contains("'release-test'"), "{}", err);
+    assert_ne!("release-test", workspace.git_head_branch_name().unwrap());
+    assert_eq!(
+        branch_oid,
+        workspace
+            .git_repo()
+            .refname_to_id("refs/heads/release-test")
+            .unwrap()
+    );
+}
+
+#[test]
+fn branch_policy_reuse_existing() {
+    let workspace_mocker = example_workspace_1().unwrap();
+    let workspace = ReleaseWorkspace::try_new(workspace_mocker.root()).unwrap();
+    let (branch_oid, _) = with_existing_branch(&workspace_mocker, &workspace, "release-test");
+
+    workspace
+        .git_checkout_new_branch_with_policy("release-test", BranchPolicy::ReuseExisting, false)
+        .unwrap();
+    assert_eq!("release-test", workspace.git_head_branch_name().unwrap());
+    assert_eq!(
+        branch_oid,
+        workspace.git_repo().head().unwrap().target().unwrap()
+    );
+    assert_eq!(
+        "# Changed on the branch",
+        std::fs::read_to_string(workspace_mocker.root().join("crates/crate_a/README.md")).unwrap()
+    );
+}
+
+#[test]
+fn branch_policy_recreate() {
+    let workspace_mocker = example_workspace_1().unwrap();
+    let workspace = ReleaseWorkspace::try_new(workspace_mocker.root()).unwrap();
+    let (branch_oid, head_oid) =
+        with_existing_branch(&workspace_mocker, &workspace, "release-test");
+
+    workspace
+        .git_checkout_new_branch_with_policy("release-test", BranchPolicy::Recreate, false)
+        .unwrap();
+    assert_eq!("release-test", workspace.git_head_branch_name().unwrap());
+    let new_branch_oid = workspace.git_repo().head().unwrap().target().unwrap();
+    assert_eq!(head_oid, new_branch_oid);
+    assert_ne!(branch_oid, new_branch_oid);
+}
+
+#[test]
+fn push_release_artifacts_to_bare_remote() {
+    let workspace_mocker = example_workspace_1().unwrap();
+    let tmpdir = tempfile::tempdir().unwrap();
+    let bare = git2::Repository::init_bare(tmpdir.path()).unwrap();
+
+    let workspace = ReleaseWorkspace::try_new(workspace_mocker.root()).unwrap();
+    workspace
+        .git_repo()
+        .remote("origin", &tmpdir.path().display().to_string())
+        .unwrap();
+    workspace
+        .git_checkout_new_branch("release-test", false)
+        .unwrap();
+    workspace
+        .git_tag(
+            "crate_a-0.0.2",
+            TagKind::default_annotated("crate_a-0.0.2"),
+            false,
+        )
+        .unwrap();
+
+    let pushed = workspace.push_release_artifacts("origin", false).unwrap();
+    assert_eq!(
+        vec![
+            "refs/heads/release-test:refs/heads/release-test".to_string(),
+            "refs/tags/crate_a-0.0.2:refs/tags/crate_a-0.0.2".to_string(),
+        ],
+        pushed
+    );
+
+    assert_eq!(
+        workspace.git_repo().head().unwrap().target().unwrap(),
+        bare.refname_to_id("refs/heads/release-test").unwrap()
+    );
+    assert!(bare.find_reference("refs/tags/crate_a-0.0.2").is_ok());
+    // tags which weren't created in this run aren't pushed
+    assert!(bare.find_reference("refs/tags/crate_a-0.0.1").is_err());
+}
+
+#[test]
+fn push_release_artifacts_dry_run() {
+    let workspace_mocker = example_workspace_1().unwrap();
+    let tmpdir = tempfile::tempdir().unwrap();
+    let bare = git2::Repository::init_bare(tmpdir.path()).unwrap();
+
+    let workspace = ReleaseWorkspace::try_new(workspace_mocker.root()).unwrap();
+    workspace
+        .git_repo()
+        .remote("origin", &tmpdir.path().display().to_string())
+        .unwrap();
+    workspace
+        .git_checkout_new_branch("release-test", false)
+        .unwrap();
+    workspace
+        .git_tag(
+            "crate_a-0.0.2",
+            TagKind::default_annotated("crate_a-0.0.2"),
+            false,
+        )
+        .unwrap();
+
+    let refspecs = workspace.push_release_artifacts("origin", true).unwrap();
+    assert_eq!(2, refspecs.len());
+    assert!(bare.references().unwrap().next().is_none());
+}
+
+fn tag_names<'a>(workspace: &'a ReleaseWorkspace<'a>) -> BTreeSet<String> {
+    workspace
+        .git_repo()
+        .tag_names(None)
+        .unwrap()
+        .iter()
+        .flatten()
+        .map(String::from)
+        .collect()
+}
+
+#[test]
+fn rollback_after_failure_following_tagging() {
+    let workspace_mocker = example_workspace_1().unwrap();
+    let readme_path = workspace_mocker.root().join("crates/crate_a/README.md");
+    let readme_committed = std::fs::read_to_string(&readme_path).unwrap();
+
+    let (head_before, tags_before) = {
+        let workspace = ReleaseWorkspace::try_new(workspace_mocker.root()).unwrap();
+        workspace.git_checkout_new_branch("develop", false).unwrap();
+        let head = workspace.git_repo().head().unwrap().target().unwrap();
+        (head, tag_names(&workspace))
+    };
+
+    // a release run that fails after tagging
+    {
+        let workspace = ReleaseWorkspace::try_new(workspace_mocker.root())
+            .unwrap()
+            .with_journal();
+        workspace
+            .git_checkout_new_branch("release-test", false)
+            .unwrap();
+        workspace_mocker.add_or_replace_file("crates/crate_a/README.md", "# Released");
+        workspace
+            .git_add_all_and_commit("create a release", None, false)
+            .unwrap();
+        workspace
+            .git_tag(
+                "crate_a-0.0.2",
+                TagKind::default_annotated("crate_a-0.0.2"),
+                false,
+            )
+            .unwrap();
+        workspace
+            .git_tag(
+                "crate_b-0.0.1",
+                TagKind::default_annotated("crate_b-0.0.1"),
+                false,
+            )
+            .unwrap();
+        workspace
+            .git_tag(
+                "crate_a-0.0.1",
+                TagKind::default_annotated("crate_a-0.0.1"),
+                true,
+            )
+            .unwrap();
+        // a leftover modification of the failed step
+        workspace_mocker.add_or_replace_file("crates/crate_a/README.md", "# Half done");
+    }
+
+    let journal = ReleaseJournal::load(&workspace_mocker.root())
+        .unwrap()
+        .unwrap();
+    assert_eq!("refs/heads/develop", journal.original_head);
+    assert_eq!(tags_before, journal.preexisting_tags);
+    assert_eq!(5, journal.entries.len(), "{:#?}", journal.entries);
+
+    let workspace = ReleaseWorkspace::try_new(workspace_mocker.root()).unwrap();
+    workspace.rollback(false).unwrap();
+
+    assert_eq!(tags_before, tag_names(&workspace));
+    assert_eq!("develop", workspace.git_head_branch_name().unwrap());
+    assert_eq!(
+        head_before,
+        workspace.git_repo().head().unwrap().target().unwrap()
+    );
+    assert!(workspace
+        .git_repo()
+        .find_branch("release-test", git2::BranchType::Local)
+        .is_err());
+    assert_eq!(
+        readme_committed,
+        std::fs::read_to_string(&readme_path).unwrap()
+    );
+    assert!(ReleaseJournal::load(&workspace_mocker.root())
+        .unwrap()
+        .is_none());
+}
+
+#[test]
+fn rollback_dry_run_and_journal_opt_in() {
+    let workspace_mocker = example_workspace_1().unwrap();
+
+    {
+        // without opting in nothing is recorded
+        let workspace = ReleaseWorkspace::try_new(workspace_mocker.root()).unwrap();
+        workspace.git_checkout_new_branch("develop", false).unwrap();
+        workspace
+            .git_tag(
+                "crate_b-0.0.1",
+                TagKind::default_annotated("crate_b-0.0.1"),
+                false,
+            )
+            .unwrap();
+        assert!(ReleaseJournal::load(&workspace_mocker.root())
+            .unwrap()
+            .is_none());
+        assert!(workspace.rollback(false).is_err());
+    }
+
+    let workspace = ReleaseWorkspace::try_new(workspace_mocker.root())
+        .unwrap()
+        .with_journal();
+    workspace
+        .git_checkout_new_branch("release-test", false)
+        .unwrap();
+    workspace
+        .git_tag(
+            "crate_a-0.0.2",
+            TagKind::default_annotated("crate_a-0.0.2"),
+            false,
+        )
+        .unwrap();
+
+    let tags_before = tag_names(&workspace);
+    workspace.rollback(true).unwrap();
+
+    assert_eq!(tags_before, tag_names(&workspace));
+    assert_eq!("release-test", workspace.git_head_branch_name().unwrap());
+    assert!(ReleaseJournal::load(&workspace_mocker.root())
+        .unwrap()
+        .is_some());
+    // the journal stays out of commits
+    assert!(workspace.git_dirty_paths().unwrap().is_empty());
+    assert!(workspace
+        .git_repo()
+        .is_path_ignored(Path::new(".release-automation/journal.json"))
+        .unwrap());
+}
+
+#[test]
+fn clean_worktree_passes() {
+    let workspace_mocker = example_workspace_1().unwrap();
+    let workspace = ReleaseWorkspace::try_new(workspace_mocker.root()).unwrap();
+
+    workspace.ensure_clean_worktree().unwrap();
+}
+
+#[test]
+fn dirty_worktree_fails() {
+    for (path, expected) in [
+        (
+            "crates/crate_a/README.md",
+            DirtyWorktreeError {
+                modified: vec!["crates/crate_a/README.md".to_string()],
+                untracked: vec![],
+            },
+        ),
+        (
+            "scratch.txt",
+            DirtyWorktreeError {
+                modified: vec![],
+                untracked: vec!["scratch.txt".to_string()],
+            },
+        ),
+    ] {
+        let workspace_mocker = example_workspace_1().unwrap();
+        workspace_mocker.add_or_replace_file(path, "local change");
+        let workspace = ReleaseWorkspace::try_new(workspace_mocker.root()).unwrap();
+
+        let err = workspace
+            .ensure_clean_worktree()
+            .unwrap_err()
+            .downcast::<DirtyWorktreeError>()
+            .unwrap();
+        assert_eq!(expected, err);
+    }
+}
+
+#[test]
+fn allow_dirty_bypasses_clean_worktree_check() {
+    let workspace_mocker = example_workspace_1().unwrap();
+    workspace_mocker.add_or_replace_file("crates/crate_a/README.md", "local change");
+    workspace_mocker.add_or_replace_file("scratch.txt", "local change");
+
+    let criteria = SelectionCriteria {
+        allow_dirty: true,
+        ..Default::default()
+    };
+    let workspace =
+        ReleaseWorkspace::try_new_with_criteria(workspace_mocker.root(), criteria).unwrap();
+
+    workspace.ensure_clean_worktree().unwrap();
+}
+
+fn release_branch_criteria() -> SelectionCriteria {
+    SelectionCriteria {
+        allowed_branch_patterns: vec![
+            fancy_regex::Regex::new("^main$").unwrap(),
+            fancy_regex::Regex::new("^release-.*").unwrap(),
+        ],
+        ..Default::default()
+    }
+}
+
+#[test]
+fn allowed_branch_matches() {
+    let workspace_mocker = example_workspace_1().unwrap();
+    ReleaseWorkspace::try_new(workspace_mocker.root())
+        .unwrap()
+        .git_checkout_new_branch("release-20220101.000000", false)
+        .unwrap();
+
+    let workspace =
+        ReleaseWorkspace::try_new_with_criteria(workspace_mocker.root(), release_branch_criteria())
+            .unwrap();
+    workspace.ensure_allowed_branch().unwrap();
+}
+
+#[test]
+fn disallowed_branch_fails() {
+    let workspace_mocker = example_workspace_1().unwrap();
+    ReleaseWorkspace::try_new(workspace_mocker.root())
+        .unwrap()
+        .git_checkout_new_branch("feature/foo", false)
+        .unwrap();
+
+    let workspace =
+        ReleaseWorkspace::try_new_with_criteria(workspace_mocker.root(), release_branch_criteria())
+            .unwrap();
+    let err = workspace.ensure_allowed_branch().unwrap_err();
+    assert_eq!(
+        "current branch 'feature/foo' does not match allowed patterns: ^main$, ^release-.*",
+        err.to_string()
+    );
+    assert!(err.is::<DisallowedBranchError>());
+}
+
+#[test]
+fn detached_head_is_not_allowed_by_default() {
+    let workspace_mocker = example_workspace_1().unwrap();
+    {
+        let workspace = ReleaseWorkspace::try_new(workspace_mocker.root()).unwrap();
+        let head = workspace.git_repo().head().unwrap().target().unwrap();
+        workspace.git_repo().set_head_detached(head).unwrap();
+
+        // this applies even without any branch patterns
+        assert_eq!(
+            Some(&GitHeadError::Detached(head)),
+            workspace
+                .ensure_allowed_branch()
+                .unwrap_err()
+                .downcast_ref::<GitHeadError>()
+        );
+    }
+
+    let workspace = ReleaseWorkspace::try_new_with_criteria(
+        workspace_mocker.root(),
+        SelectionCriteria {
+            allow_detached_head: true,
+            ..release_branch_criteria()
+        },
+    )
+    .unwrap();
+    workspace.ensure_allowed_branch().unwrap();
+}
+
+#[test]
+fn release_tag_name_default_template() {
+    assert_eq!(
+        "crate_a-0.1.0-beta-rc.1",
+        release_tag_name("crate_a", &Version::parse("0.1.0-beta-rc.1").unwrap())
+    );
+}
+
+#[test]
+fn release_tag_name_custom_template() {
+    let version = Version::new(1, 2, 3);
+    assert_eq!(
+        "v1.2.3",
+        format_release_tag_name("v{version}", "crate_a", &version)
+    );
+    assert_eq!(
+        "release/crate_a/1.2.3",
+        format_release_tag_name("release/{name}/{version}", "crate_a", &version)
+    );
+
+    let workspace_mocker = example_workspace_1().unwrap();
+    let workspace = ReleaseWorkspace::try_new_with_criteria(
+        workspace_mocker.root(),
+        SelectionCriteria {
+            release_tag_template: Some("v{version}".to_string()),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        "v1.2.3",
+        workspace.release_tag_name("crate_a", &version).unwrap()
+    );
+    assert_eq!(
+        Some((None, version)),
+        workspace.parse_release_tag_name("v1.2.3")
+    );
+}
+
+#[test]
+fn parse_release_tag_name_roundtrip() {
+    for (template, name, version) in [
+        (DEFAULT_RELEASE_TAG_TEMPLATE, "crate_a", "0.0.1"),
+        (
+            DEFAULT_RELEASE_TAG_TEMPLATE,
+            "holochain-cli",
+            "0.1.0-beta-rc.0",
+        ),
+        (DEFAULT_RELEASE_TAG_TEMPLATE, "crate-1", "1.0.0+build.1"),
+        ("release/{name}/{version}", "crate_a", "2.0.0"),
+    ] {
+        let version = Version::parse(version).unwrap();
+        let tag_name = format_release_tag_name(template, name, &version);
+
+        assert_eq!(
+            Some((Some(name.to_string()), version)),
+            parse_release_tag_name(template, &tag_name),
+            "{}",
+            tag_name
+        );
+    }
+
+    assert_eq!(
+        None,
+        parse_release_tag_name(DEFAULT_RELEASE_TAG_TEMPLATE, "release-20210304.120604")
+    );
+    assert_eq!(None, parse_release_tag_name("v{version}", "crate_a-0.0.1"));
+}
+
+#[test]
+fn git_tag_kinds() {
+    let workspace_mocker = example_workspace_1().unwrap();
+    let workspace = ReleaseWorkspace::try_new(workspace_mocker.root()).unwrap();
+    let repo = workspace.git_repo();
+    let head = repo.head().unwrap().target().unwrap();
+
+    let message = "tag for release crate_a-0.0.2\n\n### Added\n\n- something new";
+    let annotated = workspace
+        .git_tag(
+            "crate_a-0.0.2",
+            TagKind::Annotated {
+                message: message.to_string(),
+            },
+            false,
+        )
+        .unwrap();
+    let tag = repo.find_tag(annotated.oid).unwrap();
+    assert_eq!(Some(message), tag.message().map(str::trim_end));
+    assert_eq!(head, tag.target_id());
+
+    let lightweight = workspace
+        .git_tag("crate_b-0.0.2", TagKind::Lightweight, false)
+        .unwrap();
+    assert_eq!("crate_b-0.0.2", lightweight.name);
+    assert_eq!(head, lightweight.oid);
+    assert!(repo.find_tag(lightweight.oid).is_err());
+    assert_eq!(head, repo.refname_to_id("refs/tags/crate_b-0.0.2").unwrap());
+}
+
+fn release_tags_to_create() -> Vec<(String, TagKind)> {
+    ["crate_a-0.0.2", "crate_b-0.0.2", "crate_e-0.0.2"]
+        .iter()
+        .map(|name| (name.to_string(), TagKind::default_annotated(name)))
+        .collect()
+}
+
+#[test]
+fn existing_tag_policy_error() {
+    let workspace_mocker = example_workspace_1().unwrap();
+    let workspace = ReleaseWorkspace::try_new(workspace_mocker.root()).unwrap();
+    workspace
+        .git_tag("crate_b-0.0.2", TagKind::Lightweight, false)
+        .unwrap();
+
+    let err = workspace
+        .create_release_tags(release_tags_to_create(), ExistingTagPolicy::Error, false)
+        .unwrap_err();
+    assert!(err.to_string().contains("crate_b-0.0.2"), "{}", err);
+
+    // no tag is created if any of them already exists
+    assert!(git_lookup_tag(workspace.git_repo(), "crate_a-0.0.2").is_none());
+    assert!(git_lookup_tag(workspace.git_repo(), "crate_e-0.0.2").is_none());
+}
+
+#[test]
+fn existing_tag_policy_skip() {
+    let workspace_mocker = example_workspace_1().unwrap();
+    let workspace = ReleaseWorkspace::try_new(workspace_mocker.root()).unwrap();
+    let existing = workspace
+        .git_tag("crate_b-0.0.2", TagKind::Lightweight, false)
+        .unwrap();
+
+    let report = workspace
+        .create_release_tags(release_tags_to_create(), ExistingTagPolicy::Skip, false)
+        .unwrap();
+    assert_eq!(vec!["crate_a-0.0.2", "crate_e-0.0.2"], report.created);
+    assert_eq!(vec!["crate_b-0.0.2"], report.skipped);
+
+    assert!(git_lookup_tag(workspace.git_repo(), "crate_a-0.0.2").is_some());
+    assert!(git_lookup_tag(workspace.git_repo(), "crate_e-0.0.2").is_some());
+    assert_eq!(
+        existing.oid,
+        workspace
+            .git_repo()
+            .refname_to_id("refs/tags/crate_b-0.0.2")
+            .unwrap()
+    );
+}
+
+#[test]
+fn existing_tag_policy_force() {
+    let workspace_mocker = example_workspace_1().unwrap();
+    let workspace = ReleaseWorkspace::try_new(workspace_mocker.root()).unwrap();
+    workspace
+        .git_tag("crate_b-0.0.2", TagKind::Lightweight, false)
+        .unwrap();
+
+    let report = workspace
+        .create_release_tags(release_tags_to_create(), ExistingTagPolicy::Force, false)
+        .unwrap();
+    assert_eq!(
+        vec!["crate_a-0.0.2", "crate_b-0.0.2", "crate_e-0.0.2"],
+        report.created
+    );
+    assert!(report.skipped.is_empty());
+
+    // the recreated tag is now an annotated one
+    let oid = workspace
+        .git_repo()
+        .refname_to_id("refs/tags/crate_b-0.0.2")
+        .unwrap();
+    assert!(workspace.git_repo().find_tag(oid).is_ok());
+}
+
+#[test]
+fn existing_tag_policy_force_refuses_different_tree() {
+    let workspace_mocker = example_workspace_1().unwrap();
+    let workspace = ReleaseWorkspace::try_new(workspace_mocker.root()).unwrap();
+    let existing = workspace
+        .git_tag("crate_b-0.0.2", TagKind::Lightweight, false)
+        .unwrap();
+
+    workspace_mocker.add_or_replace_file("README", "changed after tagging");
+    workspace_mocker.commit(None);
+
+    let err = workspace
+        .create_release_tags(release_tags_to_create(), ExistingTagPolicy::Force, false)
+        .unwrap_err();
+    assert!(err.to_string().contains("different tree"), "{}", err);
+
+    assert_eq!(
+        existing.oid,
+        workspace
+            .git_repo()
+            .refname_to_id("refs/tags/crate_b-0.0.2")
+            .unwrap()
+    );
+}
+
+#[test]
+fn git_head_branch_attached_and_detached() {
+    let workspace_mocker = example_workspace_1().unwrap();
+    let workspace = ReleaseWorkspace::try_new(workspace_mocker.root()).unwrap();
+    workspace.git_checkout_new_branch("develop", false).unwrap();
+
+    assert!(matches!(
+        workspace.git_head_branch().unwrap(),
+        HeadState::Branch(_, git2::BranchType::Local)
+    ));
+    assert_eq!("develop", workspace.git_head_branch_name().unwrap());
+    assert_eq!(
+        "develop",
+        workspace.git_head_branch_name_required().unwrap()
+    );
+
+    let head = workspace.git_repo().head().unwrap().target().unwrap();
+    workspace.git_repo().set_head_detached(head).unwrap();
+
+    assert!(matches!(
+        workspace.git_head_branch().unwrap(),
+        HeadState::Detached(oid) if oid == head
+    ));
+    assert!(head
+        .to_string()
+        .starts_with(&workspace.git_head_branch_name().unwrap()));
+    assert_eq!(
+        GitHeadError::Detached(head),
+        workspace
+            .git_head_branch_name_required()
+            .unwrap_err()
+            .downcast::<GitHeadError>()
+            .unwrap()
+    );
+
+    let workspace = ReleaseWorkspace::try_new(workspace_mocker.root())
+        .unwrap()
+        .with_git_options(GitOptions {
+            detached_head_placeholder: Some("detached".to_string()),
+            ..Default::default()
+        });
+    assert_eq!("detached", workspace.git_head_branch_name().unwrap());
+}
+
+#[test]
+fn workspace_members() {
+    let workspace_mocker = example_workspace_1().unwrap();
+    let workspace = ReleaseWorkspace::try_new(workspace_mocker.root()).unwrap();
+
+    let result = workspace
+        .members()
+        .unwrap()
+        .iter()
+        .map(|crt| crt.name())
+        .collect::<HashSet<_>>();
+
+    let expected_result = ["crate_a", "crate_b", "crate_c", "crate_e", "crate_f"]
+        .iter()
+        .map(std::string::ToString::to_string)
+        .collect::<HashSet<_>>();
+
+    assert_eq!(expected_result, result);
+}
+
+#[test]
+fn detect_changed_crates() {
+    let workspace_mocker = example_workspace_1().unwrap();
+    workspace_mocker.add_or_replace_file(
+        "README",
+        r#"# Example
+
+            Some changes
+        "#,
+    );
+    let before = workspace_mocker.head().unwrap();
+    let after = workspace_mocker.commit(None);
+
+    let workspace = ReleaseWorkspace::try_new(workspace_mocker.root()).unwrap();
+
+    assert_eq!(
+        vec![PathBuf::from(workspace.root()).join("README")],
+        changed_files(workspace.root(), &before, &after).unwrap()
+    );
+}
+
+#[test]
+fn release_selection() {
+    let criteria = SelectionCriteria {
+        match_filter: fancy_regex::Regex::new("crate_(b|a|e)").unwrap(),
+        disallowed_version_reqs: vec![semver::VersionReq::from_str(">=0.1.0").unwrap()],
+        allowed_dev_dependency_blockers: make_bitflags!(CrateStateFlags::{MissingReadme}),
+        allowed_selection_blockers: make_bitflags!(CrateStateFlags::{MissingReadme}),
+
+        ..Default::default()
+    };
+
+    let workspace_mocker = example_workspace_1().unwrap();
+    let workspace =
+        ReleaseWorkspace::try_new_with_criteria(workspace_mocker.root(), criteria).unwrap();
+
+    let selection = workspace
+        .release_selection()
+        .unwrap()
+        .into_iter()
+        .map(|c| c.name())
+        .collect::<Vec<_>>();
+    let expected_selection = vec!["crate_b", "crate_a", "crate_e"];
+
+    assert_eq!(expected_selection, selection);
+}
+
+#[test]
+fn members_dependencies() {
+    let workspace_mocker = example_workspace_2().unwrap();
+    let workspace = ReleaseWorkspace::try_new_with_criteria(
+        workspace_mocker.root(),
+        SelectionCriteria {
+            exclude_optional_deps: true,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    let result = workspace
+        .members()
+        .unwrap()
+        .iter()
+        .map(|crt| {
+            (
+                crt.name(),
+                crt.dependencies_in_workspace()
+                    .unwrap()
+                    .into_iter()
+                    .map(|(dep_name, _)| dep_name.clone())
+                    .collect::<HashSet<_>>(),
+            )
+        })
+        .collect::<Vec<_>>();
+
+    let expected_result = [
+        ("crate_b".to_string(), vec![]),
+        ("crate_c".to_string(), vec!["crate_b".to_string()]),
+        (
+            "crate_a".to_string(),
+            vec!["crate_c".to_string(), "crate_b".to_string()],
+        ),
+        (
+            "crate_d".to_string(),
+            vec![
+                "crate_a".to_string(),
+                "crate_c".to_string(),
+                "crate_b".to_string(),
+            ],
+        ),
+    ]
+    .into_iter()
+    .map(|(name, deps)| (name, deps.into_iter().collect::<HashSet<_>>()))
+    .collect::<Vec<_>>();
+
+    pretty_assertions::assert_eq!(expected_result, result, "left is expected");
+}
+
+#[test]
+fn members_sorted_ws1() {
+    let workspace_mocker = example_workspace_1().unwrap();
+    let workspace = ReleaseWorkspace::try_new_with_criteria(
+        workspace_mocker.root(),
+        SelectionCriteria {
+            allowed_dev_dependency_blockers: (&[
+                IsWorkspaceDependency,
+                UnreleasableViaChangelogFrontmatter,
+                MissingChangelog,
+            ] as &[CrateStateFlags])
+                .iter()
+                .cloned()
+                .collect(),
+
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    let result = workspace
+        .members()
+        .unwrap()
+        .iter()
+        .map(|crt| crt.name())
+        .collect::<Vec<_>>();
+
+    let expected_result = ["crate_b", "crate_a", "crate_c", "crate_e", "crate_f"]
+        .iter()
+        .map(std::string::ToString::to_string)
+        .collect::<Vec<_>>();
+
+    assert_eq!(expected_result, result);
+}
+
+#[test]
+fn members_sorted_ws2() {
+    let workspace_mocker = example_workspace_2().unwrap();
+    let workspace = ReleaseWorkspace::try_new(workspace_mocker.root()).unwrap();
+
+    let crates = workspace.members().unwrap();
+    ensure_release_order_consistency(crates).unwrap();
+
+    let result = crates.iter().map(|crt| crt.name()).collect::<Vec<_>>();
+
+    let expected_result = ["crate_b", "crate_c", "crate_a", "crate_d"]
+        .iter()
+        .map(std::string::ToString::to_string)
+        .collect::<Vec<_>>();
+
+    assert_eq!(expected_result, result);
+}
+
+#[test]
+fn members_sorted_chain() {
+    let workspace_mocker = example_workspace_chain().unwrap();
+    let workspace = ReleaseWorkspace::try_new(workspace_mocker.root()).unwrap();
+
+    let crates = workspace.members().unwrap();
+    ensure_release_order_consistency(crates).unwrap();
+
+    let result = crates.iter().map(|crt| crt.name()).collect::<Vec<_>>();
+
+    assert_eq!(vec!["crate_c", "crate_b", "crate_a"], result);
+}
+
+#[test]
+fn include_dependents_selects_the_dependencies_of_dependents() {
+    let workspace_mocker = example_workspace_diamond().unwrap();
+    for name in ["crate_a", "crate_b", "crate_c", "crate_d"] {
+        workspace_mocker.add_or_replace_file(
+            &format!("crates/{}/CHANGELOG.md", name),
+            indoc::indoc! {r#"
+                # Changelog
+
+                ## [Unreleased]
+                Awesome changes!
+                "#
+            },
+        );
+    }
+    let workspace = ReleaseWorkspace::try_new_with_criteria(
+        workspace_mocker.root(),
+        SelectionCriteria {
+            match_filter: fancy_regex::Regex::new("^crate_b$").unwrap(),
+            include_dependents: true,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    let report = workspace.states_report().unwrap();
+
+    assert!(report["crate_a"]
+        .flags
+        .contains(CrateStateFlags::IsDependentOfMatched));
+    // crate_c is only reachable as a dependency of the included dependent
+    assert!(report["crate_c"]
+        .flags
+        .contains(CrateStateFlags::IsWorkspaceDependency));
+    assert!(report["crate_c"].required_by.contains("crate_a"));
+}
+
+#[test]
+fn include_dependents_selects_transitive_dependents() {
+    let workspace_mocker = example_workspace_chain().unwrap();
+    let workspace = ReleaseWorkspace::try_new_with_criteria(
+        workspace_mocker.root(),
+        SelectionCriteria {
+            match_filter: fancy_regex::Regex::new("^crate_c$").unwrap(),
+            include_dependents: true,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    let report = workspace.states_report().unwrap();
+
+    assert!(report["crate_c"].flags.contains(CrateStateFlags::Matched));
+    assert!(report["crate_c"].dependent_of.is_empty());
+
+    for name in &["crate_b", "crate_a"] {
+        let crate_report = &report[*name];
+        assert!(
+            crate_report
+                .flags
+                .contains(CrateStateFlags::IsDependentOfMatched),
+            "{}: {:?}",
+            name,
+            crate_report
+        );
+        assert!(
+            crate_report
+                .meta_flags
+                .contains(MetaCrateStateFlags::Selected),
+            "{}: {:?}",
+            name,
+            crate_report
+        );
+        assert_eq!(
+            ["crate_c".to_string()].into_iter().collect::<BTreeSet<_>>(),
+            crate_report.dependent_of
+        );
+    }
+
+    let crate_a = workspace
+        .members()
+        .unwrap()
+        .iter()
+        .find(|crt| crt.name() == "crate_a")
+        .unwrap();
+    let formatted = CratesStateReport::new(
+        "",
+        vec![(
+            crate_a.name(),
+            CrateStateReport::new(&crate_a.version(), &crate_a.state().unwrap()),
+        )],
+    )
+    .showing(false, true, false)
+    .to_string();
+    assert!(
+        formatted.contains("selected because it depends on crate_c"),
+        "{}",
+        formatted
+    );
+}
+
+#[test]
+fn dependents_are_not_selected_by_default() {
+    let workspace_mocker = example_workspace_chain().unwrap();
+    let workspace = ReleaseWorkspace::try_new_with_criteria(
+        workspace_mocker.root(),
+        SelectionCriteria {
+            match_filter: fancy_regex::Regex::new("^crate_c$").unwrap(),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    let report = workspace.states_report().unwrap();
+
+    for name in &["crate_b", "crate_a"] {
+        assert!(!report[*name]
+            .flags
+            .contains(CrateStateFlags::IsDependentOfMatched));
+        assert!(!report[*name]
+            .meta_flags
+            .contains(MetaCrateStateFlags::Selected));
+    }
+}
+
+fn chain_of_four_with_max_depth<'a>(
+    workspace_mocker: &WorkspaceMocker,
+    max_dependency_depth: usize,
+) -> ReleaseWorkspace<'a> {
+    ReleaseWorkspace::try_new_with_criteria(
+        workspace_mocker.root(),
+        SelectionCriteria {
+            match_filter: fancy_regex::Regex::new("^crate_a$").unwrap(),
+            max_dependency_depth: Some(max_dependency_depth),
+            ..Default::default()
+        },
+    )
+    .unwrap()
+}
+
+fn dependency_flagged_names<'a>(workspace: &'a ReleaseWorkspace<'a>) -> Vec<String> {
+    workspace
+        .members()
+        .unwrap()
+        .iter()
+        .filter(|crt| crt.state().unwrap().is_dependency())
+        .map(|crt| crt.name())
+        .collect()
+}
+
+#[test]
+fn dependency_depths_on_chain() {
+    let workspace_mocker = example_workspace_chain_of_four().unwrap();
+    let workspace = ReleaseWorkspace::try_new(workspace_mocker.root()).unwrap();
+
+    let crate_a = workspace
+        .members()
+        .unwrap()
+        .iter()
+        .find(|crt| crt.name() == "crate_a")
+        .unwrap();
+
+    assert_eq!(Some(1), crate_a.dependency_depth("crate_b").unwrap());
+    assert_eq!(Some(2), crate_a.dependency_depth("crate_c").unwrap());
+    assert_eq!(Some(3), crate_a.dependency_depth("crate_d").unwrap());
+    assert_eq!(None, crate_a.dependency_depth("crate_a").unwrap());
+}
+
+#[test]
+fn max_dependency_depth_1() {
+    let workspace_mocker = example_workspace_chain_of_four().unwrap();
+    let workspace = chain_of_four_with_max_depth(&workspace_mocker, 1);
+
+    assert_eq!(vec!["crate_b"], dependency_flagged_names(&workspace));
+
+    let err = workspace.release_selection().unwrap_err().to_string();
+    assert!(err.contains("maximum dependency depth of 1"), "{}", err);
+    assert!(err.contains("- crate_b -> crate_c"), "{}", err);
+}
+
+#[test]
+fn max_dependency_depth_2() {
+    let workspace_mocker = example_workspace_chain_of_four().unwrap();
+    let workspace = chain_of_four_with_max_depth(&workspace_mocker, 2);
+
+    assert_eq!(
+        vec!["crate_c", "crate_b"],
+        dependency_flagged_names(&workspace)
+    );
+
+    let err = workspace.release_selection().unwrap_err().to_string();
+    assert!(err.contains("maximum dependency depth of 2"), "{}", err);
+    assert!(err.contains("- crate_c -> crate_d"), "{}", err);
+}
+
+#[test]
+fn max_dependency_depth_covering_the_chain() {
+    let workspace_mocker = example_workspace_chain_of_four().unwrap();
+    let workspace = chain_of_four_with_max_depth(&workspace_mocker, 3);
+
+    let selection = workspace
+        .release_selection()
+        .unwrap()
+        .into_iter()
+        .map(|crt| crt.name())
+        .collect::<Vec<_>>();
+
+    assert_eq!(vec!["crate_d", "crate_c", "crate_b", "crate_a"], selection);
+}
+
+fn crate_a_depends_on_optional_crate_b(
+    exclude_optional_deps: bool,
+    optional_deps_overrides: &[(&str, bool)],
+) -> bool {
+    let workspace_mocker = example_workspace_optional_dependency().unwrap();
+    let workspace = ReleaseWorkspace::try_new_with_criteria(
+        workspace_mocker.root(),
+        SelectionCriteria {
+            exclude_optional_deps,
+            optional_deps_overrides: optional_deps_overrides
+                .iter()
+                .map(|(name, exclude)| (name.to_string(), *exclude))
+                .collect(),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    let crate_a = workspace
+        .members()
+        .unwrap()
+        .iter()
+        .find(|crt| crt.name() == "crate_a")
+        .unwrap();
+
+    crate_a
+        .dependencies_in_workspace()
+        .unwrap()
+        .contains_key("crate_b")
+}
+
+#[test]
+fn optional_deps_follow_the_global_setting() {
+    assert!(crate_a_depends_on_optional_crate_b(false, &[]));
+    assert!(!crate_a_depends_on_optional_crate_b(true, &[]));
+}
+
+#[test]
+fn optional_deps_overrides_take_precedence() {
+    assert!(crate_a_depends_on_optional_crate_b(
+        true,
+        &[("crate_a", false)]
+    ));
+    assert!(!crate_a_depends_on_optional_crate_b(
+        false,
+        &[("crate_a", true)]
+    ));
+
+    // overrides for other crates don't apply
+    assert!(!crate_a_depends_on_optional_crate_b(
+        true,
+        &[("crate_b", false)]
+    ));
+}
+
+fn dep_kinds_workspace<'a>(
+    workspace_mocker: &WorkspaceMocker,
+    exclude_dep_kinds: &[CargoDepKind],
+    dep_kind_overrides: Vec<(&str, Vec<CargoDepKind>)>,
+) -> ReleaseWorkspace<'a> {
+    ReleaseWorkspace::try_new_with_criteria(
+        workspace_mocker.root(),
+        SelectionCriteria {
+            match_filter: fancy_regex::Regex::new("^crate_a$").unwrap(),
+            exclude_dep_kinds: exclude_dep_kinds.iter().copied().collect(),
+            dep_kind_overrides: dep_kind_overrides
+                .into_iter()
+                .map(|(name, kinds)| (name.to_string(), kinds.into_iter().collect()))
+                .collect(),
+            ..Default::default()
+        },
+    )
+    .unwrap()
+}
+
+fn crate_a_workspace_dependencies<'a>(workspace: &'a ReleaseWorkspace<'a>) -> BTreeSet<String> {
+    workspace
+        .members()
+        .unwrap()
+        .iter()
+        .find(|crt| crt.name() == "crate_a")
+        .unwrap()
+        .dependencies_in_workspace()
+        .unwrap()
+        .keys()
+        .cloned()
+        .collect()
+}
+
+#[test]
+fn exclude_development_dep_kind() {
+    let to_set = |names: &[&str]| {
+        names
+            .iter()
+            .map(std::string::ToString::to_string)
+            .collect::<BTreeSet<_>>()
+    };
+
+    let workspace_mocker = example_workspace_dep_kinds().unwrap();
+
+    let workspace = dep_kinds_workspace(&workspace_mocker, &[], vec![]);
+    assert_eq!(
+        to_set(&["crate_b", "crate_c", "crate_d"]),
+        crate_a_workspace_dependencies(&workspace)
+    );
+    let report = workspace.states_report().unwrap();
+    assert!(report["crate_b"]
+        .flags
+        .contains(CrateStateFlags::IsWorkspaceDevDependency));
+
+    let workspace = dep_kinds_workspace(&workspace_mocker, &[CargoDepKind::Development], vec![]);
+    assert_eq!(
+        to_set(&["crate_c", "crate_d"]),
+        crate_a_workspace_dependencies(&workspace)
+    );
+    let report = workspace.states_report().unwrap();
+    assert!(!report["crate_b"]
+        .meta_flags
+        .contains(MetaCrateStateFlags::Selected));
+    assert!(report["crate_d"]
+        .flags
+        .contains(CrateStateFlags::IsWorkspaceDependency));
+}
+
+#[test]
+fn dep_kind_overrides_take_precedence() {
+    let to_set = |names: &[&str]| {
+        names
+            .iter()
+            .map(std::string::ToString::to_string)
+            .collect::<BTreeSet<_>>()
+    };
+
+    let workspace_mocker = example_workspace_dep_kinds().unwrap();
+
+    let global = [CargoDepKind::Development, CargoDepKind::Build];
+    let workspace = dep_kinds_workspace(&workspace_mocker, &global, vec![]);
+    assert_eq!(
+        to_set(&["crate_d"]),
+        crate_a_workspace_dependencies(&workspace)
+    );
+
+    // include the build dependencies of crate_a despite the global setting
+    let workspace = dep_kinds_workspace(
+        &workspace_mocker,
+        &global,
+        vec![("crate_a", vec![CargoDepKind::Development])],
+    );
+    assert_eq!(
+        to_set(&["crate_c", "crate_d"]),
+        crate_a_workspace_dependencies(&workspace)
+    );
+
+    // overrides only apply to the given crate
+    let workspace = dep_kinds_workspace(&workspace_mocker, &global, vec![("crate_b", vec![])]);
+    assert_eq!(
+        to_set(&["crate_d"]),
+        crate_a_workspace_dependencies(&workspace)
+    );
+}
+
+#[test]
+fn members_sorted_diamond() {
+    let workspace_mocker = example_workspace_diamond().unwrap();
+    let workspace = ReleaseWorkspace::try_new(workspace_mocker.root()).unwrap();
+
+    let crates = workspace.members().unwrap();
+    ensure_release_order_consistency(crates).unwrap();
+
+    let result = crates.iter().map(|crt| crt.name()).collect::<Vec<_>>();
+
+    assert_eq!(vec!["crate_d", "crate_b", "crate_c", "crate_a"], result);
+}
+
+#[test]
+fn crate_state_serde_roundtrip() {
+    let state = CrateState::new(
+        make_bitflags!(CrateStateFlags::{Matched | MissingReadme | NoPreviousRelease}),
+        Default::default(),
+        make_bitflags!(CrateStateFlags::{MissingReadme}),
+        CrateState::BLOCKING_STATES,
+    );
+
+    let json = serde_json::to_string(&state).unwrap();
+    assert!(json.contains(r#""MissingReadme""#), "{}", json);
+    assert!(json.contains(r#""Selected""#), "{}", json);
+
+    let deserialized: CrateState = serde_json::from_str(&json).unwrap();
+    assert_eq!(state, deserialized);
+}
+
+#[test]
+fn states_report_serde_roundtrip() {
+    let workspace_mocker = example_workspace_1().unwrap();
+    let workspace = ReleaseWorkspace::try_new(workspace_mocker.root()).unwrap();
+
+    let report = workspace.states_report().unwrap();
+    let json = serde_json::to_string(&report).unwrap();
+    let deserialized: StatesReport = serde_json::from_str(&json).unwrap();
+
+    assert_eq!(report, deserialized);
+}
+
+#[test]
+fn states_report_matches_release_selection() {
+    let criteria = SelectionCriteria {
+        match_filter: fancy_regex::Regex::new("crate_(b|a|e)").unwrap(),
+        disallowed_version_reqs: vec![semver::VersionReq::from_str(">=0.1.0").unwrap()],
+        allowed_dev_dependency_blockers: make_bitflags!(CrateStateFlags::{MissingReadme}),
+        allowed_selection_blockers: make_bitflags!(CrateStateFlags::{MissingReadme}),
+
+        ..Default::default()
+    };
+
+    let workspace_mocker = example_workspace_1().unwrap();
+    let workspace =
+        ReleaseWorkspace::try_new_with_criteria(workspace_mocker.root(), criteria).unwrap();
+
+    let selection = workspace
+        .release_selection()
+        .unwrap()
+        .into_iter()
+        .map(|c| (c.name(), c.version().to_string()))
+        .collect::<BTreeSet<_>>();
+
+    let report = workspace.states_report().unwrap();
+    let reported_selection = report
+        .crates
+        .iter()
+        .filter(|(_, crate_report)| crate_report.release_selection)
+        .map(|(name, crate_report)| (name.clone(), crate_report.version.clone()))
+        .collect::<BTreeSet<_>>();
+
+    assert_eq!(selection, reported_selection);
+    assert_eq!(workspace.members().unwrap().len(), report.crates.len());
+}
+
+#[test]
+fn dependency_graph_dot() {
+    let workspace_mocker = example_workspace_diamond().unwrap();
+    let workspace = ReleaseWorkspace::try_new(workspace_mocker.root()).unwrap();
+
+    let dot = workspace.dependency_graph_dot().unwrap();
+
+    assert!(dot.starts_with("digraph workspace {"), "{}", dot);
+    for name in &["crate_a", "crate_b", "crate_c", "crate_d"] {
+        assert!(dot.contains(&format!("\"{}\" [", name)), "{}", dot);
+    }
+    for (from, to) in &[
+        ("crate_a", "crate_b"),
+        ("crate_a", "crate_c"),
+        ("crate_b", "crate_d"),
+        ("crate_c", "crate_d"),
+    ] {
+        assert!(
+            dot.contains(&format!("\"{}\" -> \"{}\" [label=\"Normal\"]", from, to)),
+            "{}",
+            dot
+        );
+    }
+    assert!(!dot.contains("\"crate_a\" -> \"crate_d\""), "{}", dot);
+}
+
+#[test]
+fn state_of_non_member_crate_errors() {
+    let workspace_mocker = example_workspace_1().unwrap();
+    let workspace = ReleaseWorkspace::try_new(workspace_mocker.root()).unwrap();
+
+    // crate_d is excluded from the workspace and thus has no member state
+    let cargo_config = cargo::util::config::Config::default().unwrap();
+    let excluded_manifest = workspace_mocker
+        .root()
+        .join("crates")
+        .join("crate_d")
+        .join("Cargo.toml");
+    let excluded_package = CargoWorkspace::new(&excluded_manifest, &cargo_config)
+        .unwrap()
+        .current()
+        .unwrap()
+        .clone();
+    let crt = Crate::with_cargo_package(excluded_package, &workspace).unwrap();
+
+    let err = crt.state().unwrap_err().to_string();
+    assert!(err.contains("crate_d"), "{}", err);
+}
+
+#[test]
+fn members_cycle_error() {
+    let workspace_mocker = example_workspace_cycle().unwrap();
+    let workspace = ReleaseWorkspace::try_new(workspace_mocker.root()).unwrap();
+
+    let err = workspace.members().unwrap_err().to_string();
+
+    assert!(
+        err.contains("crate_a -> crate_b -> crate_c -> crate_a"),
+        "{}",
+        err
+    );
+}
+
+#[test]
+fn unreleasable_dependencies_error() {
+    let workspace_mocker = example_workspace_3().unwrap();
+    let workspace = ReleaseWorkspace::try_new(workspace_mocker.root()).unwrap();
+
+    let err = workspace.release_selection().unwrap_err().to_string();
+
+    assert!(err.contains("blocked"), "{}", err);
+}
+
+#[test]
+fn never_release_crates_are_not_matched() {
+    let workspace_mocker = example_workspace_never_release().unwrap();
+    let workspace = ReleaseWorkspace::try_new(workspace_mocker.root()).unwrap();
+
+    let matched = workspace
+        .members_matched()
+        .unwrap()
+        .iter()
+        .map(|crt| crt.name())
+        .collect::<HashSet<_>>();
+
+    let expected_matched = ["crate_a", "crate_b"]
+        .iter()
+        .map(std::string::ToString::to_string)
+        .collect::<HashSet<_>>();
+
+    assert_eq!(expected_matched, matched);
+}
+
+fn matched_names<'a>(workspace: &'a ReleaseWorkspace<'a>) -> BTreeSet<String> {
+    workspace
+        .members()
+        .unwrap()
+        .iter()
+        .filter(|crt| crt.state().unwrap().contains(CrateStateFlags::Matched))
+        .map(|crt| crt.name())
+        .collect()
+}
+
+#[test]
+fn selection_names_unknown_name_errors() {
+    let workspace_mocker = example_workspace_1().unwrap();
+    let workspace = ReleaseWorkspace::try_new_with_criteria(
+        workspace_mocker.root(),
+        SelectionCriteria {
+            selection_names: vec!["crate_a".to_string(), "crate_typo".to_string()],
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    let err = workspace.members_matched().unwrap_err().to_string();
+    assert!(err.contains("crate_typo"), "{}", err);
+    assert!(!err.contains("crate_a"), "{}", err);
+}
+
+#[test]
+fn selection_names_are_matched() {
+    let workspace_mocker = example_workspace_1().unwrap();
+    let workspace = ReleaseWorkspace::try_new_with_criteria(
+        workspace_mocker.root(),
+        SelectionCriteria {
+            match_filter: fancy_regex::Regex::new("^$").unwrap(),
+            selection_names: vec!["crate_a".to_string(), "crate_c".to_string()],
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    assert_eq!(
+        ["crate_a", "crate_c"]
+            .iter()
+            .map(std::string::ToString::to_string)
+            .collect::<BTreeSet<_>>(),
+        matched_names(&workspace)
+    );
+}
+
+#[test]
+fn selection_names_combine_with_match_filter() {
+    let workspace_mocker = example_workspace_1().unwrap();
+    let workspace = ReleaseWorkspace::try_new_with_criteria(
+        workspace_mocker.root(),
+        SelectionCriteria {
+            match_filter: fancy_regex::Regex::new("^crate_(a|b)$").unwrap(),
+            selection_names: vec!["crate_e".to_string()],
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    assert_eq!(
+        ["crate_a", "crate_b", "crate_e"]
+            .iter()
+            .map(std::string::ToString::to_string)
+            .collect::<BTreeSet<_>>(),
+        matched_names(&workspace)
+    );
+}
+
+#[test]
+fn never_release_dependency_blocks_with_chain() {
+    let workspace_mocker = example_workspace_never_release().unwrap();
+    let workspace = ReleaseWorkspace::try_new_with_criteria(
+        workspace_mocker.root(),
+        SelectionCriteria {
+            match_filter: fancy_regex::Regex::new("^crate_a$").unwrap(),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    let err = workspace.release_selection().unwrap_err().to_string();
+
+    assert!(err.contains("NeverRelease"), "{}", err);
+    assert!(err.contains("crate_a -> crate_b -> crate_c"), "{}", err);
+}
+
+fn matched_names_by_path_globs(globs: &[&str]) -> BTreeSet<String> {
+    let workspace_mocker = example_workspace_nested().unwrap();
+    let workspace = ReleaseWorkspace::try_new_with_criteria(
+        workspace_mocker.root(),
+        SelectionCriteria {
+            match_filter: fancy_regex::Regex::new("^$").unwrap(),
+            selection_path_globs: globs
+                .iter()
+                .map(|glob| glob::Pattern::new(glob).unwrap())
+                .collect(),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    matched_names(&workspace)
+}
+
+#[test]
+fn selection_path_globs_match_nested_crates() {
+    let to_set = |names: &[&str]| {
+        names
+            .iter()
+            .map(std::string::ToString::to_string)
+            .collect::<BTreeSet<_>>()
+    };
+
+    assert_eq!(
+        to_set(&["wasm_a", "wasm_b"]),
+        matched_names_by_path_globs(&["test_utils/wasm/*"])
+    );
+    assert_eq!(
+        to_set(&["test_fixture", "wasm_a", "wasm_b"]),
+        matched_names_by_path_globs(&["test_utils/**"])
+    );
+    // a single star doesn't cross directory boundaries
+    assert_eq!(
+        to_set(&["test_fixture"]),
+        matched_names_by_path_globs(&["test_utils/*"])
+    );
+    assert_eq!(
+        to_set(&["crate_a", "wasm_b"]),
+        matched_names_by_path_globs(&["crates/crate_a", "test_utils/wasm/wasm_b"])
+    );
+    assert_eq!(
+        BTreeSet::new(),
+        matched_names_by_path_globs(&["crates/crate_b"])
+    );
+}
+
+#[test]
+fn selection_path_globs_combine_with_match_filter() {
+    let workspace_mocker = example_workspace_nested().unwrap();
+    let workspace = ReleaseWorkspace::try_new_with_criteria(
+        workspace_mocker.root(),
+        SelectionCriteria {
+            match_filter: fancy_regex::Regex::new("^crate_a$").unwrap(),
+            selection_path_globs: vec![glob::Pattern::new("test_utils/fixture").unwrap()],
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    assert_eq!(
+        ["crate_a", "test_fixture"]
+            .iter()
+            .map(std::string::ToString::to_string)
+            .collect::<BTreeSet<_>>(),
+        matched_names(&workspace)
+    );
+}
+
+fn matched_names_with_filter_mode(
+    mode: SelectionFilterMode,
+    match_filter: &str,
+    exclusion_filter: Option<&str>,
+) -> BTreeSet<String> {
+    let workspace_mocker = example_workspace_overlapping_names().unwrap();
+    let workspace = ReleaseWorkspace::try_new_with_criteria(
+        workspace_mocker.root(),
+        SelectionCriteria {
+            match_filter: fancy_regex::Regex::new(match_filter).unwrap(),
+            exclusion_filter: exclusion_filter
+                .map(|exclusion_filter| fancy_regex::Regex::new(exclusion_filter).unwrap()),
+            selection_filter_mode: mode,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    matched_names(&workspace)
+}
+
+#[test]
+fn selection_filter_mode_substring_is_default() {
+    assert_eq!(
+        SelectionFilterMode::Substring,
+        SelectionCriteria::default().selection_filter_mode
+    );
+
+    assert_eq!(
+        ["hdk", "hdk_derive", "holochain_hdk_compat"]
+            .iter()
+            .map(std::string::ToString::to_string)
+            .collect::<BTreeSet<_>>(),
+        matched_names_with_filter_mode(SelectionFilterMode::Substring, "hdk", None)
+    );
+}
+
+#[test]
+fn selection_filter_mode_full_match() {
+    assert_eq!(
+        ["hdk".to_string()].into_iter().collect::<BTreeSet<_>>(),
+        matched_names_with_filter_mode(SelectionFilterMode::FullMatch, "hdk", None)
+    );
+
+    // alternations are anchored as a whole
+    assert_eq!(
+        ["hdk".to_string(), "hdk_derive".to_string()]
+            .into_iter()
+            .collect::<BTreeSet<_>>(),
+        matched_names_with_filter_mode(SelectionFilterMode::FullMatch, "hdk|hdk_derive", None)
+    );
+}
+
+#[test]
+fn selection_filter_mode_applies_to_exclusion_filter() {
+    assert_eq!(
+        ["hdk_derive".to_string(), "holochain_hdk_compat".to_string()]
+            .into_iter()
+            .collect::<BTreeSet<_>>(),
+        matched_names_with_filter_mode(SelectionFilterMode::FullMatch, ".*hdk.*", Some("hdk"))
+    );
+
+    assert_eq!(
+        BTreeSet::new(),
+        matched_names_with_filter_mode(SelectionFilterMode::Substring, ".*hdk.*", Some("hdk"))
+    );
+}
+
+#[test]
+fn excluded_crate_is_not_matched() {
+    let workspace_mocker = example_workspace_1().unwrap();
+    let workspace = ReleaseWorkspace::try_new_with_criteria(
+        workspace_mocker.root(),
+        SelectionCriteria {
+            match_filter: fancy_regex::Regex::new("^crate_(a|b|e)$").unwrap(),
+            exclusion_filter: Some(fancy_regex::Regex::new("^crate_e$").unwrap()),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    assert_eq!(
+        ["crate_a", "crate_b"]
+            .iter()
+            .map(std::string::ToString::to_string)
+            .collect::<BTreeSet<_>>(),
+        matched_names(&workspace)
+    );
+
+    let crate_e = workspace
+        .members()
+        .unwrap()
+        .iter()
+        .find(|crt| crt.name() == "crate_e")
+        .unwrap();
+    assert!(crate_e.state().unwrap().contains(CrateStateFlags::Excluded));
+}
+
+#[test]
+fn excluded_dependency_blocks_with_chain() {
+    let workspace_mocker = example_workspace_never_release().unwrap();
+    let workspace = ReleaseWorkspace::try_new_with_criteria(
+        workspace_mocker.root(),
+        SelectionCriteria {
+            match_filter: fancy_regex::Regex::new("^crate_(a|b)$").unwrap(),
+            excluded_names: vec!["crate_b".to_string()],
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    let err = workspace.release_selection().unwrap_err().to_string();
+
+    assert!(err.contains("Excluded"), "{}", err);
+    assert!(
+        err.contains(
+            "the following crates are excluded but are required via: \n- crate_a -> crate_b"
+        ),
+        "{}",
+        err
+    );
+}
+
+#[test]
+fn excluded_dependency_allowed() {
+    let workspace_mocker = example_workspace_never_release().unwrap();
+    let workspace = ReleaseWorkspace::try_new_with_criteria(
+        workspace_mocker.root(),
+        SelectionCriteria {
+            match_filter: fancy_regex::Regex::new("^crate_(a|b)$").unwrap(),
+            excluded_names: vec!["crate_b".to_string()],
+            allow_excluded_as_dependency: true,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    let crate_b = workspace
+        .members()
+        .unwrap()
+        .iter()
+        .find(|crt| crt.name() == "crate_b")
+        .unwrap();
+    let state = crate_b.state().unwrap();
+
+    assert!(!state.is_matched());
+    assert!(state.is_dependency());
+    assert!(!state.contains(CrateStateFlags::Excluded));
+}
+
+#[test]
+fn publish_false_leaf_is_not_matched() {
+    let workspace_mocker = example_workspace_publish_false().unwrap();
+    let workspace = ReleaseWorkspace::try_new_with_criteria(
+        workspace_mocker.root(),
+        SelectionCriteria {
+            match_filter: fancy_regex::Regex::new("^crate_(b|fixture)$").unwrap(),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    let report = workspace.states_report().unwrap();
+    assert!(report["crate_fixture"]
+        .flags
+        .contains(CrateStateFlags::ManifestPublishFalse));
+    assert!(!report["crate_fixture"]
+        .flags
+        .contains(CrateStateFlags::Matched));
+    assert!(report["crate_fixture"]
+        .flags
+        .contains(CrateStateFlags::IsWorkspaceDevDependency));
+
+    // the unpublishable dev-dependency doesn't block the release
+    let selection = workspace
+        .release_selection()
+        .unwrap()
+        .into_iter()
+        .map(|crt| crt.name())
+        .collect::<Vec<_>>();
+    assert_eq!(vec!["crate_b"], selection);
+}
+
+#[test]
+fn publish_false_dependency_blocks_with_chain() {
+    let workspace_mocker = example_workspace_publish_false().unwrap();
+    let workspace = ReleaseWorkspace::try_new_with_criteria(
+        workspace_mocker.root(),
+        SelectionCriteria {
+            match_filter: fancy_regex::Regex::new("^crate_a$").unwrap(),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    let err = workspace.release_selection().unwrap_err().to_string();
+
+    assert!(err.contains("ManifestPublishFalse"), "{}", err);
+    assert!(
+        err.contains("the following crates set `publish = false` but are required via: \n- crate_a -> crate_private"),
+        "{}",
+        err
+    );
+}
+
+#[test]
+fn missing_publish_metadata_lists_the_fields() {
+    let workspace_mocker = example_workspace_publish_metadata().unwrap();
+    let workspace = ReleaseWorkspace::try_new(workspace_mocker.root()).unwrap();
+
+    let report = workspace.states_report().unwrap();
+    for (name, expected) in [
+        ("crate_complete", vec![]),
+        ("crate_without_description", vec!["description"]),
+        ("crate_without_license", vec!["license"]),
+        ("crate_without_repository", vec!["repository"]),
+        (
+            "crate_without_any",
+            vec!["description", "license", "repository"],
+        ),
+    ] {
+        assert_eq!(
+            !expected.is_empty(),
+            report[name]
+                .blocking_flags
+                .contains(CrateStateFlags::MissingPublishMetadata),
+            "{}",
+            name
+        );
+        assert_eq!(expected, report[name].missing_publish_metadata, "{}", name);
+    }
+}
+
+#[test]
+fn missing_publish_metadata_blocks_the_selection() {
+    let workspace_mocker = example_workspace_publish_metadata().unwrap();
+
+    for name in [
+        "crate_without_description",
+        "crate_without_license",
+        "crate_without_repository",
+    ] {
+        let workspace = ReleaseWorkspace::try_new_with_criteria(
+            workspace_mocker.root(),
+            SelectionCriteria {
+                match_filter: fancy_regex::Regex::new(&format!("^{}$", name)).unwrap(),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        let err = workspace.release_selection().unwrap_err().to_string();
+        assert!(err.contains("MissingPublishMetadata"), "{}", err);
+        assert!(err.contains(name), "{}", err);
+    }
+}
+
+#[test]
+fn missing_publish_metadata_can_be_allowed() {
+    let workspace_mocker = example_workspace_publish_metadata().unwrap();
+    let workspace = ReleaseWorkspace::try_new_with_criteria(
+        workspace_mocker.root(),
+        SelectionCriteria {
+            match_filter: fancy_regex::Regex::new("^crate_(complete|without_repository)$").unwrap(),
+            allowed_selection_blockers: make_bitflags!(CrateStateFlags::{MissingPublishMetadata}),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    let selection = workspace
+        .release_selection()
+        .unwrap()
+        .into_iter()
+        .map(|crt| crt.name())
+        .collect::<Vec<_>>();
+    assert_eq!(vec!["crate_complete"], selection);
+}
+
+#[test]
+fn missing_license_file() {
+    let workspace_mocker = example_workspace_license_files().unwrap();
+    let workspace = ReleaseWorkspace::try_new(workspace_mocker.root()).unwrap();
+
+    let report = workspace.states_report().unwrap();
+    for (name, missing) in [
+        ("crate_with_license", false),
+        ("crate_without_license", true),
+        ("crate_with_workspace_license", false),
+    ] {
+        assert_eq!(
+            missing,
+            report[name]
+                .blocking_flags
+                .contains(CrateStateFlags::MissingLicenseFile),
+            "{}",
+            name
+        );
+    }
+}
+
+#[test]
+fn missing_license_file_blocks_the_selection() {
+    let workspace_mocker = example_workspace_license_files().unwrap();
+    let workspace = ReleaseWorkspace::try_new_with_criteria(
+        workspace_mocker.root(),
+        SelectionCriteria {
+            match_filter: fancy_regex::Regex::new("^crate_without_license$").unwrap(),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    let err = workspace.release_selection().unwrap_err().to_string();
+    assert!(err.contains("MissingLicenseFile"), "{}", err);
+}
+
+#[test]
+fn license_file_in_workspace_root_is_accepted() {
+    let workspace_mocker = example_workspace_license_files().unwrap();
+    let workspace = ReleaseWorkspace::try_new_with_criteria(
+        workspace_mocker.root(),
+        SelectionCriteria {
+            match_filter: fancy_regex::Regex::new("^crate_with_(workspace_)?license$").unwrap(),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    let member = workspace
+        .members()
+        .unwrap()
+        .iter()
+        .find(|crt| crt.name() == "crate_with_workspace_license")
+        .copied()
+        .unwrap();
+    assert_eq!(
+        workspace_mocker
+            .root()
+            .join("LICENSE")
+            .canonicalize()
+            .unwrap(),
+        member.license_file().unwrap().canonicalize().unwrap()
+    );
+
+    let selection = workspace
+        .release_selection()
+        .unwrap()
+        .into_iter()
+        .map(|crt| crt.name())
+        .collect::<BTreeSet<_>>();
+    assert_eq!(
+        ["crate_with_license", "crate_with_workspace_license"]
+            .iter()
+            .map(std::string::ToString::to_string)
+            .collect::<BTreeSet<_>>(),
+        selection
+    );
+}
+
+#[test]
+fn wildcard_dependency_blocks_the_dependency() {
+    let workspace_mocker = example_workspace_wildcard().unwrap();
+    let workspace = ReleaseWorkspace::try_new_with_criteria(
+        workspace_mocker.root(),
+        SelectionCriteria {
+            match_filter: fancy_regex::Regex::new("^crate_a$").unwrap(),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    let report = workspace.states_report().unwrap();
+    assert!(report["crate_wild"]
+        .blocking_flags
+        .contains(CrateStateFlags::HasWildcardDependency));
+    assert!(!report["crate_a"]
+        .flags
+        .contains(CrateStateFlags::HasWildcardDependency));
+
+    let err = workspace.release_selection().unwrap_err().to_string();
+    assert!(err.contains("HasWildcardDependency"), "{}", err);
+    assert!(err.contains("crate_wild"), "{}", err);
+}
+
+#[test]
+fn wildcard_dependency_can_be_allowed_for_dev_dependencies() {
+    let workspace_mocker = example_workspace_wildcard().unwrap();
+    let workspace = ReleaseWorkspace::try_new_with_criteria(
+        workspace_mocker.root(),
+        SelectionCriteria {
+            match_filter: fancy_regex::Regex::new("^crate_b$").unwrap(),
+            allowed_dev_dependency_blockers: make_bitflags!(
+                CrateStateFlags::{HasWildcardDependency}
+            ),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    let selection = workspace
+        .release_selection()
+        .unwrap()
+        .into_iter()
+        .map(|crt| crt.name())
+        .collect::<Vec<_>>();
+    assert_eq!(vec!["crate_b"], selection);
+}
+
+#[test]
+fn wildcard_dev_dependency_does_not_block() {
+    let workspace_mocker = example_workspace_wildcard().unwrap();
+    let workspace = ReleaseWorkspace::try_new_with_criteria(
+        workspace_mocker.root(),
+        SelectionCriteria {
+            match_filter: fancy_regex::Regex::new("^crate_dev_wild$").unwrap(),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    let report = workspace.states_report().unwrap();
+    assert!(report["crate_dev_wild"]
+        .flags
+        .contains(CrateStateFlags::HasWildcardDevDependency));
+    assert!(report["crate_dev_wild"].blocking_flags.is_empty());
+
+    workspace.release_selection().unwrap();
+}
+
+#[test]
+fn git_dependency_blocks() {
+    let workspace_mocker = example_workspace_git_dependency().unwrap();
+
+    for allow_git_dev_dependencies in [false, true] {
+        let workspace = ReleaseWorkspace::try_new_with_criteria(
+            workspace_mocker.root(),
+            SelectionCriteria {
+                match_filter: fancy_regex::Regex::new("^crate_git$").unwrap(),
+                allow_git_dev_dependencies,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        let err = workspace.release_selection().unwrap_err().to_string();
+        assert!(err.contains("HasGitDependency"), "{}", err);
+    }
+}
+
+#[test]
+fn git_dev_dependency_blocks_unless_allowed() {
+    let workspace_mocker = example_workspace_git_dependency().unwrap();
+
+    let workspace = ReleaseWorkspace::try_new_with_criteria(
+        workspace_mocker.root(),
+        SelectionCriteria {
+            match_filter: fancy_regex::Regex::new("^crate_git_dev$").unwrap(),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    let err = workspace.release_selection().unwrap_err().to_string();
+    assert!(err.contains("HasGitDependency"), "{}", err);
+
+    let workspace = ReleaseWorkspace::try_new_with_criteria(
+        workspace_mocker.root(),
+        SelectionCriteria {
+            match_filter: fancy_regex::Regex::new("^crate_git_dev$").unwrap(),
+            allow_git_dev_dependencies: true,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    let selection = workspace
+        .release_selection()
+        .unwrap()
+        .into_iter()
+        .map(|crt| crt.name())
+        .collect::<Vec<_>>();
+    assert_eq!(vec!["crate_git_dev"], selection);
+}
+
+#[test]
+fn path_dep_missing_version_blocks() {
+    let workspace_mocker = example_workspace_path_dep_version().unwrap();
+    let workspace = ReleaseWorkspace::try_new_with_criteria(
+        workspace_mocker.root(),
+        SelectionCriteria {
+            match_filter: fancy_regex::Regex::new("^crate_unversioned$").unwrap(),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    let err = workspace.release_selection().unwrap_err().to_string();
+    assert!(err.contains("PathDepMissingVersion"), "{}", err);
+    assert!(err.contains("crate_unversioned"), "{}", err);
+
+    // a path dependency without a version is not a wildcard requirement
+    let report = workspace.states_report().unwrap();
+    assert!(!report["crate_unversioned"]
+        .flags
+        .contains(CrateStateFlags::HasWildcardDependency));
+}
+
+#[test]
+fn path_dep_with_version_passes() {
+    let workspace_mocker = example_workspace_path_dep_version().unwrap();
+    let workspace = ReleaseWorkspace::try_new_with_criteria(
+        workspace_mocker.root(),
+        SelectionCriteria {
+            match_filter: fancy_regex::Regex::new("^crate_(versioned|unversioned_dev)$").unwrap(),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    let report = workspace.states_report().unwrap();
+    for name in ["crate_versioned", "crate_unversioned_dev", "crate_leaf"] {
+        assert!(
+            !report[name]
+                .flags
+                .contains(CrateStateFlags::PathDepMissingVersion),
+            "{}",
+            name
+        );
+    }
+    assert!(!report["crate_unversioned_dev"]
+        .flags
+        .contains(CrateStateFlags::HasWildcardDevDependency));
+
+    let selection = workspace
+        .release_selection()
+        .unwrap()
+        .into_iter()
+        .map(|crt| crt.name())
+        .collect::<BTreeSet<_>>();
+    assert_eq!(
+        ["crate_leaf", "crate_versioned", "crate_unversioned_dev"]
+            .iter()
+            .map(std::string::ToString::to_string)
+            .collect::<BTreeSet<_>>(),
+        selection
+    );
+}
+
+#[test]
+fn invalid_keywords_or_categories() {
+    let workspace_mocker = example_workspace_keywords_and_categories().unwrap();
+    let workspace = ReleaseWorkspace::try_new(workspace_mocker.root()).unwrap();
+
+    let report = workspace.states_report().unwrap();
+    for (name, invalid) in [
+        ("crate_valid", false),
+        ("crate_six_keywords", true),
+        ("crate_six_categories", true),
+        ("crate_invalid_keyword", true),
+    ] {
+        assert_eq!(
+            invalid,
+            report[name]
+                .blocking_flags
+                .contains(CrateStateFlags::InvalidKeywordsOrCategories),
+            "{}",
+            name
+        );
+    }
+}
+
+#[test]
+fn six_keywords_block_the_selection() {
+    let workspace_mocker = example_workspace_keywords_and_categories().unwrap();
+    let workspace = ReleaseWorkspace::try_new_with_criteria(
+        workspace_mocker.root(),
+        SelectionCriteria {
+            match_filter: fancy_regex::Regex::new("^crate_six_keywords$").unwrap(),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    let err = workspace.release_selection().unwrap_err().to_string();
+    assert!(err.contains("InvalidKeywordsOrCategories"), "{}", err);
+}
+
+#[test]
+fn deprecated_keyword_flag_names_are_aliases() {
+    for name in [
+        "ManifestKeywordExceeds20Chars",
+        "ManifestKeywordContainsInvalidChar",
+        "ManifestKeywordsMoreThan5",
+    ] {
+        assert_eq!(
+            Some(CrateStateFlags::InvalidKeywordsOrCategories),
+            CrateStateFlags::from_deprecated_name(name)
+        );
+        assert_eq!(
+            CrateStateFlags::InvalidKeywordsOrCategories,
+            serde_json::from_value::<CrateStateFlags>(serde_json::json!(name)).unwrap()
+        );
+    }
+}
+
+#[test]
+fn package_too_large() {
+    let workspace_mocker = example_workspace_large_package().unwrap();
+    let workspace = ReleaseWorkspace::try_new_with_criteria(
+        workspace_mocker.root(),
+        SelectionCriteria {
+            verify_package_contents: true,
+            max_package_size: 32 * 1024,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    let report = workspace.states_report().unwrap();
+    assert!(report["crate_large"]
+        .blocking_flags
+        .contains(CrateStateFlags::PackageTooLarge));
+    assert_eq!(
+        ("fixtures/generated.bin".to_string(), 64 * 1024),
+        report["crate_large"].largest_package_files[0]
+    );
+    assert!(report["crate_large"].largest_package_files.len() <= 5);
+    assert!(!report["crate_small"]
+        .flags
+        .contains(CrateStateFlags::PackageTooLarge));
+    assert!(report["crate_small"].largest_package_files.is_empty());
+
+    let err = workspace.release_selection().unwrap_err().to_string();
+    assert!(err.contains("PackageTooLarge"), "{}", err);
+    assert!(err.contains("fixtures/generated.bin"), "{}", err);
+}
+
+#[test]
+fn package_contents_are_only_verified_on_request() {
+    let workspace_mocker = example_workspace_large_package().unwrap();
+    let workspace = ReleaseWorkspace::try_new_with_criteria(
+        workspace_mocker.root(),
+        SelectionCriteria {
+            max_package_size: 32 * 1024,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    let report = workspace.states_report().unwrap();
+    assert!(!report["crate_large"]
+        .flags
+        .contains(CrateStateFlags::PackageTooLarge));
+}
+
+#[test]
+fn version_changelog_mismatch() {
+    let workspace_mocker = example_workspace_version_changelog().unwrap();
+    let workspace = ReleaseWorkspace::try_new(workspace_mocker.root()).unwrap();
+
+    let report = workspace.states_report().unwrap();
+    for (name, mismatch) in [
+        ("crate_matching", false),
+        ("crate_manifest_ahead", false),
+        ("crate_changelog_ahead", true),
+    ] {
+        assert_eq!(
+            mismatch,
+            report[name]
+                .flags
+                .contains(CrateStateFlags::VersionChangelogMismatch),
+            "{}",
+            name
+        );
+    }
+
+    // not blocking by default
+    assert!(report["crate_changelog_ahead"].blocking_flags.is_empty());
+    workspace.release_selection().unwrap();
+}
+
+#[test]
+fn version_changelog_mismatch_blocks_on_request() {
+    let workspace_mocker = example_workspace_version_changelog().unwrap();
+    let workspace = ReleaseWorkspace::try_new_with_criteria(
+        workspace_mocker.root(),
+        SelectionCriteria {
+            block_on_version_changelog_mismatch: true,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    let report = workspace.states_report().unwrap();
+    assert!(report["crate_changelog_ahead"]
+        .blocking_flags
+        .contains(CrateStateFlags::VersionChangelogMismatch));
+    assert!(report["crate_manifest_ahead"].blocking_flags.is_empty());
+
+    let err = workspace.release_selection().unwrap_err().to_string();
+    assert!(err.contains("VersionChangelogMismatch"), "{}", err);
+    assert!(err.contains("crate_changelog_ahead"), "{}", err);
+}
+
+#[test]
+fn parse_rust_versions() {
+    assert_eq!(Version::new(1, 60, 0), parse_rust_version("1.60").unwrap());
+    assert_eq!(
+        Version::new(1, 60, 1),
+        parse_rust_version("1.60.1").unwrap()
+    );
+    assert_eq!(Version::new(1, 0, 0), parse_rust_version("1").unwrap());
+    assert!(parse_rust_version("1.60.0.0").is_err());
+    assert!(parse_rust_version("one").is_err());
+}
+
+#[test]
+fn msrv_violations() {
+    let workspace_mocker = example_workspace_msrv().unwrap();
+    let workspace = ReleaseWorkspace::try_new_with_criteria(
+        workspace_mocker.root(),
+        SelectionCriteria {
+            max_msrv: Some(Version::new(1, 65, 0)),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    let report = workspace.states_report().unwrap();
+    for (name, violation) in [
+        // depends on a crate with a higher MSRV
+        ("crate_old", true),
+        // doesn't declare an MSRV
+        ("crate_unspecified", false),
+        // equals the policy
+        ("crate_mid", false),
+        // exceeds the policy
+        ("crate_new", true),
+    ] {
+        assert_eq!(
+            violation,
+            report[name]
+                .blocking_flags
+                .contains(CrateStateFlags::MsrvViolation),
+            "{}",
+            name
+        );
+    }
+
+    let err = workspace.release_selection().unwrap_err().to_string();
+    assert!(err.contains("MsrvViolation"), "{}", err);
+}
+
+#[test]
+fn msrv_without_policy() {
+    let workspace_mocker = example_workspace_msrv().unwrap();
+    let workspace = ReleaseWorkspace::try_new_with_criteria(
+        workspace_mocker.root(),
+        SelectionCriteria {
+            match_filter: fancy_regex::Regex::new("^crate_(new|unspecified)$").unwrap(),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    let selection = workspace
+        .release_selection()
+        .unwrap()
+        .into_iter()
+        .map(|crt| crt.name())
+        .collect::<BTreeSet<_>>();
+    assert_eq!(
+        ["crate_mid", "crate_new", "crate_unspecified"]
+            .iter()
+            .map(std::string::ToString::to_string)
+            .collect::<BTreeSet<_>>(),
+        selection
+    );
+}
+
+#[test]
+fn configurable_blocking_states() {
+    let workspace_mocker = example_workspace_crate_metadata().unwrap();
+    let criteria = |blocking_states| SelectionCriteria {
+        match_filter: fancy_regex::Regex::new("^crate_unconfigured$").unwrap(),
+        blocking_states,
+        ..Default::default()
+    };
+
+    let workspace = ReleaseWorkspace::try_new_with_criteria(
+        workspace_mocker.root(),
+        criteria(CrateState::BLOCKING_STATES),
+    )
+    .unwrap();
+    let err = workspace.release_selection().unwrap_err().to_string();
+    assert!(err.contains("MissingReadme"), "{}", err);
+
+    let mut blocking_states = CrateState::BLOCKING_STATES;
+    blocking_states.remove(CrateStateFlags::MissingReadme);
+    let workspace =
+        ReleaseWorkspace::try_new_with_criteria(workspace_mocker.root(), criteria(blocking_states))
+            .unwrap();
+
+    let report = workspace.states_report().unwrap();
+    assert!(report["crate_unconfigured"]
+        .flags
+        .contains(CrateStateFlags::MissingReadme));
+    assert!(report["crate_unconfigured"].blocking_flags.is_empty());
+
+    let selection = workspace
+        .release_selection()
+        .unwrap()
+        .into_iter()
+        .map(|crt| crt.name())
+        .collect::<Vec<_>>();
+    assert_eq!(vec!["crate_unconfigured"], selection);
+}
+
+/// Serves the given versions instead of querying crates.io.
+struct StubCratesIndex(HashMap<String, Vec<Version>>);
+
+impl StubCratesIndex {
+    fn boxed(published: &[(&str, &str)]) -> Box<dyn CratesIndex> {
+        let mut versions = HashMap::<String, Vec<Version>>::new();
+        for (name, version) in published {
+            versions
+                .entry(name.to_string())
+                .or_default()
+                .push(Version::parse(version).unwrap());
+        }
+
+        Box::new(Self(versions))
+    }
+}
+
+impl CratesIndex for StubCratesIndex {
+    fn published_versions(&self, name: &str) -> Fallible<Vec<Version>> {
+        Ok(self.0.get(name).cloned().unwrap_or_default())
+    }
+}
+
+/// Fails like an unreachable index.
+struct FailingCratesIndex;
+
+impl CratesIndex for FailingCratesIndex {
+    fn published_versions(&self, _name: &str) -> Fallible<Vec<Version>> {
+        bail!("network is unreachable")
+    }
+}
+
+#[test]
+fn version_already_published() {
+    let workspace_mocker = example_workspace_license_files().unwrap();
+    let workspace = ReleaseWorkspace::try_new_with_criteria(
+        workspace_mocker.root(),
+        SelectionCriteria {
+            match_filter: fancy_regex::Regex::new("^crate_with_(workspace_)?license$").unwrap(),
+            check_crates_io: true,
+            ..Default::default()
+        },
+    )
+    .unwrap()
+    .with_crates_index(StubCratesIndex::boxed(&[
+        ("crate_with_license", "0.0.1"),
+        ("crate_with_workspace_license", "0.0.0"),
+    ]));
+
+    let report = workspace.states_report().unwrap();
+    assert!(report["crate_with_license"]
+        .blocking_flags
+        .contains(CrateStateFlags::VersionAlreadyPublished));
+    assert!(!report["crate_with_workspace_license"]
+        .flags
+        .contains(CrateStateFlags::VersionAlreadyPublished));
+
+    let err = workspace.release_selection().unwrap_err().to_string();
+    assert!(err.contains("VersionAlreadyPublished"), "{}", err);
+}
+
+#[test]
+fn crates_io_is_only_checked_on_request() {
+    let workspace_mocker = example_workspace_license_files().unwrap();
+    let workspace = ReleaseWorkspace::try_new(workspace_mocker.root())
+        .unwrap()
+        .with_crates_index(Box::new(FailingCratesIndex));
+
+    let report = workspace.states_report().unwrap();
+    assert!(!report["crate_with_license"]
+        .flags
+        .contains(CrateStateFlags::VersionAlreadyPublished));
+    assert_eq!(0, workspace.crates_index_queries.load(Ordering::Relaxed));
+}
+
+#[test]
+fn crates_io_check_failures() {
+    let workspace_mocker = example_workspace_license_files().unwrap();
+    let criteria = |strict| SelectionCriteria {
+        match_filter: fancy_regex::Regex::new("^crate_with_license$").unwrap(),
+        check_crates_io: true,
+        strict,
+        ..Default::default()
+    };
+
+    // warns and continues by default
+    let workspace =
+        ReleaseWorkspace::try_new_with_criteria(workspace_mocker.root(), criteria(false))
+            .unwrap()
+            .with_crates_index(Box::new(FailingCratesIndex));
+    let selection = workspace
+        .release_selection()
+        .unwrap()
+        .into_iter()
+        .map(|crt| crt.name())
+        .collect::<Vec<_>>();
+    assert_eq!(vec!["crate_with_license"], selection);
+
+    let workspace =
+        ReleaseWorkspace::try_new_with_criteria(workspace_mocker.root(), criteria(true))
+            .unwrap()
+            .with_crates_index(Box::new(FailingCratesIndex));
+    let err = format!("{:?}", workspace.release_selection().unwrap_err());
+    assert!(err.contains("network is unreachable"), "{}", err);
+}
+
+#[test]
+fn published_versions_are_cached() {
+    let workspace_mocker = example_workspace_license_files().unwrap();
+    let workspace = ReleaseWorkspace::try_new(workspace_mocker.root())
+        .unwrap()
+        .with_crates_index(StubCratesIndex::boxed(&[("crate_with_license", "0.0.1")]));
+
+    for _ in 0..2 {
+        assert_eq!(
+            vec![Version::new(0, 0, 1)],
+            workspace.published_versions("crate_with_license").unwrap()
+        );
+    }
+    assert_eq!(1, workspace.crates_index_queries.load(Ordering::Relaxed));
+}
+
+#[test]
+fn untagged_release_is_reconciled() {
+    let workspace_mocker = example_workspace_untagged_release().unwrap();
+    let workspace = ReleaseWorkspace::try_new_with_criteria(
+        workspace_mocker.root(),
+        SelectionCriteria {
+            reconcile_untagged_releases: true,
+            ..Default::default()
+        },
+    )
+    .unwrap()
+    .with_crates_index(StubCratesIndex::boxed(&[
+        ("crate_tagged", "0.0.1"),
+        ("crate_untagged", "0.0.1"),
+    ]));
+
+    let release_commit = workspace
+        .git_repo()
+        .revparse_single("HEAD~1")
+        .unwrap()
+        .id()
+        .to_string();
+
+    let report = workspace.states_report().unwrap();
+
+    let untagged = &report["crate_untagged"];
+    assert!(untagged
+        .flags
+        .contains(make_bitflags!(CrateStateFlags::{HasPreviousRelease | PreviousReleaseUntagged})));
+    assert!(!untagged.flags.contains(CrateStateFlags::MissingReleaseTag));
+    assert!(!untagged.release_selection);
+    assert_eq!(Some(release_commit), untagged.untagged_release_commit);
+
+    assert!(report["crate_tagged"]
+        .flags
+        .contains(CrateStateFlags::HasPreviousRelease));
+    assert!(!report["crate_tagged"]
+        .flags
+        .contains(CrateStateFlags::PreviousReleaseUntagged));
+
+    let unpublished = &report["crate_unpublished"];
+    assert!(unpublished
+        .flags
+        .contains(CrateStateFlags::MissingReleaseTag));
+    assert!(!unpublished
+        .flags
+        .contains(CrateStateFlags::PreviousReleaseUntagged));
+    assert_eq!(None, unpublished.untagged_release_commit);
+
+    assert!(workspace.created_tags().is_empty());
+}
+
+#[test]
+fn untagged_release_commit_ignores_matching_dependency_requirements() {
+    let workspace_mocker = example_workspace_untagged_release_with_matching_dependency().unwrap();
+    let workspace = ReleaseWorkspace::try_new_with_criteria(
+        workspace_mocker.root(),
+        SelectionCriteria {
+            reconcile_untagged_releases: true,
+            ..Default::default()
+        },
+    )
+    .unwrap()
+    .with_crates_index(StubCratesIndex::boxed(&[
+        ("crate_dep", "0.1.5"),
+        ("crate_untagged", "0.1.5"),
+    ]));
+
+    // HEAD only adds `crate_dep = { .., version = "0.1.5" }` to the manifest
+    let release_commit = workspace
+        .git_repo()
+        .revparse_single("HEAD~1")
+        .unwrap()
+        .id()
+        .to_string();
+
+    let report = workspace.states_report().unwrap();
+    assert!(report["crate_untagged"]
+        .flags
+        .contains(CrateStateFlags::PreviousReleaseUntagged));
+    assert_eq!(
+        Some(release_commit),
+        report["crate_untagged"].untagged_release_commit
+    );
+}
+
+#[test]
+fn untagged_releases_are_only_reconciled_on_request() {
+    let workspace_mocker = example_workspace_untagged_release().unwrap();
+    let workspace = ReleaseWorkspace::try_new(workspace_mocker.root())
+        .unwrap()
+        .with_crates_index(StubCratesIndex::boxed(&[("crate_untagged", "0.0.1")]));
+
+    let report = workspace.states_report().unwrap();
+    assert!(report["crate_untagged"]
+        .flags
+        .contains(CrateStateFlags::MissingReleaseTag));
+    assert!(!report["crate_untagged"]
+        .flags
+        .contains(CrateStateFlags::PreviousReleaseUntagged));
+    assert_eq!(0, workspace.crates_index_queries.load(Ordering::Relaxed));
+}
+
+#[test]
+fn missing_release_tags_are_created() {
+    let workspace_mocker = example_workspace_untagged_release().unwrap();
+    let workspace = ReleaseWorkspace::try_new_with_criteria(
+        workspace_mocker.root(),
+        SelectionCriteria {
+            reconcile_untagged_releases: true,
+            ..Default::default()
+        },
+    )
+    .unwrap()
+    .with_crates_index(StubCratesIndex::boxed(&[("crate_untagged", "0.0.1")]));
+
+    assert!(workspace
+        .members_states()
+        .unwrap()
+        .get("crate_untagged")
+        .unwrap()
+        .contains(CrateStateFlags::PreviousReleaseUntagged));
+    // the selection doesn't modify the repository
+    assert!(workspace.created_tags().is_empty());
+
+    assert_eq!(
+        vec!["crate_untagged-0.0.1"],
+        workspace.create_missing_release_tags(true).unwrap()
+    );
+    assert!(workspace.created_tags().is_empty());
+    assert!(workspace
+        .git_repo()
+        .revparse_single("crate_untagged-0.0.1")
+        .is_err());
+
+    assert_eq!(
+        vec!["crate_untagged-0.0.1"],
+        workspace.create_missing_release_tags(false).unwrap()
+    );
+    assert_eq!(vec!["crate_untagged-0.0.1"], workspace.created_tags());
+
+    let release_commit = workspace.git_repo().revparse_single("HEAD~1").unwrap().id();
+    let tagged_commit = workspace
+        .git_repo()
+        .revparse_single("crate_untagged-0.0.1")
+        .unwrap()
+        .peel_to_commit()
+        .unwrap()
+        .id();
+    assert_eq!(release_commit, tagged_commit);
+
+    // the created tag is picked up like any other release tag
+    let workspace = ReleaseWorkspace::try_new(workspace_mocker.root()).unwrap();
+    let state = workspace
+        .members_states()
+        .unwrap()
+        .get("crate_untagged")
+        .unwrap();
+    assert!(state.contains(CrateStateFlags::HasPreviousRelease));
+    assert!(!state.contains(CrateStateFlags::PreviousReleaseUntagged));
+    assert!(!state.changed());
+}
+
+#[test]
+fn unpublished_dependencies_are_detected_in_cargo_output() {
+    assert_eq!(
+        Some("crate_b".to_string()),
+        unpublished_dependency(indoc::indoc! {r#"
+            error: failed to prepare local package for uploading
+
+            Caused by:
+              no matching package named `crate_b` found
+              location searched: registry `crates-io`
+              required by package `crate_a v0.0.2 (/workspace/crates/crate_a)`
+            "#
+        })
+    );
+
+    assert_eq!(
+        Some("crate_b".to_string()),
+        unpublished_dependency(indoc::indoc! {r#"
+            error: failed to prepare local package for uploading
+
+            Caused by:
+              failed to select a version for the requirement `crate_b = "^0.0.2"`
+              candidate versions found which didn't match: 0.0.1
+              location searched: crates.io index
+              required by package `crate_a v0.0.2 (/workspace/crates/crate_a)`
+            "#
+        })
+    );
+
+    assert_eq!(
+        None,
+        unpublished_dependency("error: failed to parse manifest at `/workspace/Cargo.toml`")
+    );
+}
+
+#[test]
+fn first_error_line_of_cargo_output() {
+    assert_eq!(
+        "error: `crate_a` cannot be published.",
+        first_error_line(indoc::indoc! {r#"
+               Packaging crate_a v0.0.1 (/workspace/crates/crate_a)
+            error: `crate_a` cannot be published.
+            The registry `crates-io` is not listed in the `publish` value in Cargo.toml.
+            "#
+        })
+    );
+    assert_eq!("killed", first_error_line("Packaging crate_a\nkilled\n\n"));
+}
+
+#[test]
+fn verify_publishable_reports_broken_manifests() {
+    let workspace_mocker = example_workspace_broken_manifest().unwrap();
+    let workspace = ReleaseWorkspace::try_new_with_criteria(
+        workspace_mocker.root(),
+        SelectionCriteria {
+            // let cargo detect the problem instead
+            blocking_states: CrateState::BLOCKING_STATES
+                & !BitFlags::from(CrateStateFlags::RegistryNotAllowed),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    let err = workspace.verify_publishable(&None).unwrap_err().to_string();
+    assert!(err.contains("\n- crate_broken: error"), "{}", err);
+    assert!(!err.contains("crate_ok"), "{}", err);
+}
+
+#[test]
+fn verify_crates_publishable_tolerates_allowed_missing_dependencies() {
+    let workspace_mocker = example_workspace_chain().unwrap();
+    let runner = MockCommandRunner::default();
+    let workspace = ReleaseWorkspace::try_new(workspace_mocker.root())
+        .unwrap()
+        .with_command_runner(Box::new(runner.clone()));
+    let crates = workspace.members().unwrap().clone();
+
+    let missing = "error: no matching package named `crate_external` found";
+    runner.script("crate_a", vec![MockCommandRunner::failure(missing); 2]);
+
+    let err = workspace
+        .verify_crates_publishable(&crates, &None, &HashSet::new())
+        .unwrap_err()
+        .to_string();
+    assert!(err.contains("\n- crate_a: error"), "{}", err);
+
+    workspace
+        .verify_crates_publishable(
+            &crates,
+            &None,
+            &["crate_external".to_string()].into_iter().collect(),
+        )
+        .unwrap();
+}
+
+/// The outputs that are left to answer with, keyed by crate name.
+type ScriptedOutputs = HashMap<String, std::collections::VecDeque<CommandOutput>>;
+
+/// The crate name and the arguments of each command that was run.
+type RecordedCommands = Vec<(String, Vec<String>)>;
+
+/// Records the commands and pauses instead of running and waiting.
+/// Answers with the scripted outputs for each crate in order and succeeds once they're used up.
+#[derive(Clone, Default)]
+struct MockCommandRunner {
+    outputs: Rc<RefCell<ScriptedOutputs>>,
+    commands: Rc<RefCell<RecordedCommands>>,
+    sleeps: Rc<RefCell<Vec<Duration>>>,
+}
+
+impl MockCommandRunner {
+    fn script(&self, crate_name: &str, outputs: Vec<CommandOutput>) {
+        self.outputs
+            .borrow_mut()
+            .insert(crate_name.to_string(), outputs.into());
+    }
+
+    fn crate_names(&self) -> Vec<String> {
+        self.commands
+            .borrow()
+            .iter()
+            .map(|(name, _)| name.clone())
+            .collect()
+    }
+
+    fn failure(stderr: &str) -> CommandOutput {
+        CommandOutput {
+            success: false,
+            stderr: stderr.to_string(),
+            ..Default::default()
+        }
+    }
+}
+
+impl CommandRunner for MockCommandRunner {
+    fn run(&self, cmd: &mut Command) -> Fallible<CommandOutput> {
+        let args = cmd
+            .get_args()
+            .map(|arg| arg.to_string_lossy().to_string())
+            .collect::<Vec<_>>();
+        let crate_name = args
+            .iter()
+            .find_map(|arg| arg.strip_prefix("--manifest-path="))
+            .and_then(|path| Path::new(path).parent()?.file_name())
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap();
+
+        self.commands.borrow_mut().push((crate_name.clone(), args));
+
+        Ok(self
+            .outputs
+            .borrow_mut()
+            .get_mut(&crate_name)
+            .and_then(|outputs| outputs.pop_front())
+            .unwrap_or(CommandOutput {
+                success: true,
+                ..Default::default()
+            }))
+    }
+
+    fn sleep(&self, duration: Duration) {
+        self.sleeps.borrow_mut().push(duration);
+    }
+}
+
+fn publish_options() -> PublishOptions {
+    PublishOptions {
+        index_availability_timeout: None,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn publish_in_dependency_order() {
+    let workspace_mocker = example_workspace_chain().unwrap();
+    let runner = MockCommandRunner::default();
+    let workspace = ReleaseWorkspace::try_new(workspace_mocker.root())
+        .unwrap()
+        .with_command_runner(Box::new(runner.clone()));
+
+    let mut selection = workspace.members().unwrap().clone();
+    selection.reverse();
+
+    let report = workspace
+        .publish(
+            &selection,
+            PublishOptions {
+                no_verify: ["crate_b".to_string()].into_iter().collect(),
+                ..publish_options()
+            },
+        )
+        .unwrap();
+
+    assert_eq!(vec!["crate_c", "crate_b", "crate_a"], runner.crate_names());
+    assert_eq!(
+        vec!["crate_c-0.0.1", "crate_b-0.0.1", "crate_a-0.0.1"],
+        report.published
+    );
+    for (name, args) in runner.commands.borrow().iter() {
+        assert_eq!(
+            name == "crate_b",
+            args.contains(&"--no-verify".to_string()),
+            "{}: {:?}",
+            name,
+            args
+        );
+    }
+    assert!(runner.sleeps.borrow().is_empty());
+}
+
+/// Records the progress updates as readable events.
+#[derive(Clone, Default)]
+struct RecordingProgressReporter {
+    events: Rc<RefCell<Vec<String>>>,
+}
+
+impl RecordingProgressReporter {
+    fn events_with_prefix(&self, prefix: &str) -> Vec<String> {
+        self.events
+            .borrow()
+            .iter()
+            .filter(|event| event.starts_with(prefix))
+            .cloned()
+            .collect()
+    }
+}
+
+impl ProgressReporter for RecordingProgressReporter {
+    fn on_phase_started(&self, phase: ReleasePhase, total: usize) {
+        self.events
+            .borrow_mut()
+            .push(format!("started {:?} {}", phase, total));
+    }
+
+    fn on_member_analyzed(&self, name: &str, index: usize, total: usize) {
+        self.events
+            .borrow_mut()
+            .push(format!("analyzed {} {}/{}", name, index + 1, total));
+    }
+
+    fn on_crate_processed(&self, phase: ReleasePhase, name: &str, index: usize, total: usize) {
+        self.events.borrow_mut().push(format!(
+            "processed {:?} {} {}/{}",
+            phase,
+            name,
+            index + 1,
+            total
+        ));
+    }
+
+    fn on_phase_finished(&self, phase: ReleasePhase) {
+        self.events
+            .borrow_mut()
+            .push(format!("finished {:?}", phase));
+    }
+}
+
+#[test]
+fn progress_reporter_visits_every_member_once() {
+    let workspace_mocker = example_workspace_cascade().unwrap();
+    let reporter = RecordingProgressReporter::default();
+    let workspace = ReleaseWorkspace::try_new(workspace_mocker.root())
+        .unwrap()
+        .with_progress_reporter(Box::new(reporter.clone()));
+
+    // the analysis is cached and must only be reported once
+    workspace.states_report().unwrap();
+    workspace.states_report().unwrap();
+
+    let mut analyzed = reporter.events_with_prefix("analyzed");
+    analyzed.sort();
+    assert_eq!(
+        vec![
+            "analyzed crate_a",
+            "analyzed crate_b",
+            "analyzed crate_c",
+            "analyzed crate_d",
+        ],
+        analyzed
+            .iter()
+            .map(|event| event.rsplit_once(' ').unwrap().0)
+            .collect::<Vec<_>>()
+    );
+    assert_eq!(
+        vec!["started Selection 4"],
+        reporter.events_with_prefix("started")
+    );
+    assert_eq!(
+        vec!["finished Selection"],
+        reporter.events_with_prefix("finished")
+    );
+}
+
+#[test]
+fn progress_reporter_follows_the_publish_order() {
+    let workspace_mocker = example_workspace_chain().unwrap();
+    let reporter = RecordingProgressReporter::default();
+    let workspace = ReleaseWorkspace::try_new(workspace_mocker.root())
+        .unwrap()
+        .with_command_runner(Box::new(MockCommandRunner::default()))
+        .with_progress_reporter(Box::new(reporter.clone()));
+
+    let selection = workspace.members().unwrap().clone();
+    workspace.publish(&selection, publish_options()).unwrap();
+
+    assert_eq!(
+        vec![
+            "started Publish 3",
+            "processed Publish crate_c 1/3",
+            "processed Publish crate_b 2/3",
+            "processed Publish crate_a 3/3",
+            "finished Publish",
+        ],
+        *reporter.events.borrow()
+    );
+}
+
+#[test]
+fn publish_retries_transient_failures() {
+    let workspace_mocker = example_workspace_chain().unwrap();
+    let runner = MockCommandRunner::default();
+    runner.script(
+        "crate_c",
+        vec![
+            MockCommandRunner::failure(
+                "error: failed to get a 200 OK response, got 503\nService Unavailable",
+            ),
+            MockCommandRunner::failure("error: the operation timed out"),
+        ],
+    );
+    let workspace = ReleaseWorkspace::try_new(workspace_mocker.root())
+        .unwrap()
+        .with_command_runner(Box::new(runner.clone()));
+
+    let retry_delay = Duration::from_secs(1);
+    let report = workspace
+        .publish(
+            workspace.members().unwrap(),
+            PublishOptions {
+                retry_delay,
+                ..publish_options()
+            },
+        )
+        .unwrap();
+
+    assert_eq!(
+        vec!["crate_c", "crate_c", "crate_c", "crate_b", "crate_a"],
+        runner.crate_names()
+    );
+    assert_eq!(3, report.published.len());
+    assert_eq!(vec![retry_delay, retry_delay * 2], *runner.sleeps.borrow());
+}
+
+#[test]
+fn publish_gives_up_on_persistent_failures() {
+    let workspace_mocker = example_workspace_chain().unwrap();
+    let runner = MockCommandRunner::default();
+    runner.script(
+        "crate_c",
+        vec![MockCommandRunner::failure("error: got 500"); 3],
+    );
+    runner.script(
+        "crate_b",
+        vec![MockCommandRunner::failure(
+            "error: failed to verify package tarball",
+        )],
+    );
+    let workspace = ReleaseWorkspace::try_new(workspace_mocker.root())
+        .unwrap()
+        .with_command_runner(Box::new(runner.clone()));
+
+    let err = workspace
+        .publish(
+            workspace.members().unwrap(),
+            PublishOptions {
+                max_retries: 2,
+                ..publish_options()
+            },
+        )
+        .unwrap_err()
+        .to_string();
+    assert!(
+        err.contains("publishing crate_c-0.0.1 failed after 3 attempt(s): error: got 500"),
+        "{}",
+        err
+    );
+
+    // non-transient failures aren't retried
+    let runner = MockCommandRunner::default();
+    runner.script(
+        "crate_c",
+        vec![MockCommandRunner::failure(
+            "error: failed to verify package tarball",
+        )],
+    );
+    let workspace = ReleaseWorkspace::try_new(workspace_mocker.root())
+        .unwrap()
+        .with_command_runner(Box::new(runner.clone()));
+
+    assert!(workspace
+        .publish(workspace.members().unwrap(), publish_options())
+        .is_err());
+    assert_eq!(vec!["crate_c"], runner.crate_names());
+}
+
+#[test]
+fn publish_respects_the_rate_limit() {
+    let workspace_mocker = example_workspace_chain().unwrap();
+    let runner = MockCommandRunner::default();
+    let workspace = ReleaseWorkspace::try_new(workspace_mocker.root())
+        .unwrap()
+        .with_command_runner(Box::new(runner.clone()));
+
+    let rate_limit_interval = Duration::from_secs(600);
+    workspace
+        .publish(
+            workspace.members().unwrap(),
+            PublishOptions {
+                rate_limit_burst: 2,
+                rate_limit_interval,
+                ..publish_options()
+            },
+        )
+        .unwrap();
+
+    assert_eq!(vec![rate_limit_interval], *runner.sleeps.borrow());
+}
+
+#[test]
+fn publish_resumes_from_the_journal() {
+    let workspace_mocker = example_workspace_chain().unwrap();
+
+    let runner = MockCommandRunner::default();
+    runner.script(
+        "crate_b",
+        vec![MockCommandRunner::failure(
+            "error: failed to verify package tarball",
+        )],
+    );
+    let workspace = ReleaseWorkspace::try_new(workspace_mocker.root())
+        .unwrap()
+        .with_command_runner(Box::new(runner))
+        .with_journal();
+    assert!(workspace
+        .publish(workspace.members().unwrap(), publish_options())
+        .is_err());
+
+    let journal = ReleaseJournal::load(&workspace_mocker.root())
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        vec![
+            JournalEntry::Publish {
+                name: "crate_c".to_string(),
+                version: "0.0.1".to_string(),
+                attempt: 1,
+                outcome: PublishOutcome::Published,
+            },
+            JournalEntry::Publish {
+                name: "crate_b".to_string(),
+                version: "0.0.1".to_string(),
+                attempt: 1,
+                outcome: PublishOutcome::Failed {
+                    error: "error: failed to verify package tarball".to_string()
+                },
+            },
+        ],
+        journal.entries
+    );
+
+    let runner = MockCommandRunner::default();
+    let workspace = ReleaseWorkspace::try_new(workspace_mocker.root())
+        .unwrap()
+        .with_command_runner(Box::new(runner.clone()))
+        .with_journal();
+    let report = workspace
+        .publish(workspace.members().unwrap(), publish_options())
+        .unwrap();
+
+    assert_eq!(vec!["crate_b", "crate_a"], runner.crate_names());
+    assert_eq!(vec!["crate_c-0.0.1"], report.skipped);
+    assert_eq!(vec!["crate_b-0.0.1", "crate_a-0.0.1"], report.published);
+}
+
+#[test]
+fn registry_restrictions_are_validated() {
+    let workspace_mocker = example_workspace_broken_manifest().unwrap();
+
+    let workspace = ReleaseWorkspace::try_new(workspace_mocker.root()).unwrap();
+    let report = workspace.states_report().unwrap();
+    assert!(report["crate_broken"]
+        .blocking_flags
+        .contains(CrateStateFlags::RegistryNotAllowed));
+    assert!(!report["crate_ok"]
+        .flags
+        .contains(CrateStateFlags::RegistryNotAllowed));
+
+    let workspace = ReleaseWorkspace::try_new_with_criteria(
+        workspace_mocker.root(),
+        SelectionCriteria {
+            registry: Some("internal".to_string()),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    let report = workspace.states_report().unwrap();
+    assert!(!report["crate_broken"]
+        .flags
+        .contains(CrateStateFlags::RegistryNotAllowed));
+    assert!(!report["crate_ok"]
+        .flags
+        .contains(CrateStateFlags::RegistryNotAllowed));
+}
+
+#[test]
+fn registry_index_url_is_resolved_from_the_cargo_config() {
+    use crate::crates_index::{registry_index_url, CRATES_IO_SPARSE_INDEX_URL};
+
+    let workspace_mocker = example_workspace_chain().unwrap();
+    workspace_mocker.add_or_replace_file(
+        ".cargo/config.toml",
+        indoc::indoc! {r#"
+            [registries.internal]
+            index = "sparse+https://registry.example.com/index/"
+
+            [registries.git-based]
+            index = "https://example.com/git-index"
+            "#
+        },
+    );
+    let root = workspace_mocker.root();
+
+    assert_eq!(
+        "https://registry.example.com/index/",
+        registry_index_url(&root, "internal").unwrap()
+    );
+    assert_eq!(
+        CRATES_IO_SPARSE_INDEX_URL,
+        registry_index_url(&root, "crates-io").unwrap()
+    );
+    assert!(registry_index_url(&root, "git-based")
+        .unwrap_err()
+        .to_string()
+        .contains("not a sparse index"));
+    assert!(registry_index_url(&root, "unknown")
+        .unwrap_err()
+        .to_string()
+        .contains("not configured"));
+}
+
+#[test]
+fn registry_index_is_queried_with_the_http_client() {
+    let workspace_mocker = example_workspace_chain().unwrap();
+    workspace_mocker.add_or_replace_file(
+        ".cargo/config.toml",
+        indoc::indoc! {r#"
+            [registries.internal]
+            index = "sparse+https://registry.example.com/index/"
+            "#
+        },
+    );
+    let workspace = ReleaseWorkspace::try_new_with_criteria(
+        workspace_mocker.root(),
+        SelectionCriteria {
+            registry: Some("internal".to_string()),
+            ..Default::default()
+        },
+    )
+    .unwrap()
+    .with_http_client(Box::new(MockHttpClient(
+        [(
+            "https://registry.example.com/index/cr/at/crate_a".to_string(),
+            Some(r#"{"name":"crate_a","vers":"0.0.1"}"#.to_string()),
+        )]
+        .into_iter()
+        .collect(),
+    )));
+
+    assert_eq!(
+        vec![Version::new(0, 0, 1)],
+        workspace.published_versions("crate_a").unwrap()
+    );
+    assert!(workspace.published_versions("crate_b").is_err());
+}
+
+#[test]
+fn publish_to_the_configured_registry() {
+    let workspace_mocker = example_workspace_chain().unwrap();
+    let runner = MockCommandRunner::default();
+    let workspace = ReleaseWorkspace::try_new_with_criteria(
+        workspace_mocker.root(),
+        SelectionCriteria {
+            registry: Some("internal".to_string()),
+            ..Default::default()
+        },
+    )
+    .unwrap()
+    .with_command_runner(Box::new(runner.clone()))
+    .with_crates_index(StubCratesIndex::boxed(&[]));
+
+    workspace
+        .publish(workspace.members().unwrap(), publish_options())
+        .unwrap();
+
+    for (name, args) in runner.commands.borrow().iter() {
+        assert!(
+            args.windows(2)
+                .any(|pair| pair[0] == "--registry" && pair[1] == "internal"),
+            "{}: {:?}",
+            name,
+            args
+        );
+    }
+}
+
+/// Serves the given owners per crate instead of querying crates.io, crates without an entry fail to be looked up.
+struct MockHttpClient(HashMap<String, Option<String>>);
+
+impl MockHttpClient {
+    fn boxed(owners: &[(&str, Option<&[&str]>)]) -> Box<dyn HttpClient> {
+        Box::new(Self(
+            owners
+                .iter()
+                .map(|(name, logins)| {
+                    let body = logins.map(|logins| {
+                        serde_json::json!({
+                            "users": logins
+                                .iter()
+                                .map(|login| serde_json::json!({ "login": login, "kind": "user" }))
+                                .collect::<Vec<_>>()
+                        })
+                        .to_string()
+                    });
+
+                    (
+                        format!("{}/crates/{}/owners", CRATES_IO_API_URL, name),
+                        body,
+                    )
+                })
+                .collect(),
+        ))
+    }
+}
+
+impl HttpClient for MockHttpClient {
+    fn get(&self, url: &str) -> Fallible<Option<String>> {
+        match self.0.get(url) {
+            Some(body) => Ok(body.clone()),
+            None => bail!("connection refused"),
+        }
+    }
+}
+
+fn workspace_with_expected_owner<'a>(
+    workspace_mocker: &WorkspaceMocker,
+    http_client: Box<dyn HttpClient>,
+) -> ReleaseWorkspace<'a> {
+    ReleaseWorkspace::try_new_with_criteria(
+        workspace_mocker.root(),
+        SelectionCriteria {
+            expected_owner: Some("release-bot".to_string()),
+            ..Default::default()
+        },
+    )
+    .unwrap()
+    .with_http_client(http_client)
+}
+
+#[test]
+fn verify_ownership_of_owned_and_new_crates() {
+    let workspace_mocker = example_workspace_chain_of_four().unwrap();
+    let workspace = workspace_with_expected_owner(
+        &workspace_mocker,
+        MockHttpClient::boxed(&[
+            ("crate_a", Some(&["release-bot", "someone"][..])),
+            ("crate_b", Some(&["release-bot"][..])),
+            ("crate_c", Some(&["someone", "release-bot"][..])),
+            ("crate_d", None),
+        ]),
+    );
+
+    let mut report = workspace.verify_ownership().unwrap();
+    report.verified.sort();
+
+    assert_eq!(vec!["crate_a", "crate_b", "crate_c"], report.verified);
+    assert_eq!(vec!["crate_d"], report.first_publish);
+}
+
+#[test]
+fn verify_ownership_lists_all_unverified_crates() {
+    let workspace_mocker = example_workspace_chain_of_four().unwrap();
+    let workspace = workspace_with_expected_owner(
+        &workspace_mocker,
+        MockHttpClient::boxed(&[
+            ("crate_a", Some(&["someone", "someone-else"][..])),
+            ("crate_b", Some(&["release-bot"][..])),
+            ("crate_d", None),
+        ]),
+    );
+
+    let err = workspace.verify_ownership().unwrap_err().to_string();
+
+    assert!(
+        err.contains("- crate_a: not owned by release-bot, owners: someone, someone-else"),
+        "{}",
+        err
+    );
+    assert!(
+        err.contains(
+            "- crate_c: unknown ownership, looking up the owners failed: connection refused"
+        ),
+        "{}",
+        err
+    );
+    assert!(!err.contains("crate_b"), "{}", err);
+    assert!(!err.contains("crate_d"), "{}", err);
+}
+
+#[test]
+fn verify_ownership_requires_crates_io() {
+    let workspace_mocker = example_workspace_chain_of_four().unwrap();
+
+    let workspace = ReleaseWorkspace::try_new(workspace_mocker.root()).unwrap();
+    assert!(workspace
+        .verify_ownership()
+        .unwrap_err()
+        .to_string()
+        .contains("no expected crate owner"));
+
+    let workspace = ReleaseWorkspace::try_new_with_criteria(
+        workspace_mocker.root(),
+        SelectionCriteria {
+            expected_owner: Some("release-bot".to_string()),
+            registry: Some("internal".to_string()),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    assert!(workspace
+        .verify_ownership()
+        .unwrap_err()
+        .to_string()
+        .contains("only be verified on crates.io"));
+}
+
+#[test]
+fn next_version_for_every_increment_mode() {
+    use crate::common::SemverIncrementMode::*;
+
+    let workspace_mocker = example_workspace_prerelease_versions().unwrap();
+    let workspace = ReleaseWorkspace::try_new(workspace_mocker.root()).unwrap();
+    let member = |name: &str| {
+        workspace
+            .members()
+            .unwrap()
+            .iter()
+            .find(|crt| crt.name() == name)
+            .copied()
+            .unwrap()
+    };
+
+    for (name, mode, expected) in [
+        ("crate_pre", Major, "1.0.0"),
+        ("crate_pre", Minor, "0.2.0"),
+        ("crate_pre", Patch, "0.2.0"),
+        ("crate_pre", Pre("beta".to_string()), "0.2.0-beta.4"),
+        ("crate_pre", Pre("rc".to_string()), "0.2.0-rc.0"),
+        ("crate_pre", PreMajor("rc".to_string()), "1.0.0-rc.0"),
+        ("crate_pre", PreMinor("rc".to_string()), "0.2.0-rc.0"),
+        ("crate_pre", PrePatch("beta".to_string()), "0.2.0-beta.4"),
+        ("crate_build", Major, "2.0.0"),
+        ("crate_build", Minor, "1.3.0"),
+        ("crate_build", Patch, "1.2.4"),
+        ("crate_build", PreMajor("rc".to_string()), "2.0.0-rc.0"),
+        ("crate_build", PreMinor("rc".to_string()), "1.3.0-rc.0"),
+        ("crate_build", PrePatch("rc".to_string()), "1.2.4-rc.0"),
+        ("crate_minor", Major, "1.0.0"),
+        ("crate_minor", Minor, "0.2.0"),
+        ("crate_minor", Patch, "0.1.1"),
+    ] {
+        assert_eq!(
+            Version::parse(expected).unwrap(),
+            member(name).next_version(mode.clone()).unwrap(),
+            "{} {:?}",
+            name,
+            mode
+        );
+    }
+
+    // only pre-releases can be continued
+    let err = member("crate_build")
+        .next_version(Pre("rc".to_string()))
+        .unwrap_err();
+    assert!(
+        format!("{:?}", err).contains("pre-release increment requested but none found"),
+        "{:?}",
+        err
+    );
+}
+
+#[test]
+fn compute_version_bumps_with_defaults() {
+    let workspace_mocker = example_workspace_prerelease_versions().unwrap();
+    let workspace = ReleaseWorkspace::try_new(workspace_mocker.root()).unwrap();
+
+    let bumps = workspace.compute_version_bumps().unwrap();
+    let bump = |name: &str| {
+        let (current, next) = &bumps[name];
+        (current.to_string(), next.to_string())
+    };
+
+    assert_eq!(
+        ("1.2.3+build.5".to_string(), "1.2.4".to_string()),
+        bump("crate_build")
+    );
+    assert_eq!(
+        ("0.2.0-beta.3".to_string(), "0.2.0".to_string()),
+        bump("crate_pre")
+    );
+    assert_eq!(
+        ("0.1.0".to_string(), "0.2.0".to_string()),
+        bump("crate_minor")
+    );
+
+    // dependencies come first
+    let names = bumps.keys().cloned().collect::<Vec<_>>();
+    let position = |name: &str| names.iter().position(|n| n == name).unwrap();
+    assert!(
+        position("crate_build") < position("crate_pre"),
+        "{:?}",
+        names
+    );
+}
+
+#[test]
+fn compute_version_bumps_with_overrides() {
+    let workspace_mocker = example_workspace_prerelease_versions().unwrap();
+    let workspace = ReleaseWorkspace::try_new_with_criteria(
+        workspace_mocker.root(),
+        SelectionCriteria {
+            semver_increment_mode: crate::common::SemverIncrementMode::Major,
+            semver_increment_overrides: [(
+                "crate_pre".to_string(),
+                crate::common::SemverIncrementMode::Pre("beta".to_string()),
+            )]
+            .into_iter()
+            .collect(),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    let bumps = workspace.compute_version_bumps().unwrap();
+
+    assert_eq!(Version::new(2, 0, 0), bumps["crate_build"].1);
+    assert_eq!(
+        Version::parse("0.2.0-beta.4").unwrap(),
+        bumps["crate_pre"].1
+    );
+    // the manifest metadata takes precedence over the default mode
+    assert_eq!(Version::new(0, 2, 0), bumps["crate_minor"].1);
+}
+
+#[test]
+fn next_versions_follow_the_topmost_changelog_release() {
+    let workspace_mocker = example_workspace_prerelease_versions().unwrap();
+    // the manifest was set to the intended version ahead of the release
+    workspace_mocker.add_or_replace_file(
+        "crates/crate_minor/CHANGELOG.md",
+        &released_changelog("0.0.4"),
+    );
+    workspace_mocker.commit(None);
+
+    let workspace = ReleaseWorkspace::try_new(workspace_mocker.root()).unwrap();
+    let bumps = workspace.compute_version_bumps().unwrap();
+    assert_eq!(
+        (Version::new(0, 1, 0), Version::new(0, 1, 0)),
+        bumps["crate_minor"]
+    );
+
+    // the changelog can't be ahead of the manifest
+    workspace_mocker.add_or_replace_file(
+        "crates/crate_minor/CHANGELOG.md",
+        &released_changelog("0.2.0"),
+    );
+    workspace_mocker.commit(None);
+
+    let workspace = ReleaseWorkspace::try_new(workspace_mocker.root()).unwrap();
+    let err = format!("{:?}", workspace.compute_version_bumps().unwrap_err());
+    assert!(
+        err.contains(
+            "previously documented release version '0.2.0' is greater than this release version '0.1.0'"
+        ),
+        "{}",
+        err
+    );
+}
+
+#[test]
+fn crates_without_a_previous_release_keep_their_version() {
+    let workspace_mocker = example_workspace_prerelease_versions().unwrap();
+    workspace_mocker.add_or_replace_file(
+        "crates/crate_minor/CHANGELOG.md",
+        indoc::indoc! {r#"
+            # Changelog
+
+            ## [Unreleased]
+            Awesome changes!
+            "#
+        },
+    );
+    workspace_mocker.commit(None);
+
+    let workspace = ReleaseWorkspace::try_new_with_criteria(
+        workspace_mocker.root(),
+        SelectionCriteria {
+            semver_increment_mode: crate::common::SemverIncrementMode::Major,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    let bumps = workspace.compute_version_bumps().unwrap();
+
+    assert_eq!(
+        (Version::new(0, 1, 0), Version::new(0, 1, 0)),
+        bumps["crate_minor"]
+    );
+    // only pre-releases are incremented
+    assert_eq!(
+        (
+            Version::parse("0.2.0-beta.3").unwrap(),
+            Version::new(1, 0, 0)
+        ),
+        bumps["crate_pre"]
+    );
+}
+
+fn release_candidate_bumps(workspace_mocker: &WorkspaceMocker) -> Vec<(String, String)> {
+    let workspace = ReleaseWorkspace::try_new_with_criteria(
+        workspace_mocker.root(),
+        SelectionCriteria {
+            semver_increment_overrides: ["crate_rc", "crate_stable"]
+                .iter()
+                .map(|name| {
+                    (
+                        name.to_string(),
+                        crate::common::SemverIncrementMode::Pre("rc".to_string()),
+                    )
+                })
+                .collect(),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    workspace
+        .compute_version_bumps()
+        .unwrap()
+        .into_iter()
+        .map(|(name, (_, next))| (name, next.to_string()))
+        .collect::<BTreeMap<_, _>>()
+        .into_iter()
+        .collect()
+}
+
+#[test]
+fn release_candidates_are_counted_from_the_tags() {
+    let workspace_mocker = example_workspace_release_candidate().unwrap();
+
+    assert_eq!(
+        vec![
+            ("crate_rc".to_string(), "0.2.0-rc.2".to_string()),
+            // the series starts after incrementing by the crate's configured mode
+            ("crate_stable".to_string(), "0.2.0-rc.1".to_string()),
+        ],
+        release_candidate_bumps(&workspace_mocker)
+    );
+
+    workspace_mocker.tag("crate_rc-0.2.0-rc.2");
+    workspace_mocker.tag("crate_stable-0.2.0-rc.1");
+    workspace_mocker.add_or_replace_file(
+        "crates/crate_rc/src/changes.rs",
+        "pub fn more_changes() {}\n",
+    );
+    workspace_mocker.add_or_replace_file(
+        "crates/crate_stable/src/changes.rs",
+        "pub fn more_changes() {}\n",
+    );
+    workspace_mocker.commit(None);
+
+    assert_eq!(
+        vec![
+            ("crate_rc".to_string(), "0.2.0-rc.3".to_string()),
+            ("crate_stable".to_string(), "0.2.0-rc.2".to_string()),
+        ],
+        release_candidate_bumps(&workspace_mocker)
+    );
+}
+
+#[test]
+fn released_versions_are_enumerated_from_the_tags() {
+    let workspace_mocker = example_workspace_release_candidate().unwrap();
+    let workspace = ReleaseWorkspace::try_new(workspace_mocker.root()).unwrap();
+
+    assert_eq!(
+        vec![
+            Version::parse("0.2.0-beta.4").unwrap(),
+            Version::parse("0.2.0-rc.1").unwrap()
+        ],
+        workspace.released_versions("crate_rc").unwrap()
+    );
+    assert_eq!(
+        vec![Version::new(0, 1, 5)],
+        workspace.released_versions("crate_stable").unwrap()
+    );
+}
+
+#[test]
+fn version_reqs_apply_to_release_candidates() {
+    let workspace_mocker = example_workspace_release_candidate().unwrap();
+    let flags = |disallowed: &str, enforced: &str| {
+        let workspace = ReleaseWorkspace::try_new_with_criteria(
+            workspace_mocker.root(),
+            SelectionCriteria {
+                disallowed_version_reqs: vec![semver::VersionReq::parse(disallowed).unwrap()],
+                enforced_version_reqs: vec![semver::VersionReq::parse(enforced).unwrap()],
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        workspace.states_report().unwrap()["crate_rc"].flags
+    };
+
+    // 0.2.0-rc.1 is greater than 0.1 and less than 0.3
+    let rc_flags = flags(">=0.1", "<0.3");
+    assert!(rc_flags.contains(DisallowedVersionReqViolated));
+    assert!(!rc_flags.contains(CrateStateFlags::EnforcedVersionReqViolated));
+
+    // but precedes 0.2.0
+    let rc_flags = flags(">=0.2.0", ">=0.2.0");
+    assert!(!rc_flags.contains(DisallowedVersionReqViolated));
+    assert!(rc_flags.contains(CrateStateFlags::EnforcedVersionReqViolated));
+
+    // requirements on pre-releases keep working as before
+    let rc_flags = flags("=0.2.0-rc.1", ">=0.2.0-rc.1, <0.2.0");
+    assert!(rc_flags.contains(DisallowedVersionReqViolated));
+    assert!(!rc_flags.contains(CrateStateFlags::EnforcedVersionReqViolated));
+}
+
+fn promotion_workspace<'a>(
+    workspace_mocker: &WorkspaceMocker,
+    promote: bool,
+) -> ReleaseWorkspace<'a> {
+    ReleaseWorkspace::try_new_with_criteria(
+        workspace_mocker.root(),
+        SelectionCriteria {
+            release_tag_template: Some("{name}-v{version}".to_string()),
+            // would increment 0.3.0-rc.2 to 1.0.0
+            semver_increment_mode: crate::common::SemverIncrementMode::Major,
+            promote,
+            ..Default::default()
+        },
+    )
+    .unwrap()
+}
+
+#[test]
+fn unchanged_pre_releases_are_not_selected_without_promotion() {
+    let workspace_mocker = example_workspace_promotion().unwrap();
+    let workspace = promotion_workspace(&workspace_mocker, false);
+
+    let report = workspace.states_report().unwrap();
+    assert!(report["crate_foo"]
+        .flags
+        .contains(CrateStateFlags::HasPreviousRelease));
+    assert!(!report["crate_foo"]
+        .flags
+        .contains(CrateStateFlags::PendingStablePromotion));
+    assert!(workspace.release_selection().unwrap().is_empty());
+}
+
+#[test]
+fn pre_releases_are_promoted_to_stable() {
+    let workspace_mocker = example_workspace_promotion().unwrap();
+    let workspace = promotion_workspace(&workspace_mocker, true);
+
+    let report = workspace.states_report().unwrap();
+    assert!(report["crate_foo"]
+        .flags
+        .contains(CrateStateFlags::PendingStablePromotion));
+    assert!(!report["crate_foo"]
+        .flags
+        .contains(CrateStateFlags::ChangedSincePreviousRelease));
+    assert!(report["crate_foo"].release_selection);
+    assert!(!report["crate_bar"]
+        .flags
+        .contains(CrateStateFlags::PendingStablePromotion));
+    assert!(!report["crate_bar"].release_selection);
+
+    let bumps = workspace.compute_version_bumps().unwrap();
+    assert_eq!(
+        vec![(
+            "crate_foo".to_string(),
+            (Version::parse("0.3.0-rc.2").unwrap(), Version::new(0, 3, 0))
+        )],
+        bumps.into_iter().collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn breaking_bumps_cascade_to_dependants() {
+    let workspace_mocker = example_workspace_cascade().unwrap();
+    let workspace = ReleaseWorkspace::try_new_with_criteria(
+        workspace_mocker.root(),
+        SelectionCriteria {
+            semver_increment_overrides: [(
+                "crate_c".to_string(),
+                crate::common::SemverIncrementMode::Breaking,
+            )]
+            .into_iter()
+            .collect(),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    // the escalations are known without computing the version bumps first
+    let report = workspace.states_report().unwrap();
+    for name in ["crate_c", "crate_d"] {
+        assert!(
+            !report[name].flags.contains(CrateStateFlags::CascadeBumped),
+            "{}",
+            name
+        );
+        assert!(report[name].cascade_chain.is_empty(), "{}", name);
+    }
+    assert!(report["crate_b"]
+        .flags
+        .contains(CrateStateFlags::CascadeBumped));
+    assert_eq!(vec!["crate_c"], report["crate_b"].cascade_chain);
+    assert!(report["crate_a"]
+        .flags
+        .contains(CrateStateFlags::CascadeBumped));
+    assert_eq!(vec!["crate_c", "crate_b"], report["crate_a"].cascade_chain);
+
+    let bumps = workspace.compute_version_bumps().unwrap();
+    assert_eq!(Version::new(0, 2, 0), bumps["crate_c"].1);
+    assert_eq!(Version::new(0, 2, 0), bumps["crate_b"].1);
+    assert_eq!(Version::new(0, 2, 0), bumps["crate_a"].1);
+    // only depends on crate_c for its tests
+    assert_eq!(Version::new(0, 1, 1), bumps["crate_d"].1);
+}
+
+#[test]
+fn bumps_only_cascade_via_direct_requirements() {
+    let workspace_mocker = example_workspace_cascade().unwrap();
+    let workspace = ReleaseWorkspace::try_new_with_criteria(
+        workspace_mocker.root(),
+        SelectionCriteria {
+            semver_increment_overrides: [(
+                "crate_c".to_string(),
+                crate::common::SemverIncrementMode::Breaking,
+            )]
+            .into_iter()
+            .collect(),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    let members = workspace.members().unwrap();
+    let member = |name: &str| *members.iter().find(|crt| crt.name() == name).unwrap();
+
+    // crate_a only requires crate_c via crate_b, which isn't part of these crates
+    let cascades = workspace
+        .version_bump_cascades(
+            &[member("crate_c"), member("crate_a")],
+            workspace.members_states().unwrap(),
+        )
+        .unwrap();
+    assert!(cascades.is_empty(), "{:?}", cascades);
+}
+
+#[test]
+fn compatible_bumps_dont_cascade() {
+    let workspace_mocker = example_workspace_cascade().unwrap();
+    let workspace = ReleaseWorkspace::try_new(workspace_mocker.root()).unwrap();
+
+    let bumps = workspace.compute_version_bumps().unwrap();
+    for name in ["crate_a", "crate_b", "crate_c", "crate_d"] {
+        assert_eq!(Version::new(0, 1, 1), bumps[name].1, "{}", name);
+    }
+
+    let report = workspace.states_report().unwrap();
+    assert!(!report
+        .crates
+        .values()
+        .any(|crate_report| crate_report.flags.contains(CrateStateFlags::CascadeBumped)));
+}
+
+#[test]
+fn exact_requirements_dont_cascade() {
+    let workspace_mocker = example_workspace_cascade().unwrap();
+    let manifest_path = "crates/crate_b/Cargo.toml";
+    let manifest = std::fs::read_to_string(workspace_mocker.root().join(manifest_path)).unwrap();
+    workspace_mocker.add_or_replace_file(
+        manifest_path,
+        &manifest.replace(
+            r#"crate_c = { path = "../crate_c", version = "0.1.0" }"#,
+            r#"crate_c = { path = "../crate_c", version = "=0.1.0" }"#,
+        ),
+    );
+    workspace_mocker.commit(None);
+
+    let workspace = ReleaseWorkspace::try_new_with_criteria(
+        workspace_mocker.root(),
+        SelectionCriteria {
+            semver_increment_overrides: [("crate_c".to_string(), SemverIncrementMode::Breaking)]
+                .into_iter()
+                .collect(),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    let report = workspace.states_report().unwrap();
+    assert!(!report
+        .crates
+        .values()
+        .any(|crate_report| crate_report.flags.contains(CrateStateFlags::CascadeBumped)));
+
+    // the pin is rewritten to the new version instead
+    let bumps = workspace.compute_version_bumps().unwrap();
+    assert_eq!(Version::new(0, 2, 0), bumps["crate_c"].1);
+    for name in ["crate_a", "crate_b", "crate_d"] {
+        assert_eq!(Version::new(0, 1, 1), bumps[name].1, "{}", name);
+    }
+}
+
+fn frontmatter_bumps(
+    workspace_mocker: &WorkspaceMocker,
+    semver_increment_overrides: &[(&str, crate::common::SemverIncrementMode)],
+) -> BTreeMap<String, String> {
+    let workspace = ReleaseWorkspace::try_new_with_criteria(
+        workspace_mocker.root(),
+        SelectionCriteria {
+            semver_increment_mode: crate::common::SemverIncrementMode::Major,
+            semver_increment_overrides: semver_increment_overrides
+                .iter()
+                .map(|(name, mode)| (name.to_string(), mode.clone()))
+                .collect(),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    workspace
+        .compute_version_bumps()
+        .unwrap()
+        .into_iter()
+        .map(|(name, (_, next))| (name, next.to_string()))
+        .collect()
+}
+
+#[test]
+fn changelog_frontmatter_increment_mode_precedence() {
+    let workspace_mocker = example_workspace_frontmatter_increments().unwrap();
+
+    let bumps = frontmatter_bumps(&workspace_mocker, &[]);
+    // the frontmatter takes precedence over the default of the criteria
+    assert_eq!("0.2.0", bumps["crate_frontmatter"]);
+    // the manifest metadata takes precedence over the frontmatter
+    assert_eq!("0.1.1", bumps["crate_metadata"]);
+    assert_eq!("0.3.0-rc.2", bumps["crate_rc"]);
+    assert_eq!("1.0.0", bumps["crate_plain"]);
+
+    // the overrides of the criteria take precedence over the frontmatter
+    let bumps = frontmatter_bumps(
+        &workspace_mocker,
+        &[(
+            "crate_frontmatter",
+            crate::common::SemverIncrementMode::Patch,
+        )],
+    );
+    assert_eq!("0.1.1", bumps["crate_frontmatter"]);
+}
+
+#[test]
+fn changelog_frontmatter_increment_mode_is_reset_after_release() {
+    let workspace_mocker = example_workspace_frontmatter_increments().unwrap();
+
+    {
+        let workspace = ReleaseWorkspace::try_new(workspace_mocker.root()).unwrap();
+        for member in workspace.members().unwrap() {
+            member
+                .changelog()
+                .unwrap()
+                .reset_front_matter_to_defaults()
+                .unwrap();
+        }
+    }
+
+    let bumps = frontmatter_bumps(&workspace_mocker, &[]);
+    // falls back to the default of the frontmatter
+    assert_eq!("0.1.1", bumps["crate_frontmatter"]);
+    // falls back to the default of the criteria
+    assert_eq!("1.0.0", bumps["crate_rc"]);
+}
+
+#[test]
+fn invalid_changelog_frontmatter_increment_mode() {
+    let workspace_mocker = example_workspace_frontmatter_increments().unwrap();
+    workspace_mocker.add_or_replace_file(
+        "crates/crate_plain/CHANGELOG.md",
+        indoc::indoc! {r#"
+            ---
+            semver_increment_mode: huge
+            ---
+            # Changelog
+
+            ## [Unreleased]
+            Awesome changes!
+            "#
+        },
+    );
+
+    let workspace = ReleaseWorkspace::try_new(workspace_mocker.root()).unwrap();
+    let err = format!("{:?}", workspace.compute_version_bumps().unwrap_err());
+
+    assert!(err.contains("crate_plain"), "{}", err);
+    assert!(
+        err.contains("unknown semver increment mode 'huge'"),
+        "{}",
+        err
+    );
+}
+
+fn semver_checks_workspace<'a>(
+    workspace_mocker: &WorkspaceMocker,
+    semver_increment_overrides: &[(&str, crate::common::SemverIncrementMode)],
+) -> ReleaseWorkspace<'a> {
+    ReleaseWorkspace::try_new_with_criteria(
+        workspace_mocker.root(),
+        SelectionCriteria {
+            semver_increment_overrides: semver_increment_overrides
+                .iter()
+                .map(|(name, mode)| (name.to_string(), mode.clone()))
+                .collect(),
+            ..Default::default()
+        },
+    )
+    .unwrap()
+}
+
+fn semver_checks_breaking_output() -> CommandOutput {
+    CommandOutput {
+        success: false,
+        stdout: indoc::indoc! {r#"
+            --- failure function_missing: pub fn removed or renamed ---
+
+            Description:
+            A publicly-visible function cannot be imported by its prior path. A `pub use` may have been removed, or the function itself may have been renamed or removed entirely.
+            "#}
+        .to_string(),
+        stderr: indoc::indoc! {r#"
+                 Parsing crate_a v0.1.0 (current)
+                 Parsing crate_a v0.1.0 (baseline)
+                Checking crate_a v0.1.0 -> v0.1.0 (no change)
+                 Checked [   0.010s] 60 checks: 59 pass, 1 fail, 0 warn, 0 skip
+                 Summary semver requires new minor version: 1 major and 0 minor checks failed
+            "#}
+        .to_string(),
+    }
+}
+
+#[test]
+fn semver_checks_block_too_weak_increments() {
+    let workspace_mocker = example_workspace_semver_checks().unwrap();
+    let runner = MockCommandRunner::default();
+    runner.script("crate_a", vec![semver_checks_breaking_output()]);
+    let workspace = semver_checks_workspace(&workspace_mocker, &[])
+        .with_command_runner(Box::new(runner.clone()));
+
+    // they're not a part of the selection
+    workspace.states_report().unwrap();
+    assert!(runner.commands.borrow().is_empty());
+
+    let report = workspace.semver_checks().unwrap();
+
+    assert!(report["crate_a"]
+        .blocking_flags
+        .contains(CrateStateFlags::SemverIncrementTooWeak));
+    assert_eq!(
+        Some(SemverChecksReport {
+            baseline_rev: "crate_a-0.1.0".to_string(),
+            required_increment: Some(crate::common::SemverIncrementMode::Minor),
+            findings: vec!["function_missing: pub fn removed or renamed".to_string()],
+        }),
+        report["crate_a"].semver_checks
+    );
+
+    assert!(!report["crate_b"]
+        .flags
+        .contains(CrateStateFlags::SemverIncrementTooWeak));
+    assert_eq!(
+        Some(false),
+        report["crate_b"]
+            .semver_checks
+            .as_ref()
+            .map(SemverChecksReport::changes_detected)
+    );
+
+    for (name, args) in runner.commands.borrow().iter() {
+        assert_eq!(&["semver-checks", "check-release"], &args[..2]);
+        assert!(
+            args.contains(&format!("--baseline-rev={}-0.1.0", name)),
+            "{:?}",
+            args
+        );
+    }
+    assert_eq!(vec!["crate_a", "crate_b"], runner.crate_names());
+}
+
+#[test]
+fn semver_checks_accept_sufficient_increments() {
+    let workspace_mocker = example_workspace_semver_checks().unwrap();
+    let runner = MockCommandRunner::default();
+    runner.script("crate_a", vec![semver_checks_breaking_output()]);
+    let workspace = semver_checks_workspace(
+        &workspace_mocker,
+        &[("crate_a", crate::common::SemverIncrementMode::Breaking)],
+    )
+    .with_command_runner(Box::new(runner.clone()));
+
+    let report = workspace.semver_checks().unwrap();
+
+    assert!(!report["crate_a"]
+        .flags
+        .contains(CrateStateFlags::SemverIncrementTooWeak));
+    assert!(report["crate_a"]
+        .semver_checks
+        .as_ref()
+        .unwrap()
+        .changes_detected());
+}
+
+#[test]
+fn semver_checks_are_skipped_if_not_installed() {
+    let workspace_mocker = example_workspace_semver_checks().unwrap();
+    let runner = MockCommandRunner::default();
+    runner.script(
+        "crate_a",
+        vec![MockCommandRunner::failure(
+            "error: no such command: `semver-checks`\n\n\tView all installed commands with `cargo --list`",
+        )],
+    );
+    let workspace = semver_checks_workspace(&workspace_mocker, &[])
+        .with_command_runner(Box::new(runner.clone()));
+
+    let report = workspace.semver_checks().unwrap();
+
+    // the remaining crates aren't attempted after the first one
+    assert_eq!(vec!["crate_a"], runner.crate_names());
+    assert!(report.is_empty(), "{:?}", report);
+    for name in ["crate_a", "crate_b"] {
+        assert!(workspace.states_report().unwrap()[name].release_selection);
+    }
+}
+
+#[test]
+fn semver_checks_only_run_for_changed_crates_with_a_previous_release() {
+    let workspace_mocker = example_workspace_cascade().unwrap();
+    let runner = MockCommandRunner::default();
+    let workspace = semver_checks_workspace(&workspace_mocker, &[])
+        .with_command_runner(Box::new(runner.clone()));
+
+    workspace.semver_checks().unwrap();
+    assert!(runner.commands.borrow().is_empty());
+
+    let workspace_mocker = example_workspace_released().unwrap();
+    let runner = MockCommandRunner::default();
+    let workspace = semver_checks_workspace(&workspace_mocker, &[])
+        .with_command_runner(Box::new(runner.clone()));
+
+    workspace.semver_checks().unwrap();
+    assert!(runner.commands.borrow().is_empty());
+}
+
+#[ignore = "requires cargo-semver-checks"]
+#[test]
+fn semver_checks_detect_removed_functions() {
+    let workspace_mocker = example_workspace_semver_checks().unwrap();
+    let workspace = semver_checks_workspace(&workspace_mocker, &[]);
+
+    let report = workspace.semver_checks().unwrap();
+
+    assert!(report["crate_a"]
+        .blocking_flags
+        .contains(CrateStateFlags::SemverIncrementTooWeak));
+    assert!(report["crate_a"]
+        .semver_checks
+        .as_ref()
+        .unwrap()
+        .findings
+        .iter()
+        .any(|finding| finding.starts_with("function_missing")));
+    assert!(!report["crate_b"]
+        .flags
+        .contains(CrateStateFlags::SemverIncrementTooWeak));
+}
+
+#[test]
+fn set_manifest_version_preserves_formatting() {
+    let workspace_mocker = example_workspace_formatted_manifest().unwrap();
+    let workspace = ReleaseWorkspace::try_new(workspace_mocker.root()).unwrap();
+    let crate_a = workspace
+        .members()
+        .unwrap()
+        .iter()
+        .find(|crt| crt.name() == "crate_a")
+        .unwrap();
+
+    crate_a
+        .set_manifest_version(&Version::new(0, 2, 0))
+        .unwrap();
+
+    let manifest = std::fs::read_to_string(crate_a.manifest_path()).unwrap();
+    assert_eq!(
+        crate::tests::workspace_mocker::FORMATTED_MANIFEST.replace(
+            r#"version = "0.1.0"   # keep"#,
+            r#"version = "0.2.0"   # keep"#
+        ),
+        manifest
+    );
+}
+
+#[test]
+fn set_manifest_version_refuses_inherited_versions() {
+    let workspace_mocker = example_workspace_formatted_manifest().unwrap();
+    let workspace = ReleaseWorkspace::try_new(workspace_mocker.root()).unwrap();
+    let crate_b = workspace
+        .members()
+        .unwrap()
+        .iter()
+        .find(|crt| crt.name() == "crate_b")
+        .unwrap();
+
+    let manifest = std::fs::read_to_string(crate_b.manifest_path())
+        .unwrap()
+        .replace(r#"version = "0.1.0""#, "version = { workspace = true }");
+    std::fs::write(crate_b.manifest_path(), &manifest).unwrap();
+
+    let err = crate_b
+        .set_manifest_version(&Version::new(0, 2, 0))
+        .unwrap_err()
+        .to_string();
+    assert!(err.contains("inherited from the workspace"), "{}", err);
+    assert_eq!(
+        manifest,
+        std::fs::read_to_string(crate_b.manifest_path()).unwrap()
+    );
+}
+
+#[test]
+fn apply_version_bumps_writes_the_next_versions() {
+    let workspace_mocker = example_workspace_formatted_manifest().unwrap();
+    let workspace = ReleaseWorkspace::try_new_with_criteria(
+        workspace_mocker.root(),
+        SelectionCriteria {
+            semver_increment_overrides: [(
+                "crate_b".to_string(),
+                crate::common::SemverIncrementMode::Minor,
+            )]
+            .into_iter()
+            .collect(),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    let manifests_before = workspace
+        .members()
+        .unwrap()
+        .iter()
+        .map(|crt| {
+            (
+                crt.name(),
+                std::fs::read_to_string(crt.manifest_path()).unwrap(),
+            )
+        })
+        .collect::<HashMap<_, _>>();
+
+    let mut modified_manifests = workspace.apply_version_bumps().unwrap();
+    modified_manifests.sort();
+    assert_eq!(
+        vec![
+            workspace_mocker.root().join("crates/crate_a/Cargo.toml"),
+            workspace_mocker.root().join("crates/crate_b/Cargo.toml"),
+        ],
+        modified_manifests
+    );
+
+    for (name, expected_version) in [("crate_a", "0.1.1"), ("crate_b", "0.2.0")] {
+        let manifest_after = std::fs::read_to_string(
+            workspace_mocker
+                .root()
+                .join(format!("crates/{}/Cargo.toml", name)),
+        )
+        .unwrap();
+
+        // only the version line differs
+        let changed_lines = manifests_before[name]
+            .lines()
+            .zip(manifest_after.lines())
+            .filter(|(before, after)| before != after)
+            .collect::<Vec<_>>();
+        assert_eq!(1, changed_lines.len(), "{:?}", changed_lines);
+        assert!(
+            changed_lines[0]
+                .1
+                .starts_with(&format!(r#"version = "{}""#, expected_version)),
+            "{:?}",
+            changed_lines
+        );
+        assert_eq!(
+            manifests_before[name].lines().count(),
+            manifest_after.lines().count()
+        );
+    }
+}
+
+#[test_case("0.1", "0.2.0", "0.2")]
+#[test_case("^0.1", "0.2.0", "^0.2"; "caret")]
+#[test_case("0.1.3", "0.2.0", "0.2.0")]
+#[test_case("=0.1.3", "0.2.0", "=0.2.0"; "exact")]
+#[test_case("~0.1.3", "0.2.0", "~0.2.0"; "tilde")]
+#[test_case("~1", "2.0.1", "~2"; "tilde major only")]
+#[test_case("0.1.*", "0.2.0", "0.2.*"; "wildcard")]
+#[test_case("0.1", "0.2.0-rc.1", "0.2.0-rc.1"; "pre-releases need the full precision")]
+#[test_case(">=0.1, <0.2", "0.2.0", "0.2.0"; "multiple comparators")]
+fn rewrite_version_req_keeps_the_style(existing: &str, version: &str, expected: &str) {
+    assert_eq!(
+        expected,
+        rewrite_version_req(existing, &Version::parse(version).unwrap()).unwrap()
+    );
+}
+
+#[test]
+fn dependent_requirements_are_rewritten() {
+    let workspace_mocker = example_workspace_dependency_requirements().unwrap();
+    let workspace = ReleaseWorkspace::try_new_with_criteria(
+        workspace_mocker.root(),
+        SelectionCriteria {
+            semver_increment_overrides: [(
+                "crate_dep".to_string(),
+                crate::common::SemverIncrementMode::Minor,
+            )]
+            .into_iter()
+            .collect(),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    let manifest = |name: &str| {
+        std::fs::read_to_string(
+            workspace_mocker
+                .root()
+                .join(format!("crates/{}/Cargo.toml", name)),
+        )
+        .unwrap()
+    };
+    let satisfied_manifest_before = manifest("crate_satisfied");
+
+    let mut modified_manifests = workspace.update_dependent_requirements().unwrap();
+    modified_manifests.sort();
+    assert_eq!(
+        ["crate_caret", "crate_exact", "crate_target", "crate_tilde"]
+            .iter()
+            .map(|name| workspace_mocker
+                .root()
+                .join(format!("crates/{}/Cargo.toml", name)))
+            .collect::<Vec<_>>(),
+        modified_manifests
+    );
+
+    for (name, table, expected_version_req) in [
+        ("crate_caret", "[dependencies]", "0.2"),
+        ("crate_exact", "[dev-dependencies]", "=0.2.0"),
+        ("crate_tilde", "[build-dependencies]", "~0.2.0"),
+        (
+            "crate_target",
+            "[target.'cfg(unix)'.dependencies]",
+            "^0.2.0",
+        ),
+    ] {
+        let manifest = manifest(name);
+        let expected = format!(
+            "{}\ncrate_dep = {{ path = \"../crate_dep\", version = \"{}\" }}",
+            table, expected_version_req
+        );
+        assert!(manifest.contains(&expected), "{}:\n{}", name, manifest);
+    }
+
+    assert_eq!(satisfied_manifest_before, manifest("crate_satisfied"));
+}
+
+fn planned_versions_workspace(workspace_mocker: &WorkspaceMocker) -> ReleaseWorkspace<'_> {
+    ReleaseWorkspace::try_new_with_criteria(
+        workspace_mocker.root(),
+        SelectionCriteria {
+            semver_increment_overrides: [(
+                "crate_dep".to_string(),
+                crate::common::SemverIncrementMode::Minor,
+            )]
+            .into_iter()
+            .collect(),
+            ..Default::default()
+        },
+    )
+    .unwrap()
+    .with_command_runner(Box::new(MockCommandRunner::default()))
+}
+
+/// The contents of all manifests by their path relative to the workspace root.
+fn manifests(workspace_mocker: &WorkspaceMocker) -> BTreeMap<String, String> {
+    let root = workspace_mocker.root();
+
+    std::fs::read_dir(root.join("crates"))
+        .unwrap()
+        .map(|entry| entry.unwrap().path().join("Cargo.toml"))
+        .chain(std::iter::once(root.join("Cargo.toml")))
+        .map(|path| {
+            (
+                path.strip_prefix(&root).unwrap().display().to_string(),
+                std::fs::read_to_string(&path).unwrap(),
+            )
+        })
+        .collect()
+}
+
+#[test]
+fn planned_versions_are_applied_once() {
+    let workspace_mocker = example_workspace_dependency_requirements().unwrap();
+    let workspace = planned_versions_workspace(&workspace_mocker);
+    let plan = workspace.compute_plan().unwrap();
+
+    workspace.write_planned_versions(&plan).unwrap();
+    let manifests_after_single_run = manifests(&workspace_mocker);
+
+    let workspace = planned_versions_workspace(&workspace_mocker);
+    assert_eq!(
+        Version::new(0, 2, 0),
+        workspace
+            .members()
+            .unwrap()
+            .iter()
+            .find(|member| member.name() == "crate_dep")
+            .unwrap()
+            .version()
+    );
+    let modified_files = workspace.write_planned_versions(&plan).unwrap();
+    assert_eq!(
+        vec![workspace_mocker.root().join("Cargo.lock")],
+        modified_files
+    );
+    assert_eq!(manifests_after_single_run, manifests(&workspace_mocker));
+
+    // the versions are bumped but the run was interrupted before rewriting the requirements
+    let workspace_mocker = example_workspace_dependency_requirements().unwrap();
+    let workspace = planned_versions_workspace(&workspace_mocker);
+    let plan = workspace.compute_plan().unwrap();
+    workspace.apply_planned_version_bumps(&plan).unwrap();
+
+    let workspace = planned_versions_workspace(&workspace_mocker);
+    let modified_files = workspace.write_planned_versions(&plan).unwrap();
+    assert!(modified_files.contains(
+        &workspace_mocker
+            .root()
+            .join("crates/crate_caret/Cargo.toml")
+    ));
+    assert!(!modified_files.contains(&workspace_mocker.root().join("crates/crate_dep/Cargo.toml")));
+    assert_eq!(manifests_after_single_run, manifests(&workspace_mocker));
+}
+
+#[test]
+fn planned_versions_are_not_applied_over_unexpected_versions() {
+    let workspace_mocker = example_workspace_dependency_requirements().unwrap();
+    let workspace = planned_versions_workspace(&workspace_mocker);
+    let plan = workspace.compute_plan().unwrap();
+
+    workspace
+        .members()
+        .unwrap()
+        .iter()
+        .find(|member| member.name() == "crate_dep")
+        .unwrap()
+        .set_manifest_version(&Version::new(0, 5, 0))
+        .unwrap();
+
+    let workspace = planned_versions_workspace(&workspace_mocker);
+    let err = workspace.apply_planned_version_bumps(&plan).unwrap_err();
+    assert!(
+        err.to_string().contains(
+            "expected version 0.1.3 or 0.2.0 in the manifest according to the plan, found 0.5.0"
+        ),
+        "{:?}",
+        err
+    );
+}
+
+fn inherited_versions_workspace<'a>(
+    workspace_mocker: &WorkspaceMocker,
+    semver_increment_overrides: &[(&str, crate::common::SemverIncrementMode)],
+    lockstep: bool,
+) -> ReleaseWorkspace<'a> {
+    ReleaseWorkspace::try_new_with_criteria(
+        workspace_mocker.root(),
+        SelectionCriteria {
+            semver_increment_overrides: semver_increment_overrides
+                .iter()
+                .map(|(name, mode)| (name.to_string(), mode.clone()))
+                .collect(),
+            lockstep,
+            ..Default::default()
+        },
+    )
+    .unwrap()
+}
+
+fn workspace_package_version(workspace_mocker: &WorkspaceMocker) -> String {
+    let manifest: toml_edit::Document =
+        std::fs::read_to_string(workspace_mocker.root().join("Cargo.toml"))
+            .unwrap()
+            .parse()
+            .unwrap();
+
+    manifest["workspace"]["package"]["version"]
+        .as_str()
+        .unwrap()
+        .to_string()
+}
+
+#[test]
+fn inherited_versions_are_bumped_in_the_root_manifest() {
+    let workspace_mocker = example_workspace_inherited_versions().unwrap();
+    let workspace = inherited_versions_workspace(&workspace_mocker, &[], false);
+
+    let versions = workspace
+        .members()
+        .unwrap()
+        .iter()
+        .map(|crt| Ok((crt.name(), (crt.version(), crt.inherits_version()?))))
+        .collect::<Fallible<HashMap<_, _>>>()
+        .unwrap();
+    assert_eq!((Version::new(0, 1, 0), true), versions["crate_a"]);
+    assert_eq!((Version::new(0, 1, 0), true), versions["crate_b"]);
+    assert_eq!((Version::new(0, 3, 0), false), versions["crate_c"]);
+
+    let crate_manifests_before = ["crate_a", "crate_b"].map(|name| {
+        std::fs::read_to_string(
+            workspace_mocker
+                .root()
+                .join(format!("crates/{}/Cargo.toml", name)),
+        )
+        .unwrap()
+    });
+
+    let mut modified_manifests = workspace.apply_version_bumps().unwrap();
+    modified_manifests.sort();
+    assert_eq!(
+        vec![
+            workspace_mocker.root().join("Cargo.toml"),
+            workspace_mocker.root().join("crates/crate_c/Cargo.toml"),
+        ],
+        modified_manifests
+    );
+
+    assert_eq!("0.1.1", workspace_package_version(&workspace_mocker));
+    assert_eq!(
+        crate_manifests_before,
+        ["crate_a", "crate_b"].map(|name| {
+            std::fs::read_to_string(
+                workspace_mocker
+                    .root()
+                    .join(format!("crates/{}/Cargo.toml", name)),
+            )
+            .unwrap()
+        })
+    );
+}
+
+#[test]
+fn inherited_versions_must_not_diverge() {
+    let workspace_mocker = example_workspace_inherited_versions().unwrap();
+    let workspace = inherited_versions_workspace(
+        &workspace_mocker,
+        &[("crate_b", crate::common::SemverIncrementMode::Minor)],
+        false,
+    );
+    let root_manifest_before =
+        std::fs::read_to_string(workspace_mocker.root().join("Cargo.toml")).unwrap();
+    let crate_c_manifest_before =
+        std::fs::read_to_string(workspace_mocker.root().join("crates/crate_c/Cargo.toml")).unwrap();
+
+    let err = workspace.apply_version_bumps().unwrap_err().to_string();
+    assert!(err.contains("- crate_a: 0.1.1"), "{}", err);
+    assert!(err.contains("- crate_b: 0.2.0"), "{}", err);
+
+    // nothing is written if the bumps can't be applied
+    assert_eq!(
+        root_manifest_before,
+        std::fs::read_to_string(workspace_mocker.root().join("Cargo.toml")).unwrap()
+    );
+    assert_eq!(
+        crate_c_manifest_before,
+        std::fs::read_to_string(workspace_mocker.root().join("crates/crate_c/Cargo.toml")).unwrap()
+    );
+}
+
+#[test]
+fn inherited_versions_are_bumped_in_lockstep() {
+    let workspace_mocker = example_workspace_inherited_versions().unwrap();
+    let workspace = inherited_versions_workspace(
+        &workspace_mocker,
+        &[("crate_b", crate::common::SemverIncrementMode::Minor)],
+        true,
+    );
+
+    workspace.apply_version_bumps().unwrap();
+    assert_eq!("0.2.0", workspace_package_version(&workspace_mocker));
+}
+
+#[test]
+fn inherited_dependency_requirements_are_rewritten_in_the_root_manifest() {
+    let workspace_mocker = example_workspace_inherited_dependencies().unwrap();
+    let workspace = ReleaseWorkspace::try_new_with_criteria(
+        workspace_mocker.root(),
+        SelectionCriteria {
+            semver_increment_overrides: [(
+                "crate_dep".to_string(),
+                crate::common::SemverIncrementMode::Minor,
+            )]
+            .into_iter()
+            .collect(),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    let manifest_path = |name: &str| {
+        workspace_mocker
+            .root()
+            .join(format!("crates/{}/Cargo.toml", name))
+    };
+    let inherited_manifests_before = ["crate_inherited", "crate_inherited_dev"]
+        .map(|name| std::fs::read_to_string(manifest_path(name)).unwrap());
+
+    let mut modified_manifests = workspace.update_dependent_requirements().unwrap();
+    modified_manifests.sort();
+    assert_eq!(
+        vec![
+            workspace_mocker.root().join("Cargo.toml"),
+            manifest_path("crate_overridden"),
+            manifest_path("crate_plain"),
+        ],
+        modified_manifests
+    );
+
+    let root_manifest =
+        std::fs::read_to_string(workspace_mocker.root().join("Cargo.toml")).unwrap();
+    assert!(
+        root_manifest.contains(r#"crate_dep = { path = "crates/crate_dep", version = "0.2" }"#),
+        "{}",
+        root_manifest
+    );
+
+    for (name, expected_version_req) in [("crate_overridden", "=0.2.0"), ("crate_plain", "0.2")] {
+        let manifest = std::fs::read_to_string(manifest_path(name)).unwrap();
+        assert!(
+            manifest.contains(&format!(
+                r#"crate_dep = {{ path = "../crate_dep", version = "{}" }}"#,
+                expected_version_req
+            )),
+            "{}:\n{}",
+            name,
+            manifest
+        );
+    }
+
+    assert_eq!(
+        inherited_manifests_before,
+        ["crate_inherited", "crate_inherited_dev"]
+            .map(|name| std::fs::read_to_string(manifest_path(name)).unwrap())
+    );
+}
+
+/// Returns the versions of the lockfile entries by package name.
+fn lockfile_packages(workspace_mocker: &WorkspaceMocker) -> HashMap<String, String> {
+    let lockfile: toml_edit::Document =
+        std::fs::read_to_string(workspace_mocker.root().join("Cargo.lock"))
+            .unwrap()
+            .parse()
+            .unwrap();
+
+    lockfile["package"]
+        .as_array_of_tables()
+        .unwrap()
+        .iter()
+        .map(|package| {
+            (
+                package["name"].as_str().unwrap().to_string(),
+                package["version"].as_str().unwrap().to_string(),
+            )
+        })
+        .collect()
+}
+
+#[test]
+fn release_versions_are_written_to_the_lockfile() {
+    let workspace_mocker = example_workspace_external_dependency().unwrap();
+    let workspace = ReleaseWorkspace::try_new_with_criteria(
+        workspace_mocker.root(),
+        SelectionCriteria {
+            semver_increment_overrides: [(
+                "crate_a".to_string(),
+                crate::common::SemverIncrementMode::Minor,
+            )]
+            .into_iter()
+            .collect(),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    let packages_before = lockfile_packages(&workspace_mocker);
+
+    let mut modified_files = workspace.write_release_versions().unwrap();
+    modified_files.sort();
+    assert_eq!(
+        vec![
+            workspace_mocker.root().join("Cargo.lock"),
+            workspace_mocker.root().join("crates/crate_a/Cargo.toml"),
+            workspace_mocker.root().join("crates/crate_b/Cargo.toml"),
+        ],
+        modified_files
+    );
+
+    let packages_after = lockfile_packages(&workspace_mocker);
+    for name in ["crate_a", "crate_b"] {
+        assert_eq!("0.1.0", packages_before[name]);
+        // crate_b's bump is escalated as its requirement on crate_a breaks
+        assert_eq!("0.2.0", packages_after[name]);
+    }
+    assert_eq!("0.5.0", packages_before["crate_external"]);
+    assert_eq!("0.5.0", packages_after["crate_external"]);
+}
+
+#[test]
+fn compute_plan_describes_the_release_without_modifying_anything() {
+    let workspace_mocker = example_workspace_cascade().unwrap();
+    let workspace = ReleaseWorkspace::try_new_with_criteria(
+        workspace_mocker.root(),
+        SelectionCriteria {
+            semver_increment_overrides: [("crate_c".to_string(), SemverIncrementMode::Breaking)]
+                .into_iter()
+                .collect(),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    let head_before = workspace_mocker.head().unwrap();
+    let tags_before = tag_names(&workspace);
+
+    let plan = workspace.compute_plan().unwrap();
+
+    assert_eq!(head_before, plan.head_commit);
+    assert_eq!(
+        serde_json::json!({ "crate_c": "breaking" }),
+        plan.criteria["semver_increment_overrides"]
+    );
+
+    let planned_crate =
+        |name: &str, next_version: &str, increment_mode: SemverIncrementMode| PlannedCrate {
+            name: name.to_string(),
+            current_version: "0.1.0".to_string(),
+            next_version: Some(next_version.to_string()),
+            increment_mode: Some(increment_mode),
+            tag_name: Some(format!("{}-{}", name, next_version)),
+            publish: true,
+            allowed_blockers: vec![],
+        };
+    let mut crates = plan.crates.clone();
+    crates.sort_by(|a, b| a.name.cmp(&b.name));
+    assert_eq!(
+        vec![
+            // escalated because of crate_c
+            planned_crate("crate_a", "0.2.0", SemverIncrementMode::Breaking),
+            planned_crate("crate_b", "0.2.0", SemverIncrementMode::Breaking),
+            planned_crate("crate_c", "0.2.0", SemverIncrementMode::Breaking),
+            planned_crate("crate_d", "0.1.1", SemverIncrementMode::Patch),
+        ],
+        crates
+    );
+
+    // dependencies are published first
+    let position = |name: &str| {
+        plan.crates
+            .iter()
+            .position(|planned| planned.name == name)
+            .unwrap()
+    };
+    assert!(position("crate_c") < position("crate_b"));
+    assert!(position("crate_b") < position("crate_a"));
+
+    assert_eq!(head_before, workspace_mocker.head().unwrap());
+    assert_eq!(tags_before, tag_names(&workspace));
+    assert!(workspace.git_dirty_paths().unwrap().is_empty());
+
+    let plan_dir = tempfile::tempdir().unwrap();
+    for file_name in ["release-plan.yaml", "release-plan.json"] {
+        let path = plan_dir.path().join(file_name);
+        plan.save(&path).unwrap();
+        assert_eq!(plan, ReleasePlan::load(&path).unwrap(), "{}", file_name);
+    }
+    assert!(plan
+        .save(&plan_dir.path().join("release-plan.toml"))
+        .is_err());
+}
+
+#[test]
+fn compute_plan_includes_skipped_crates_with_their_allowed_blockers() {
+    let workspace_mocker = example_workspace_cascade().unwrap();
+    workspace_mocker.add_or_replace_file(
+        "crates/crate_d/CHANGELOG.md",
+        indoc::indoc! {r#"
+            ---
+            unreleasable: true
+            ---
+            # Changelog
+
+            ## [Unreleased]
+            Awesome changes!
+            "#
+        },
+    );
+    workspace_mocker.commit(None);
+
+    let workspace = ReleaseWorkspace::try_new_with_criteria(
+        workspace_mocker.root(),
+        SelectionCriteria {
+            allowed_selection_blockers: make_bitflags!(
+                CrateStateFlags::{UnreleasableViaChangelogFrontmatter}
+            ),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    let plan = workspace.compute_plan().unwrap();
+    let crate_d = plan
+        .crates
+        .iter()
+        .find(|planned| planned.name == "crate_d")
+        .unwrap();
+
+    assert_eq!(
+        &PlannedCrate {
+            name: "crate_d".to_string(),
+            current_version: "0.1.0".to_string(),
+            next_version: None,
+            increment_mode: None,
+            tag_name: None,
+            publish: false,
+            allowed_blockers: vec![CrateStateFlags::UnreleasableViaChangelogFrontmatter],
+        },
+        crate_d
+    );
+    assert_eq!(4, plan.crates.len());
+}
+
+#[test]
+fn release_is_resumed_from_the_plan_after_an_interruption() {
+    let workspace_mocker = example_workspace_cascade().unwrap();
+    let root = workspace_mocker.root();
+
+    // the interrupted run got as far as tagging the release commit
+    let plan = {
+        let workspace = ReleaseWorkspace::try_new(root.clone())
+            .unwrap()
+            .with_command_runner(Box::new(MockCommandRunner::default()))
+            .with_journal();
+
+        let plan = workspace.compute_plan().unwrap();
+        plan.save_state(&root).unwrap();
+
+        let mut release_paths = workspace.apply_version_bumps().unwrap();
+        release_paths.extend(workspace.update_dependent_requirements().unwrap());
+        workspace
+            .git_commit_paths(
+                "create a release",
+                &release_paths
+                    .iter()
+                    .map(PathBuf::as_path)
+                    .collect::<Vec<_>>(),
+            )
+            .unwrap();
+        workspace
+            .create_release_tags(
+                plan.crates
+                    .iter()
+                    .map(|planned| (planned.tag_name.clone().unwrap(), TagKind::Lightweight))
+                    .collect(),
+                ExistingTagPolicy::Error,
+                false,
+            )
+            .unwrap();
+
+        plan
+    };
+    assert_eq!(
+        Some(&plan),
+        ReleasePlan::load_state(&root).unwrap().as_ref()
+    );
+
+    // the first resumption fails to publish crate_a
+    let runner = MockCommandRunner::default();
+    runner.script(
+        "crate_a",
+        vec![MockCommandRunner::failure(
+            "error: failed to verify package tarball",
+        )],
+    );
+    let workspace = ReleaseWorkspace::try_new(root.clone())
+        .unwrap()
+        .with_command_runner(Box::new(runner))
+        .with_journal();
 
-    let result = workspace
-        .members()
+    workspace.verify_plan(&plan).unwrap();
+    let progress = workspace.release_progress(&plan).unwrap();
+    assert_eq!(4, progress.len());
+    assert!(
+        progress
+            .iter()
+            .all(|crt| crt.committed && crt.tagged && !crt.published),
+        "{:?}",
+        progress
+    );
+    assert!(workspace
+        .publish(
+            &workspace.planned_publish_selection(&plan).unwrap(),
+            publish_options()
+        )
+        .is_err());
+
+    // the second resumption only publishes what's left
+    let runner = MockCommandRunner::default();
+    let workspace = ReleaseWorkspace::try_new(root.clone())
         .unwrap()
-        .iter()
-        .map(|crt| crt.name())
-        .collect::<HashSet<_>>();
+        .with_command_runner(Box::new(runner.clone()))
+        .with_journal();
 
-    let expected_result = ["crate_a", "crate_b", "crate_c", "crate_e", "crate_f"]
-        .iter()
-        .map(std::string::ToString::to_string)
-        .collect::<HashSet<_>>();
+    workspace.verify_plan(&plan).unwrap();
+    let progress = workspace.release_progress(&plan).unwrap();
+    let published = |name: &str| {
+        progress
+            .iter()
+            .find(|crt| crt.name == name)
+            .map(|crt| crt.published)
+            .unwrap()
+    };
+    assert!(published("crate_b"));
+    assert!(published("crate_c"));
+    assert!(!published("crate_a"));
 
-    assert_eq!(expected_result, result);
+    let report = workspace
+        .publish(
+            &workspace.planned_publish_selection(&plan).unwrap(),
+            publish_options(),
+        )
+        .unwrap();
+    assert!(report.published.contains(&"crate_a-0.1.1".to_string()));
+    assert!(report.skipped.contains(&"crate_b-0.1.1".to_string()));
+    assert!(report.skipped.contains(&"crate_c-0.1.1".to_string()));
+    assert!(!runner.crate_names().contains(&"crate_c".to_string()));
+
+    // commits that weren't created by the release run invalidate the plan
+    workspace_mocker.add_or_replace_file("README.md", "unrelated change");
+    workspace_mocker.commit(None);
+    let workspace = ReleaseWorkspace::try_new(root).unwrap();
+    let err = workspace.verify_plan(&plan).unwrap_err();
+    assert!(
+        err.to_string()
+            .contains("but the release plan was computed at"),
+        "{:?}",
+        err
+    );
+}
+
+fn phases_workspace<'a>(
+    workspace_mocker: &WorkspaceMocker,
+    runner: &MockCommandRunner,
+) -> ReleaseWorkspace<'a> {
+    ReleaseWorkspace::try_new(workspace_mocker.root())
+        .unwrap()
+        .with_command_runner(Box::new(runner.clone()))
+}
+
+fn phase_options() -> ReleasePhaseOptions {
+    ReleasePhaseOptions {
+        release_name: "20261016.120000".to_string(),
+        publish: publish_options(),
+        ..Default::default()
+    }
+}
+
+/// The contents of the manifests and changelogs, the message of the HEAD commit and the tags.
+fn release_state(
+    workspace_mocker: &WorkspaceMocker,
+) -> (BTreeMap<String, String>, String, BTreeSet<String>) {
+    let root = workspace_mocker.root();
+
+    let files = std::fs::read_dir(root.join("crates"))
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .chain(std::iter::once(root.clone()))
+        .flat_map(|dir| [dir.join("Cargo.toml"), dir.join("CHANGELOG.md")])
+        .filter(|path| path.exists())
+        .map(|path| {
+            (
+                path.strip_prefix(&root).unwrap().display().to_string(),
+                std::fs::read_to_string(&path).unwrap(),
+            )
+        })
+        .collect();
+
+    let workspace = ReleaseWorkspace::try_new(root).unwrap();
+    assert_eq!(Vec::<String>::new(), workspace.git_dirty_paths().unwrap());
+    let head_message = workspace
+        .git_repo()
+        .head()
+        .unwrap()
+        .peel_to_commit()
+        .unwrap()
+        .message()
+        .unwrap()
+        .to_string();
+
+    (files, head_message, tag_names(&workspace))
 }
 
 #[test]
-fn detect_changed_crates() {
-    let workspace_mocker = example_workspace_1().unwrap();
-    workspace_mocker.add_or_replace_file(
-        "README",
-        r#"# Example
+fn release_phases_run_separately_match_the_orchestrated_release() {
+    let options = phase_options();
 
-            Some changes
-        "#,
+    let orchestrated_mocker = example_workspace_cascade().unwrap();
+    let orchestrated_runner = MockCommandRunner::default();
+    let orchestrated_workspace = phases_workspace(&orchestrated_mocker, &orchestrated_runner);
+    let orchestrated = orchestrated_workspace
+        .run_release_phases(BitFlags::all(), None, &options)
+        .unwrap();
+
+    let published = orchestrated.publish.clone().unwrap().published;
+    assert_eq!(4, published.len());
+    assert!(
+        published
+            .iter()
+            .all(|name_version| name_version.ends_with("-0.1.1")),
+        "{:?}",
+        published
     );
-    let before = workspace_mocker.head().unwrap();
-    let after = workspace_mocker.commit(None);
 
-    let workspace = ReleaseWorkspace::try_new(workspace_mocker.root()).unwrap();
+    let separate_mocker = example_workspace_cascade().unwrap();
+    let separate_runner = MockCommandRunner::default();
 
+    // the plan is persisted in between like it would be between separate runs
+    let plan = phases_workspace(&separate_mocker, &separate_runner)
+        .compute_plan()
+        .unwrap();
+    let plan: ReleasePlan = serde_yaml::from_str(&serde_yaml::to_string(&plan).unwrap()).unwrap();
+    assert_eq!(orchestrated.plan.crates, plan.crates);
+
+    // every phase reads the workspace anew
+    let version_bump = phases_workspace(&separate_mocker, &separate_runner)
+        .bump_planned_versions(&plan)
+        .unwrap();
+    let manifest_apply = phases_workspace(&separate_mocker, &separate_runner)
+        .apply_planned_manifests(&plan)
+        .unwrap();
+    let changelog_apply = phases_workspace(&separate_mocker, &separate_runner)
+        .apply_planned_changelogs(
+            &plan,
+            &options.release_name,
+            options.empty_unreleased_policy,
+        )
+        .unwrap();
     assert_eq!(
-        vec![PathBuf::from(workspace.root()).join("README")],
-        changed_files(workspace.root(), &before, &after).unwrap()
+        orchestrated
+            .changelog_apply
+            .as_ref()
+            .unwrap()
+            .crate_releases,
+        changelog_apply.crate_releases
+    );
+
+    let commit = phases_workspace(&separate_mocker, &separate_runner)
+        .commit_planned_release(
+            &plan,
+            &[
+                version_bump.modified_files,
+                manifest_apply.modified_files,
+                changelog_apply.modified_files,
+            ]
+            .concat(),
+        )
+        .unwrap();
+    assert!(commit.created);
+
+    let tag = phases_workspace(&separate_mocker, &separate_runner)
+        .tag_planned_release(&plan, options.existing_tag_policy, options.lightweight_tags)
+        .unwrap();
+    assert_eq!(orchestrated.tag, Some(tag));
+
+    let verify = phases_workspace(&separate_mocker, &separate_runner)
+        .verify_planned_release(
+            &plan,
+            &options.cargo_target_dir,
+            &options.allowed_missing_dependencies,
+        )
+        .unwrap();
+    assert_eq!(orchestrated.verify, Some(verify));
+
+    let publish = phases_workspace(&separate_mocker, &separate_runner)
+        .publish_planned_release(&plan, options.publish.clone())
+        .unwrap();
+    assert_eq!(orchestrated.publish, Some(publish));
+
+    assert_eq!(
+        release_state(&orchestrated_mocker),
+        release_state(&separate_mocker)
     );
 }
 
 #[test]
-fn release_selection() {
-    let criteria = SelectionCriteria {
-        match_filter: fancy_regex::Regex::new("crate_(b|a|e)").unwrap(),
-        disallowed_version_reqs: vec![semver::VersionReq::from_str(">=0.1.0").unwrap()],
-        allowed_dev_dependency_blockers: make_bitflags!(CrateStateFlags::{MissingReadme}),
-        allowed_selection_blockers: make_bitflags!(CrateStateFlags::{MissingReadme}),
+fn release_phases_can_be_skipped() {
+    let workspace_mocker = example_workspace_cascade().unwrap();
+    let runner = MockCommandRunner::default();
+    let options = phase_options();
+    let changelog_before =
+        std::fs::read_to_string(workspace_mocker.root().join("CHANGELOG.md")).unwrap();
 
-        ..Default::default()
-    };
+    let workspace = phases_workspace(&workspace_mocker, &runner);
+    let plan = workspace
+        .run_release_phases(ReleasePhase::Selection.into(), None, &options)
+        .unwrap()
+        .plan;
 
-    let workspace_mocker = example_workspace_1().unwrap();
-    let workspace =
-        ReleaseWorkspace::try_new_with_criteria(workspace_mocker.root(), criteria).unwrap();
+    // a plan can't be computed and given at once
+    assert!(workspace
+        .run_release_phases(ReleasePhase::Selection.into(), Some(plan.clone()), &options)
+        .is_err());
+    assert!(workspace
+        .run_release_phases(ReleasePhase::Publish.into(), None, &options)
+        .is_err());
 
-    let selection = workspace
-        .release_selection()
-        .unwrap()
-        .into_iter()
-        .map(|c| c.name())
-        .collect::<Vec<_>>();
-    let expected_selection = vec!["crate_b", "crate_a", "crate_e"];
+    // bump and commit the versions without touching the changelogs or publishing
+    let workspace = phases_workspace(&workspace_mocker, &runner);
+    let results = workspace
+        .run_release_phases(
+            make_bitflags!(ReleasePhase::{VersionBump | ManifestApply | Commit}),
+            Some(plan.clone()),
+            &options,
+        )
+        .unwrap();
+    assert!(results.commit.unwrap().created);
+    assert_eq!(None, results.changelog_apply);
+    assert_eq!(None, results.tag);
+    assert_eq!(None, results.publish);
+    assert_eq!(
+        changelog_before,
+        std::fs::read_to_string(workspace_mocker.root().join("CHANGELOG.md")).unwrap()
+    );
+    assert_eq!(BTreeSet::new(), tag_names(&workspace));
+    assert!(runner
+        .commands
+        .borrow()
+        .iter()
+        .all(|(_, args)| !args.contains(&"publish".to_string())));
 
-    assert_eq!(expected_selection, selection);
+    // publish later on with a workspace that was read after the commit
+    let workspace = phases_workspace(&workspace_mocker, &runner);
+    let results = workspace
+        .run_release_phases(ReleasePhase::Publish.into(), Some(plan), &options)
+        .unwrap();
+    assert_eq!(4, results.publish.unwrap().published.len());
+    assert_eq!(None, results.commit);
 }
 
 #[test]
-fn members_dependencies() {
-    let workspace_mocker = example_workspace_2().unwrap();
+fn explanation_names_the_dependency_path() {
+    let workspace_mocker = example_workspace_chain_of_four().unwrap();
     let workspace = ReleaseWorkspace::try_new_with_criteria(
         workspace_mocker.root(),
         SelectionCriteria {
-            exclude_optional_deps: true,
+            match_filter: fancy_regex::Regex::new("^crate_a$").unwrap(),
             ..Default::default()
         },
     )
     .unwrap();
 
-    let result = workspace
+    let explanation = workspace.explain("crate_c").unwrap();
+    assert!(!explanation.filter_matched);
+    assert!(!explanation.matched);
+    assert_eq!(
+        Some(vec![
+            "crate_a".to_string(),
+            "crate_b".to_string(),
+            "crate_c".to_string()
+        ]),
+        explanation.dependency_path
+    );
+    assert!(explanation.blocking_flags.is_empty());
+    assert!(explanation.changed);
+    assert_eq!(None, explanation.previous_release);
+    assert!(explanation.release_selection);
+    assert!(explanation
+        .to_string()
+        .contains("selected as a dependency of crate_a via crate_a -> crate_b -> crate_c"));
+
+    let explanation = workspace.explain("crate_a").unwrap();
+    assert!(explanation.filter_matched);
+    assert!(explanation.matched);
+    assert_eq!(None, explanation.dependency_path);
+
+    let err = workspace.explain("crate_x").unwrap_err().to_string();
+    assert!(err.contains("'crate_x' is not a member"), "{}", err);
+}
+
+#[test]
+fn dependencies_record_the_matched_crates_requiring_them() {
+    let workspace_mocker = example_workspace_cascade().unwrap();
+    let workspace = ReleaseWorkspace::try_new_with_criteria(
+        workspace_mocker.root(),
+        SelectionCriteria {
+            match_filter: fancy_regex::Regex::new("^crate_(a|d)$").unwrap(),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    let report = workspace.states_report().unwrap();
+    // crate_a requires crate_c via crate_b
+    assert_eq!(
+        BTreeSet::from(["crate_a".to_string(), "crate_d".to_string()]),
+        report["crate_c"].required_by
+    );
+    assert_eq!(
+        BTreeSet::from(["crate_a".to_string()]),
+        report["crate_b"].required_by
+    );
+    assert!(report["crate_a"].required_by.is_empty());
+
+    let crate_c = workspace
         .members()
         .unwrap()
         .iter()
-        .map(|crt| {
-            (
-                crt.name(),
-                crt.dependencies_in_workspace()
-                    .unwrap()
-                    .into_iter()
-                    .map(|(dep_name, _)| dep_name.clone())
-                    .collect::<HashSet<_>>(),
-            )
+        .find(|crt| crt.name() == "crate_c")
+        .unwrap();
+    let state = crate_c.state().unwrap();
+    assert_eq!(&report["crate_c"].required_by, state.required_by());
+
+    let formatted = CratesStateReport::new(
+        "",
+        vec![(
+            crate_c.name(),
+            CrateStateReport::new(&crate_c.version(), &state),
+        )],
+    )
+    .showing(false, true, false)
+    .to_string();
+    assert!(
+        formatted.contains("required by: crate_a, crate_d"),
+        "{}",
+        formatted
+    );
+}
+
+/// Collects the output of a subscriber for inspection.
+#[derive(Clone, Default)]
+struct SharedBuffer(std::sync::Arc<std::sync::Mutex<Vec<u8>>>);
+
+impl std::io::Write for SharedBuffer {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.lock().unwrap().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+#[test]
+fn warnings_carry_the_name_of_the_analyzed_crate() {
+    let workspace_mocker = example_workspace_cascade().unwrap();
+    let workspace = ReleaseWorkspace::try_new_with_criteria(
+        workspace_mocker.root(),
+        SelectionCriteria {
+            match_filter: fancy_regex::Regex::new("^crate_a$").unwrap(),
+            disallowed_version_reqs: vec![semver::VersionReq::parse(">=0.1.0").unwrap()],
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    let buffer = SharedBuffer::default();
+    let subscriber = tracing_subscriber::fmt()
+        .with_writer({
+            let buffer = buffer.clone();
+            move || buffer.clone()
         })
-        .collect::<Vec<_>>();
+        .with_ansi(false)
+        .with_max_level(tracing::Level::WARN)
+        .finish();
+    let report =
+        tracing::subscriber::with_default(subscriber, || workspace.states_report()).unwrap();
+    assert!(report["crate_a"]
+        .blocking_flags
+        .contains(CrateStateFlags::DisallowedVersionReqViolated));
 
-    let expected_result = [
-        ("crate_b".to_string(), vec![]),
-        ("crate_c".to_string(), vec!["crate_b".to_string()]),
-        (
-            "crate_a".to_string(),
-            vec!["crate_c".to_string(), "crate_b".to_string()],
-        ),
-        (
-            "crate_d".to_string(),
-            vec![
+    let output = String::from_utf8(buffer.0.lock().unwrap().clone()).unwrap();
+    let warning = output
+        .lines()
+        .find(|line| line.contains("'crate_a' version '0.1.0' matches the disallowed requirement"))
+        .unwrap_or_else(|| panic!("missing warning in:\n{}", output));
+    assert!(warning.contains("member{crate=crate_a}"), "{}", warning);
+    assert!(warning.contains("phase{phase=Selection}"), "{}", warning);
+}
+
+#[test]
+fn parallel_analysis_matches_the_serial_one() {
+    let workspace_mocker = example_workspace_generated(48).unwrap();
+
+    let states_report = |analysis_threads| {
+        let workspace = ReleaseWorkspace::try_new_with_criteria(
+            workspace_mocker.root(),
+            SelectionCriteria {
+                analysis_threads: Some(analysis_threads),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        workspace.states_report().unwrap()
+    };
+
+    let serial = states_report(1);
+    assert_eq!(serial, states_report(8));
+
+    assert_eq!(48, serial.crates.len());
+    // tagged and changed
+    assert!(serial["crate_000"]
+        .flags
+        .contains(CrateStateFlags::ChangedSincePreviousRelease));
+    // tagged and unchanged
+    assert!(!serial["crate_002"]
+        .flags
+        .contains(CrateStateFlags::ChangedSincePreviousRelease));
+    assert!(serial["crate_001"]
+        .flags
+        .contains(CrateStateFlags::MissingReleaseTag));
+}
+
+fn crates_state_report_fixture() -> CratesStateReport {
+    CratesStateReport::new(
+        "The crates of the fixture.",
+        vec![
+            (
                 "crate_a".to_string(),
-                "crate_c".to_string(),
+                CrateStateReport {
+                    version: "0.1.0".to_string(),
+                    flags: enumflags2::make_bitflags!(CrateStateFlags::{Matched | HasPreviousRelease | ChangedSincePreviousRelease}),
+                    meta_flags: enumflags2::make_bitflags!(MetaCrateStateFlags::{Allowed | Changed | Selected}),
+                    change_classes: ChangeClass::Source.into(),
+                    release_selection: true,
+                    ..Default::default()
+                },
+            ),
+            (
                 "crate_b".to_string(),
-            ],
-        ),
-    ]
-    .into_iter()
-    .map(|(name, deps)| (name, deps.into_iter().collect::<HashSet<_>>()))
-    .collect::<Vec<_>>();
+                CrateStateReport {
+                    version: "0.2.0".to_string(),
+                    flags: enumflags2::make_bitflags!(CrateStateFlags::{IsWorkspaceDependency | NoPreviousRelease | MissingReadme}),
+                    blocking_flags: CrateStateFlags::MissingReadme.into(),
+                    disallowed_blocking_flags: CrateStateFlags::MissingReadme.into(),
+                    meta_flags: enumflags2::make_bitflags!(MetaCrateStateFlags::{Blocked | Changed | Selected}),
+                    required_by: BTreeSet::from(["crate_a".to_string()]),
+                    release_selection: false,
+                    ..Default::default()
+                },
+            ),
+        ],
+    )
+}
 
-    pretty_assertions::assert_eq!(expected_result, result, "left is expected");
+#[test]
+fn crates_state_report_display() {
+    let report = crates_state_report_fixture();
+    let separator = "-".repeat(80);
+    let indent = " ".repeat(30);
+
+    assert_eq!(
+        [
+            "".to_string(),
+            separator.clone(),
+            "The crates of the fixture.".to_string(),
+            separator.clone(),
+            "crate_a                       0.1.0".to_string(),
+            separator.clone(),
+            "crate_b                       0.2.0".to_string(),
+            "".to_string(),
+        ]
+        .join("\n"),
+        report.to_string()
+    );
+
+    assert_eq!(
+        [
+            "".to_string(),
+            separator.clone(),
+            "The crates of the fixture.".to_string(),
+            "Showing states: * Disallowed Blocking * Flags * Meta".to_string(),
+            separator.clone(),
+            "crate_a                       0.1.0".to_string(),
+            format!("{}[]", indent),
+            format!(
+                "{}[Matched, HasPreviousRelease, ChangedSincePreviousRelease]",
+                indent
+            ),
+            format!("{}changes: [Source]", indent),
+            format!("{}[Allowed, Changed, Selected]", indent),
+            separator,
+            "crate_b                       0.2.0".to_string(),
+            format!("{}[MissingReadme]", indent),
+            format!(
+                "{}[IsWorkspaceDependency, NoPreviousRelease, MissingReadme]",
+                indent
+            ),
+            format!("{}required by: crate_a", indent),
+            format!("{}[Blocked, Changed, Selected]", indent),
+            "".to_string(),
+        ]
+        .join("\n"),
+        report.showing(true, true, true).to_string()
+    );
 }
 
 #[test]
-fn members_sorted_ws1() {
-    let workspace_mocker = example_workspace_1().unwrap();
+fn crates_state_report_markdown_table() {
+    assert_eq!(
+        indoc::indoc! {"
+            | crate | version | flags | blocking flags | meta flags | release selection |
+            | --- | --- | --- | --- | --- | --- |
+            | crate_a | 0.1.0 | Matched, HasPreviousRelease, ChangedSincePreviousRelease |  | Allowed, Changed, Selected | yes |
+            | crate_b | 0.2.0 | IsWorkspaceDependency, NoPreviousRelease, MissingReadme | MissingReadme | Blocked, Changed, Selected | no |
+        "},
+        crates_state_report_fixture().to_markdown_table()
+    );
+
+    // the table doesn't depend on the states that are shown
+    assert_eq!(
+        crates_state_report_fixture().to_markdown_table(),
+        crates_state_report_fixture()
+            .showing(true, true, true)
+            .to_markdown_table()
+    );
+}
+
+#[test]
+fn release_summary_markdown() {
+    let workspace_mocker = example_workspace_cascade().unwrap();
+    workspace_mocker.add_or_replace_file(
+        "crates/crate_b/CHANGELOG.md",
+        indoc::indoc! {r#"
+        # Changelog
+
+        ## Unreleased
+
+        ### Fixed
+
+        - A bug.
+
+        ## 0.1.0
+        "#},
+    );
+    let workspace = ReleaseWorkspace::try_new(workspace_mocker.root()).unwrap();
+
+    let planned_crate = |name: &str, next_version: Option<&str>| PlannedCrate {
+        name: name.to_string(),
+        current_version: "0.1.0".to_string(),
+        next_version: next_version.map(str::to_string),
+        increment_mode: None,
+        tag_name: None,
+        publish: next_version.is_some(),
+        allowed_blockers: vec![],
+    };
+    let plan = ReleasePlan {
+        head_commit: workspace_mocker.head().unwrap(),
+        criteria: serde_json::Value::Null,
+        crates: vec![
+            planned_crate("crate_a", Some("0.1.1")),
+            planned_crate("crate_b", Some("0.2.0")),
+            PlannedCrate {
+                allowed_blockers: vec![CrateStateFlags::MissingReadme],
+                ..planned_crate("crate_c", None)
+            },
+        ],
+    };
+
+    let summary = workspace.render_release_summary_markdown(&plan).unwrap();
+    assert_eq!(
+        indoc::indoc! {r#"
+        | crate | old version | new version | changelog | allowed blockers |
+        | --- | --- | --- | --- | --- |
+        | crate_a | 0.1.0 | 0.1.1 | [CHANGELOG](crates/crate_a/CHANGELOG.md#011) |  |
+        | crate_b | 0.1.0 | 0.2.0 | [CHANGELOG](crates/crate_b/CHANGELOG.md#020) |  |
+        | crate_c | 0.1.0 | skipped | [CHANGELOG](crates/crate_c/CHANGELOG.md) | MissingReadme |
+
+        <details>
+        <summary>crate_a 0.1.1</summary>
+
+        Awesome changes\!
+
+        </details>
+
+        <details>
+        <summary>crate_b 0.2.0</summary>
+
+        ### Fixed
+
+        - A bug.
+
+        </details>
+        "#},
+        summary
+    );
+
+    // rendering is deterministic
+    assert_eq!(
+        summary,
+        ReleaseWorkspace::try_new(workspace_mocker.root())
+            .unwrap()
+            .render_release_summary_markdown(&plan)
+            .unwrap()
+    );
+}
+
+#[test]
+fn github_heading_anchors() {
+    assert_eq!("011", github_heading_anchor("0.1.1"));
+    assert_eq!("020-beta-rc1", github_heading_anchor("0.2.0-beta-rc.1"));
+    assert_eq!("unreleased", github_heading_anchor("[Unreleased]"));
+}
+
+#[test]
+fn crate_metadata_config_parsing() {
+    let workspace_mocker = example_workspace_crate_metadata().unwrap();
+    let workspace = ReleaseWorkspace::try_new(workspace_mocker.root()).unwrap();
+    let member = |name: &str| {
+        workspace
+            .members()
+            .unwrap()
+            .iter()
+            .find(|crt| crt.name() == name)
+            .copied()
+            .unwrap()
+    };
+
+    assert_eq!(
+        &CrateMetadataConfig {
+            skip: true,
+            ..Default::default()
+        },
+        member("crate_skipped").metadata_config().unwrap()
+    );
+    assert_eq!(
+        &CrateMetadataConfig {
+            tag_template: Some("v{version}".to_string()),
+            semver_increment: Some(crate::common::SemverIncrementMode::Minor),
+            ..Default::default()
+        },
+        member("crate_tagged").metadata_config().unwrap()
+    );
+    assert_eq!(
+        make_bitflags!(CrateStateFlags::{MissingReadme}),
+        member("crate_without_readme")
+            .metadata_config()
+            .unwrap()
+            .allowed_blockers()
+    );
+    assert_eq!(
+        &CrateMetadataConfig::default(),
+        member("crate_unconfigured").metadata_config().unwrap()
+    );
+}
+
+#[test]
+fn crate_metadata_config_allowed_blockers_only_apply_to_the_crate() {
+    let workspace_mocker = example_workspace_crate_metadata().unwrap();
     let workspace = ReleaseWorkspace::try_new_with_criteria(
         workspace_mocker.root(),
         SelectionCriteria {
-            allowed_dev_dependency_blockers: (&[
-                IsWorkspaceDependency,
-                UnreleasableViaChangelogFrontmatter,
-                MissingChangelog,
-            ] as &[CrateStateFlags])
-                .iter()
-                .cloned()
-                .collect(),
-
+            match_filter: fancy_regex::Regex::new("^crate_(without_readme|unconfigured)$").unwrap(),
             ..Default::default()
         },
     )
     .unwrap();
 
-    let result = workspace
-        .members()
-        .unwrap()
-        .iter()
-        .map(|crt| crt.name())
-        .collect::<Vec<_>>();
-
-    let expected_result = ["crate_b", "crate_a", "crate_c", "crate_e", "crate_f"]
-        .iter()
-        .map(std::string::ToString::to_string)
-        .collect::<Vec<_>>();
-
-    assert_eq!(expected_result, result);
+    let err = workspace.release_selection().unwrap_err().to_string();
+    assert!(err.contains("crate_unconfigured"), "{}", err);
+    assert!(!err.contains("crate_without_readme"), "{}", err);
 }
 
 #[test]
-fn members_sorted_ws2() {
-    let workspace_mocker = example_workspace_2().unwrap();
+fn crate_metadata_config_rejects_unknown_keys() {
+    let workspace_mocker = WorkspaceMocker::try_new(
+        None,
+        vec![MockProject {
+            name: "crate_misconfigured".to_string(),
+            version: "0.0.1".to_string(),
+            release_metadata: Some("skipp = true".to_string()),
+            ..Default::default()
+        }],
+    )
+    .unwrap();
     let workspace = ReleaseWorkspace::try_new(workspace_mocker.root()).unwrap();
 
-    let crates = workspace.members().unwrap();
-    ensure_release_order_consistency(crates).unwrap();
+    let err = workspace
+        .members()
+        .unwrap()
+        .first()
+        .unwrap()
+        .metadata_config()
+        .unwrap_err();
+    let err = format!("{:#}", err);
+    assert!(err.contains("crate_misconfigured"), "{}", err);
+    assert!(err.contains("skipp"), "{}", err);
 
-    let result = crates.iter().map(|crt| crt.name()).collect::<Vec<_>>();
+    // the state computation fails as well
+    assert!(workspace.members_matched().is_err());
+}
 
-    let expected_result = ["crate_b", "crate_c", "crate_a", "crate_d"]
-        .iter()
-        .map(std::string::ToString::to_string)
+#[test]
+fn crate_metadata_config_takes_precedence_over_criteria() {
+    let workspace_mocker = example_workspace_crate_metadata().unwrap();
+    let workspace = ReleaseWorkspace::try_new_with_criteria(
+        workspace_mocker.root(),
+        SelectionCriteria {
+            match_filter: fancy_regex::Regex::new("^crate_(skipped|tagged|without_readme)$")
+                .unwrap(),
+            release_tag_template: Some("{name}-{version}".to_string()),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    let report = workspace.states_report().unwrap();
+    assert!(!report["crate_skipped"]
+        .flags
+        .contains(CrateStateFlags::Matched));
+    assert!(report["crate_without_readme"]
+        .flags
+        .contains(CrateStateFlags::MissingReadme));
+
+    // the allowed blocker doesn't fail the selection, albeit the blocked crate isn't released
+    let selection = workspace
+        .release_selection()
+        .unwrap()
+        .into_iter()
+        .map(|crt| crt.name())
         .collect::<Vec<_>>();
+    assert_eq!(vec!["crate_tagged"], selection);
 
-    assert_eq!(expected_result, result);
+    let version = Version::new(1, 2, 3);
+    assert_eq!(
+        "v1.2.3",
+        workspace
+            .release_tag_name("crate_tagged", &version)
+            .unwrap()
+    );
+    assert_eq!(
+        "crate_without_readme-1.2.3",
+        workspace
+            .release_tag_name("crate_without_readme", &version)
+            .unwrap()
+    );
 }
 
 #[test]
-fn unreleasable_dependencies_error() {
-    let workspace_mocker = example_workspace_3().unwrap();
+fn never_release_drift() {
+    let workspace_mocker = example_workspace_never_release().unwrap();
     let workspace = ReleaseWorkspace::try_new(workspace_mocker.root()).unwrap();
 
-    let err = workspace.release_selection().unwrap_err().to_string();
-
-    assert!(err.contains("blocked"), "{}", err);
+    assert_eq!(
+        NeverReleaseDrift {
+            unknown: ["crate_removed".to_string()].into_iter().collect(),
+            unlisted_unpublishable: ["crate_fixture".to_string()].into_iter().collect(),
+        },
+        workspace.never_release_drift().unwrap()
+    );
 }
 
 use CrateStateFlags::ChangedSincePreviousRelease;
@@ -244,7 +6564,12 @@ fn crate_state_block_consistency() {
         .iter()
         .cloned()
         .collect();
-    let state = CrateState::new(flags, allowed_dev_dependency_blockers, Default::default());
+    let state = CrateState::new(
+        flags,
+        allowed_dev_dependency_blockers,
+        Default::default(),
+        CrateState::BLOCKING_STATES,
+    );
 
     assert!(
         !state.blocked_by().is_empty(),
@@ -281,7 +6606,12 @@ fn crate_state_allowed_dev_dependency_blockers() {
         .cloned()
         .collect();
 
-    let state = CrateState::new(flags, allowed_blockers, Default::default());
+    let state = CrateState::new(
+        flags,
+        allowed_blockers,
+        Default::default(),
+        CrateState::BLOCKING_STATES,
+    );
 
     assert!(
         state.blocked() && !state.blocked_by().is_empty() && state.disallowed_blockers().is_empty(),
@@ -312,7 +6642,12 @@ fn crate_state_allowed_selection_blockers() {
         .cloned()
         .collect();
 
-    let state = CrateState::new(flags, Default::default(), allowed_blockers);
+    let state = CrateState::new(
+        flags,
+        Default::default(),
+        allowed_blockers,
+        CrateState::BLOCKING_STATES,
+    );
 
     assert!(
         state.blocked() && !state.blocked_by().is_empty() && state.disallowed_blockers().is_empty(),
@@ -329,3 +6664,584 @@ fn crate_state_allowed_selection_blockers() {
 //     let workspace = ReleaseWorkspace::try_new(workspace_mocker.root()).unwrap();
 
 // }
+
+#[test]
+fn workspace_is_discovered_from_a_nested_directory() {
+    let workspace_mocker = example_workspace_nested().unwrap();
+    let root = workspace_mocker.root().canonicalize().unwrap();
+
+    // the manifest of wasm_a is passed on the way up
+    let nested = workspace_mocker.root().join("test_utils/wasm/wasm_a/src");
+    assert_eq!(root, ReleaseWorkspace::discover_root(&nested).unwrap());
+
+    let workspace = ReleaseWorkspace::try_discover(&nested).unwrap();
+    assert_eq!(root, workspace.root());
+    assert!(workspace
+        .members()
+        .unwrap()
+        .iter()
+        .any(|member| member.name() == "wasm_a"));
+}
+
+#[test]
+fn workspace_is_discovered_from_a_linked_worktree() {
+    let workspace_mocker = example_workspace_1().unwrap();
+    let repo = git2::Repository::open(workspace_mocker.root()).unwrap();
+
+    let worktrees = tempfile::tempdir().unwrap();
+    let worktree_path = worktrees.path().join("linked");
+    repo.worktree("linked", &worktree_path, None).unwrap();
+    let worktree_path = worktree_path.canonicalize().unwrap();
+
+    let workspace = ReleaseWorkspace::try_discover(&worktree_path.join("crates/crate_a")).unwrap();
+    assert_eq!(worktree_path, workspace.root());
+    assert_eq!(
+        Some(worktree_path.as_path()),
+        workspace
+            .git_repo()
+            .workdir()
+            .map(|workdir| workdir.canonicalize().unwrap())
+            .as_deref()
+    );
+    assert_eq!(
+        ["crate_a", "crate_b", "crate_c", "crate_e", "crate_f"]
+            .iter()
+            .map(std::string::ToString::to_string)
+            .collect::<HashSet<_>>(),
+        workspace
+            .members()
+            .unwrap()
+            .iter()
+            .map(|member| member.name())
+            .collect::<HashSet<_>>()
+    );
+}
+
+#[test]
+fn workspace_discovery_fails_outside_of_a_workspace() {
+    let dir = tempfile::tempdir().unwrap();
+
+    let err = ReleaseWorkspace::discover_root(dir.path()).unwrap_err();
+    assert!(err
+        .to_string()
+        .contains("couldn't find a workspace manifest"));
+}
+
+#[test]
+fn nested_excluded_crates_are_reported() {
+    let workspace_mocker = example_workspace_nested_excluded().unwrap();
+    let workspace = ReleaseWorkspace::try_new(workspace_mocker.root()).unwrap();
+
+    assert_eq!(
+        vec![PathBuf::from("crates/crate_a/vendored")],
+        workspace.excluded_paths().unwrap()
+    );
+    assert_eq!(
+        &vec![ExcludedCrate {
+            name: Some("crate_vendored".to_string()),
+            path: PathBuf::from("crates/crate_a/vendored"),
+            listed_in_exclude: true,
+        }],
+        workspace.excluded_crates().unwrap()
+    );
+    assert_eq!(
+        workspace.excluded_crates().unwrap(),
+        &workspace.states_report().unwrap().excluded_crates
+    );
+}
+
+#[test]
+fn changes_of_nested_excluded_crates_are_not_attributed_to_the_parent() {
+    let workspace_mocker = example_workspace_nested_excluded().unwrap();
+    workspace_mocker.add_or_replace_file(
+        "crates/crate_a/vendored/src/lib.rs",
+        "pub fn vendored() {}\n",
+    );
+    workspace_mocker.commit(None);
+
+    let workspace = ReleaseWorkspace::try_new(workspace_mocker.root()).unwrap();
+    let crate_a = workspace
+        .members()
+        .unwrap()
+        .iter()
+        .find(|member| member.name() == "crate_a")
+        .unwrap();
+    let state = crate_a.state().unwrap();
+    assert!(state.contains(CrateStateFlags::HasPreviousRelease));
+    assert!(!state.contains(CrateStateFlags::ChangedSincePreviousRelease));
+    assert!(workspace
+        .explain("crate_a")
+        .unwrap()
+        .changed_files
+        .is_empty());
+
+    // the crate's own changes are still detected
+    workspace_mocker.add_or_replace_file("crates/crate_a/src/lib.rs", "pub fn a() {}\n");
+    workspace_mocker.commit(None);
+
+    let workspace = ReleaseWorkspace::try_new(workspace_mocker.root()).unwrap();
+    assert_eq!(
+        vec![PathBuf::from("src/lib.rs")],
+        workspace.explain("crate_a").unwrap().changed_files
+    );
+}
+
+#[test_case(None, &["crate_common", "crate_win", "crate_unix"] ; "without a filter")]
+#[test_case(Some("x86_64-unknown-linux-gnu"), &["crate_common", "crate_unix"] ; "linux")]
+#[test_case(Some("x86_64-pc-windows-msvc"), &["crate_common", "crate_win"] ; "windows")]
+fn dependencies_are_filtered_by_platform(target: Option<&str>, expected: &[&str]) {
+    let workspace_mocker = example_workspace_platform_dependencies().unwrap();
+    let workspace = ReleaseWorkspace::try_new_with_criteria(
+        workspace_mocker.root(),
+        SelectionCriteria {
+            dependency_platform_filter: target.map(str::to_string),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    let crate_a = workspace
+        .members()
+        .unwrap()
+        .iter()
+        .find(|member| member.name() == "crate_a")
+        .unwrap();
+
+    assert_eq!(
+        expected
+            .iter()
+            .map(std::string::ToString::to_string)
+            .collect::<BTreeSet<_>>(),
+        crate_a
+            .dependencies_in_workspace()
+            .unwrap()
+            .keys()
+            .cloned()
+            .collect::<BTreeSet<_>>()
+    );
+}
+
+#[test_case(example_workspace_1 ; "workspace 1")]
+#[test_case(example_workspace_chain_of_four ; "chain of four")]
+#[test_case(example_workspace_diamond ; "diamond")]
+#[test_case(example_workspace_dep_kinds ; "dependency kinds")]
+fn workspace_dependency_crates_match_the_dependency_names(
+    fixture: fn() -> Fallible<WorkspaceMocker>,
+) {
+    let workspace_mocker = fixture().unwrap();
+    let workspace = ReleaseWorkspace::try_new(workspace_mocker.root()).unwrap();
+
+    for member in workspace.members().unwrap() {
+        let dependency_names = member
+            .dependencies_in_workspace()
+            .unwrap()
+            .keys()
+            .cloned()
+            .collect::<Vec<_>>();
+        let dependency_crates = member.workspace_dependency_crates().unwrap();
+
+        assert_eq!(
+            dependency_names,
+            dependency_crates
+                .iter()
+                .map(|dependency| dependency.name())
+                .collect::<Vec<_>>(),
+            "dependencies of {}",
+            member.name()
+        );
+
+        // the references point to the members themselves
+        for dependency in dependency_crates {
+            assert!(workspace
+                .members()
+                .unwrap()
+                .iter()
+                .any(|other| std::ptr::eq(*other, dependency)));
+        }
+    }
+}
+
+#[test_case("crate_d", DependentsScope::Direct, &["crate_b", "crate_c"])]
+#[test_case("crate_d", DependentsScope::Transitive, &["crate_a", "crate_b", "crate_c"])]
+#[test_case("crate_b", DependentsScope::Direct, &["crate_a"])]
+#[test_case("crate_b", DependentsScope::Transitive, &["crate_a"])]
+#[test_case("crate_a", DependentsScope::Direct, &[])]
+#[test_case("crate_a", DependentsScope::Transitive, &[])]
+fn dependants_in_workspace_by_scope(name: &str, scope: DependentsScope, expected: &[&str]) {
+    let workspace_mocker = example_workspace_diamond().unwrap();
+    let workspace = ReleaseWorkspace::try_new(workspace_mocker.root()).unwrap();
+
+    let crt = workspace
+        .members()
+        .unwrap()
+        .iter()
+        .find(|member| member.name() == name)
+        .unwrap();
+
+    assert_eq!(
+        expected
+            .iter()
+            .map(std::string::ToString::to_string)
+            .collect::<BTreeSet<_>>(),
+        crt.dependants_in_workspace(scope)
+            .unwrap()
+            .iter()
+            .map(|dependant| dependant.name())
+            .collect::<BTreeSet<_>>()
+    );
+}
+
+#[test]
+fn transitive_dependants_follow_the_member_order() {
+    let workspace_mocker = example_workspace_diamond().unwrap();
+    let workspace = ReleaseWorkspace::try_new(workspace_mocker.root()).unwrap();
+
+    let members = workspace.members().unwrap();
+    let crate_d = members
+        .iter()
+        .find(|member| member.name() == "crate_d")
+        .unwrap();
+
+    let expected = members
+        .iter()
+        .map(|member| member.name())
+        .filter(|name| name != "crate_d")
+        .collect::<Vec<_>>();
+    assert_eq!(
+        expected,
+        crate_d
+            .dependants_in_workspace(DependentsScope::Transitive)
+            .unwrap()
+            .iter()
+            .map(|dependant| dependant.name())
+            .collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn reverse_dependency_index_is_built_once() {
+    let workspace_mocker = example_workspace_generated(100).unwrap();
+    let workspace = ReleaseWorkspace::try_new(workspace_mocker.root()).unwrap();
+
+    let members = workspace.members().unwrap();
+    for member in members {
+        member
+            .dependants_in_workspace(DependentsScope::Direct)
+            .unwrap();
+        member
+            .dependants_in_workspace(DependentsScope::Transitive)
+            .unwrap();
+    }
+    assert_eq!(
+        1,
+        workspace
+            .reverse_dependency_index_builds
+            .load(Ordering::Relaxed)
+    );
+
+    let index = workspace.reverse_dependency_index().unwrap();
+    assert_eq!(
+        Some(&BTreeSet::from(["crate_001".to_string()])),
+        index.get("crate_000")
+    );
+    assert_eq!(None, index.get("crate_099"));
+
+    let first = members
+        .iter()
+        .find(|member| member.name() == "crate_000")
+        .unwrap();
+    assert_eq!(
+        99,
+        first
+            .dependants_in_workspace(DependentsScope::Transitive)
+            .unwrap()
+            .len()
+    );
+    assert_eq!(
+        1,
+        workspace
+            .reverse_dependency_index_builds
+            .load(Ordering::Relaxed)
+    );
+}
+
+#[test_case(example_workspace_1 ; "workspace 1")]
+#[test_case(example_workspace_nested ; "nested")]
+#[test_case(example_workspace_diamond ; "diamond")]
+fn members_order_is_independent_of_the_workspace_order(fixture: fn() -> Fallible<WorkspaceMocker>) {
+    let workspace_mocker = fixture().unwrap();
+    let workspace = ReleaseWorkspace::try_new(workspace_mocker.root()).unwrap();
+
+    let names = |members: &[&Crate]| {
+        members
+            .iter()
+            .map(|member| member.name())
+            .collect::<Vec<_>>()
+    };
+    let expected = names(workspace.members().unwrap());
+
+    let unsorted = workspace
+        .members_unsorted()
+        .unwrap()
+        .iter()
+        .collect::<Vec<_>>();
+
+    // every rotation of the reversed and the original order
+    for reversed in [false, true] {
+        for rotation in 0..unsorted.len() {
+            let mut shuffled = unsorted.clone();
+            if reversed {
+                shuffled.reverse();
+            }
+            shuffled.rotate_left(rotation);
+
+            assert_eq!(
+                expected,
+                names(&sort_members(&shuffled).unwrap()),
+                "sorting {:?}",
+                names(&shuffled)
+            );
+        }
+    }
+}
+
+#[test]
+fn changelog_parse_errors_fail_the_selection() {
+    let workspace_mocker = example_workspace_broken_changelog().unwrap();
+    let workspace = ReleaseWorkspace::try_new(workspace_mocker.root()).unwrap();
+
+    let err = format!("{:#}", workspace.members_matched().unwrap_err());
+    assert!(
+        err.contains("parsing the changelog of 'crate_broken'"),
+        "{}",
+        err
+    );
+    assert!(err.contains("crates/crate_broken/CHANGELOG.md"), "{}", err);
+}
+
+#[test]
+fn changelog_parse_errors_block_the_crate_if_tolerated() {
+    let workspace_mocker = example_workspace_broken_changelog().unwrap();
+    let workspace = ReleaseWorkspace::try_new_with_criteria(
+        workspace_mocker.root(),
+        SelectionCriteria {
+            tolerate_changelog_parse_errors: true,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    let report = workspace.states_report().unwrap();
+
+    let broken = &report["crate_broken"];
+    assert!(broken.flags.contains(CrateStateFlags::ChangelogParseError));
+    assert!(broken
+        .blocking_flags
+        .contains(CrateStateFlags::ChangelogParseError));
+    assert!(broken
+        .changelog_parse_error
+        .as_deref()
+        .unwrap()
+        .contains("no heading text found"));
+
+    let ok = &report["crate_ok"];
+    assert!(!ok.flags.contains(CrateStateFlags::ChangelogParseError));
+    assert!(ok.flags.contains(CrateStateFlags::HasPreviousRelease));
+    assert_eq!(None, ok.changelog_parse_error);
+}
+
+#[test_case(true, None, true ; "workspace default applies")]
+#[test_case(true, Some(false), false ; "crate overrides the workspace default with false")]
+#[test_case(false, Some(true), true ; "crate overrides the workspace default with true")]
+#[test_case(false, None, false ; "releasable by default")]
+fn workspace_default_unreleasable(
+    workspace_default: bool,
+    crate_unreleasable: Option<bool>,
+    expected: bool,
+) {
+    let workspace_changelog = indoc::formatdoc!(
+        r#"
+        ---
+        default_unreleasable: {}
+        ---
+        # Changelog
+        "#,
+        workspace_default
+    );
+
+    let crate_changelog = format!(
+        "{}# Changelog\n\n## Unreleased\n",
+        crate_unreleasable
+            .map(|unreleasable| format!("---\nunreleasable: {}\n---\n", unreleasable))
+            .unwrap_or_default()
+    );
+
+    let workspace_mocker = WorkspaceMocker::try_new(
+        Some(&workspace_changelog),
+        vec![MockProject {
+            name: "crate_a".to_string(),
+            version: "0.0.1".to_string(),
+            changelog: Some(crate_changelog),
+            ..Default::default()
+        }],
+    )
+    .unwrap();
+    let workspace = ReleaseWorkspace::try_new(workspace_mocker.root()).unwrap();
+
+    assert_eq!(
+        expected,
+        workspace.states_report().unwrap()["crate_a"]
+            .flags
+            .contains(CrateStateFlags::UnreleasableViaChangelogFrontmatter)
+    );
+}
+
+#[test]
+fn previous_release_is_looked_up_via_the_frontmatter_tag_override() {
+    let workspace_mocker = example_workspace_renamed_crate("crate_old-0.0.9").unwrap();
+    let workspace = ReleaseWorkspace::try_new(workspace_mocker.root()).unwrap();
+
+    let report = workspace.states_report().unwrap();
+    let renamed = &report["crate_renamed"];
+    assert!(renamed.flags.contains(CrateStateFlags::HasPreviousRelease));
+    assert!(renamed
+        .flags
+        .contains(CrateStateFlags::ChangedSincePreviousRelease));
+    assert!(!renamed.flags.contains(CrateStateFlags::MissingReleaseTag));
+
+    let explanation = workspace.explain("crate_renamed").unwrap();
+    assert_eq!(
+        Some("crate_old-0.0.9".to_string()),
+        explanation.previous_release
+    );
+}
+
+#[test]
+fn missing_frontmatter_tag_override_is_ignored() {
+    let workspace_mocker = example_workspace_renamed_crate("crate_older-0.0.9").unwrap();
+    let workspace = ReleaseWorkspace::try_new(workspace_mocker.root()).unwrap();
+
+    let report = workspace.states_report().unwrap();
+    let renamed = &report["crate_renamed"];
+    assert!(renamed.flags.contains(CrateStateFlags::MissingReleaseTag));
+    assert!(!renamed.flags.contains(CrateStateFlags::HasPreviousRelease));
+
+    let explanation = workspace.explain("crate_renamed").unwrap();
+    assert_eq!(None, explanation.previous_release);
+}
+
+#[test]
+fn malformed_changelogs_are_linted_and_fixed() {
+    let workspace_mocker = example_workspace_malformed_changelogs().unwrap();
+    let workspace = ReleaseWorkspace::try_new(workspace_mocker.root()).unwrap();
+    let crate_a_changelog = workspace_mocker.root().join("crates/crate_a/CHANGELOG.md");
+    let crate_b_changelog = workspace_mocker.root().join("crates/crate_b/CHANGELOG.md");
+    let crate_b_content = std::fs::read_to_string(&crate_b_changelog).unwrap();
+
+    let findings = workspace.lint_changelogs(false).unwrap();
+    assert_eq!(
+        vec![
+            (
+                "crate_a",
+                6,
+                changelog::ChangelogLintKind::MissingReleaseDate
+            ),
+            (
+                "crate_a",
+                9,
+                changelog::ChangelogLintKind::MisplacedUnreleased
+            ),
+        ],
+        findings
+            .iter()
+            .map(|finding| (finding.crate_name.as_str(), finding.line, finding.kind))
+            .collect::<Vec<_>>()
+    );
+    assert_eq!(
+        serde_json::json!({
+            "crate": "crate_a",
+            "line": 6,
+            "kind": "missing_release_date",
+            "message": "the release 0.1.0 has no date",
+        }),
+        serde_json::to_value(&findings[0]).unwrap()
+    );
+
+    // the release date can't be fixed
+    let findings = workspace.lint_changelogs(true).unwrap();
+    assert_eq!(
+        vec![(
+            "crate_a",
+            11,
+            changelog::ChangelogLintKind::MissingReleaseDate
+        )],
+        findings
+            .iter()
+            .map(|finding| (finding.crate_name.as_str(), finding.line, finding.kind))
+            .collect::<Vec<_>>()
+    );
+    assert_eq!(
+        indoc::indoc! {r#"
+        ---
+        default_semver_increment_mode: patch
+        ---
+
+        # Changelog
+
+        ## Unreleased
+
+        - a change
+
+        ## 0.1.0
+
+        - the initial release
+        "#
+        },
+        std::fs::read_to_string(&crate_a_changelog).unwrap()
+    );
+    assert_eq!(
+        crate_b_content,
+        std::fs::read_to_string(&crate_b_changelog).unwrap()
+    );
+}
+
+fn assert_send<T: Send>() {}
+fn assert_sync<T: Sync>() {}
+
+#[test]
+fn analysis_results_can_be_sent_between_threads() {
+    // the workspace itself stays on one thread as cargo's packages are reference counted with `Rc`
+    assert_send::<CrateState>();
+    assert_send::<MemberStates>();
+    assert_send::<CrateStateReport>();
+    assert_send::<crate::plan::ReleasePlan>();
+    assert_send::<TagIndex>();
+
+    assert_send::<OnceCell<MemberStates>>();
+    assert_sync::<OnceCell<MemberStates>>();
+    assert_sync::<Mutex<ChangedFilesCache>>();
+    assert_sync::<Mutex<Option<TagIndex>>>();
+}
+
+#[test]
+fn members_states_are_equal_when_analyzed_on_separate_threads() {
+    let workspace_mocker = example_workspace_1().unwrap();
+
+    let analyze = |root: PathBuf| -> MemberStates {
+        let workspace = ReleaseWorkspace::try_new(root).unwrap();
+        let members_states = workspace.members_states().unwrap().clone();
+        members_states
+    };
+
+    let expected = analyze(workspace_mocker.root());
+    let threads = (0..2)
+        .map(|_| {
+            let root = workspace_mocker.root();
+            std::thread::spawn(move || analyze(root))
+        })
+        .collect::<Vec<_>>();
+
+    for thread in threads {
+        assert_eq!(expected, thread.join().unwrap());
+    }
+}
@@ -0,0 +1,408 @@
+//! Lookups of the versions that are published on a crates registry.
+
+use crate::Fallible;
+
+use anyhow::{anyhow, bail, Context};
+use semver::Version;
+use serde::Deserialize;
+use std::path::Path;
+use std::time::{Duration, Instant};
+use tracing::{info, warn};
+
+/// The sparse index of crates.io.
+pub const CRATES_IO_SPARSE_INDEX_URL: &str = "https://index.crates.io";
+
+/// The web API of crates.io.
+pub const CRATES_IO_API_URL: &str = "https://crates.io/api/v1";
+
+/// The name cargo uses for crates.io, e.g. in the `publish` field of the manifest.
+pub const CRATES_IO_REGISTRY_NAME: &str = "crates-io";
+
+/// How long to wait for a newly published version to become visible in the index by default.
+pub const DEFAULT_INDEX_AVAILABILITY_TIMEOUT: Duration = Duration::from_secs(5 * 60);
+
+const INITIAL_POLL_DELAY: Duration = Duration::from_secs(1);
+const MAX_POLL_DELAY: Duration = Duration::from_secs(60);
+
+/// Provides the published versions of crates.
+pub trait CratesIndex {
+    /// Returns the published versions of the given crate, which are empty if the crate is unknown to the index.
+    fn published_versions(&self, name: &str) -> Fallible<Vec<Version>>;
+}
+
+impl<T: CratesIndex + ?Sized> CratesIndex for &T {
+    fn published_versions(&self, name: &str) -> Fallible<Vec<Version>> {
+        (**self).published_versions(name)
+    }
+}
+
+/// Performs HTTP requests, which allows replacing them in tests.
+pub trait HttpClient {
+    /// Returns the body of a successful response, or `None` if the resource doesn't exist.
+    fn get(&self, url: &str) -> Fallible<Option<String>>;
+}
+
+/// Performs the requests with curl.
+#[derive(Clone, Debug, Default)]
+pub struct CurlHttpClient;
+
+impl HttpClient for CurlHttpClient {
+    fn get(&self, url: &str) -> Fallible<Option<String>> {
+        let mut handle = curl::easy::Easy::new();
+        handle.url(url)?;
+        handle.follow_location(true)?;
+        handle.useragent(concat!(
+            env!("CARGO_PKG_NAME"),
+            "/",
+            env!("CARGO_PKG_VERSION")
+        ))?;
+        handle.timeout(Duration::from_secs(30))?;
+
+        let mut body = Vec::new();
+        {
+            let mut transfer = handle.transfer();
+            transfer.write_function(|data| {
+                body.extend_from_slice(data);
+                Ok(data.len())
+            })?;
+            transfer.perform().context(format!("requesting {}", url))?;
+        }
+
+        match handle.response_code()? {
+            200 => Ok(Some(String::from_utf8(body)?)),
+            404 | 410 => Ok(None),
+            code => bail!("unexpected response code {} for {}", code, url),
+        }
+    }
+}
+
+/// Queries a sparse registry index via HTTP with the given client.
+#[derive(Clone)]
+pub struct SparseIndex<'c> {
+    url: String,
+    http_client: &'c dyn HttpClient,
+}
+
+impl<'c> SparseIndex<'c> {
+    pub fn new(url: &str, http_client: &'c dyn HttpClient) -> Self {
+        Self {
+            url: url.trim_end_matches('/').to_string(),
+            http_client,
+        }
+    }
+
+    pub fn crates_io(http_client: &'c dyn HttpClient) -> Self {
+        Self::new(CRATES_IO_SPARSE_INDEX_URL, http_client)
+    }
+}
+
+/// An entry of a crate's index file, which contains one JSON object per published version.
+#[derive(Deserialize)]
+struct IndexEntry {
+    vers: String,
+}
+
+impl<'c> CratesIndex for SparseIndex<'c> {
+    fn published_versions(&self, name: &str) -> Fallible<Vec<Version>> {
+        let url = format!("{}/{}", self.url, sparse_index_path(name));
+
+        let body = match self.http_client.get(&url)? {
+            Some(body) => body,
+            None => return Ok(vec![]),
+        };
+
+        body.lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| {
+                let entry: IndexEntry = serde_json::from_str(line)
+                    .context(format!("parsing index entry of {}", name))?;
+                Ok(Version::parse(&entry.vers)?)
+            })
+            .collect()
+    }
+}
+
+/// An owner of a crate on crates.io, which is either a user or a team.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+pub struct CrateOwner {
+    /// The user name, or `github:{org}:{team}` for teams.
+    pub login: String,
+    pub kind: String,
+}
+
+#[derive(Deserialize)]
+struct CrateOwners {
+    users: Vec<CrateOwner>,
+}
+
+/// Looks up the owners of the given crate via the crates.io API.
+/// Returns `None` if the crate doesn't exist on crates.io.
+pub fn crate_owners(client: &dyn HttpClient, name: &str) -> Fallible<Option<Vec<CrateOwner>>> {
+    let url = format!("{}/crates/{}/owners", CRATES_IO_API_URL, name);
+
+    client
+        .get(&url)?
+        .map(|body| -> Fallible<Vec<CrateOwner>> {
+            let owners: CrateOwners =
+                serde_json::from_str(&body).context(format!("parsing owners of {}", name))?;
+            Ok(owners.users)
+        })
+        .transpose()
+}
+
+/// Resolves the index URL of the registry with the given name from the cargo configuration that applies to the given directory,
+/// i.e. `registries.{name}.index`. Only sparse indices are supported.
+pub fn registry_index_url(dir: &Path, registry: &str) -> Fallible<String> {
+    if registry == CRATES_IO_REGISTRY_NAME {
+        return Ok(CRATES_IO_SPARSE_INDEX_URL.to_string());
+    }
+
+    let mut config = cargo::util::config::Config::default()?;
+    config.reload_rooted_at(dir)?;
+
+    let index = config
+        .get::<Option<String>>(&format!("registries.{}.index", registry))?
+        .ok_or_else(|| {
+            anyhow!(
+                "the registry '{}' is not configured, expected `registries.{}.index` in the cargo configuration",
+                registry,
+                registry
+            )
+        })?;
+
+    match index.strip_prefix("sparse+") {
+        Some(url) => Ok(url.to_string()),
+        None => bail!(
+            "the index of the registry '{}' at {} is not a sparse index, which is the only kind that is supported",
+            registry,
+            index
+        ),
+    }
+}
+
+/// Polls the index until the given version of the crate is visible, backing off exponentially between the attempts.
+/// Fails if it's not visible before the timeout elapses.
+pub fn wait_for_index_availability(
+    index: &dyn CratesIndex,
+    crate_name: &str,
+    version: &Version,
+    timeout: Duration,
+) -> Fallible<()> {
+    poll_index_availability(index, crate_name, version, timeout, INITIAL_POLL_DELAY)
+}
+
+fn poll_index_availability(
+    index: &dyn CratesIndex,
+    crate_name: &str,
+    version: &Version,
+    timeout: Duration,
+    initial_delay: Duration,
+) -> Fallible<()> {
+    let started = Instant::now();
+    let mut delay = initial_delay;
+
+    loop {
+        match index.published_versions(crate_name) {
+            Ok(versions) if versions.contains(version) => {
+                info!(
+                    "{}-{} is available in the index after {:?}",
+                    crate_name,
+                    version,
+                    started.elapsed()
+                );
+                return Ok(());
+            }
+            Ok(_) => info!(
+                "{}-{} is not available in the index yet, checking again in {:?}",
+                crate_name, version, delay
+            ),
+            Err(err) => warn!(
+                "couldn't look up {} in the index, checking again in {:?}: {:?}",
+                crate_name, delay, err
+            ),
+        }
+
+        let elapsed = started.elapsed();
+        if elapsed >= timeout {
+            bail!(
+                "{}-{} didn't become available in the index within {:?}",
+                crate_name,
+                version,
+                timeout
+            );
+        }
+
+        std::thread::sleep(delay.min(timeout - elapsed));
+        delay = (delay * 2).min(MAX_POLL_DELAY);
+    }
+}
+
+/// Returns the path of the given crate's file within a sparse index.
+/// See https://doc.rust-lang.org/cargo/reference/registry-index.html#index-files
+pub fn sparse_index_path(name: &str) -> String {
+    let name = name.to_lowercase();
+
+    match name.len() {
+        1 => format!("1/{}", name),
+        2 => format!("2/{}", name),
+        3 => format!("3/{}/{}", &name[..1], name),
+        _ => format!("{}/{}/{}", &name[..2], &name[2..4], name),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::cell::Cell;
+    use std::collections::HashMap;
+
+    /// Publishes the version after the given number of queries.
+    struct PropagatingIndex {
+        version: Version,
+        queries_until_available: usize,
+        queries: Cell<usize>,
+    }
+
+    impl CratesIndex for PropagatingIndex {
+        fn published_versions(&self, _name: &str) -> Fallible<Vec<Version>> {
+            self.queries.set(self.queries.get() + 1);
+
+            match self.queries.get() {
+                1 => bail!("connection reset"),
+                queries if queries > self.queries_until_available => Ok(vec![self.version.clone()]),
+                _ => Ok(vec![]),
+            }
+        }
+    }
+
+    #[test]
+    fn waits_until_the_version_is_available() {
+        let index = PropagatingIndex {
+            version: Version::new(0, 0, 1),
+            queries_until_available: 3,
+            queries: Default::default(),
+        };
+
+        poll_index_availability(
+            &index,
+            "crate_a",
+            &Version::new(0, 0, 1),
+            Duration::from_secs(10),
+            Duration::from_millis(1),
+        )
+        .unwrap();
+        assert_eq!(4, index.queries.get());
+    }
+
+    #[test]
+    fn times_out_if_the_version_never_becomes_available() {
+        let index = PropagatingIndex {
+            version: Version::new(0, 0, 1),
+            queries_until_available: usize::MAX,
+            queries: Default::default(),
+        };
+
+        let err = poll_index_availability(
+            &index,
+            "crate_a",
+            &Version::new(0, 0, 1),
+            Duration::from_millis(50),
+            Duration::from_millis(1),
+        )
+        .unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("crate_a-0.0.1 didn't become available"));
+        assert!(index.queries.get() > 1);
+    }
+
+    #[ignore = "queries crates.io"]
+    #[test]
+    fn published_version_is_available_in_the_crates_io_index() {
+        wait_for_index_availability(
+            &SparseIndex::crates_io(&CurlHttpClient),
+            "serde",
+            &Version::new(1, 0, 0),
+            Duration::from_secs(30),
+        )
+        .unwrap();
+    }
+
+    struct MockHttpClient(HashMap<String, Option<String>>);
+
+    impl HttpClient for MockHttpClient {
+        fn get(&self, url: &str) -> Fallible<Option<String>> {
+            match self.0.get(url) {
+                Some(response) => Ok(response.clone()),
+                None => bail!("connection refused"),
+            }
+        }
+    }
+
+    #[test]
+    fn crate_owners_are_parsed() {
+        let client = MockHttpClient(HashMap::from([
+            (
+                format!("{}/crates/crate_a/owners", CRATES_IO_API_URL),
+                Some(
+                    r#"{"users":[{"id":1,"login":"steveeJ","kind":"user","name":null},{"id":2,"login":"github:holochain:core-dev","kind":"team","name":"core-dev"}]}"#
+                        .to_string(),
+                ),
+            ),
+            (format!("{}/crates/crate_b/owners", CRATES_IO_API_URL), None),
+        ]));
+
+        assert_eq!(
+            Some(vec![
+                CrateOwner {
+                    login: "steveeJ".to_string(),
+                    kind: "user".to_string()
+                },
+                CrateOwner {
+                    login: "github:holochain:core-dev".to_string(),
+                    kind: "team".to_string()
+                },
+            ]),
+            crate_owners(&client, "crate_a").unwrap()
+        );
+        assert_eq!(None, crate_owners(&client, "crate_b").unwrap());
+        assert!(crate_owners(&client, "crate_c").is_err());
+    }
+
+    #[test]
+    fn sparse_index_versions_are_requested_with_the_client() {
+        let client = MockHttpClient(HashMap::from([
+            (
+                "https://index.example.com/cr/at/crate_a".to_string(),
+                Some(
+                    indoc::indoc! {r#"
+                        {"name":"crate_a","vers":"0.1.0","deps":[],"cksum":"","features":{},"yanked":false}
+                        {"name":"crate_a","vers":"0.2.0-rc.0","deps":[],"cksum":"","features":{},"yanked":false}
+                        "#}
+                    .to_string(),
+                ),
+            ),
+            ("https://index.example.com/cr/at/crate_b".to_string(), None),
+        ]));
+        let index = SparseIndex::new("https://index.example.com/", &client);
+
+        assert_eq!(
+            vec![Version::new(0, 1, 0), Version::parse("0.2.0-rc.0").unwrap()],
+            index.published_versions("crate_a").unwrap()
+        );
+        assert!(index.published_versions("crate_b").unwrap().is_empty());
+        assert!(index.published_versions("crate_c").is_err());
+    }
+
+    #[test]
+    fn sparse_index_paths() {
+        assert_eq!("1/a", sparse_index_path("a"));
+        assert_eq!("2/ab", sparse_index_path("ab"));
+        assert_eq!("3/a/abc", sparse_index_path("abc"));
+        assert_eq!(
+            "ho/lo/holochain_types",
+            sparse_index_path("holochain_types")
+        );
+        assert_eq!("ca/rg/cargo", sparse_index_path("Cargo"));
+    }
+}
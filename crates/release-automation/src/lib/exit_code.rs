@@ -0,0 +1,109 @@
+//! The exit codes of the commands, which let CI tell the outcomes of a run apart.
+
+use crate::crate_selection::{
+    BlockedSelectionError, DirtyWorktreeError, DisallowedBranchError, EmptySelectionError,
+    GitHeadError,
+};
+
+/// The exit codes of the process, see `ErrorCode::code` for how errors are mapped to them.
+#[repr(i32)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ExitCode {
+    /// The command succeeded, e.g. the selection is ready to be released.
+    Success = 0,
+    /// Any error that isn't covered by the other codes.
+    InternalError = 1,
+    /// Nothing is selected for the release.
+    EmptySelection = 2,
+    /// Crates that are required for the release are blocked.
+    Blocked = 3,
+    /// A precondition of the command isn't met, e.g. the working tree is dirty.
+    PreconditionFailed = 4,
+}
+
+/// Determines the exit code of a failed command.
+pub trait ErrorCode {
+    fn code(&self) -> ExitCode;
+}
+
+impl ErrorCode for anyhow::Error {
+    /// Uses the first error in the chain that has a dedicated exit code, so adding context keeps the code.
+    fn code(&self) -> ExitCode {
+        for cause in self.chain() {
+            if cause.is::<EmptySelectionError>() {
+                return ExitCode::EmptySelection;
+            }
+
+            if cause.is::<BlockedSelectionError>() {
+                return ExitCode::Blocked;
+            }
+
+            if cause.is::<DirtyWorktreeError>()
+                || cause.is::<GitHeadError>()
+                || cause.is::<DisallowedBranchError>()
+            {
+                return ExitCode::PreconditionFailed;
+            }
+        }
+
+        ExitCode::InternalError
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use anyhow::Context;
+
+    #[test]
+    fn errors_map_to_exit_codes() {
+        assert_eq!(
+            ExitCode::EmptySelection,
+            anyhow::Error::from(EmptySelectionError).code()
+        );
+        assert_eq!(
+            ExitCode::Blocked,
+            anyhow::Error::from(BlockedSelectionError {
+                report: Default::default(),
+                never_release: vec!["crate_a -> crate_b".to_string()],
+                excluded: vec![],
+                publish_false: vec![],
+            })
+            .code()
+        );
+        assert_eq!(
+            ExitCode::PreconditionFailed,
+            anyhow::Error::from(DirtyWorktreeError {
+                modified: vec!["Cargo.toml".to_string()],
+                untracked: vec![],
+            })
+            .code()
+        );
+        assert_eq!(
+            ExitCode::PreconditionFailed,
+            anyhow::Error::from(GitHeadError::Detached(git2::Oid::zero())).code()
+        );
+        assert_eq!(
+            ExitCode::PreconditionFailed,
+            anyhow::Error::from(DisallowedBranchError {
+                branch: "feature/foo".to_string(),
+                patterns: vec!["^main$".to_string()],
+            })
+            .code()
+        );
+        assert_eq!(
+            ExitCode::InternalError,
+            anyhow::anyhow!("something went wrong").code()
+        );
+    }
+
+    #[test]
+    fn context_keeps_the_exit_code() {
+        let err = Err::<(), _>(EmptySelectionError)
+            .context("checking the workspace")
+            .unwrap_err();
+
+        assert_eq!(ExitCode::EmptySelection, err.code());
+        assert_eq!(2, err.code() as i32);
+    }
+}
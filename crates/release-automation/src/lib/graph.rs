@@ -0,0 +1,16 @@
+//! Graph command functionality.
+
+use super::*;
+
+/// Prints the workspace dependency graph in Graphviz DOT format.
+pub fn cmd(args: &cli::Args, cmd_args: &cli::GraphArgs) -> CommandResult {
+    let ws = crate_selection::ReleaseWorkspace::try_new_with_criteria(
+        args.workspace_path.clone(),
+        cmd_args.check_args.to_selection_criteria(args),
+    )?
+    .with_progress_reporter(args.progress_reporter()?);
+
+    print!("{}", ws.dependency_graph_dot()?);
+
+    Ok(())
+}
@@ -5,11 +5,11 @@ use anyhow::bail;
 use anyhow::Context;
 use comrak::{format_commonmark, parse_document, Arena, ComrakOptions};
 use enumflags2::{bitflags, BitFlags};
-use log::{debug, error, info, trace, warn};
 use std::collections::{BTreeSet, HashSet};
 use structopt::StructOpt;
+use tracing::{debug, error, info, trace, warn};
 
-use crate_selection::{aliases::CargoDepKind, CrateState, CrateStateFlags};
+use crate_selection::{aliases::CargoDepKind, ChangeClass, CrateState, CrateStateFlags};
 use release::ReleaseSteps;
 
 pub mod changelog;
@@ -17,7 +17,15 @@ pub mod check;
 pub mod common;
 pub mod crate_;
 pub mod crate_selection;
+pub mod crates_index;
+pub mod exit_code;
+pub mod graph;
+pub mod phases;
+pub mod plan;
+pub mod progress;
+pub mod publish;
 pub mod release;
+pub mod rollback;
 
 #[cfg(test)]
 pub mod tests;
@@ -26,14 +34,19 @@ pub type Fallible<T> = anyhow::Result<T>;
 pub type CommandResult = Fallible<()>;
 
 pub mod cli {
+    use crate::changelog::EmptyUnreleasedPolicy;
     use crate::crate_::CrateArgs;
 
     use super::*;
-    use crate_selection::SelectionCriteria;
+    use crate_selection::{
+        BranchPolicy, ExistingTagPolicy, SelectionCriteria, SelectionFilterMode,
+    };
     use semver::Version;
     use std::ffi::OsStr;
     use std::path::PathBuf;
 
+    const DEFAULT_MATCH_FILTER: &str = ".*";
+
     #[derive(Debug, StructOpt)]
     #[structopt(name = "release-automation")]
     pub struct Args {
@@ -52,17 +65,44 @@ pub mod cli {
         /// Allows filtering to a subset of crates that will be processed for the given command.
         /// This string will be used as a regex to filter the package names.
         /// By default, all crates will be considered.
-        #[structopt(long, default_value = ".*")]
+        #[structopt(long, default_value = DEFAULT_MATCH_FILTER)]
         pub match_filter: fancy_regex::Regex,
+
+        /// Draw progress bars while analyzing the workspace, bumping the versions and publishing.
+        /// Requires the `progress-bar` feature.
+        #[structopt(long)]
+        pub progress: bool,
+    }
+
+    impl Args {
+        /// The progress reporter that is selected by `--progress`.
+        pub fn progress_reporter(&self) -> Fallible<Box<dyn crate::progress::ProgressReporter>> {
+            if !self.progress {
+                return Ok(Box::new(crate::progress::NoopProgressReporter));
+            }
+
+            #[cfg(feature = "progress-bar")]
+            return Ok(Box::new(crate::progress::ProgressBarReporter::default()));
+
+            #[cfg(not(feature = "progress-bar"))]
+            bail!("--progress requires release-automation to be built with the `progress-bar` feature");
+        }
     }
 
     #[derive(Debug, StructOpt)]
     #[structopt(name = "ra")]
+    #[allow(clippy::large_enum_variant)]
     pub enum Commands {
         Changelog(ChangelogArgs),
         Release(ReleaseArgs),
         Check(CheckArgs),
         Crate(CrateArgs),
+        Graph(GraphArgs),
+        Rollback(RollbackArgs),
+        Plan(PlanArgs),
+        Explain(ExplainArgs),
+        /// Continue an interrupted release run from its plan and journal, taking the same arguments as `release`.
+        Resume(ReleaseArgs),
     }
 
     #[derive(Debug, StructOpt)]
@@ -82,10 +122,25 @@ pub mod cli {
         pub frontmatter_yaml_path: PathBuf,
     }
 
+    /// Find problems in the structure of the crate changelogs, e.g. release headings without a date.
+    /// Fails if any are found.
+    #[derive(Debug, StructOpt)]
+    pub struct ChangelogLintArgs {
+        /// Rewrite the changelogs into their canonical structure first, which leaves only the problems that can't be fixed automatically.
+        #[structopt(long)]
+        pub fix: bool,
+
+        /// Output format of the findings.
+        /// Valid values are: text, json
+        #[structopt(long, default_value = "text", parse(try_from_str = parse_output_format))]
+        pub output_format: OutputFormat,
+    }
+
     #[derive(Debug, StructOpt)]
     pub enum ChangelogCommands {
         Aggregate(ChangelogAggregateArgs),
         SetFrontmatter(ChangelogSetFrontmatterArgs),
+        Lint(ChangelogLintArgs),
     }
 
     #[derive(StructOpt, Debug)]
@@ -97,6 +152,43 @@ pub mod cli {
     /// Determine whether there are any release blockers by analyzing the state of the workspace.
     #[derive(StructOpt, Debug)]
     pub struct CheckArgs {
+        /// How `--match-filter` and `--exclusion-filter` are matched against the crate names.
+        /// Valid values are: Substring, FullMatch
+        #[structopt(long, default_value = "Substring", parse(try_from_str = parse_selection_filter_mode))]
+        pub selection_filter_mode: SelectionFilterMode,
+
+        /// Names of crates that are selected in addition to the ones matching `--match-filter`.
+        /// Unless a match filter is passed explicitly, only these crates and the ones located via `--selection-path-globs` are selected.
+        #[structopt(long, use_delimiter = true)]
+        pub selection_names: Vec<String>,
+
+        /// Crates located in directories matching any of these globs are selected in addition to the ones matching `--match-filter`.
+        /// The globs are matched against the crate directories relative to the workspace root, e.g. "crates/hdk/*".
+        /// Unless a match filter is passed explicitly, only these crates and the ones passed via `--selection-names` are selected.
+        #[structopt(long, use_delimiter = true)]
+        pub selection_path_globs: Vec<glob::Pattern>,
+
+        /// Crates whose names match this regex are never selected, even if they match otherwise.
+        #[structopt(long)]
+        pub exclusion_filter: Option<fancy_regex::Regex>,
+
+        /// Names of crates that are never selected, even if they match otherwise.
+        #[structopt(long, use_delimiter = true)]
+        pub excluded_names: Vec<String>,
+
+        /// Allow excluded crates to be released as dependencies of selected crates.
+        #[structopt(long)]
+        pub allow_excluded_as_dependency: bool,
+
+        /// Additionally select all crates that depend on a selected crate, directly or transitively.
+        #[structopt(long)]
+        pub include_dependents: bool,
+
+        /// Only select workspace dependencies up to this depth. Direct dependencies have a depth of 1.
+        /// By default all transitive dependencies are selected.
+        #[structopt(long)]
+        pub max_dependency_depth: Option<usize>,
+
         /// All existing versions must match these requirements.
         /// Can be passed more than once to specify multiple.
         /// See https://docs.rs/semver/0.11.0/semver/?search=#requirements
@@ -121,9 +213,264 @@ pub mod cli {
         #[structopt(long, default_value = "", parse(try_from_str = parse_cratestateflags))]
         pub allowed_matched_blockers: BitFlags<CrateStateFlags>,
 
+        /// Replaces the set of states that block the release.
+        /// Comma separated.
+        /// By default, all states that prevent publishing the crate are blocking.
+        #[structopt(long, parse(try_from_str = parse_cratestateflags))]
+        pub blocking_states: Option<BitFlags<CrateStateFlags>>,
+
         /// Exclude optional dependencies.
         #[structopt(long)]
         pub exclude_optional_deps: bool,
+
+        /// Overrides `--exclude-optional-deps` for the optional dependencies of specific crates.
+        /// Expects a comma separated list of `<crate name>=<true|false>`, e.g. "crate_a=false,crate_b=true".
+        #[structopt(long, use_delimiter = true, parse(try_from_str = parse_optional_deps_override))]
+        pub optional_deps_overrides: Vec<(String, bool)>,
+
+        /// Don't block the release on dev-dependencies with git sources, as they're stripped on publish.
+        #[structopt(long)]
+        pub allow_git_dev_dependencies: bool,
+
+        /// Exclude dependencies of these kinds, e.g. "development,build".
+        #[structopt(long, default_value = "", parse(try_from_str = parse_depkind))]
+        pub exclude_dep_kinds: HashSet<CargoDepKind>,
+
+        /// Overrides `--exclude-dep-kinds` for the dependencies of a specific crate.
+        /// Expects `<crate name>=<dependency kinds>`, e.g. "crate_a=development". Can be passed more than once.
+        #[structopt(long, parse(try_from_str = parse_dep_kind_override))]
+        pub dep_kind_overrides: Vec<(String, HashSet<CargoDepKind>)>,
+
+        /// Mark crates whose changelog can't be parsed as blocked instead of failing.
+        #[structopt(long)]
+        pub tolerate_changelog_parse_errors: bool,
+
+        /// Only consider the target-specific dependencies that apply to this target triple, e.g. "x86_64-unknown-linux-gnu".
+        #[structopt(long)]
+        pub dependency_platform_filter: Option<String>,
+
+        /// Changes to files matching these globs, relative to the crate root, don't mark a crate as changed.
+        /// Comma separated.
+        #[structopt(long, default_value = "CHANGELOG.md", use_delimiter = true)]
+        pub change_detection_ignore_globs: Vec<glob::Pattern>,
+
+        /// Changes to these workspace-relative paths mark all crates with a previous release as changed.
+        /// Comma separated, e.g. Cargo.toml,rust-toolchain.toml
+        #[structopt(long, use_delimiter = true)]
+        pub workspace_change_paths: Vec<PathBuf>,
+
+        /// Only changes of these classes mark a crate as changed since its previous release.
+        /// Comma separated.
+        /// Valid values are: Source, Tests, Benches, Examples, Docs, Other
+        #[structopt(
+            long,
+            default_value = "Source,Tests,Benches,Examples,Docs,Other",
+            parse(try_from_str = parse_changeclasses)
+        )]
+        pub change_classes_triggering_release: BitFlags<ChangeClass>,
+
+        /// Don't cache the results of the change detection.
+        #[structopt(long)]
+        pub no_cache: bool,
+
+        /// The number of threads that detect the changes of the crates. Defaults to the number of CPUs.
+        #[structopt(long)]
+        pub analysis_threads: Option<usize>,
+
+        /// Fetch all tags from the given remote before looking up previous releases.
+        /// Useful for shallow checkouts in CI.
+        #[structopt(long)]
+        pub fetch_tags_from_remote: Option<String>,
+
+        /// Allow releasing with local modifications or untracked files in the working tree.
+        #[structopt(long)]
+        pub allow_dirty: bool,
+
+        /// Template for the release tag names, `{name}` and `{version}` are replaced with the crate's name and version.
+        /// E.g. `v{version}` for single crate repositories.
+        #[structopt(long, default_value = crate_selection::DEFAULT_RELEASE_TAG_TEMPLATE)]
+        pub release_tag_template: String,
+
+        /// Block crates whose manifest version is older than the latest release in their changelog.
+        #[structopt(long)]
+        pub block_on_version_changelog_mismatch: bool,
+
+        /// The highest `rust-version` the selected crates may declare, e.g. "1.65".
+        #[structopt(long, parse(try_from_str = crate_selection::parse_rust_version))]
+        pub max_msrv: Option<Version>,
+
+        /// Look up whether the versions of the selected crates are already published on crates.io.
+        #[structopt(long)]
+        pub check_crates_io: bool,
+
+        /// Fail if any of the online checks can't be performed, instead of warning and continuing.
+        #[structopt(long)]
+        pub strict: bool,
+
+        /// The name of the registry to release to as configured in the cargo configuration, e.g. via `registries.{name}.index`.
+        /// Defaults to crates.io.
+        #[structopt(long)]
+        pub registry: Option<String>,
+
+        /// The crates.io user or team, e.g. "github:{org}:{team}", that must own all selected crates which are already published.
+        /// The ownership is verified before the release is started if this is given.
+        #[structopt(long)]
+        pub expected_crate_owner: Option<String>,
+
+        /// Treat crates whose manifest version is published on crates.io but lacks its release tag as released
+        /// at the commit that last set the manifest version.
+        #[structopt(long)]
+        pub reconcile_untagged_releases: bool,
+
+        /// Sum up the sizes of the files that would be packaged for each selected crate and block the ones exceeding `--max-package-size`.
+        /// This is slow and thus disabled by default.
+        #[structopt(long)]
+        pub verify_package_contents: bool,
+
+        /// The maximum size in bytes of the files that would be packaged, used with `--verify-package-contents`.
+        /// Defaults to 10 MiB.
+        #[structopt(long, default_value = "10485760")]
+        pub max_package_size: u64,
+
+        /// Only allow releasing from branches whose name matches any of these regexes.
+        /// Comma separated, e.g. ^main$,^release-.*
+        /// By default, all branches are allowed.
+        #[structopt(long, use_delimiter = true)]
+        pub allowed_branch_patterns: Vec<fancy_regex::Regex>,
+
+        /// Allow releasing from a detached HEAD.
+        #[structopt(long)]
+        pub allow_detached_head: bool,
+
+        /// Select crates whose latest release is a pre-release, e.g. "0.3.0-rc.2", for releasing the corresponding stable version,
+        /// even if they haven't changed since.
+        #[structopt(long)]
+        pub promote: bool,
+
+        /// Run `cargo semver-checks` against the previous release of every selected crate that has one
+        /// after the selection, and block the crates whose declared semver increment is too weak for the detected changes.
+        /// Skipped with a warning if cargo-semver-checks is not installed.
+        #[structopt(long)]
+        pub semver_checks: bool,
+
+        /// Release the crates that inherit their version from the workspace with the highest of their next versions,
+        /// instead of failing if their next versions differ.
+        #[structopt(long)]
+        pub lockstep: bool,
+
+        /// Output format of the check result.
+        /// Valid values are: text, json
+        #[structopt(long, default_value = "text", parse(try_from_str = parse_output_format))]
+        pub output_format: OutputFormat,
+    }
+
+    /// Undo the branches, commits and tags that were created by a failed release run and restore HEAD.
+    #[derive(StructOpt, Debug)]
+    pub struct RollbackArgs {
+        /// Only print what would be rolled back.
+        #[structopt(long)]
+        pub dry_run: bool,
+    }
+
+    /// Write a plan of the release without modifying the repository or the working tree.
+    #[derive(StructOpt, Debug)]
+    pub struct PlanArgs {
+        #[structopt(flatten)]
+        pub check_args: CheckArgs,
+
+        /// Output path of the plan, its extension determines the format.
+        /// Valid extensions are: yaml, yml, json
+        #[structopt(long, default_value = "release-plan.yaml")]
+        pub output_path: PathBuf,
+
+        /// Also write a markdown summary of the plan to this path, e.g. for the description of the release PR.
+        #[structopt(long)]
+        pub summary_path: Option<PathBuf>,
+    }
+
+    /// Explain why a crate is or isn't selected for the release.
+    #[derive(StructOpt, Debug)]
+    pub struct ExplainArgs {
+        #[structopt(flatten)]
+        pub check_args: CheckArgs,
+
+        /// Name of the crate to explain.
+        pub crate_name: String,
+    }
+
+    /// Print the workspace dependency graph including the crates' states in Graphviz DOT format.
+    #[derive(StructOpt, Debug)]
+    pub struct GraphArgs {
+        #[structopt(flatten)]
+        pub check_args: CheckArgs,
+    }
+
+    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+    pub enum OutputFormat {
+        Text,
+        Json,
+    }
+
+    fn parse_output_format(input: &str) -> Fallible<OutputFormat> {
+        Ok(match input.to_lowercase().as_str() {
+            "text" => OutputFormat::Text,
+            "json" => OutputFormat::Json,
+
+            invalid => bail!("invalid output format: {}", invalid),
+        })
+    }
+
+    fn parse_branch_policy(input: &str) -> Fallible<BranchPolicy> {
+        use std::str::FromStr;
+
+        BranchPolicy::from_str(input)
+            .map_err(|_| anyhow::anyhow!("invalid branch policy: {}", input))
+    }
+
+    fn parse_selection_filter_mode(input: &str) -> Fallible<SelectionFilterMode> {
+        use std::str::FromStr;
+
+        SelectionFilterMode::from_str(input)
+            .map_err(|_| anyhow::anyhow!("invalid selection filter mode: {}", input))
+    }
+
+    fn parse_existing_tag_policy(input: &str) -> Fallible<ExistingTagPolicy> {
+        use std::str::FromStr;
+
+        ExistingTagPolicy::from_str(input)
+            .map_err(|_| anyhow::anyhow!("invalid existing tag policy: {}", input))
+    }
+
+    fn parse_empty_unreleased_policy(input: &str) -> Fallible<EmptyUnreleasedPolicy> {
+        use std::str::FromStr;
+
+        EmptyUnreleasedPolicy::from_str(input)
+            .map_err(|_| anyhow::anyhow!("invalid empty unreleased policy: {}", input))
+    }
+
+    fn parse_optional_deps_override(input: &str) -> Fallible<(String, bool)> {
+        let (name, exclude) = input.split_once('=').ok_or_else(|| {
+            anyhow::anyhow!("expected '<crate name>=<true|false>', got '{}'", input)
+        })?;
+
+        Ok((
+            name.trim().to_string(),
+            exclude
+                .trim()
+                .parse()
+                .context(format!("parsing the override for '{}'", name))?,
+        ))
+    }
+
+    fn parse_dep_kind_override(input: &str) -> Fallible<(String, HashSet<CargoDepKind>)> {
+        let (name, dep_kinds) = input.split_once('=').ok_or_else(|| {
+            anyhow::anyhow!(
+                "expected '<crate name>=<dependency kinds>', got '{}'",
+                input
+            )
+        })?;
+
+        Ok((name.trim().to_string(), parse_depkind(dep_kinds)?))
     }
 
     fn parse_depkind(input: &str) -> Fallible<HashSet<CargoDepKind>> {
@@ -151,7 +498,15 @@ pub mod cli {
             .filter(|s| !s.is_empty())
             .map(|csf| {
                 CrateStateFlags::from_str(csf)
-                    .map_err(|_| anyhow::anyhow!("could not parse '{}' as CrateStateFlags", input))
+                    .or_else(|_| {
+                        let flag = CrateStateFlags::from_deprecated_name(csf).ok_or(())?;
+                        // the arguments are parsed before logging is set up
+                        eprintln!("warning: '{}' is deprecated, use '{:?}' instead", csf, flag);
+                        Ok(flag)
+                    })
+                    .map_err(|_: ()| {
+                        anyhow::anyhow!("could not parse '{}' as CrateStateFlags", input)
+                    })
             })
             .try_fold(
                 Default::default(),
@@ -162,16 +517,80 @@ pub mod cli {
             )
     }
 
+    fn parse_changeclasses(input: &str) -> Fallible<BitFlags<ChangeClass>> {
+        use std::str::FromStr;
+
+        input
+            .split(',')
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .map(|class| {
+                ChangeClass::from_str(class)
+                    .map_err(|_| anyhow::anyhow!("could not parse '{}' as ChangeClass", class))
+            })
+            .collect()
+    }
+
     impl CheckArgs {
         /// Boilerplate to instantiate `SelectionCriteria` from `CheckArgs`
         pub fn to_selection_criteria(&self, args: &Args) -> SelectionCriteria {
+            // the default match filter would select all crates and render the names meaningless
+            let match_filter = if !(self.selection_names.is_empty()
+                && self.selection_path_globs.is_empty())
+                && args.match_filter.as_str() == DEFAULT_MATCH_FILTER
+            {
+                fancy_regex::Regex::new("^$").expect("matching nothing is valid")
+            } else {
+                args.match_filter.clone()
+            };
+
             SelectionCriteria {
-                match_filter: args.match_filter.clone(),
+                match_filter,
+                selection_filter_mode: self.selection_filter_mode,
+                selection_names: self.selection_names.clone(),
+                selection_path_globs: self.selection_path_globs.clone(),
+                exclusion_filter: self.exclusion_filter.clone(),
+                excluded_names: self.excluded_names.clone(),
+                allow_excluded_as_dependency: self.allow_excluded_as_dependency,
+                include_dependents: self.include_dependents,
+                max_dependency_depth: self.max_dependency_depth,
                 disallowed_version_reqs: self.disallowed_version_reqs.clone(),
                 enforced_version_reqs: self.enforced_version_reqs.clone(),
                 allowed_dev_dependency_blockers: self.allowed_dev_dependency_blockers,
                 allowed_selection_blockers: self.allowed_matched_blockers,
+                blocking_states: self
+                    .blocking_states
+                    .unwrap_or(crate_selection::CrateState::BLOCKING_STATES),
                 exclude_optional_deps: self.exclude_optional_deps,
+                optional_deps_overrides: self.optional_deps_overrides.iter().cloned().collect(),
+                allow_git_dev_dependencies: self.allow_git_dev_dependencies,
+                exclude_dep_kinds: self.exclude_dep_kinds.clone(),
+                dep_kind_overrides: self.dep_kind_overrides.iter().cloned().collect(),
+                tolerate_changelog_parse_errors: self.tolerate_changelog_parse_errors,
+                dependency_platform_filter: self.dependency_platform_filter.clone(),
+                change_detection_ignore_globs: self.change_detection_ignore_globs.clone(),
+                workspace_change_paths: self.workspace_change_paths.clone(),
+                change_classes_triggering_release: self.change_classes_triggering_release,
+                no_change_detection_cache: self.no_cache,
+                analysis_threads: self.analysis_threads,
+                fetch_tags_from_remote: self.fetch_tags_from_remote.clone(),
+                allow_dirty: self.allow_dirty,
+                release_tag_template: Some(self.release_tag_template.clone()),
+                block_on_version_changelog_mismatch: self.block_on_version_changelog_mismatch,
+                max_msrv: self.max_msrv.clone(),
+                check_crates_io: self.check_crates_io,
+                strict: self.strict,
+                registry: self.registry.clone(),
+                expected_owner: self.expected_crate_owner.clone(),
+                reconcile_untagged_releases: self.reconcile_untagged_releases,
+                verify_package_contents: self.verify_package_contents,
+                max_package_size: self.max_package_size,
+                allowed_branch_patterns: self.allowed_branch_patterns.clone(),
+                allow_detached_head: self.allow_detached_head,
+                promote: self.promote,
+                lockstep: self.lockstep,
+                semver_increment_mode: Default::default(),
+                semver_increment_overrides: Default::default(),
             }
         }
     }
@@ -191,6 +610,11 @@ pub mod cli {
         #[structopt(long)]
         pub release_branch_name: Option<String>,
 
+        /// What to do if the release branch already exists, e.g. when resuming a release.
+        /// Valid values are: ErrorIfExists, ReuseExisting, Recreate
+        #[structopt(long, default_value = "ReuseExisting", parse(try_from_str = parse_branch_policy))]
+        pub release_branch_policy: BranchPolicy,
+
         /// The release steps to perform.
         /// These will be reordered to their defined ordering.
         ///
@@ -203,9 +627,35 @@ pub mod cli {
         pub force_branch_creation: bool,
 
         /// Force creation of the git tags.
+        /// Shorthand for `--existing-tag-policy=Force`.
         #[structopt(long)]
         pub force_tag_creation: bool,
 
+        /// What to do if a release tag already exists, e.g. when a previous run failed after tagging.
+        /// Valid values are: Error, Skip, Force
+        /// Force only recreates tags which point at the same tree as HEAD.
+        #[structopt(long, default_value = "Error", parse(try_from_str = parse_existing_tag_policy))]
+        pub existing_tag_policy: ExistingTagPolicy,
+
+        /// Create lightweight instead of annotated git tags.
+        #[structopt(long)]
+        pub lightweight_tags: bool,
+
+        /// What to do if a released crate has no changes below the unreleased heading of its changelog.
+        /// Valid values are: Error, Placeholder
+        #[structopt(long, default_value = "Placeholder", parse(try_from_str = parse_empty_unreleased_policy))]
+        pub empty_unreleased_policy: EmptyUnreleasedPolicy,
+
+        /// Additionally create a tag for the whole workspace release once all crate tags were created.
+        /// The template may contain `{release}` for the name of the workspace release and `{timestamp}`.
+        /// e.g. "workspace-{release}"
+        #[structopt(long)]
+        pub workspace_release_tag_template: Option<String>,
+
+        /// Push the release branch and the created tags to this remote after creating them.
+        #[structopt(long)]
+        pub push_to_remote: Option<String>,
+
         /// Force creation of the git tags.
         #[structopt(long)]
         pub no_tag_creation: bool,
@@ -219,7 +669,7 @@ pub mod cli {
         #[structopt(long)]
         pub cargo_target_dir: Option<PathBuf>,
 
-        /// Don't run consistency verification checks.
+        /// Don't run consistency verification checks, and pass `--no-verify` to `cargo publish`.
         #[structopt(long)]
         pub no_verify: bool,
 
@@ -231,6 +681,15 @@ pub mod cli {
         #[structopt(long)]
         pub no_verify_post: bool,
 
+        /// How many seconds to wait for each published crate to become available in the crates.io index
+        /// before publishing the crates that depend on it.
+        #[structopt(long, default_value = "300")]
+        pub index_availability_timeout: u64,
+
+        /// How often publishing a crate is retried after a transient failure, e.g. a server error or a timeout.
+        #[structopt(long, default_value = "3")]
+        pub publish_max_retries: usize,
+
         /// Paths to manifest that will also be considered when updating the Cargo.lock files
         #[structopt(long)]
         pub additional_manifests: Vec<String>,
@@ -242,6 +701,38 @@ pub mod cli {
             multiple = false,
         )]
         pub minimum_crate_owners: Vec<String>,
+
+        /// Author and committer name for release commits and tags.
+        /// Defaults to `user.name` of the repository's git config.
+        #[structopt(long)]
+        pub git_author_name: Option<String>,
+
+        /// Author and committer email for release commits and tags.
+        /// Defaults to `user.email` of the repository's git config.
+        #[structopt(long)]
+        pub git_author_email: Option<String>,
+
+        /// Sign release commits and tags with GPG.
+        #[structopt(long)]
+        pub git_sign: bool,
+
+        /// The GPG key used for signing.
+        /// Defaults to `user.signingkey` of the repository's git config.
+        #[structopt(long)]
+        pub git_signing_key: Option<String>,
+    }
+
+    impl ReleaseArgs {
+        /// Boilerplate to instantiate `GitOptions` from `ReleaseArgs`
+        pub fn to_git_options(&self) -> crate_selection::GitOptions {
+            crate_selection::GitOptions {
+                name: self.git_author_name.clone(),
+                email: self.git_author_email.clone(),
+                sign: self.git_sign,
+                signing_key: self.git_signing_key.clone(),
+                ..Default::default()
+            }
+        }
     }
 
     /// Parses a commad separated input string to a set of strings.
@@ -0,0 +1,446 @@
+//! The phases of a release, which can be run one by one with a plan in between, e.g. to publish days after the version bumps.
+
+use crate::changelog::EmptyUnreleasedPolicy;
+use crate::crate_selection::{
+    ExistingTagPolicy, PublishOptions, PublishReport, ReleaseTagsReport, ReleaseWorkspace, TagKind,
+};
+use crate::plan::ReleasePlan;
+use crate::Fallible;
+use anyhow::{anyhow, bail, Context};
+use educe::Educe;
+use enumflags2::{bitflags, BitFlags};
+use std::collections::HashSet;
+use std::path::PathBuf;
+use tracing::{debug, error_span, info};
+
+/// The phases of a release in the order they're run by `ReleaseWorkspace::run_release_phases`.
+///
+/// Except for the selection, every phase takes the plan it produced as input.
+#[bitflags]
+#[repr(u16)]
+#[derive(enum_utils::FromStr, Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ReleasePhase {
+    /// Computes the plan, see `ReleaseWorkspace::compute_plan`.
+    Selection,
+    /// Writes the next versions into the manifests of the planned crates.
+    VersionBump,
+    /// Rewrites the requirements of the dependants and refreshes the lockfile.
+    ManifestApply,
+    /// Adds the release headings to the crate changelogs and the workspace changelog.
+    ChangelogApply,
+    /// Commits the modified files.
+    Commit,
+    /// Creates the release tags at HEAD.
+    Tag,
+    /// Runs `cargo publish --dry-run` for the crates that are going to be published.
+    Verify,
+    /// Publishes the crates to the registry.
+    Publish,
+}
+
+/// Options for the phases that are run by `ReleaseWorkspace::run_release_phases`.
+#[derive(Clone, Debug, PartialEq, Eq, Educe)]
+#[educe(Default)]
+pub struct ReleasePhaseOptions {
+    /// The title of the release in the workspace changelog.
+    pub release_name: String,
+    #[educe(Default(expression = "ExistingTagPolicy::Error"))]
+    pub existing_tag_policy: ExistingTagPolicy,
+    /// Create lightweight tags instead of annotated ones that contain the changelog section of the release.
+    pub lightweight_tags: bool,
+    /// What to do if a planned crate has no changes below the unreleased heading of its changelog.
+    #[educe(Default(expression = "EmptyUnreleasedPolicy::Placeholder"))]
+    pub empty_unreleased_policy: EmptyUnreleasedPolicy,
+    pub cargo_target_dir: Option<PathBuf>,
+    /// The dependencies that are allowed to be missing at the search location despite not being released.
+    pub allowed_missing_dependencies: HashSet<String>,
+    pub publish: PublishOptions,
+}
+
+/// The outcome of `ReleaseWorkspace::bump_planned_versions`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct VersionBumpResult {
+    pub modified_files: Vec<PathBuf>,
+}
+
+/// The outcome of `ReleaseWorkspace::apply_planned_manifests`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ManifestApplyResult {
+    pub modified_files: Vec<PathBuf>,
+}
+
+/// The outcome of `ReleaseWorkspace::apply_planned_changelogs`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ChangelogApplyResult {
+    /// The crate releases that were added to the workspace changelog, e.g. `crate_a-0.1.1`.
+    pub crate_releases: Vec<String>,
+    pub modified_files: Vec<PathBuf>,
+}
+
+/// The outcome of `ReleaseWorkspace::commit_planned_release`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct CommitResult {
+    /// The release commit, or HEAD if there was nothing to commit.
+    pub commit: String,
+    pub created: bool,
+}
+
+/// The outcome of `ReleaseWorkspace::verify_planned_release`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct VerifyResult {
+    /// The crates that were verified to be publishable.
+    pub verified: Vec<String>,
+}
+
+/// The outcomes of the phases that were run by `ReleaseWorkspace::run_release_phases`.
+/// Phases that weren't run have no outcome.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ReleasePhaseResults {
+    /// The plan the phases were run with, which was computed by the selection phase if it was run.
+    pub plan: ReleasePlan,
+    pub version_bump: Option<VersionBumpResult>,
+    pub manifest_apply: Option<ManifestApplyResult>,
+    pub changelog_apply: Option<ChangelogApplyResult>,
+    pub commit: Option<CommitResult>,
+    pub tag: Option<ReleaseTagsReport>,
+    pub verify: Option<VerifyResult>,
+    pub publish: Option<PublishReport>,
+}
+
+impl ReleasePhaseResults {
+    pub fn new(plan: ReleasePlan) -> Self {
+        Self {
+            plan,
+            version_bump: None,
+            manifest_apply: None,
+            changelog_apply: None,
+            commit: None,
+            tag: None,
+            verify: None,
+            publish: None,
+        }
+    }
+
+    /// The files that were modified by the phases that were run, in the order they were modified.
+    pub fn modified_files(&self) -> Vec<PathBuf> {
+        let mut modified_files = linked_hash_set::LinkedHashSet::new();
+
+        for files in [
+            self.version_bump
+                .as_ref()
+                .map(|result| &result.modified_files),
+            self.manifest_apply
+                .as_ref()
+                .map(|result| &result.modified_files),
+            self.changelog_apply
+                .as_ref()
+                .map(|result| &result.modified_files),
+        ]
+        .into_iter()
+        .flatten()
+        {
+            modified_files.extend(files.iter().cloned());
+        }
+
+        modified_files.into_iter().collect()
+    }
+}
+
+impl<'a> ReleaseWorkspace<'a> {
+    /// Runs the given phases in their order with the given plan, or with the plan that's computed by the selection phase.
+    ///
+    /// A plan is required unless the selection phase is run, and it's not verified against the workspace, see `verify_plan`.
+    /// If the commit phase is run without any of the phases that modify files, it commits all modified tracked files,
+    /// which are expected to be modified by running these phases before.
+    pub fn run_release_phases(
+        &'a self,
+        phases: BitFlags<ReleasePhase>,
+        plan: Option<ReleasePlan>,
+        options: &ReleasePhaseOptions,
+    ) -> Fallible<ReleasePhaseResults> {
+        let plan = match (phases.contains(ReleasePhase::Selection), plan) {
+            (true, None) => self.compute_plan()?,
+            (false, Some(plan)) => plan,
+            (true, Some(_)) => bail!("a plan was given but the selection phase computes a new one"),
+            (false, None) => bail!("a plan is required if the selection phase is skipped"),
+        };
+
+        let mut results = ReleasePhaseResults::new(plan);
+        for phase in phases.iter() {
+            let _span = error_span!("phase", phase = ?phase).entered();
+            debug!("running the {:?} phase", phase);
+
+            match phase {
+                ReleasePhase::Selection => {}
+                ReleasePhase::VersionBump => {
+                    results.version_bump = Some(self.bump_planned_versions(&results.plan)?)
+                }
+                ReleasePhase::ManifestApply => {
+                    results.manifest_apply = Some(self.apply_planned_manifests(&results.plan)?)
+                }
+                ReleasePhase::ChangelogApply => {
+                    results.changelog_apply = Some(self.apply_planned_changelogs(
+                        &results.plan,
+                        &options.release_name,
+                        options.empty_unreleased_policy,
+                    )?)
+                }
+                ReleasePhase::Commit => {
+                    let paths = if phases.intersects(
+                        ReleasePhase::VersionBump
+                            | ReleasePhase::ManifestApply
+                            | ReleasePhase::ChangelogApply,
+                    ) {
+                        results.modified_files()
+                    } else {
+                        self.git_dirty_paths()?
+                            .into_iter()
+                            .map(PathBuf::from)
+                            .collect()
+                    };
+
+                    results.commit = Some(self.commit_planned_release(&results.plan, &paths)?)
+                }
+                ReleasePhase::Tag => {
+                    results.tag = Some(self.tag_planned_release(
+                        &results.plan,
+                        options.existing_tag_policy,
+                        options.lightweight_tags,
+                    )?)
+                }
+                ReleasePhase::Verify => {
+                    results.verify = Some(self.verify_planned_release(
+                        &results.plan,
+                        &options.cargo_target_dir,
+                        &options.allowed_missing_dependencies,
+                    )?)
+                }
+                ReleasePhase::Publish => {
+                    results.publish =
+                        Some(self.publish_planned_release(&results.plan, options.publish.clone())?)
+                }
+            }
+        }
+
+        Ok(results)
+    }
+
+    /// The version bump phase, which writes the planned next versions into the manifests, see `apply_planned_version_bumps`.
+    pub fn bump_planned_versions(&'a self, plan: &ReleasePlan) -> Fallible<VersionBumpResult> {
+        Ok(VersionBumpResult {
+            modified_files: self.apply_planned_version_bumps(plan)?,
+        })
+    }
+
+    /// The manifest phase, which rewrites the requirements of the dependants of the planned crates and refreshes the lockfile,
+    /// see `update_planned_dependent_requirements`.
+    pub fn apply_planned_manifests(&'a self, plan: &ReleasePlan) -> Fallible<ManifestApplyResult> {
+        let mut modified_files = self.update_planned_dependent_requirements(plan)?;
+        modified_files.push(self.refresh_lockfile()?);
+
+        Ok(ManifestApplyResult { modified_files })
+    }
+
+    /// The changelog phase, which adds the planned next version as a release heading with today's date to the changelog
+    /// of each planned crate, taking over its unreleased changes, and adds these crate releases under the given release name
+    /// to the workspace changelog.
+    ///
+    /// Changelogs whose topmost release already is the planned one are left untouched.
+    pub fn apply_planned_changelogs(
+        &'a self,
+        plan: &ReleasePlan,
+        release_name: &str,
+        empty_unreleased_policy: EmptyUnreleasedPolicy,
+    ) -> Fallible<ChangelogApplyResult> {
+        let members = self.members()?;
+        let date = chrono::Utc::now().naive_utc().date();
+
+        let mut result = ChangelogApplyResult::default();
+        let mut crate_sections = vec![];
+
+        for (name, (current_version, next_version)) in plan.version_bumps()? {
+            let changelog = members
+                .iter()
+                .find(|member| member.name() == name)
+                .ok_or_else(|| {
+                    anyhow!(
+                        "the planned crate '{}' is not a member of the workspace",
+                        name
+                    )
+                })?
+                .changelog()
+                .ok_or_else(|| anyhow!("[{}] cannot add the release: missing changelog", name))?;
+            let title = next_version.to_string();
+
+            let topmost_release = changelog.topmost_release()?;
+            if matches!(topmost_release, Some(release) if release.title() == title) {
+                info!(
+                    "[{}] the changelog already contains the release {}, skipping it",
+                    name, title
+                );
+            } else {
+                debug!(
+                    "[{}] creating crate release heading '{}' in '{:?}'",
+                    name,
+                    title,
+                    changelog.path(),
+                );
+                changelog
+                    .add_release(&next_version, date, empty_unreleased_policy)
+                    .context(format!("adding release to changelog for '{}'", name))?;
+
+                if next_version != current_version {
+                    changelog.reset_front_matter_to_defaults()?;
+                }
+
+                result.modified_files.push(changelog.path().to_path_buf());
+            }
+
+            let section = changelog.release_section(&title)?.unwrap_or_default();
+            crate_sections.push((name, next_version, section));
+        }
+
+        if crate_sections.is_empty() {
+            return Ok(result);
+        }
+
+        let ws_changelog = self
+            .changelog()
+            .ok_or_else(|| anyhow!("workspace has no changelog"))?;
+        let topmost_release = ws_changelog.topmost_release()?;
+        if matches!(topmost_release, Some(release) if release.title() == release_name) {
+            info!(
+                "the workspace changelog already contains the release {}, skipping it",
+                release_name
+            );
+        } else {
+            ws_changelog.add_release(release_name, &crate_sections)?;
+            result
+                .modified_files
+                .push(ws_changelog.path().to_path_buf());
+        }
+
+        result.crate_releases = crate_sections
+            .iter()
+            .map(|(name, version, _)| format!("{}-{}", name, version))
+            .collect();
+
+        Ok(result)
+    }
+
+    /// The commit phase, which commits the given paths with a message that names the current branch
+    /// and lists the planned crate releases.
+    pub fn commit_planned_release(
+        &'a self,
+        plan: &ReleasePlan,
+        paths: &[PathBuf],
+    ) -> Fallible<CommitResult> {
+        let head = self.git_repo.head()?.peel_to_commit()?.id();
+
+        let msg = indoc::formatdoc!(
+            r#"
+            create a release from branch {}
+
+            the following crates are part of this release:
+            {}
+            "#,
+            self.git_head_branch_name()?,
+            plan.version_bumps()?
+                .iter()
+                .map(|(name, (_, next_version))| format!("\n- {}-{}", name, next_version))
+                .collect::<String>()
+        );
+
+        let mut paths = paths.to_vec();
+        paths.sort();
+        paths.dedup();
+
+        info!("creating the following commit: {}", msg);
+        let commit = self.git_commit_paths(
+            &msg,
+            &paths.iter().map(PathBuf::as_path).collect::<Vec<_>>(),
+        )?;
+
+        Ok(CommitResult {
+            commit: commit.to_string(),
+            created: commit != head,
+        })
+    }
+
+    /// The tag phase, which creates the planned release tags at HEAD and handles existing ones according to the policy.
+    /// Unless they're lightweight, the tags contain the changelog section of the release.
+    pub fn tag_planned_release(
+        &'a self,
+        plan: &ReleasePlan,
+        policy: ExistingTagPolicy,
+        lightweight: bool,
+    ) -> Fallible<ReleaseTagsReport> {
+        let members = self.members()?;
+
+        let mut tags = vec![];
+        for planned in &plan.crates {
+            let (next_version, tag_name) = match (&planned.next_version, &planned.tag_name) {
+                (Some(next_version), Some(tag_name)) => (next_version, tag_name),
+                _ => continue,
+            };
+
+            let tag_kind = if lightweight {
+                TagKind::Lightweight
+            } else {
+                let changelog_section = match members
+                    .iter()
+                    .find(|member| member.name() == planned.name)
+                    .and_then(|member| member.changelog())
+                {
+                    Some(changelog) => changelog.release_section(next_version)?,
+                    None => None,
+                };
+
+                crate::release::release_tag_kind(tag_name, changelog_section)
+            };
+
+            tags.push((tag_name.clone(), tag_kind));
+        }
+
+        self.create_release_tags(tags, policy, false)
+    }
+
+    /// The verify phase, which runs `cargo publish --dry-run` for the planned crates that are going to be published,
+    /// see `verify_publishable`.
+    pub fn verify_planned_release(
+        &'a self,
+        plan: &ReleasePlan,
+        cargo_target_dir: &Option<PathBuf>,
+        allowed_missing_dependencies: &HashSet<String>,
+    ) -> Fallible<VerifyResult> {
+        let crates = self.planned_publish_selection(plan)?;
+        self.verify_crates_publishable(&crates, cargo_target_dir, allowed_missing_dependencies)?;
+
+        Ok(VerifyResult {
+            verified: crates.iter().map(|crt| crt.name()).collect(),
+        })
+    }
+
+    /// The publish phase, which publishes the planned crates with their planned next versions, see `publish`.
+    /// The versions are taken from the plan so this works with a workspace that was read before the version bump phase.
+    pub fn publish_planned_release(
+        &'a self,
+        plan: &ReleasePlan,
+        opts: PublishOptions,
+    ) -> Fallible<PublishReport> {
+        let next_versions = plan.version_bumps()?;
+        let selection = self
+            .planned_publish_selection(plan)?
+            .into_iter()
+            .map(|crt| {
+                let (_, next_version) = next_versions
+                    .get(&crt.name())
+                    .ok_or_else(|| anyhow!("no next version planned for {}", crt.name()))?;
+
+                Ok((crt, next_version.clone()))
+            })
+            .collect::<Fallible<Vec<_>>>()?;
+
+        self.publish_versions(&selection, opts)
+    }
+}
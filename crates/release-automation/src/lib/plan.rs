@@ -0,0 +1,396 @@
+//! Plan command functionality, which describes a release without performing it.
+
+use super::*;
+
+use crate::common::SemverIncrementMode;
+use crate::crate_selection::{
+    github_heading_anchor, normalized_path, path_relative_to, Crate, ReleaseWorkspace,
+};
+use crate::rollback::{ReleaseJournal, STATE_DIR};
+use anyhow::anyhow;
+use linked_hash_map::LinkedHashMap;
+use semver::Version;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+const PLAN_FILENAME: &str = "plan.json";
+
+/// The outcome of the release analysis, see `ReleaseWorkspace::compute_plan`.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReleasePlan {
+    /// The commit HEAD pointed to when the plan was computed.
+    pub head_commit: String,
+    /// The selection criteria the plan was computed with.
+    pub criteria: serde_json::Value,
+    /// The selected crates in the order they're going to be published.
+    pub crates: Vec<PlannedCrate>,
+}
+
+/// The release of a single crate as described by a `ReleasePlan`.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PlannedCrate {
+    pub name: String,
+    pub current_version: String,
+    /// Unset if the crate is skipped because of its allowed blockers.
+    pub next_version: Option<String>,
+    /// The increment mode that determined the next version.
+    /// Unset for crates that are promoted to their stable release or are skipped.
+    pub increment_mode: Option<SemverIncrementMode>,
+    pub tag_name: Option<String>,
+    /// Whether the next version is going to be published to the registry.
+    pub publish: bool,
+    /// The flags that would block the release by default but were allowed.
+    #[serde(default)]
+    pub allowed_blockers: Vec<CrateStateFlags>,
+}
+
+/// The serialization formats of a plan, determined by the extension of its path.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum PlanFormat {
+    Yaml,
+    Json,
+}
+
+impl PlanFormat {
+    fn from_path(path: &Path) -> Fallible<Self> {
+        match path.extension().and_then(|extension| extension.to_str()) {
+            Some("yaml") | Some("yml") => Ok(Self::Yaml),
+            Some("json") => Ok(Self::Json),
+            _ => bail!(
+                "can't determine the format of the release plan at {}, expected a .yaml, .yml or .json extension",
+                path.display()
+            ),
+        }
+    }
+}
+
+impl ReleasePlan {
+    /// The planned current and next versions of the crates that aren't skipped, in the order of the plan.
+    pub fn version_bumps(&self) -> Fallible<LinkedHashMap<String, (Version, Version)>> {
+        self.crates
+            .iter()
+            .filter_map(|planned| Some((planned, planned.next_version.as_ref()?)))
+            .map(|(planned, next_version)| {
+                Ok((
+                    planned.name.clone(),
+                    (
+                        Version::parse(&planned.current_version)?,
+                        Version::parse(next_version)?,
+                    ),
+                ))
+            })
+            .collect()
+    }
+
+    /// Load the plan from the given path.
+    pub fn load(path: &Path) -> Fallible<Self> {
+        let content = common::load_from_file(path)?;
+
+        Ok(match PlanFormat::from_path(path)? {
+            PlanFormat::Yaml => serde_yaml::from_str(&content)
+                .context(format!("parsing release plan at {}", path.display()))?,
+            PlanFormat::Json => serde_json::from_str(&content)
+                .context(format!("parsing release plan at {}", path.display()))?,
+        })
+    }
+
+    /// Persist the plan at the given path.
+    pub fn save(&self, path: &Path) -> Fallible<()> {
+        let content = match PlanFormat::from_path(path)? {
+            PlanFormat::Yaml => serde_yaml::to_string(self)?,
+            PlanFormat::Json => serde_json::to_string_pretty(self)?,
+        };
+
+        std::fs::write(path, content)?;
+
+        Ok(())
+    }
+
+    /// Location of the plan of the release run in the workspace at the given root.
+    pub fn state_path(root: &Path) -> PathBuf {
+        root.join(STATE_DIR).join(PLAN_FILENAME)
+    }
+
+    /// Load the plan of the release run in the workspace at the given root if there is one.
+    pub fn load_state(root: &Path) -> Fallible<Option<Self>> {
+        let path = Self::state_path(root);
+        if !path.exists() {
+            return Ok(None);
+        }
+
+        Self::load(&path).map(Some)
+    }
+
+    /// Persist the plan as the one of the release run in the workspace at the given root.
+    pub fn save_state(&self, root: &Path) -> Fallible<()> {
+        crate::rollback::ensure_state_dir(root)?;
+        self.save(&Self::state_path(root))
+    }
+
+    /// Remove the plan of the release run in the workspace at the given root if there is one.
+    pub fn remove_state(root: &Path) -> Fallible<()> {
+        let path = Self::state_path(root);
+        if path.exists() {
+            std::fs::remove_file(path)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// How far the release of a planned crate has progressed, see `ReleaseWorkspace::release_progress`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CrateReleaseProgress {
+    pub name: String,
+    /// The planned next version.
+    pub version: String,
+    pub tag_name: String,
+    /// The manifest contains the planned version.
+    pub committed: bool,
+    /// The planned release tag exists.
+    pub tagged: bool,
+    /// The journal records the planned version as published.
+    pub published: bool,
+}
+
+impl<'a> ReleaseWorkspace<'a> {
+    /// Performs the release analysis and describes its outcome for the selected crates in the order they're going to be published.
+    /// Neither the repository nor the working tree are modified, unless the criteria ask for creating the missing release tags.
+    ///
+    /// Selected crates whose blockers are allowed are included without a next version, as they're skipped.
+    pub fn compute_plan(&'a self) -> Fallible<ReleasePlan> {
+        let bumps = self.compute_version_bumps()?;
+
+        let mut crates = vec![];
+        for member in self.members()? {
+            let state = member.state()?;
+            if !state.selected() {
+                continue;
+            }
+
+            let planned_crate = match bumps.get(&member.name()) {
+                Some((_, next_version)) => {
+                    let increment_mode = if state.contains(CrateStateFlags::PendingStablePromotion)
+                    {
+                        None
+                    } else if state.contains(CrateStateFlags::CascadeBumped) {
+                        Some(SemverIncrementMode::Breaking)
+                    } else {
+                        Some(self.declared_increment_mode(member)?)
+                    };
+
+                    PlannedCrate {
+                        name: member.name(),
+                        current_version: member.version().to_string(),
+                        next_version: Some(next_version.to_string()),
+                        increment_mode,
+                        tag_name: Some(self.release_tag_name(&member.name(), next_version)?),
+                        publish: !state.contains(CrateStateFlags::ManifestPublishFalse),
+                        allowed_blockers: state.allowed_blockers().iter().collect(),
+                    }
+                }
+                // the analysis has failed already if any of these blockers were disallowed
+                None if state.blocked() => PlannedCrate {
+                    name: member.name(),
+                    current_version: member.version().to_string(),
+                    next_version: None,
+                    increment_mode: None,
+                    tag_name: None,
+                    publish: false,
+                    allowed_blockers: state.allowed_blockers().iter().collect(),
+                },
+                None => continue,
+            };
+
+            crates.push(planned_crate);
+        }
+
+        Ok(ReleasePlan {
+            head_commit: self.git_repo.head()?.peel_to_commit()?.id().to_string(),
+            criteria: serde_json::to_value(&self.criteria)?,
+            crates,
+        })
+    }
+
+    /// Errors unless the plan was computed with the same criteria and HEAD still points to the planned commit,
+    /// or to a commit on top of it that was created by the release run according to the journal.
+    pub fn verify_plan(&'a self, plan: &ReleasePlan) -> Fallible<()> {
+        if serde_json::to_value(&self.criteria)? != plan.criteria {
+            bail!("the release plan was computed with different selection criteria");
+        }
+
+        let head = self.git_repo.head()?.peel_to_commit()?.id();
+        let planned_head = git2::Oid::from_str(&plan.head_commit)?;
+        if head == planned_head {
+            return Ok(());
+        }
+
+        let journal = ReleaseJournal::load(&self.root_path)?.unwrap_or_default();
+        if journal.contains_commit(&head.to_string())
+            && self.git_repo.graph_descendant_of(head, planned_head)?
+        {
+            return Ok(());
+        }
+
+        bail!(
+            "HEAD is at {} but the release plan was computed at {}{}",
+            head,
+            planned_head,
+            journal
+                .release_branch()
+                .map(|branch| format!(", consider checking out the release branch '{}'", branch))
+                .unwrap_or_default()
+        )
+    }
+
+    /// Determines how far the release of each planned crate that isn't skipped has progressed,
+    /// based on the manifests in the working tree, the existing tags and the journal.
+    pub fn release_progress(&'a self, plan: &ReleasePlan) -> Fallible<Vec<CrateReleaseProgress>> {
+        let published_versions = ReleaseJournal::load(&self.root_path)?
+            .map(|journal| journal.published_versions())
+            .unwrap_or_default();
+        let members = self.members()?;
+
+        plan.crates
+            .iter()
+            .filter_map(|planned| {
+                Some((
+                    planned,
+                    planned.next_version.as_ref()?,
+                    planned.tag_name.as_ref()?,
+                ))
+            })
+            .map(|(planned, version, tag_name)| {
+                let member = members
+                    .iter()
+                    .find(|member| member.name() == planned.name)
+                    .ok_or_else(|| {
+                        anyhow!(
+                            "the planned crate '{}' is not a member of the workspace",
+                            planned.name
+                        )
+                    })?;
+
+                Ok(CrateReleaseProgress {
+                    name: planned.name.clone(),
+                    version: version.clone(),
+                    tag_name: tag_name.clone(),
+                    committed: member.version().to_string() == *version,
+                    tagged: self.tag_index()?.contains_key(tag_name),
+                    published: published_versions
+                        .contains(&(planned.name.clone(), version.clone())),
+                })
+            })
+            .collect()
+    }
+
+    /// Renders a GitHub-flavored markdown summary of the plan, e.g. for the description of the release PR.
+    ///
+    /// It consists of a table of the planned crates and the unreleased changelog entries of the crates that aren't skipped.
+    pub fn render_release_summary_markdown(&'a self, plan: &ReleasePlan) -> Fallible<String> {
+        let members = self.members()?;
+
+        let mut table = String::from(
+            "| crate | old version | new version | changelog | allowed blockers |\n\
+            | --- | --- | --- | --- | --- |\n",
+        );
+        let mut details = String::new();
+
+        for planned in &plan.crates {
+            let member = members
+                .iter()
+                .find(|member| member.name() == planned.name)
+                .ok_or_else(|| {
+                    anyhow!(
+                        "the planned crate '{}' is not a member of the workspace",
+                        planned.name
+                    )
+                })?;
+            let changelog = member.changelog();
+
+            let changelog_link = match changelog {
+                Some(changelog) => {
+                    let path = path_relative_to(changelog.path(), &self.root_path)
+                        .unwrap_or_else(|| changelog.path().to_path_buf());
+                    // the release heading is going to be added by the release
+                    let anchor = planned
+                        .next_version
+                        .as_ref()
+                        .map(|version| format!("#{}", github_heading_anchor(version)))
+                        .unwrap_or_default();
+
+                    format!("[CHANGELOG]({}{})", normalized_path(&path), anchor)
+                }
+                None => "-".to_string(),
+            };
+
+            table += &format!(
+                "| {} | {} | {} | {} | {} |\n",
+                planned.name,
+                planned.current_version,
+                planned.next_version.as_deref().unwrap_or("skipped"),
+                changelog_link,
+                planned
+                    .allowed_blockers
+                    .iter()
+                    .map(|flag| format!("{:?}", flag))
+                    .collect::<Vec<_>>()
+                    .join(", "),
+            );
+
+            if let Some(next_version) = &planned.next_version {
+                let excerpt = changelog
+                    .map(|changelog| changelog.unreleased_section())
+                    .transpose()?
+                    .flatten()
+                    .filter(|excerpt| !excerpt.is_empty())
+                    .unwrap_or_else(|| "_no changes were recorded in the changelog_".to_string());
+
+                details += &format!(
+                    "\n<details>\n<summary>{} {}</summary>\n\n{}\n\n</details>\n",
+                    planned.name, next_version, excerpt
+                );
+            }
+        }
+
+        Ok(table + &details)
+    }
+
+    /// Returns the planned crates that are going to be published, in the order of the plan.
+    pub fn planned_publish_selection(&'a self, plan: &ReleasePlan) -> Fallible<Vec<&'a Crate<'a>>> {
+        let members = self.members()?;
+
+        Ok(plan
+            .crates
+            .iter()
+            .filter(|planned| planned.publish && planned.next_version.is_some())
+            .filter_map(|planned| members.iter().find(|member| member.name() == planned.name))
+            .copied()
+            .collect())
+    }
+}
+
+/// Computes the release plan and writes it to the output path.
+pub fn cmd(args: &cli::Args, cmd_args: &cli::PlanArgs) -> CommandResult {
+    let ws = crate_selection::ReleaseWorkspace::try_new_with_criteria(
+        args.workspace_path.clone(),
+        cmd_args.check_args.to_selection_criteria(args),
+    )?
+    .with_progress_reporter(args.progress_reporter()?);
+
+    let plan = ws.compute_plan()?;
+    plan.save(&cmd_args.output_path)?;
+
+    info!(
+        "wrote the release plan for {} crates to {}",
+        plan.crates.len(),
+        cmd_args.output_path.display()
+    );
+
+    if let Some(summary_path) = &cmd_args.summary_path {
+        std::fs::write(summary_path, ws.render_release_summary_markdown(&plan)?)?;
+        info!("wrote the release summary to {}", summary_path.display());
+    }
+
+    Ok(())
+}
@@ -0,0 +1,73 @@
+//! Progress reporting for the long-running parts of a release, e.g. the analysis of the workspace members.
+
+use crate::phases::ReleasePhase;
+
+/// Receives progress updates from `ReleaseWorkspace`, see `ReleaseWorkspace::with_progress_reporter`.
+///
+/// All callbacks do nothing by default.
+pub trait ProgressReporter {
+    /// The given phase started and is going to process the given number of crates.
+    fn on_phase_started(&self, _phase: ReleasePhase, _total: usize) {}
+
+    /// The state of the member with the given name was analyzed, with the index counting from 0.
+    fn on_member_analyzed(&self, _name: &str, _index: usize, _total: usize) {}
+
+    /// The crate with the given name was processed by the given phase, with the index counting from 0.
+    fn on_crate_processed(&self, _phase: ReleasePhase, _name: &str, _index: usize, _total: usize) {}
+
+    fn on_phase_finished(&self, _phase: ReleasePhase) {}
+}
+
+/// Ignores all progress updates.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct NoopProgressReporter;
+
+impl ProgressReporter for NoopProgressReporter {}
+
+/// Draws a progress bar for each phase to stderr.
+#[cfg(feature = "progress-bar")]
+#[derive(Default)]
+pub struct ProgressBarReporter {
+    bar: std::cell::RefCell<Option<indicatif::ProgressBar>>,
+}
+
+#[cfg(feature = "progress-bar")]
+impl ProgressBarReporter {
+    fn advance(&self, name: &str, index: usize) {
+        if let Some(bar) = self.bar.borrow().as_ref() {
+            bar.set_message(name.to_string());
+            bar.set_position(index as u64 + 1);
+        }
+    }
+}
+
+#[cfg(feature = "progress-bar")]
+impl ProgressReporter for ProgressBarReporter {
+    fn on_phase_started(&self, phase: ReleasePhase, total: usize) {
+        let bar = indicatif::ProgressBar::new(total as u64);
+        if let Ok(style) =
+            indicatif::ProgressStyle::with_template("{prefix:>12} [{bar:40}] {pos}/{len} {msg}")
+        {
+            bar.set_style(style.progress_chars("=> "));
+        }
+        bar.set_prefix(format!("{:?}", phase));
+
+        if let Some(previous) = self.bar.replace(Some(bar)) {
+            previous.finish_and_clear();
+        }
+    }
+
+    fn on_member_analyzed(&self, name: &str, index: usize, _total: usize) {
+        self.advance(name, index);
+    }
+
+    fn on_crate_processed(&self, _phase: ReleasePhase, name: &str, index: usize, _total: usize) {
+        self.advance(name, index);
+    }
+
+    fn on_phase_finished(&self, _phase: ReleasePhase) {
+        if let Some(bar) = self.bar.take() {
+            bar.finish_and_clear();
+        }
+    }
+}
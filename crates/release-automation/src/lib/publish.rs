@@ -0,0 +1,463 @@
+//! Publishing a release, i.e. pushing the release branch and tags to the remote and publishing the crates to the registry.
+
+use crate::crate_selection::{
+    first_error_line, Crate, OwnershipReport, PublishOptions, PublishReport, ReleaseWorkspace,
+};
+use crate::crates_index::CRATES_IO_REGISTRY_NAME;
+use crate::phases::ReleasePhase;
+use crate::rollback::{JournalEntry, PublishOutcome, ReleaseJournal};
+use crate::Fallible;
+use anyhow::{anyhow, bail, Context};
+use regex::Regex;
+use semver::Version;
+use std::cell::{Cell, RefCell};
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+use std::process::Command;
+use tracing::{debug, info, warn};
+
+impl<'a> ReleaseWorkspace<'a> {
+    /// Push the given refspecs to the remote.
+    ///
+    /// Credentials are taken from the ssh-agent for ssh remotes and from the
+    /// `RELEASE_AUTOMATION_GIT_TOKEN` environment variable for https remotes.
+    /// Fails with a list of all refspecs which were rejected by the remote.
+    pub fn git_push(&self, remote_name: &str, refspecs: &[String]) -> Fallible<()> {
+        let mut remote = self
+            .git_repo
+            .find_remote(remote_name)
+            .context(format!("looking up remote '{}'", remote_name))?;
+
+        let rejected = RefCell::new(Vec::<String>::new());
+        let credential_attempts = Cell::new(0usize);
+
+        let mut callbacks = git2::RemoteCallbacks::new();
+        callbacks.credentials(|url, username_from_url, allowed_types| {
+            // libgit2 keeps asking for credentials as long as they're rejected
+            credential_attempts.set(credential_attempts.get() + 1);
+            if credential_attempts.get() > 1 {
+                return Err(git2::Error::from_str(&format!(
+                    "authentication for {} failed",
+                    url
+                )));
+            }
+
+            if allowed_types.contains(git2::CredentialType::SSH_KEY) {
+                git2::Cred::ssh_key_from_agent(username_from_url.unwrap_or("git"))
+            } else if allowed_types.contains(git2::CredentialType::USER_PASS_PLAINTEXT) {
+                let token = std::env::var(Self::GIT_TOKEN_ENV_VAR).map_err(|_| {
+                    git2::Error::from_str(&format!(
+                        "{} must be set to authenticate to {}",
+                        Self::GIT_TOKEN_ENV_VAR,
+                        url
+                    ))
+                })?;
+                git2::Cred::userpass_plaintext("x-access-token", &token)
+            } else {
+                git2::Cred::default()
+            }
+        });
+        callbacks.push_update_reference(|refname, status| {
+            if let Some(message) = status {
+                rejected
+                    .borrow_mut()
+                    .push(format!("{}: {}", refname, message));
+            }
+            Ok(())
+        });
+
+        let mut push_options = git2::PushOptions::new();
+        push_options.remote_callbacks(callbacks);
+
+        debug!("pushing {:?} to remote '{}'", refspecs, remote_name);
+        remote
+            .push(refspecs, Some(&mut push_options))
+            .context(format!("pushing to remote '{}'", remote_name))?;
+        drop(push_options);
+
+        let rejected = rejected.into_inner();
+        if !rejected.is_empty() {
+            bail!(
+                "remote '{}' rejected {} of {} refspec(s):\n{}",
+                remote_name,
+                rejected.len(),
+                refspecs.len(),
+                rejected
+                    .iter()
+                    .map(|rejection| format!("- {}\n", rejection))
+                    .collect::<String>()
+            );
+        }
+
+        Ok(())
+    }
+
+    /// Push the current branch and all tags that were created via this workspace instance to the remote.
+    ///
+    /// Returns the refspecs which were pushed, or would have been pushed in case of `dry_run`.
+    pub fn push_release_artifacts(
+        &'a self,
+        remote_name: &str,
+        dry_run: bool,
+    ) -> Fallible<Vec<String>> {
+        let branch_name = self.git_head_branch_name_required()?;
+
+        let refspecs = std::iter::once(format!("refs/heads/{0}:refs/heads/{0}", branch_name))
+            .chain(
+                self.created_tags()
+                    .iter()
+                    .map(|tag| format!("refs/tags/{0}:refs/tags/{0}", tag)),
+            )
+            .collect::<Vec<_>>();
+
+        if dry_run {
+            info!(
+                "[dry-run] would push the following to remote '{}':{}",
+                remote_name,
+                refspecs
+                    .iter()
+                    .map(|refspec| format!("\n- {}", refspec))
+                    .collect::<String>()
+            );
+        } else {
+            self.git_push(remote_name, &refspecs)?;
+        }
+
+        Ok(refspecs)
+    }
+
+    /// Publishes the given crates in the order of their dependencies.
+    ///
+    /// Transient failures are retried with an exponential backoff, and the publishes are paced to stay within the rate limit.
+    /// Every attempt is recorded in the release journal, and crates that were published by a previous run with the same journal are skipped.
+    pub fn publish(
+        &'a self,
+        selection: &[&'a Crate<'a>],
+        opts: PublishOptions,
+    ) -> Fallible<PublishReport> {
+        self.publish_versions(
+            &selection
+                .iter()
+                .map(|crt| (*crt, crt.version()))
+                .collect::<Vec<_>>(),
+            opts,
+        )
+    }
+
+    /// Like `publish` for the given versions of the crates, which are expected in their manifests
+    /// but may differ from the ones the workspace was read with.
+    pub(crate) fn publish_versions(
+        &'a self,
+        selection: &[(&'a Crate<'a>, Version)],
+        opts: PublishOptions,
+    ) -> Fallible<PublishReport> {
+        self.journal_begin()?;
+
+        let published_previously = if self.journal_enabled {
+            ReleaseJournal::load(&self.root_path)?
+                .map(|journal| journal.published_versions())
+                .unwrap_or_default()
+        } else {
+            Default::default()
+        };
+
+        let selected_versions = selection
+            .iter()
+            .map(|(crt, version)| (crt.name(), version.clone()))
+            .collect::<HashMap<_, _>>();
+
+        let mut report = PublishReport::default();
+
+        let ordered_selection = self
+            .members()?
+            .iter()
+            .filter_map(|member| Some((*member, selected_versions.get(&member.name())?)))
+            .collect::<Vec<_>>();
+        let total = ordered_selection.len();
+        self.progress_reporter
+            .on_phase_started(ReleasePhase::Publish, total);
+
+        for (index, (crt, next_version)) in ordered_selection.into_iter().enumerate() {
+            let version = next_version.to_string();
+            let name_version = format!("{}-{}", crt.name(), version);
+
+            if published_previously.contains(&(crt.name(), version.clone())) {
+                info!("{} was published by a previous run, skipping", name_version);
+                report.skipped.push(name_version.clone());
+                self.progress_reporter.on_crate_processed(
+                    ReleasePhase::Publish,
+                    &crt.name(),
+                    index,
+                    total,
+                );
+                continue;
+            }
+
+            if report.published.len() >= opts.rate_limit_burst {
+                info!(
+                    "waiting {:?} before publishing {} to stay within the rate limit",
+                    opts.rate_limit_interval, name_version
+                );
+                self.command_runner.sleep(opts.rate_limit_interval);
+            }
+
+            let mut attempt = 0;
+            loop {
+                attempt += 1;
+
+                let mut cmd = Command::new("cargo");
+                cmd.current_dir(self.root())
+                    .args(["publish", "--locked", "--registry", self.registry()])
+                    .arg(format!(
+                        "--manifest-path={}",
+                        crt.manifest_path().to_string_lossy()
+                    ));
+                if opts.no_verify.contains(&crt.name()) {
+                    cmd.arg("--no-verify");
+                }
+                if opts.allow_dirty {
+                    cmd.arg("--allow-dirty");
+                }
+                if let Some(target_dir) = &opts.cargo_target_dir {
+                    cmd.arg(format!("--target-dir={}", target_dir.to_string_lossy()));
+                }
+                debug!("running command: {:?}", cmd);
+
+                let output = self.command_runner.run(&mut cmd)?;
+                let outcome = if output.success {
+                    PublishOutcome::Published
+                } else if output.stderr.contains("is already uploaded") {
+                    PublishOutcome::AlreadyPublished
+                } else if attempt <= opts.max_retries
+                    && is_transient_publish_failure(&output.stderr)
+                {
+                    PublishOutcome::TransientFailure {
+                        error: first_error_line(&output.stderr),
+                    }
+                } else {
+                    PublishOutcome::Failed {
+                        error: first_error_line(&output.stderr),
+                    }
+                };
+
+                self.journal_record(JournalEntry::Publish {
+                    name: crt.name(),
+                    version: version.clone(),
+                    attempt,
+                    outcome: outcome.clone(),
+                })?;
+
+                match outcome {
+                    PublishOutcome::Published => {
+                        info!("successfully published {}", name_version);
+                        report.published.push(name_version.clone());
+                        break;
+                    }
+                    PublishOutcome::AlreadyPublished => {
+                        warn!("{} is already published, skipping", name_version);
+                        report.skipped.push(name_version.clone());
+                        break;
+                    }
+                    PublishOutcome::TransientFailure { error } => {
+                        let delay = opts.retry_delay * 2u32.pow(attempt as u32 - 1);
+                        warn!(
+                            "publishing {} failed with '{}', retrying in {:?} ({}/{})",
+                            name_version, error, delay, attempt, opts.max_retries
+                        );
+                        self.command_runner.sleep(delay);
+                    }
+                    PublishOutcome::Failed { error } => bail!(
+                        "publishing {} failed after {} attempt(s): {}",
+                        name_version,
+                        attempt,
+                        error
+                    ),
+                }
+            }
+
+            if let Some(timeout) = opts.index_availability_timeout {
+                self.wait_for_index_availability(&crt.name(), next_version, timeout)?;
+            }
+
+            self.progress_reporter.on_crate_processed(
+                ReleasePhase::Publish,
+                &crt.name(),
+                index,
+                total,
+            );
+        }
+        self.progress_reporter
+            .on_phase_finished(ReleasePhase::Publish);
+
+        Ok(report)
+    }
+
+    /// Runs `cargo publish --dry-run` for each crate of the release selection in their release order
+    /// and fails with a summary of all crates for which it failed.
+    ///
+    /// Failures caused by a dependency on a workspace member that isn't published yet are only warned about.
+    pub fn verify_publishable(&'a self, cargo_target_dir: &Option<PathBuf>) -> Fallible<()> {
+        self.verify_crates_publishable(
+            &self.release_selection()?,
+            cargo_target_dir,
+            &HashSet::new(),
+        )
+    }
+
+    /// Like `verify_publishable` for the given crates, which are expected in their release order.
+    /// Failures caused by one of the allowed missing dependencies are only warned about as well.
+    pub fn verify_crates_publishable(
+        &'a self,
+        crates: &[&'a Crate<'a>],
+        cargo_target_dir: &Option<PathBuf>,
+        allowed_missing_dependencies: &HashSet<String>,
+    ) -> Fallible<()> {
+        let member_names = self
+            .members()?
+            .iter()
+            .map(|crt| crt.name())
+            .collect::<HashSet<_>>();
+
+        let mut failures = vec![];
+
+        for crt in crates {
+            let mut cmd = Command::new("cargo");
+            cmd.current_dir(self.root())
+                .args(["publish", "--dry-run", "--no-verify", "--allow-dirty"])
+                .args(["--registry", self.registry()])
+                .arg(format!(
+                    "--manifest-path={}",
+                    crt.manifest_path().to_string_lossy()
+                ));
+            if let Some(target_dir) = cargo_target_dir {
+                cmd.arg(format!("--target-dir={}", target_dir.to_string_lossy()));
+            }
+            debug!("running command: {:?}", cmd);
+
+            let output = self.command_runner.run(&mut cmd)?;
+            if output.success {
+                debug!("[{}] cargo publish --dry-run succeeded", crt.name());
+                continue;
+            }
+
+            let stderr = output.stderr;
+            match unpublished_dependency(&stderr) {
+                Some(dependency) if member_names.contains(&dependency) => warn!(
+                    "[{}] cargo publish --dry-run failed because its dependency '{}' is a workspace member that's not published yet",
+                    crt.name(),
+                    dependency
+                ),
+                Some(dependency) if allowed_missing_dependencies.contains(&dependency) => warn!(
+                    "[{}] cargo publish --dry-run failed because its dependency '{}' is missing, which is allowed",
+                    crt.name(),
+                    dependency
+                ),
+                _ => failures.push(format!(
+                    "\n- {}: {}",
+                    crt.name(),
+                    first_error_line(&stderr)
+                )),
+            }
+        }
+
+        if !failures.is_empty() {
+            bail!(
+                "cargo publish --dry-run failed for the following crates: {}",
+                failures.concat()
+            );
+        }
+
+        Ok(())
+    }
+
+    /// Looks up the owners of every crate in the release selection on crates.io and fails with a summary
+    /// of all crates that aren't owned by the expected owner, or whose owners couldn't be looked up.
+    ///
+    /// Crates that don't exist on crates.io yet will be owned by whoever publishes them first and are reported as such.
+    pub fn verify_ownership(&'a self) -> Fallible<OwnershipReport> {
+        let expected_owner = self
+            .criteria
+            .expected_owner
+            .as_ref()
+            .ok_or_else(|| anyhow!("no expected crate owner configured"))?;
+
+        if self.registry() != CRATES_IO_REGISTRY_NAME {
+            bail!(
+                "crate ownership can only be verified on crates.io, not on the registry '{}'",
+                self.registry()
+            );
+        }
+
+        let mut report = OwnershipReport::default();
+        let mut failures = vec![];
+
+        for crt in self.release_selection()? {
+            match crate::crates_index::crate_owners(self.http_client.as_ref(), &crt.name()) {
+                Ok(None) => {
+                    info!("[{}] first publish, not on crates.io yet", crt.name());
+                    report.first_publish.push(crt.name());
+                }
+                Ok(Some(owners)) if owners.iter().any(|owner| &owner.login == expected_owner) => {
+                    debug!("[{}] owned by {}", crt.name(), expected_owner);
+                    report.verified.push(crt.name());
+                }
+                Ok(Some(owners)) => failures.push(format!(
+                    "\n- {}: not owned by {}, owners: {}",
+                    crt.name(),
+                    expected_owner,
+                    owners
+                        .iter()
+                        .map(|owner| owner.login.as_str())
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                )),
+                Err(err) => failures.push(format!(
+                    "\n- {}: unknown ownership, looking up the owners failed: {}",
+                    crt.name(),
+                    err
+                )),
+            }
+        }
+
+        if !failures.is_empty() {
+            bail!(
+                "the ownership of the following crates couldn't be verified: {}",
+                failures.concat()
+            );
+        }
+
+        Ok(report)
+    }
+}
+
+/// Returns the name of the dependency that cargo couldn't find in the registry according to its output.
+pub(crate) fn unpublished_dependency(cargo_output: &str) -> Option<String> {
+    static UNPUBLISHED_DEPENDENCY_RE: once_cell::sync::OnceCell<Regex> =
+        once_cell::sync::OnceCell::new();
+
+    let captures = UNPUBLISHED_DEPENDENCY_RE
+        .get_or_init(|| {
+            Regex::new(
+                r#"no matching package named `(?P<name>[^`]+)` found|failed to select a version for the requirement `(?P<name_req>[^ `]+) = "#,
+            )
+            .expect("regex should compile")
+        })
+        .captures(cargo_output)?;
+    captures
+        .name("name")
+        .or_else(|| captures.name("name_req"))
+        .map(|name| name.as_str().to_string())
+}
+
+/// Whether cargo's output indicates a failure that may succeed when retried, like a server error or a timeout.
+pub(crate) fn is_transient_publish_failure(cargo_output: &str) -> bool {
+    static TRANSIENT_FAILURE_RE: once_cell::sync::OnceCell<Regex> =
+        once_cell::sync::OnceCell::new();
+
+    TRANSIENT_FAILURE_RE
+        .get_or_init(|| {
+            Regex::new(r"(?i)(got 5\d\d|status 5\d\d|\b5\d\d (Internal Server Error|Bad Gateway|Service Unavailable|Gateway Timeout)|timed out|timeout|connection reset)")
+                .expect("regex should compile")
+        })
+        .is_match(cargo_output)
+}
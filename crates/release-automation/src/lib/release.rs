@@ -5,19 +5,18 @@ use super::*;
 use anyhow::bail;
 use anyhow::Context;
 use bstr::ByteSlice;
-use cargo::util::VersionExt;
 use chrono::TimeZone;
 use chrono::Utc;
 use cli::ReleaseArgs;
 use comrak::{format_commonmark, parse_document, Arena, ComrakOptions};
 use enumflags2::{bitflags, BitFlags};
-use log::{debug, error, info, trace, warn};
 use once_cell::sync::OnceCell;
 use std::convert::TryInto;
 use std::iter::FromIterator;
 use std::path::Path;
 use std::sync::Arc;
 use std::sync::Mutex;
+use std::time::Duration;
 use std::{
     collections::HashMap,
     io::{Read, Write},
@@ -27,22 +26,27 @@ use std::{
     path::PathBuf,
 };
 use structopt::StructOpt;
+use tracing::{debug, error, error_span, info, trace, warn};
 
 use crate::{
-    changelog::{Changelog, WorkspaceCrateReleaseHeading},
-    common::{increment_semver, SemverIncrementMode},
+    changelog::Changelog,
     crate_::ensure_crate_io_owners,
-    crate_selection::{ensure_release_order_consistency, Crate},
+    crate_selection::{
+        BranchPolicy, Crate, ExistingTagPolicy, PublishOptions, ReleaseTagsReport, TagKind,
+    },
+    phases::{ReleasePhase, ReleasePhaseOptions},
+    plan::{CrateReleaseProgress, ReleasePlan},
 };
 pub use crate_selection::{ReleaseWorkspace, SelectionCriteria};
 
-const TARGET_DIR_SUFFIX: &str = "target/release_automation";
-
 /// These steps make up the release workflow
 #[bitflags]
 #[repr(u64)]
 #[derive(enum_utils::FromStr, Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
 pub enum ReleaseSteps {
+    /// tag the previous releases that were reconciled via `--reconcile-untagged-releases`
+    /// at the commits that set their versions
+    CreateMissingReleaseTags,
     /// create a new release branch based on develop
     CreateReleaseBranch,
     /// substeps: get crate selection, bump cargo toml versions, rotate
@@ -62,14 +66,53 @@ pub enum ReleaseSteps {
 ///
 /// For now it is manual and the release phases need to be given as an instruction.
 pub fn cmd(args: &crate::cli::Args, cmd_args: &crate::cli::ReleaseArgs) -> CommandResult {
+    {
+        let ws = ReleaseWorkspace::try_new_with_criteria(
+            args.workspace_path.clone(),
+            cmd_args.check_args.to_selection_criteria(args),
+        )?;
+
+        // local changes would end up in the release
+        ws.ensure_clean_worktree()?;
+        ws.ensure_allowed_branch()?;
+
+        // a crate that can't be published would leave the release half-finished
+        if cmd_args.check_args.expected_crate_owner.is_some()
+            && cmd_args.steps.contains(&ReleaseSteps::PublishToCratesIo)
+        {
+            let report = ws.verify_ownership()?;
+            if !report.first_publish.is_empty() {
+                info!(
+                    "the following crates will be published for the first time: {:?}",
+                    report.first_publish
+                );
+            }
+        }
+
+        // keep the plan of the run so it can be resumed if it's interrupted
+        if cmd_args.steps.contains(&ReleaseSteps::BumpReleaseVersions)
+            && !cmd_args.dry_run
+            && ReleasePlan::load_state(&args.workspace_path)?.is_none()
+        {
+            ws.compute_plan()?.save_state(&args.workspace_path)?;
+        }
+    }
+
     for step in &cmd_args.steps {
+        let _span = error_span!("step", step = ?step).entered();
         trace!("Processing step '{:?}'", step);
 
         // read the workspace after every step in case it was mutated
         let ws = ReleaseWorkspace::try_new_with_criteria(
             args.workspace_path.clone(),
             cmd_args.check_args.to_selection_criteria(args),
-        )?;
+        )?
+        .with_git_options(cmd_args.to_git_options())
+        .with_progress_reporter(args.progress_reporter()?)
+        .with_journal();
+
+        // fail early if signing was requested but isn't possible
+        ws.git_signing_key()?;
 
         macro_rules! _skip_on_empty_selection {
             ($step:expr, $body:expr) => {
@@ -82,8 +125,11 @@ pub fn cmd(args: &crate::cli::Args, cmd_args: &crate::cli::ReleaseArgs) -> Comma
         }
 
         match step {
+            ReleaseSteps::CreateMissingReleaseTags => {
+                ws.create_missing_release_tags(cmd_args.dry_run)?;
+            }
             ReleaseSteps::CreateReleaseBranch => create_release_branch(&ws, cmd_args)?,
-            ReleaseSteps::BumpReleaseVersions => bump_release_versions(&ws, cmd_args)?,
+            ReleaseSteps::BumpReleaseVersions => bump_release_versions(&ws, cmd_args, None)?,
             ReleaseSteps::PublishToCratesIo => publish_to_crates_io(&ws, cmd_args)?,
             ReleaseSteps::AddOwnersToCratesIo => ensure_crate_io_owners(
                 &ws,
@@ -94,9 +140,140 @@ pub fn cmd(args: &crate::cli::Args, cmd_args: &crate::cli::ReleaseArgs) -> Comma
         }
     }
 
+    // the run succeeded so there's nothing to roll back anymore
+    crate::rollback::ReleaseJournal::remove(&args.workspace_path)?;
+    ReleasePlan::remove_state(&args.workspace_path)?;
+
+    Ok(())
+}
+
+/// Continues an interrupted release run from the plan and the journal it left behind.
+///
+/// The given steps are processed like in `cmd`, but the work that the plan's progress shows as done is skipped.
+/// As the selection is taken from the plan, crates that look changed because of the interrupted run are not selected in addition.
+pub fn resume(args: &crate::cli::Args, cmd_args: &crate::cli::ReleaseArgs) -> CommandResult {
+    let plan = ReleasePlan::load_state(&args.workspace_path)?.ok_or_else(|| {
+        anyhow::anyhow!(
+            "no release plan found at {}, there's no release run to resume",
+            ReleasePlan::state_path(&args.workspace_path).display()
+        )
+    })?;
+
+    for step in &cmd_args.steps {
+        let _span = error_span!("step", step = ?step).entered();
+        trace!("Resuming step '{:?}'", step);
+
+        let ws = ReleaseWorkspace::try_new_with_criteria(
+            args.workspace_path.clone(),
+            cmd_args.check_args.to_selection_criteria(args),
+        )?
+        .with_git_options(cmd_args.to_git_options())
+        .with_progress_reporter(args.progress_reporter()?)
+        .with_journal();
+
+        ws.ensure_clean_worktree()?;
+        ws.verify_plan(&plan)?;
+        let progress = ws.release_progress(&plan)?;
+
+        match step {
+            // the tags that were created by the interrupted run aren't missing anymore
+            ReleaseSteps::CreateMissingReleaseTags => {
+                ws.create_missing_release_tags(cmd_args.dry_run)?;
+            }
+            ReleaseSteps::CreateReleaseBranch => match ensure_release_branch(&ws) {
+                Ok(branch_name) => info!(
+                    "already on the release branch '{}', skipping its creation",
+                    branch_name
+                ),
+                Err(_) => create_release_branch(&ws, cmd_args)?,
+            },
+            ReleaseSteps::BumpReleaseVersions if progress.iter().all(|crt| crt.committed) => {
+                info!("the planned versions are already committed, skipping the version bumps");
+                resume_release_tags(&ws, &progress, cmd_args)?;
+
+                if let Some(remote_name) = &cmd_args.push_to_remote {
+                    ws.push_release_artifacts(remote_name, cmd_args.dry_run)?;
+                }
+            }
+            ReleaseSteps::BumpReleaseVersions if progress.iter().any(|crt| crt.committed) => bail!(
+                "only some of the planned versions are committed, the release can't be resumed. missing versions: {}",
+                progress
+                    .iter()
+                    .filter(|crt| !crt.committed)
+                    .map(|crt| format!("\n- {}-{}", crt.name, crt.version))
+                    .collect::<String>()
+            ),
+            ReleaseSteps::BumpReleaseVersions => {
+                // nothing was committed, so the selection at HEAD must still be the planned one
+                if ws.compute_plan()?.crates != plan.crates {
+                    bail!(
+                        "the release selection differs from the plan, the release can't be resumed"
+                    );
+                }
+
+                bump_release_versions(&ws, cmd_args, Some(plan.clone()))?
+            }
+            // the crates that were published by the interrupted run are skipped according to the journal
+            ReleaseSteps::PublishToCratesIo => {
+                publish_crates(&ws, &ws.planned_publish_selection(&plan)?, cmd_args)?
+            }
+            ReleaseSteps::AddOwnersToCratesIo => ensure_crate_io_owners(
+                &ws,
+                cmd_args.dry_run,
+                &ws.planned_publish_selection(&plan)?,
+                &cmd_args.minimum_crate_owners,
+            )?,
+        }
+    }
+
+    crate::rollback::ReleaseJournal::remove(&args.workspace_path)?;
+    ReleasePlan::remove_state(&args.workspace_path)?;
+
     Ok(())
 }
 
+/// Creates the planned release tags that are missing, e.g. because the run was interrupted after the release commit.
+fn resume_release_tags<'a>(
+    ws: &'a ReleaseWorkspace<'a>,
+    progress: &[CrateReleaseProgress],
+    cmd_args: &'a ReleaseArgs,
+) -> Fallible<()> {
+    if cmd_args.no_tag_creation {
+        return Ok(());
+    }
+
+    let mut tags_to_create = vec![];
+    for crt in progress {
+        if crt.tagged {
+            info!(
+                "the release tag '{}' already exists, skipping it",
+                crt.tag_name
+            );
+            continue;
+        }
+
+        let tag_kind = if cmd_args.lightweight_tags {
+            TagKind::Lightweight
+        } else {
+            let changelog_section = match ws
+                .members()?
+                .iter()
+                .find(|member| member.name() == crt.name)
+                .and_then(|member| member.changelog())
+            {
+                Some(changelog) => changelog.release_section(&crt.version)?,
+                None => None,
+            };
+
+            release_tag_kind(&crt.tag_name, changelog_section)
+        };
+
+        tags_to_create.push((crt.tag_name.clone(), tag_kind));
+    }
+
+    create_crate_tags(ws, tags_to_create, cmd_args)
+}
+
 pub const RELEASE_BRANCH_PREFIX: &str = "release-";
 
 /// Generate a time-derived name for a new release branch.
@@ -113,45 +290,44 @@ pub fn create_release_branch<'a>(
     ws: &'a ReleaseWorkspace<'a>,
     cmd_args: &ReleaseArgs,
 ) -> Fallible<()> {
-    match ws.git_head_branch_name()?.as_str() {
+    let release_branch_name = cmd_args
+        .release_branch_name
+        .to_owned()
+        .unwrap_or_else(generate_release_branch_name);
+
+    // an existing release branch is resumed from wherever it's currently at
+    let resuming = cmd_args.release_branch_policy == BranchPolicy::ReuseExisting
+        && ws
+            .git_repo()
+            .find_branch(&release_branch_name, git2::BranchType::Local)
+            .is_ok();
+
+    match ws.git_head_branch_name_required()?.as_str() {
         "develop" => {
             // we're good to continue!
         }
-        _ if cmd_args.force_branch_creation => {}
+        _ if cmd_args.force_branch_creation || resuming => {}
         other => bail!(
             "only support releasing from the 'develop' branch, but found '{}'",
             other
         ),
     };
 
-    let statuses = ws
-        .git_repo()
-        .statuses(Some(git2::StatusOptions::new().include_untracked(true)))
-        .context("querying repository status")?;
-    if !statuses.is_empty() && !cmd_args.force_branch_creation {
-        bail!(
-            "repository is not clean. {} change(s): \n{}",
-            statuses.len(),
-            statuses
-                .iter()
-                .map(|statusentry| format!(
-                    "{:?}: {}\n",
-                    statusentry.status(),
-                    statusentry.path().unwrap_or_default()
-                ))
-                .collect::<String>()
-        )
-    };
-
-    let release_branch_name = cmd_args
-        .release_branch_name
-        .to_owned()
-        .unwrap_or_else(generate_release_branch_name);
+    if !cmd_args.force_branch_creation {
+        ws.ensure_clean_worktree()?;
+    }
 
     if cmd_args.dry_run {
-        info!("[dry-run] would create branch '{}'", release_branch_name);
+        info!(
+            "[dry-run] would create branch '{}' with policy {:?}",
+            release_branch_name, cmd_args.release_branch_policy
+        );
     } else {
-        ws.git_checkout_new_branch(&release_branch_name)?;
+        ws.git_checkout_new_branch_with_policy(
+            &release_branch_name,
+            cmd_args.release_branch_policy,
+            false,
+        )?;
 
         ensure_release_branch(ws)?;
     }
@@ -159,9 +335,12 @@ pub fn create_release_branch<'a>(
     Ok(())
 }
 
+/// Runs the release phases of the library from the version bumps up to the release tags with the given plan,
+/// or with a plan that's computed from the workspace.
 fn bump_release_versions<'a>(
     ws: &'a ReleaseWorkspace<'a>,
     cmd_args: &'a ReleaseArgs,
+    plan: Option<ReleasePlan>,
 ) -> Fallible<()> {
     let branch_name = match ensure_release_branch(ws) {
         Ok(branch_name) => branch_name,
@@ -170,7 +349,6 @@ fn bump_release_versions<'a>(
     };
 
     // check the workspace and determine the release selection
-    // todo: double-check that we select matching cratese that had their dependencies change
     let selection = crate::common::selection_check(&cmd_args.check_args, ws)?;
 
     if selection.is_empty() {
@@ -178,230 +356,189 @@ fn bump_release_versions<'a>(
         return Ok(());
     }
 
+    let plan = match plan {
+        Some(plan) => plan,
+        None => ws.compute_plan()?,
+    };
+
+    let release_name = branch_name
+        .strip_prefix(RELEASE_BRANCH_PREFIX)
+        .ok_or_else(|| {
+            anyhow::anyhow!(
+                "expected branch name to start with prefix '{}'. got instead: {}",
+                RELEASE_BRANCH_PREFIX,
+                branch_name,
+            )
+        })?
+        .to_string();
+    let options = release_phase_options(cmd_args, &release_name);
+
     // run the checks to ensure the repo is in a consistent state to begin with
     if !cmd_args.no_verify && !cmd_args.no_verify_pre {
         info!("running consistency checks before changing the versions...");
-        do_publish_to_crates_io(
-            &selection,
-            true,
-            true,
-            &cmd_args.allowed_missing_dependencies,
-            &cmd_args.cargo_target_dir,
+        ws.verify_planned_release(
+            &plan,
+            &options.cargo_target_dir,
+            &options.allowed_missing_dependencies,
         )
         .context("consistency checks failed")?;
     }
 
-    let mut changed_crate_changelogs = vec![];
-
-    for crt in &selection {
-        let current_version = crt.version();
-        let changelog = crt.changelog().ok_or_else(|| {
-            anyhow::anyhow!(
-                "[{}] cannot determine most recent release: missing changelog",
-                crt.name()
-            )
-        })?;
-
-        let maybe_previous_release_version = changelog
-            .topmost_release()?
-            .map(|change| semver::Version::parse(change.title()))
-            .transpose()
-            .context(format!(
-                "parsing {:#?} in {:#?} as a semantic version",
-                changelog.topmost_release(),
-                changelog.path(),
-            ))?;
-
-        let maybe_semver_increment_mode = changelog
-            .front_matter()?
-            .map(|fm| fm.semver_increment_mode());
-        let semver_increment_mode = maybe_semver_increment_mode.unwrap_or_default();
-
-        let release_version = if let Some(mut previous_release_version) =
-            maybe_previous_release_version.clone()
-        {
-            if previous_release_version > current_version {
-                bail!("previously documented release version '{}' is greater than this release version '{}'", previous_release_version, current_version);
-            }
-
-            increment_semver(&mut previous_release_version, semver_increment_mode)?;
-
-            previous_release_version
-        } else {
-            // release the current version, or bump if the current version is a pre-release
-            let mut new_version = current_version.clone();
-
-            if new_version.is_prerelease() {
-                increment_semver(&mut new_version, semver_increment_mode)?;
-            }
-
-            new_version
-        };
-
-        trace!(
-            "[{}] previous release version: '{:?}', current version: '{}', release version: '{}' ",
-            crt.name(),
-            maybe_previous_release_version,
-            current_version,
-            release_version,
+    if cmd_args.dry_run {
+        info!(
+            "[dry-run] would create release {} with the following crates: {}",
+            release_name,
+            plan.version_bumps()?
+                .iter()
+                .map(|(name, (current_version, next_version))| format!(
+                    "\n- {}: {} -> {}",
+                    name, current_version, next_version
+                ))
+                .collect::<String>()
         );
 
-        let greater_release = release_version > current_version;
-        if greater_release {
-            crt.set_version(cmd_args.dry_run, &release_version.clone())?;
-        }
-
-        let crate_release_heading_name = format!("{}", release_version);
-
-        if maybe_previous_release_version.is_none() || greater_release {
-            // create a new release entry in the crate's changelog and move all items from the unreleased heading if there are any
-
-            debug!(
-                "[{}] creating crate release heading '{}' in '{:?}'",
-                crt.name(),
-                crate_release_heading_name,
-                changelog.path(),
-            );
-
-            if !cmd_args.dry_run {
-                changelog
-                    .add_release(crate_release_heading_name.clone())
-                    .context(format!("adding release to changelog for '{}'", crt.name()))?;
-
-                // FIXME: now we should reread the whole thing?
-
-                if greater_release {
-                    // rewrite frontmatter to reset it to its defaults
-                    changelog.reset_front_matter_to_defaults()?;
-                }
-            }
-
-            changed_crate_changelogs.push(WorkspaceCrateReleaseHeading {
-                prefix: crt.name(),
-                suffix: crate_release_heading_name,
-                changelog,
-            });
-        }
+        return Ok(());
     }
 
-    ws.update_lockfile(
-        cmd_args.dry_run,
-        cmd_args.additional_manifests.iter().map(|mp| mp.as_str()),
+    let mut results = ws.run_release_phases(
+        ReleasePhase::VersionBump | ReleasePhase::ManifestApply | ReleasePhase::ChangelogApply,
+        Some(plan),
+        &options,
     )?;
 
-    /* TODO: the workspace probably needs to be re-read here because otherwise the publish dry-run will assume the previous crate versions
-     * either this or something else is leading to this issue where the verify_post checks aren't effective
-     *
-     * > [INFO  release_automation::lib::common] crates selected for the release process: [
-     * >         "holochain_cli-0.1.0-a-minor-release-test.2",
-     * >     ]
-     * > [DEBUG release_automation::lib::crate_selection] setting version to 0.1.0-a-minor-release-test.3 in manifest at "/home/steveej/src/holo/holochain/crates/hc/Cargo.toml"
-     * > [DEBUG release_automation::lib::release] [holochain_cli] creating crate release heading '0.1.0-a-minor-release-test.3' in '"/home/steveej/src/holo/holochain/crates/hc/CHANGELOG.md"'
-     * > [DEBUG release_automation::lib::crate_selection] running command: "cargo" "fetch" "--verbose" "--manifest-path" "Cargo.toml"
-     * > [DEBUG release_automation::lib::crate_selection] running command: "cargo" "update" "--workspace" "--offline" "--verbose"
-     * > [DEBUG release_automation::lib::crate_selection] running command: "cargo" "fetch" "--verbose" "--manifest-path" "crates/test_utils/wasm/wasm_workspace/Cargo.toml"
-     * >     Blocking waiting for file lock on package cache
-     * >     Blocking waiting for file lock on package cache
-     * > [DEBUG release_automation::lib::crate_selection] running command: "cargo" "update" "--workspace" "--offline" "--verbose" "--manifest-path" "crates/test_utils/wasm/wasm_workspace/Cargo.toml"
-     * >     Blocking waiting for file lock on package cache
-     * > [INFO  release_automation::lib::release] running consistency checks after changing the versions...
-     * > [DEBUG release_automation::lib::release] attempting to publish {"holochain_cli"}
-     * > [DEBUG release_automation::lib::release] holochain_cli-0.1.0-a-minor-release-test.2 is unchanged and already published, skipping..
-     * > [DEBUG release_automation::lib::release] Running command: "cargo" "check" "--locked" "--verbose" "--release" "--manifest-path=/home/steveej/src/holo/holochain/crates/hc/Cargo.toml"
-     * > [DEBUG release_automation::lib::release] Running command: "cargo" "publish" "--locked" "--verbose" "--no-verify" "--manifest-path=/home/steveej/src/holo/holochain/crates/hc/Cargo.toml" "--dry-run" "--allow-dirty"
-     * > [INFO  release_automation::lib::release] successfully published holochain_cli-0.1.0-a-minor-release-test.2
-     * > [INFO  release_automation::lib::release] crates processed: 1, consistent: 1, published: 1, skipped: 1, tolerated: 0
-     *
-     * the above shouldn't have looked up .2 but rather .3, which it wouldn't have found
-     */
+    // the lockfile of the workspace is refreshed by the manifest phase, the additional ones are refreshed here
+    let mut release_paths = results.modified_files();
+    if !cmd_args.additional_manifests.is_empty() {
+        ws.update_lockfile(
+            false,
+            cmd_args.additional_manifests.iter().map(|mp| mp.as_str()),
+        )?;
+        release_paths.extend(cmd_args.additional_manifests.iter().filter_map(|mp| {
+            Path::new(mp)
+                .parent()
+                .map(|parent| ws.root().join(parent).join("Cargo.lock"))
+        }));
+    }
 
     if !cmd_args.no_verify && !cmd_args.no_verify_post {
         info!("running consistency checks after changing the versions...");
-        do_publish_to_crates_io(
-            &selection,
-            true,
-            true,
-            &cmd_args.allowed_missing_dependencies,
-            &cmd_args.cargo_target_dir,
-        )
-        .context("cargo publish dry-run failed")?;
-    }
-
-    // ## for the workspace release:
-    let workspace_release_name = branch_name
-        .strip_prefix(RELEASE_BRANCH_PREFIX)
-        .ok_or_else(|| {
-            anyhow::anyhow!(
-                "expected branch name to start with prefix '{}'. got instead: {}",
-                RELEASE_BRANCH_PREFIX,
-                branch_name,
+        results.verify = Some(
+            ws.verify_planned_release(
+                &results.plan,
+                &options.cargo_target_dir,
+                &options.allowed_missing_dependencies,
             )
-        })?
-        .to_string();
-
-    let ws_changelog = ws
-        .changelog()
-        .ok_or_else(|| anyhow::anyhow!("workspace has no changelog"))?;
-
-    info!(
-        "adding release {} to changelog at {:?} with the following crate releases: {}",
-        workspace_release_name,
-        ws_changelog.path(),
-        changed_crate_changelogs
-            .iter()
-            .map(|cr| format!("\n- {}", cr.title()))
-            .collect::<String>()
-    );
-
-    if !cmd_args.dry_run {
-        ws_changelog.add_release(workspace_release_name, &changed_crate_changelogs)?;
+            .context("cargo publish dry-run failed")?,
+        );
     }
 
-    // create a release commit with an overview of which crates are included
-    let commit_msg = indoc::formatdoc!(
-        r#"
-        create a release from branch {}
+    results.commit = Some(ws.commit_planned_release(&results.plan, &release_paths)?);
 
-        the following crates are part of this release:
-        {}
-        "#,
-        branch_name,
-        changed_crate_changelogs
-            .iter()
-            .map(|wcrh| format!("\n- {}", wcrh.title()))
-            .collect::<String>()
-    );
+    if !cmd_args.no_tag_creation {
+        // catch manifest problems while the release is still reversible
+        if !cmd_args.no_verify && results.verify.is_none() {
+            info!("verifying that the selected crates are publishable before creating the tags...");
+            results.verify = Some(ws.verify_planned_release(
+                &results.plan,
+                &options.cargo_target_dir,
+                &options.allowed_missing_dependencies,
+            )?);
+        }
 
-    info!("creating the following commit: {}", commit_msg);
-    if !cmd_args.dry_run {
-        ws.git_add_all_and_commit(&commit_msg, None)?;
-    };
+        let report = ws.tag_planned_release(
+            &results.plan,
+            options.existing_tag_policy,
+            options.lightweight_tags,
+        )?;
+        log_created_tags(&report, false);
+
+        // mark the release commit as a whole once all crate tags are in place
+        if let Some(template) = &cmd_args.workspace_release_tag_template {
+            let workspace_tag = workspace_release_tag(
+                template,
+                &release_name,
+                &chrono::Utc::now().format("%Y%m%d.%H%M%S").to_string(),
+                &results
+                    .changelog_apply
+                    .as_ref()
+                    .map(|result| result.crate_releases.clone())
+                    .unwrap_or_default(),
+                cmd_args.lightweight_tags,
+            );
+            create_crate_tags(ws, vec![workspace_tag], cmd_args)?;
+        }
+    }
 
-    if !cmd_args.no_tag_creation {
-        // create tags for all released crates
-        let tags_to_create = changed_crate_changelogs
-            .iter()
-            .map(|wcrh| wcrh.title())
-            .collect::<Vec<String>>();
-        create_crate_tags(ws, tags_to_create, cmd_args)?;
+    if let Some(remote_name) = &cmd_args.push_to_remote {
+        ws.push_release_artifacts(remote_name, cmd_args.dry_run)?;
     }
 
     Ok(())
 }
 
+/// Boilerplate to instantiate the `ReleasePhaseOptions` for the given release from `ReleaseArgs`.
+fn release_phase_options(cmd_args: &ReleaseArgs, release_name: &str) -> ReleasePhaseOptions {
+    ReleasePhaseOptions {
+        release_name: release_name.to_string(),
+        existing_tag_policy: existing_tag_policy(cmd_args),
+        lightweight_tags: cmd_args.lightweight_tags,
+        empty_unreleased_policy: cmd_args.empty_unreleased_policy,
+        cargo_target_dir: cmd_args.cargo_target_dir.clone(),
+        allowed_missing_dependencies: cmd_args.allowed_missing_dependencies.clone(),
+        ..Default::default()
+    }
+}
+
 pub fn publish_to_crates_io<'a>(
     ws: &'a ReleaseWorkspace<'a>,
     cmd_args: &'a ReleaseArgs,
 ) -> Fallible<()> {
-    let crates = latest_release_crates(ws)?;
+    publish_crates(ws, &latest_release_crates(ws)?, cmd_args)
+}
 
-    do_publish_to_crates_io(
-        &crates,
-        cmd_args.dry_run,
-        false,
-        &Default::default(),
-        &cmd_args.cargo_target_dir,
+fn publish_crates<'a>(
+    ws: &'a ReleaseWorkspace<'a>,
+    crates: &[&'a Crate<'a>],
+    cmd_args: &'a ReleaseArgs,
+) -> Fallible<()> {
+    if cmd_args.dry_run {
+        info!(
+            "[dry-run] verifying that the following crates are publishable: {}",
+            crates
+                .iter()
+                .map(|crt| format!("\n- {}", crt.name_version()))
+                .collect::<String>()
+        );
+        ws.verify_crates_publishable(
+            crates,
+            &cmd_args.cargo_target_dir,
+            &cmd_args.allowed_missing_dependencies,
+        )?;
+
+        return Ok(());
+    }
+
+    let report = ws.publish(
+        crates,
+        PublishOptions {
+            // none of the steps builds the crates, so cargo verifies them unless that's skipped explicitly
+            no_verify: if cmd_args.no_verify {
+                crates.iter().map(|crt| crt.name()).collect()
+            } else {
+                Default::default()
+            },
+            cargo_target_dir: cmd_args.cargo_target_dir.clone(),
+            max_retries: cmd_args.publish_max_retries,
+            index_availability_timeout: Some(Duration::from_secs(
+                cmd_args.index_availability_timeout,
+            )),
+            ..Default::default()
+        },
     )?;
+    info!(
+        "published: {:?}, skipped: {:?}",
+        report.published, report.skipped
+    );
 
     Ok(())
 }
@@ -653,269 +790,102 @@ impl PublishError {
     }
 }
 
-/// Try to publish the given crates to crates.io.
-///
-/// If dry-run is given, the following error conditoins are tolerated:
-/// - a dependency is not found but is part of the release
-/// - a version of a dependency is not found bu the dependency is part of the release
-///
-/// For this to work properly all changed crates need to have their dev versions applied.
-/// If they don't, `cargo publish` will prefer a published crates to the local ones.
-pub fn do_publish_to_crates_io<'a>(
-    crates: &[&'a Crate<'a>],
-    dry_run: bool,
-    allow_dirty: bool,
-    allowed_missing_dependencies: &HashSet<String>,
-    cargo_target_dir: &Option<PathBuf>,
-) -> Fallible<()> {
-    ensure_release_order_consistency(&crates)?;
-
-    let crate_names: HashSet<String> = crates.iter().map(|crt| crt.name()).collect();
-
-    debug!("attempting to publish {:?}", crate_names);
-
-    let mut queue = crates.iter().collect::<std::collections::LinkedList<_>>();
-    let mut errors: Vec<PublishError> = vec![];
-
-    let mut check_cntr = 0;
-    let mut publish_cntr = 0;
-    let mut tolerated_cntr = 0;
-    let mut skip_cntr = 0;
-    let do_return =
-        |errors: Vec<PublishError>, check_cntr, publish_cntr, skip_cntr, tolerated_cntr| {
-            let msg = format!(
-                "crates processed: {}, consistent: {}, published: {}, skipped: {}, tolerated: {}",
-                crates.len(),
-                check_cntr,
-                publish_cntr,
-                skip_cntr,
-                tolerated_cntr,
-            );
-
-            info!("{}", msg);
-
-            if !errors.is_empty() {
-                let mut root = anyhow::anyhow!(msg);
-                for error in errors.into_iter().rev() {
-                    root = root.context(error);
-                }
-                Err(root)
-            } else {
-                Ok(())
-            }
-        };
-
-    let mut published_or_tolerated = linked_hash_set::LinkedHashSet::new();
-
-    let mut publish_cntr_inc = |name: &str| {
-        info!("successfully published {}", name);
-        publish_cntr += 1;
-    };
-
-    while let Some(crt) = queue.pop_front() {
-        let state_changed = crt.state().changed();
-
-        let name = crt.name().to_owned();
-        let ver = crt.version().to_owned();
-
-        let is_version_published = crates_index_helper::is_version_published(&name, &ver, false)?;
-
-        if !state_changed && is_version_published {
-            debug!(
-                "{} is unchanged and already published, skipping..",
-                crt.name_version()
-            );
-            skip_cntr += 1;
-        }
-
-        let manifest_path = crt.manifest_path();
-        let cargo_target_dir_string = cargo_target_dir
-            .as_ref()
-            .map(|target_dir| format!("--target-dir={}", target_dir.to_string_lossy()));
-
-        let mut cmd = std::process::Command::new("cargo");
-        cmd.args(
-            [
-                vec![
-                    "check",
-                    "--locked",
-                    "--verbose",
-                    "--release",
-                    &format!("--manifest-path={}", manifest_path.to_string_lossy()),
-                ],
-                if let Some(target_dir) = cargo_target_dir_string.as_ref() {
-                    vec![target_dir]
-                } else {
-                    vec![]
-                },
-            ]
-            .concat(),
-        );
-        debug!("Running command: {:?}", cmd);
-        let output = cmd.output().context("process exitted unsuccessfully")?;
-        if !output.status.success() {
-            let mut details = String::new();
-            for line in output.stderr.lines_with_terminator() {
-                let line = line.to_str_lossy();
-                details += &line;
-            }
-
-            let error = PublishError::CheckFailure {
-                package: crt.name(),
-                version: crt.version().to_string(),
-                log: details,
-            };
-            errors.push(error);
-        } else {
-            check_cntr += 1;
-        }
-
-        let mut cmd = std::process::Command::new("cargo");
-        cmd.args(
-            [
-                vec![
-                    "publish",
-                    "--locked",
-                    "--verbose",
-                    "--no-verify",
-                    &format!("--manifest-path={}", manifest_path.to_string_lossy()),
-                ],
-                if dry_run { vec!["--dry-run"] } else { vec![] },
-                if allow_dirty {
-                    vec!["--allow-dirty"]
-                } else {
-                    vec![]
-                },
-                if let Some(target_dir) = cargo_target_dir_string.as_ref() {
-                    vec![target_dir]
-                } else {
-                    vec![]
-                },
-            ]
-            .concat(),
-        );
-
-        debug!("Running command: {:?}", cmd);
-
-        let output = cmd.output().context("process exitted unsuccessfully")?;
-        if !output.status.success() {
-            let mut details = String::new();
-            for line in output.stderr.lines_with_terminator() {
-                let line = line.to_str_lossy();
-                details += &line;
-            }
-
-            let error = PublishError::with_str(crt.name(), crt.version().to_string(), details);
-
-            if match &error {
-                PublishError::Other(..) => true,
-                PublishError::PackageNotFound { dependency, .. }
-                | PublishError::PackageVersionNotFound { dependency, .. } => {
-                    !((dry_run
-                        && crate_names.contains(dependency)
-                        && published_or_tolerated.contains(dependency))
-                        || allowed_missing_dependencies.contains(dependency))
-                }
-                PublishError::AlreadyUploaded { version, .. } => {
-                    crt.version().to_string() != *version
-                }
-                PublishError::PublishLimitExceeded { retry_after, .. } => {
-                    let wait = *retry_after - chrono::offset::Utc::now();
-                    warn!("waiting for {:?} to adhere to the rate limit...", wait);
-                    std::thread::sleep(wait.to_std()?);
-                    queue.push_front(crt);
-                    continue;
-                }
-                PublishError::CheckFailure { .. } => true,
-            } {
-                error!("{}", error);
-                errors.push(error);
-            } else {
-                tolerated_cntr += 1;
-                debug!("tolerating error: '{:#?}'", &error);
-
-                published_or_tolerated.insert(crt.name());
-            }
-        } else if dry_run {
-            publish_cntr_inc(&crt.name_version());
-            published_or_tolerated.insert(crt.name());
-        } else {
-            // wait until the published version is live
-
-            let mut found = false;
-
-            for delay_secs in &[56, 28, 14, 7, 14, 28, 56] {
-                let duration = std::time::Duration::from_secs(*delay_secs);
-                std::thread::sleep(duration);
-
-                if crates_index_helper::is_version_published(&crt.name(), &crt.version(), true)? {
-                    debug!(
-                        "Found recently published {} on crates.io!",
-                        crt.name_version()
-                    );
-                    found = true;
-                    break;
-                }
-
-                warn!(
-                    "Did not find {} on crates.io, retrying in {:?}...",
-                    crt.name_version(),
-                    duration
-                );
-            }
-
-            if !found {
-                errors.push(PublishError::Other(
-                    crt.name_version(),
-                    "recently published version not found in time on the crates_io index"
-                        .to_string(),
-                ));
-
-                return do_return(errors, check_cntr, publish_cntr, skip_cntr, tolerated_cntr);
-            }
+/// Constructs an annotated tag whose message contains the changelog section of the release if there is one.
+pub(crate) fn release_tag_kind(tag_name: &str, changelog_section: Option<String>) -> TagKind {
+    match changelog_section.filter(|section| !section.is_empty()) {
+        Some(section) => TagKind::Annotated {
+            message: format!("tag for release {}\n\n{}", tag_name, section),
+        },
+        None => TagKind::default_annotated(tag_name),
+    }
+}
 
-            publish_cntr_inc(&crt.name_version());
-            published_or_tolerated.insert(crt.name());
+/// Constructs the tag for the workspace release by replacing `{release}` and `{timestamp}` in the template.
+/// Unless it's lightweight its message lists all released crates.
+fn workspace_release_tag(
+    template: &str,
+    workspace_release_name: &str,
+    timestamp: &str,
+    released_crates: &[String],
+    lightweight: bool,
+) -> (String, TagKind) {
+    let tag_name = template
+        .replace("{release}", workspace_release_name)
+        .replace("{timestamp}", timestamp);
+
+    let tag_kind = if lightweight {
+        TagKind::Lightweight
+    } else {
+        TagKind::Annotated {
+            message: indoc::formatdoc!(
+                r#"
+                tag for workspace release {}
+
+                the following crates are part of this release:
+                {}
+                "#,
+                workspace_release_name,
+                released_crates
+                    .iter()
+                    .map(|title| format!("\n- {}", title))
+                    .collect::<String>()
+            ),
         }
-    }
+    };
 
-    do_return(errors, check_cntr, publish_cntr, skip_cntr, tolerated_cntr)
+    (tag_name, tag_kind)
 }
 
 /// create a tag for each crate which will be used to identify its latest release
 fn create_crate_tags<'a>(
     ws: &'a ReleaseWorkspace<'a>,
-    tags_to_create: Vec<String>,
+    tags_to_create: Vec<(String, TagKind)>,
     cmd_args: &'a ReleaseArgs,
 ) -> Fallible<()> {
-    let existing_tags = tags_to_create
-        .iter()
-        .filter_map(|git_tag| crate::crate_selection::git_lookup_tag(ws.git_repo(), git_tag))
-        .collect::<Vec<_>>();
+    let report = ws.create_release_tags(
+        tags_to_create,
+        existing_tag_policy(cmd_args),
+        cmd_args.dry_run,
+    )?;
+    log_created_tags(&report, cmd_args.dry_run);
+
+    Ok(())
+}
 
-    if !cmd_args.force_tag_creation && !existing_tags.is_empty() {
-        error!(
-            "the following tags already exist: {}",
-            existing_tags
+/// `--force-tag-creation` is a shorthand for `--existing-tag-policy=Force`.
+fn existing_tag_policy(cmd_args: &ReleaseArgs) -> ExistingTagPolicy {
+    if cmd_args.force_tag_creation {
+        ExistingTagPolicy::Force
+    } else {
+        cmd_args.existing_tag_policy
+    }
+}
+
+fn log_created_tags(report: &ReleaseTagsReport, dry_run: bool) {
+    info!(
+        "{}created tags: {}",
+        if dry_run { "[dry-run] " } else { "" },
+        report
+            .created
+            .iter()
+            .map(|tag| format!("\n- {}", tag))
+            .collect::<String>()
+    );
+    if !report.skipped.is_empty() {
+        info!(
+            "skipped already existing tags: {}",
+            report
+                .skipped
                 .iter()
                 .map(|tag| format!("\n- {}", tag))
                 .collect::<String>()
-        )
-    }
-
-    for git_tag in tags_to_create {
-        debug!("creating tag '{}'", git_tag);
-        if !cmd_args.dry_run {
-            ws.git_tag(&git_tag, cmd_args.force_tag_creation)?;
-        }
+        );
     }
-
-    Ok(())
 }
 
 /// Ensure we're on a branch that starts with `Self::RELEASE_BRANCH_PREFIX`
 pub fn ensure_release_branch<'a>(ws: &'a ReleaseWorkspace<'a>) -> Fallible<String> {
-    let branch_name = ws.git_head_branch_name()?;
+    let branch_name = ws.git_head_branch_name_required()?;
     if !branch_name.starts_with(RELEASE_BRANCH_PREFIX) {
         bail!(
             "expected branch name with prefix '{}', got '{}'",
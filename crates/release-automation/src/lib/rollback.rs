@@ -0,0 +1,354 @@
+//! Rollback command functionality and the release journal it's based on.
+
+use super::*;
+
+use crate::crate_selection::{git_lookup_tag, lock_cache, ReleaseWorkspace};
+use crate::plan::ReleasePlan;
+use linked_hash_map::LinkedHashMap;
+use linked_hash_set::LinkedHashSet;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// Directory within the workspace root where state between release runs is kept.
+pub const STATE_DIR: &str = ".release-automation";
+const JOURNAL_FILENAME: &str = "journal.json";
+
+/// Records the mutating git operations of a release run so they can be rolled back.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReleaseJournal {
+    /// The reference HEAD pointed to before the run, e.g. `refs/heads/develop`, or the commit id if it was detached.
+    pub original_head: String,
+    /// Tags that existed before the run.
+    pub preexisting_tags: BTreeSet<String>,
+    /// The recorded operations in the order they were performed.
+    pub entries: Vec<JournalEntry>,
+}
+
+/// A single mutating git operation.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "operation", rename_all = "snake_case")]
+pub enum JournalEntry {
+    /// A branch was created, or recreated in which case its previous target is known.
+    Branch {
+        name: String,
+        previous_target: Option<String>,
+    },
+    /// A commit was created on top of the parent and the reference was moved to it.
+    Commit {
+        reference: Option<String>,
+        oid: String,
+        parent: Option<String>,
+    },
+    /// A tag was created.
+    Tag { name: String, oid: String },
+    /// A crate version was attempted to be published, which can't be rolled back.
+    Publish {
+        name: String,
+        version: String,
+        attempt: usize,
+        outcome: PublishOutcome,
+    },
+}
+
+/// The result of a single `cargo publish` attempt.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "result", rename_all = "snake_case")]
+pub enum PublishOutcome {
+    Published,
+    /// The registry already had this version.
+    AlreadyPublished,
+    /// Failed in a way that is worth retrying, e.g. a server error or a timeout.
+    TransientFailure {
+        error: String,
+    },
+    Failed {
+        error: String,
+    },
+}
+
+impl ReleaseJournal {
+    /// Location of the journal for the workspace at the given root.
+    pub fn path(root: &Path) -> PathBuf {
+        root.join(STATE_DIR).join(JOURNAL_FILENAME)
+    }
+
+    /// Load the journal for the workspace at the given root if there is one.
+    pub fn load(root: &Path) -> Fallible<Option<Self>> {
+        let path = Self::path(root);
+        if !path.exists() {
+            return Ok(None);
+        }
+
+        let journal = serde_json::from_str(&common::load_from_file(&path)?)
+            .context(format!("parsing release journal at {}", path.display()))?;
+
+        Ok(Some(journal))
+    }
+
+    /// Persist the journal for the workspace at the given root.
+    pub fn save(&self, root: &Path) -> Fallible<()> {
+        ensure_state_dir(root)?;
+        std::fs::write(Self::path(root), serde_json::to_string_pretty(self)?)?;
+
+        Ok(())
+    }
+
+    /// The name of the release branch that was created by the run, if any.
+    pub fn release_branch(&self) -> Option<&str> {
+        self.entries.iter().find_map(|entry| match entry {
+            JournalEntry::Branch { name, .. } => Some(name.as_str()),
+            _ => None,
+        })
+    }
+
+    /// Whether the given commit was created by the run.
+    pub fn contains_commit(&self, oid: &str) -> bool {
+        self.entries.iter().any(|entry| {
+            matches!(entry, JournalEntry::Commit { oid: committed, .. } if committed == oid)
+        })
+    }
+
+    /// The crate names and versions that were published according to the journal.
+    pub fn published_versions(&self) -> HashSet<(String, String)> {
+        self.entries
+            .iter()
+            .filter_map(|entry| match entry {
+                JournalEntry::Publish {
+                    name,
+                    version,
+                    outcome: PublishOutcome::Published | PublishOutcome::AlreadyPublished,
+                    ..
+                } => Some((name.clone(), version.clone())),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Remove the journal of the workspace at the given root if there is one.
+    pub fn remove(root: &Path) -> Fallible<()> {
+        let path = Self::path(root);
+        if path.exists() {
+            std::fs::remove_file(path)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Creates the state directory of the workspace at the given root if it doesn't exist yet.
+pub(crate) fn ensure_state_dir(root: &Path) -> Fallible<PathBuf> {
+    let dir = root.join(STATE_DIR);
+    std::fs::create_dir_all(&dir)?;
+
+    // keep the state out of commits which add all files
+    let gitignore = dir.join(".gitignore");
+    if !gitignore.exists() {
+        std::fs::write(gitignore, "*\n")?;
+    }
+
+    Ok(dir)
+}
+
+impl<'a> ReleaseWorkspace<'a> {
+    /// Starts a new release journal unless journaling is disabled or there's one already.
+    ///
+    /// Needs to be called before the first mutating operation to capture the state before the release run.
+    pub(crate) fn journal_begin(&self) -> Fallible<()> {
+        if !self.journal_enabled || ReleaseJournal::path(&self.root_path).exists() {
+            return Ok(());
+        }
+
+        let head = self.git_repo.find_reference("HEAD")?;
+        let original_head = match head.symbolic_target() {
+            Some(target) => target.to_string(),
+            None => head
+                .target()
+                .ok_or_else(|| anyhow::anyhow!("HEAD doesn't have a target"))?
+                .to_string(),
+        };
+
+        ReleaseJournal {
+            original_head,
+            preexisting_tags: self
+                .git_repo
+                .tag_names(None)?
+                .iter()
+                .flatten()
+                .map(String::from)
+                .collect(),
+            entries: Default::default(),
+        }
+        .save(&self.root_path)
+    }
+
+    /// Appends the entry to the release journal if journaling is enabled.
+    pub(crate) fn journal_record(&self, entry: JournalEntry) -> Fallible<()> {
+        if !self.journal_enabled {
+            return Ok(());
+        }
+
+        let mut journal = ReleaseJournal::load(&self.root_path)?
+            .ok_or_else(|| anyhow::anyhow!("release journal hasn't been started"))?;
+        trace!("recording {:?}", entry);
+        journal.entries.push(entry);
+        journal.save(&self.root_path)
+    }
+
+    /// Undoes the operations recorded in the release journal and restores HEAD to where it was before the release run.
+    ///
+    /// Created branches are deleted or reset to their previous target, branches that were committed to are reset to their previous commit,
+    /// and created tags are deleted, except for tags that existed before the run.
+    /// Local modifications in the working tree are discarded.
+    pub fn rollback(&self, dry_run: bool) -> Fallible<()> {
+        let journal = ReleaseJournal::load(&self.root_path)?.ok_or_else(|| {
+            anyhow::anyhow!(
+                "no release journal found at {}, there's nothing to roll back",
+                ReleaseJournal::path(&self.root_path).display()
+            )
+        })?;
+
+        // the earliest recorded state of each reference is the one to restore
+        let mut created_branches = LinkedHashMap::<String, Option<git2::Oid>>::new();
+        let mut committed_references = LinkedHashMap::<String, Option<git2::Oid>>::new();
+        let mut created_tags = LinkedHashSet::<String>::new();
+        for entry in &journal.entries {
+            match entry {
+                JournalEntry::Branch {
+                    name,
+                    previous_target,
+                } => {
+                    if !created_branches.contains_key(name) {
+                        created_branches.insert(
+                            name.clone(),
+                            previous_target
+                                .as_deref()
+                                .map(git2::Oid::from_str)
+                                .transpose()?,
+                        );
+                    }
+                }
+                JournalEntry::Commit {
+                    reference: Some(reference),
+                    parent,
+                    ..
+                } => {
+                    if !committed_references.contains_key(reference) {
+                        committed_references.insert(
+                            reference.clone(),
+                            parent.as_deref().map(git2::Oid::from_str).transpose()?,
+                        );
+                    }
+                }
+                JournalEntry::Commit {
+                    reference: None, ..
+                } => {}
+                JournalEntry::Tag { name, .. } => {
+                    created_tags.insert(name.clone());
+                }
+                JournalEntry::Publish {
+                    name,
+                    version,
+                    outcome: PublishOutcome::Published,
+                    ..
+                } => warn!(
+                    "{}-{} was published and can't be rolled back",
+                    name, version
+                ),
+                JournalEntry::Publish { .. } => {}
+            }
+        }
+
+        macro_rules! step {
+            ($description:expr, $body:expr) => {
+                if dry_run {
+                    info!("[dry-run] would {}", $description);
+                } else {
+                    info!("{}", $description);
+                    $body;
+                }
+            };
+        }
+
+        for (reference, parent) in committed_references.iter().filter(|(reference, _)| {
+            !created_branches.contains_key(reference.trim_start_matches("refs/heads/"))
+        }) {
+            match parent {
+                Some(parent) => step!(
+                    format!("reset {} to {}", reference, parent),
+                    self.git_repo
+                        .reference(reference, *parent, true, "release rollback")?
+                ),
+                None => step!(
+                    format!("delete {}", reference),
+                    self.git_repo.find_reference(reference)?.delete()?
+                ),
+            }
+        }
+
+        step!(format!("restore HEAD to {}", journal.original_head), {
+            if journal.original_head.starts_with("refs/") {
+                self.git_repo.set_head(&journal.original_head)?;
+            } else {
+                self.git_repo
+                    .set_head_detached(git2::Oid::from_str(&journal.original_head)?)?;
+            }
+            if self.git_repo.head().is_ok() {
+                self.git_repo
+                    .checkout_head(Some(git2::build::CheckoutBuilder::new().force()))?;
+            }
+        });
+
+        for (name, previous_target) in &created_branches {
+            match previous_target {
+                Some(previous_target) => step!(
+                    format!("reset branch '{}' to {}", name, previous_target),
+                    self.git_repo.reference(
+                        &format!("refs/heads/{}", name),
+                        *previous_target,
+                        true,
+                        "release rollback"
+                    )?
+                ),
+                None => step!(
+                    format!("delete branch '{}'", name),
+                    self.git_repo
+                        .find_branch(name, git2::BranchType::Local)?
+                        .delete()?
+                ),
+            }
+        }
+
+        for name in &created_tags {
+            if journal.preexisting_tags.contains(name) {
+                warn!(
+                    "refusing to delete tag '{}' because it existed before the release run",
+                    name
+                );
+                continue;
+            }
+
+            if git_lookup_tag(&self.git_repo, name).is_some() {
+                step!(
+                    format!("delete tag '{}'", name),
+                    self.git_repo.tag_delete(name)?
+                );
+            }
+        }
+
+        if !dry_run {
+            *lock_cache(&self.tag_index) = None;
+            lock_cache(&self.created_tags).clear();
+            ReleaseJournal::remove(&self.root_path)?;
+            ReleasePlan::remove_state(&self.root_path)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Undoes the git operations of a failed release run.
+pub fn cmd(args: &cli::Args, cmd_args: &cli::RollbackArgs) -> CommandResult {
+    let ws = crate_selection::ReleaseWorkspace::try_new(args.workspace_path.clone())?;
+
+    ws.rollback(cmd_args.dry_run)
+}
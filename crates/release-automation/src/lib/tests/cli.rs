@@ -6,6 +6,7 @@ use crate::common::SemverIncrementMode;
 use crate::crate_selection::ReleaseWorkspace;
 use crate::tests::workspace_mocker::{
     example_workspace_1, example_workspace_1_aggregated_changelog, example_workspace_4,
+    example_workspace_cascade, example_workspace_inherited_versions,
 };
 use anyhow::Context;
 use predicates::prelude::*;
@@ -16,7 +17,7 @@ use std::io::Write;
 fn release_createreleasebranch() {
     let workspace_mocker = example_workspace_1().unwrap();
     let workspace = ReleaseWorkspace::try_new(workspace_mocker.root()).unwrap();
-    workspace.git_checkout_new_branch("develop").unwrap();
+    workspace.git_checkout_new_branch("develop", false).unwrap();
     let mut cmd = assert_cmd::Command::cargo_bin("release-automation").unwrap();
     let cmd = cmd.args(&[
         &format!("--workspace-path={}", workspace.root().display()),
@@ -46,7 +47,7 @@ fn release_createreleasebranch_fails_on_dirty_repo() {
         },
     );
     let workspace = ReleaseWorkspace::try_new(workspace_mocker.root()).unwrap();
-    workspace.git_checkout_new_branch("develop").unwrap();
+    workspace.git_checkout_new_branch("develop", false).unwrap();
 
     let mut cmd = assert_cmd::Command::cargo_bin("release-automation").unwrap();
     let cmd = cmd.args(&[
@@ -62,7 +63,83 @@ fn release_createreleasebranch_fails_on_dirty_repo() {
 
     cmd.assert()
         .stderr(predicate::str::contains("repository is not clean"))
-        .failure();
+        .code(4);
+}
+
+#[test]
+fn check_exits_with_code_2_on_empty_selection() {
+    let workspace_mocker = example_workspace_1().unwrap();
+
+    let mut cmd = assert_cmd::Command::cargo_bin("release-automation").unwrap();
+    let cmd = cmd.args([
+        &format!("--workspace-path={}", workspace_mocker.root().display()),
+        "--match-filter=^crate_does_not_exist$",
+        "check",
+    ]);
+
+    cmd.assert()
+        .stderr(predicate::str::contains(
+            "no crates are selected for the release",
+        ))
+        .code(2);
+}
+
+#[test]
+fn check_exits_with_code_3_on_blocked_selection() {
+    let workspace_mocker = example_workspace_4().unwrap();
+
+    let mut cmd = assert_cmd::Command::cargo_bin("release-automation").unwrap();
+    let cmd = cmd.args([
+        &format!("--workspace-path={}", workspace_mocker.root().display()),
+        "--match-filter=^no_description$",
+        "check",
+    ]);
+
+    cmd.assert()
+        .stderr(predicate::str::contains(
+            "the following crates are blocked but required for the release",
+        ))
+        .code(3);
+}
+
+#[test]
+fn check_accepts_deprecated_blocker_names() {
+    let workspace_mocker = example_workspace_4().unwrap();
+
+    let mut cmd = assert_cmd::Command::cargo_bin("release-automation").unwrap();
+    let cmd = cmd.args([
+        &format!("--workspace-path={}", workspace_mocker.root().display()),
+        "--match-filter=^no_description$",
+        "check",
+        "--allowed-matched-blockers=MissingDescription",
+    ]);
+
+    // the crate has no changes to release, but it's no longer blocked either
+    cmd.assert()
+        .stderr(predicate::str::contains(
+            "'MissingDescription' is deprecated, use 'MissingPublishMetadata' instead",
+        ))
+        .stderr(predicate::str::contains("blocked but required").not())
+        .code(2);
+}
+
+#[test]
+fn check_json_output() {
+    let workspace_mocker = example_workspace_1().unwrap();
+    let workspace = ReleaseWorkspace::try_new(workspace_mocker.root()).unwrap();
+
+    let mut cmd = assert_cmd::Command::cargo_bin("release-automation").unwrap();
+    let cmd = cmd.args([
+        &format!("--workspace-path={}", workspace.root().display()),
+        "check",
+        "--output-format=json",
+    ]);
+    let output = cmd.output().unwrap();
+
+    let report: crate::crate_selection::StatesReport =
+        serde_json::from_slice(&output.stdout).unwrap();
+
+    assert_eq!(workspace.states_report().unwrap(), report);
 }
 
 #[macro_export]
@@ -117,7 +194,7 @@ fn get_crate_versions<'a>(
 fn bump_versions_on_selection() {
     let workspace_mocker = example_workspace_1().unwrap();
     let workspace = ReleaseWorkspace::try_new(workspace_mocker.root()).unwrap();
-    workspace.git_checkout_new_branch("develop").unwrap();
+    workspace.git_checkout_new_branch("develop", false).unwrap();
 
     let mut cmd = assert_cmd::Command::cargo_bin("release-automation").unwrap();
     let cmd = cmd.args(&[
@@ -357,11 +434,202 @@ fn changelog_aggregation() {
     );
 }
 
+#[test]
+fn bump_versions_creates_workspace_release_tag() {
+    let workspace_mocker = example_workspace_1().unwrap();
+    let workspace = ReleaseWorkspace::try_new(workspace_mocker.root()).unwrap();
+    workspace.git_checkout_new_branch("develop", false).unwrap();
+
+    let mut cmd = assert_cmd::Command::cargo_bin("release-automation").unwrap();
+    let cmd = cmd.args([
+        &format!("--workspace-path={}", workspace.root().display()),
+        "--log-level=trace",
+        "release",
+        &format!(
+            "--cargo-target-dir={}",
+            workspace.root().join("target").display()
+        ),
+        "--disallowed-version-reqs=>=0.2",
+        "--allowed-matched-blockers=UnreleasableViaChangelogFrontmatter,DisallowedVersionReqViolated",
+        "--steps=CreateReleaseBranch,BumpReleaseVersions",
+        "--allowed-missing-dependencies=crate_b",
+        "--workspace-release-tag-template=workspace-{release}",
+    ]);
+
+    let output = assert_cmd_success!(cmd);
+    println!("stderr:\n'{}'\n---\nstdout:\n'{}'\n---", output.0, output.1,);
+
+    let workspace_release_name = workspace
+        .git_head_branch_name()
+        .unwrap()
+        .trim_start_matches(crate::release::RELEASE_BRANCH_PREFIX)
+        .to_string();
+    let expected_tag = format!("workspace-{}", workspace_release_name);
+
+    let repo = workspace.git_repo();
+    let tag_oid = repo
+        .refname_to_id(&format!("refs/tags/{}", expected_tag))
+        .unwrap_or_else(|_| panic!("git tag '{}' not found", &expected_tag));
+    let tag = repo.find_tag(tag_oid).unwrap();
+
+    assert_eq!(
+        repo.head().unwrap().target().unwrap(),
+        tag.target_id(),
+        "the workspace release tag should point at the release commit"
+    );
+
+    assert_eq!(
+        indoc::formatdoc!(
+            r#"
+        tag for workspace release {}
+
+        the following crates are part of this release:
+
+        - crate_b-0.0.0
+        - crate_a-0.1.0
+        - crate_e-0.0.1
+        "#,
+            workspace_release_name
+        )
+        .trim_end(),
+        tag.message().unwrap().trim_end()
+    );
+
+    // the crate tags are created as well
+    for expected_crate_tag in &["crate_b-0.0.0", "crate_a-0.1.0", "crate_e-0.0.1"] {
+        crate::crate_selection::git_lookup_tag(repo, expected_crate_tag)
+            .unwrap_or_else(|| panic!("git tag '{}' not found", expected_crate_tag));
+    }
+}
+
+#[test]
+fn bump_versions_with_inherited_versions() {
+    let workspace_mocker = example_workspace_inherited_versions().unwrap();
+    workspace_mocker.add_or_replace_file("CHANGELOG.md", "# Changelog\n");
+    workspace_mocker.update_lockfile().unwrap();
+    workspace_mocker.commit(None);
+    let workspace = ReleaseWorkspace::try_new(workspace_mocker.root()).unwrap();
+    workspace.git_checkout_new_branch("develop", false).unwrap();
+    let cargo_target_dir = tempfile::tempdir().unwrap();
+
+    let mut cmd = assert_cmd::Command::cargo_bin("release-automation").unwrap();
+    let cmd = cmd.args([
+        &format!("--workspace-path={}", workspace.root().display()),
+        "--log-level=trace",
+        "release",
+        &format!("--cargo-target-dir={}", cargo_target_dir.path().display()),
+        "--no-verify",
+        "--steps=CreateReleaseBranch,BumpReleaseVersions",
+    ]);
+    let output = assert_cmd_success!(cmd);
+    println!("stderr:\n'{}'\n---\nstdout:\n'{}'\n---", output.0, output.1,);
+
+    let read_manifest = |path: &str| -> toml_edit::Document {
+        std::fs::read_to_string(workspace.root().join(path))
+            .unwrap()
+            .parse()
+            .unwrap()
+    };
+
+    // crate_a and crate_b keep sharing the version of the workspace
+    assert_eq!(
+        Some("0.1.1"),
+        read_manifest("Cargo.toml")["workspace"]["package"]["version"].as_str()
+    );
+    let released_workspace = ReleaseWorkspace::try_new(workspace_mocker.root()).unwrap();
+    for crt in released_workspace.members().unwrap() {
+        assert_eq!(
+            crt.name() != "crate_c",
+            crt.inherits_version().unwrap(),
+            "{}",
+            crt.name()
+        );
+    }
+    assert_eq!(
+        Some("0.3.1"),
+        read_manifest("crates/crate_c/Cargo.toml")["package"]["version"].as_str()
+    );
+
+    let commit_msg = workspace
+        .git_repo()
+        .head()
+        .unwrap()
+        .peel_to_commit()
+        .unwrap()
+        .message()
+        .unwrap()
+        .to_string();
+    for expected_crate_release in ["crate_a-0.1.1", "crate_b-0.1.1", "crate_c-0.3.1"] {
+        assert!(
+            commit_msg.contains(expected_crate_release),
+            "{}",
+            commit_msg
+        );
+        crate::crate_selection::git_lookup_tag(workspace.git_repo(), expected_crate_release)
+            .unwrap_or_else(|| panic!("git tag '{}' not found", expected_crate_release));
+    }
+    assert_eq!(Vec::<String>::new(), workspace.git_dirty_paths().unwrap());
+}
+
+#[test]
+fn bump_versions_escalates_cascading_bumps() {
+    let workspace_mocker = example_workspace_cascade().unwrap();
+    ChangelogT::<CrateChangelog>::at_path(
+        &workspace_mocker.root().join("crates/crate_c/CHANGELOG.md"),
+    )
+    .set_front_matter(&serde_yaml::from_str("semver_increment_mode: breaking").unwrap())
+    .unwrap();
+    workspace_mocker.update_lockfile().unwrap();
+    workspace_mocker.commit(None);
+    let workspace = ReleaseWorkspace::try_new(workspace_mocker.root()).unwrap();
+    workspace.git_checkout_new_branch("develop", false).unwrap();
+    let cargo_target_dir = tempfile::tempdir().unwrap();
+
+    let mut cmd = assert_cmd::Command::cargo_bin("release-automation").unwrap();
+    let cmd = cmd.args([
+        &format!("--workspace-path={}", workspace.root().display()),
+        "--log-level=trace",
+        "release",
+        &format!("--cargo-target-dir={}", cargo_target_dir.path().display()),
+        "--no-verify",
+        "--steps=CreateReleaseBranch,BumpReleaseVersions",
+    ]);
+    let output = assert_cmd_success!(cmd);
+    println!("stderr:\n'{}'\n---\nstdout:\n'{}'\n---", output.0, output.1,);
+
+    // the breaking bump of crate_c escalates the bumps of its dependants, except for crate_d which only uses it for its tests
+    assert_eq!(
+        vec!["0.2.0", "0.2.0", "0.2.0", "0.1.1"],
+        get_crate_versions(&["crate_a", "crate_b", "crate_c", "crate_d"], &workspace),
+    );
+
+    for (name, dep_name) in [("crate_a", "crate_b"), ("crate_b", "crate_c")] {
+        assert_eq!(
+            "0.2.0",
+            crate::common::get_dependency_version(
+                &workspace
+                    .root()
+                    .join("crates")
+                    .join(name)
+                    .join("Cargo.toml"),
+                dep_name
+            )
+            .unwrap()
+            .replace('"', "")
+            .trim(),
+            "{}",
+            name
+        );
+    }
+}
+
 #[test]
 fn release_publish() {
     let workspace_mocker = example_workspace_1().unwrap();
     let workspace = ReleaseWorkspace::try_new(workspace_mocker.root()).unwrap();
-    workspace.git_checkout_new_branch("develop").unwrap();
+    workspace.git_checkout_new_branch("develop", false).unwrap();
+    // the repository needs to stay clean between the steps, keep the build artifacts out of it
+    let cargo_target_dir = tempfile::tempdir().unwrap();
 
     // simulate a release
     let mut cmd = assert_cmd::Command::cargo_bin("release-automation").unwrap();
@@ -369,7 +637,7 @@ fn release_publish() {
         &format!("--workspace-path={}", workspace.root().display()),
         "--log-level=trace",
         "release",
-        &format!("--cargo-target-dir={}", workspace.root().join("target").display()),
+        &format!("--cargo-target-dir={}", cargo_target_dir.path().display()),
         "--disallowed-version-reqs=>=0.1",
         "--allowed-matched-blockers=UnreleasableViaChangelogFrontmatter,DisallowedVersionReqViolated",
         "--steps=CreateReleaseBranch,BumpReleaseVersions",
@@ -385,10 +653,7 @@ fn release_publish() {
         "release",
         // todo: set up a custom registry and actually publish the crates
         "--dry-run",
-        &format!(
-            "--cargo-target-dir={}",
-            workspace.root().join("target").display()
-        ),
+        &format!("--cargo-target-dir={}", cargo_target_dir.path().display()),
         "--steps=PublishToCratesIo",
     ]);
     let output = assert_cmd_success!(cmd);
@@ -402,7 +667,7 @@ fn release_publish() {
 fn post_release_version_bumps() {
     let workspace_mocker = example_workspace_1().unwrap();
     let workspace = ReleaseWorkspace::try_new(workspace_mocker.root()).unwrap();
-    workspace.git_checkout_new_branch("develop").unwrap();
+    workspace.git_checkout_new_branch("develop", false).unwrap();
 
     // simulate a release
     let mut cmd = assert_cmd::Command::cargo_bin("release-automation").unwrap();
@@ -492,6 +757,8 @@ fn post_release_version_bumps() {
 #[test]
 fn multiple_subsequent_releases() {
     let workspace_mocker = example_workspace_1().unwrap();
+    // the release commit only contains the release files, keep the build artifacts out of the repository
+    let cargo_target_dir = tempfile::tempdir().unwrap();
     type A = (PathBuf, Vec<String>, Vec<String>);
     type F = Box<dyn Fn(A)>;
 
@@ -547,7 +814,7 @@ fn multiple_subsequent_releases() {
 
                 ReleaseWorkspace::try_new(root)
                     .unwrap()
-                    .git_add_all_and_commit("some chnages", None)
+                    .git_add_all_and_commit("some chnages", None, false)
                     .unwrap();
             }) as F,
         ),
@@ -572,7 +839,7 @@ fn multiple_subsequent_releases() {
 
                 ReleaseWorkspace::try_new(root)
                     .unwrap()
-                    .git_add_all_and_commit("some chnages", None)
+                    .git_add_all_and_commit("some chnages", None, false)
                     .unwrap();
             }) as F,
         ),
@@ -613,7 +880,7 @@ fn multiple_subsequent_releases() {
 
                 ReleaseWorkspace::try_new(root)
                     .unwrap()
-                    .git_add_all_and_commit("some chnages", None)
+                    .git_add_all_and_commit("some chnages", None, false)
                     .unwrap();
             }) as F,
         ),
@@ -638,7 +905,7 @@ fn multiple_subsequent_releases() {
 
                 ReleaseWorkspace::try_new(root)
                     .unwrap()
-                    .git_add_all_and_commit("some chnages", None)
+                    .git_add_all_and_commit("some chnages", None, false)
                     .unwrap();
             }) as F,
         ),
@@ -679,7 +946,7 @@ fn multiple_subsequent_releases() {
 
                 ReleaseWorkspace::try_new(root)
                     .unwrap()
-                    .git_add_all_and_commit("some chnages", None)
+                    .git_add_all_and_commit("some chnages", None, false)
                     .unwrap();
             }) as F,
         ),
@@ -704,7 +971,7 @@ fn multiple_subsequent_releases() {
 
                 ReleaseWorkspace::try_new(root)
                     .unwrap()
-                    .git_add_all_and_commit("some chnages", None)
+                    .git_add_all_and_commit("some chnages", None, false)
                     .unwrap();
             }) as F,
         ),
@@ -749,10 +1016,7 @@ fn multiple_subsequent_releases() {
                 "--log-level=trace",
                 "--match-filter=crate_(a|b|e)",
                 "release",
-                &format!(
-                    "--cargo-target-dir={}",
-                    workspace.root().join("target").display()
-                ),
+                &format!("--cargo-target-dir={}", cargo_target_dir.path().display()),
                 "--allowed-matched-blockers=UnreleasableViaChangelogFrontmatter",
                 "--steps=CreateReleaseBranch,BumpReleaseVersions",
                 &format!(
@@ -879,7 +1143,7 @@ fn apply_dev_versions_works() {
 fn release_dry_run_fails_on_unallowed_conditions() {
     let workspace_mocker = example_workspace_4().unwrap();
     let workspace = ReleaseWorkspace::try_new(workspace_mocker.root()).unwrap();
-    workspace.git_checkout_new_branch("develop").unwrap();
+    workspace.git_checkout_new_branch("develop", false).unwrap();
 
     let members = workspace
         .members()
@@ -905,7 +1169,9 @@ fn release_dry_run_fails_on_unallowed_conditions() {
             .update_lockfile(false, std::iter::empty())
             .unwrap();
 
-        workspace.git_add_all_and_commit("msg", None).unwrap();
+        workspace
+            .git_add_all_and_commit("msg", None, false)
+            .unwrap();
 
         let mut cmd = assert_cmd::Command::cargo_bin("release-automation").unwrap();
         let cmd = cmd.args(&[
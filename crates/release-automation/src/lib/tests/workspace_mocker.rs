@@ -5,9 +5,9 @@ use cargo_test_support::git::{self, Repository};
 use cargo_test_support::{Project, ProjectBuilder};
 use cargo_test_support::paths::init_root;
 use educe::Educe;
-use log::debug;
 use std::collections::HashMap;
 use std::path::PathBuf;
+use tracing::debug;
 
 #[derive(Educe)]
 #[educe(Default)]
@@ -24,6 +24,7 @@ pub struct MockProject {
     pub version: String,
     pub dependencies: Vec<String>,
     pub dev_dependencies: Vec<String>,
+    pub build_dependencies: Vec<String>,
     pub excluded: bool,
     pub ty: MockProjectType,
     pub changelog: Option<String>,
@@ -38,7 +39,32 @@ pub struct MockProject {
     pub description: Option<String>,
     #[educe(Default(expression = r##"Some("Apache-2.0".to_string())"##))]
     pub license: Option<String>,
+    #[educe(Default(
+        expression = r##"Some("https://github.com/holochain/holochain".to_string())"##
+    ))]
+    pub repository: Option<String>,
+    /// Value of the `license-file` key in the manifest.
+    pub license_file: Option<String>,
+    /// Content of the LICENSE file in the crate root.
+    #[educe(Default(expression = r##"Some("Apache License 2.0".to_string())"##))]
+    pub license_text: Option<String>,
     pub keywords: Vec<String>,
+    pub categories: Vec<String>,
+    pub publish: Option<bool>,
+    /// Value of the `rust-version` key in the manifest.
+    pub rust_version: Option<String>,
+    /// Location of the crate relative to the workspace root. Defaults to `crates/{name}`.
+    pub path: Option<String>,
+    /// Content of the `[package.metadata.release-automation]` table.
+    pub release_metadata: Option<String>,
+}
+
+impl MockProject {
+    fn dir(&self) -> String {
+        self.path
+            .clone()
+            .unwrap_or_else(|| format!("crates/{}", self.name))
+    }
 }
 
 pub struct WorkspaceMocker {
@@ -50,6 +76,16 @@ pub struct WorkspaceMocker {
 
 impl WorkspaceMocker {
     pub fn try_new(toplevel_changelog: Option<&str>, projects: Vec<MockProject>) -> Fallible<Self> {
+        Self::try_new_with_release_metadata(toplevel_changelog, None, projects)
+    }
+
+    /// Like `try_new` but additionally writes the given TOML content to the
+    /// `[workspace.metadata.release-automation]` table of the workspace manifest.
+    pub fn try_new_with_release_metadata(
+        toplevel_changelog: Option<&str>,
+        release_metadata: Option<&str>,
+        projects: Vec<MockProject>,
+    ) -> Fallible<Self> {
         init_root(None);
 
         let (path, dir) = {
@@ -72,13 +108,13 @@ impl WorkspaceMocker {
             .map(|project| (project.name.clone(), project))
             .collect::<HashMap<_, _>>();
 
-        let excluded = projects.iter().fold(String::new(), |acc, (name, project)| {
+        let excluded = projects.iter().fold(String::new(), |acc, (_, project)| {
             if project.excluded {
                 acc + indoc::formatdoc!(
                     r#"
-                        "crates/{}",
+                        "{}",
                     "#,
-                    name
+                    project.dir()
                 )
                 .as_str()
             } else {
@@ -86,17 +122,33 @@ impl WorkspaceMocker {
             }
         });
 
+        // crates outside of the default location need to be listed explicitly
+        let additional_members = projects
+            .values()
+            .filter(|project| project.path.is_some() && !project.excluded)
+            .map(|project| format!(r#", "{}""#, project.dir()))
+            .collect::<String>();
+
         let project_builder = ProjectBuilder::new(path).file(
             "Cargo.toml",
             &indoc::formatdoc!(
                 r#"
                 [workspace]
-                members = [ "crates/*" ]
+                members = [ "crates/*"{} ]
                 exclude = [
                     {}
                 ]
+
+                {}
                 "#,
-                excluded
+                additional_members,
+                excluded,
+                release_metadata
+                    .map(|release_metadata| format!(
+                        "[workspace.metadata.release-automation]\n{}",
+                        release_metadata
+                    ))
+                    .unwrap_or_default(),
             ),
         );
 
@@ -126,6 +178,13 @@ impl WorkspaceMocker {
                         },
                     );
 
+                    let build_dependencies = project.build_dependencies.iter().fold(
+                        String::new(),
+                        |build_dependencies, dependency| {
+                            format!("{}{}\n", build_dependencies, dependency)
+                        },
+                    );
+
                     let keywords = project
                         .keywords
                         .iter()
@@ -133,9 +192,16 @@ impl WorkspaceMocker {
                         .collect::<Vec<_>>()
                         .join(",");
 
+                    let categories = project
+                        .categories
+                        .iter()
+                        .map(|category| format!(r#""{}""#, category))
+                        .collect::<Vec<_>>()
+                        .join(",");
+
                     let project_builder = project_builder
                         .file(
-                            format!("crates/{}/Cargo.toml", &name),
+                            format!("{}/Cargo.toml", project.dir()),
                             &indoc::formatdoc!(
                                 r#"
                                 [package]
@@ -144,15 +210,25 @@ impl WorkspaceMocker {
                                 authors = []
                                 {description}
                                 {license}
+                                {repository}
+                                {license_file}
+                                {rust_version}
                                 homepage = "https://github.com/holochain/holochain"
                                 documentation = "https://github.com/holochain/holochain"
                                 keywords = [{keywords}]
+                                categories = [{categories}]
+                                {publish}
 
                                 [dependencies]
                                 {dependencies}
 
                                 [dev-dependencies]
                                 {dev_dependencies}
+
+                                [build-dependencies]
+                                {build_dependencies}
+
+                                {release_metadata}
                                 "#,
                                 &name,
                                 &project.version,
@@ -166,15 +242,44 @@ impl WorkspaceMocker {
                                     .clone()
                                     .map(|d| format!(r#"license = "{}""#, d))
                                     .unwrap_or_default(),
+                                repository = &project
+                                    .repository
+                                    .clone()
+                                    .map(|d| format!(r#"repository = "{}""#, d))
+                                    .unwrap_or_default(),
+                                rust_version = &project
+                                    .rust_version
+                                    .clone()
+                                    .map(|d| format!(r#"rust-version = "{}""#, d))
+                                    .unwrap_or_default(),
+                                license_file = &project
+                                    .license_file
+                                    .clone()
+                                    .map(|d| format!(r#"license-file = "{}""#, d))
+                                    .unwrap_or_default(),
                                 dependencies = dependencies,
                                 dev_dependencies = dev_dependencies,
+                                build_dependencies = build_dependencies,
+                                release_metadata = &project
+                                    .release_metadata
+                                    .as_ref()
+                                    .map(|release_metadata| format!(
+                                        "[package.metadata.release-automation]\n{}",
+                                        release_metadata
+                                    ))
+                                    .unwrap_or_default(),
                                 keywords = keywords,
+                                categories = categories,
+                                publish = &project
+                                    .publish
+                                    .map(|publish| format!("publish = {}", publish))
+                                    .unwrap_or_default(),
                             ),
                         )
                         .file(
                             format!(
-                                "crates/{}/src/{}",
-                                &name,
+                                "{}/src/{}",
+                                project.dir(),
                                 match &project.ty {
                                     Lib => "lib.rs",
                                     Bin => "main.rs",
@@ -187,13 +292,19 @@ impl WorkspaceMocker {
                         );
 
                     let project_builder = if let Some(changelog) = &project.changelog {
-                        project_builder.file(format!("crates/{}/CHANGELOG.md", &name), changelog)
+                        project_builder.file(format!("{}/CHANGELOG.md", project.dir()), changelog)
+                    } else {
+                        project_builder
+                    };
+
+                    let project_builder = if let Some(license_text) = &project.license_text {
+                        project_builder.file(format!("{}/LICENSE", project.dir()), license_text)
                     } else {
                         project_builder
                     };
 
                     if let Some(readme) = &project.readme {
-                        project_builder.file(format!("crates/{}/README.md", &name), readme)
+                        project_builder.file(format!("{}/README.md", project.dir()), readme)
                     } else {
                         project_builder
                     }
@@ -221,6 +332,16 @@ impl WorkspaceMocker {
         self.workspace_project.change_file(path, content);
     }
 
+    pub fn remove_file(&self, path: &str) -> Fallible<()> {
+        std::fs::remove_file(self.root().join(path))?;
+
+        let mut index = self.workspace_repo.index()?;
+        index.remove_path(std::path::Path::new(path))?;
+        index.write()?;
+
+        Ok(())
+    }
+
     pub fn commit(&self, tag: Option<&str>) -> String {
         git::add(&self.workspace_repo);
         let commit = git::commit(&self.workspace_repo).to_string();
@@ -503,8 +624,10 @@ pub fn example_workspace_1<'a>() -> Fallible<WorkspaceMocker> {
         members,
     )?;
 
-    // todo: derive the tag from a function
-    workspace_mocker.tag("crate_a-0.0.1");
+    workspace_mocker.tag(&crate::crate_selection::release_tag_name(
+        "crate_a",
+        &semver::Version::new(0, 0, 1),
+    ));
     workspace_mocker.add_or_replace_file(
         "crates/crate_a/README.md",
         indoc::indoc! {r#"
@@ -779,6 +902,2102 @@ pub fn example_workspace_4<'a>() -> Fallible<WorkspaceMocker> {
     Ok(workspace_mocker)
 }
 
+/// A workspace with a dependency chain that is the reverse of the alphabetical order.
+pub fn example_workspace_chain() -> Fallible<WorkspaceMocker> {
+    use crate::tests::workspace_mocker::{self, MockProject, WorkspaceMocker};
+
+    let members = vec![
+        MockProject {
+            name: "crate_a".to_string(),
+            version: "0.0.1".to_string(),
+            dependencies: vec![
+                r#"crate_b = { path = "../crate_b", version = "0.0.1" }"#.to_string()
+            ],
+            ty: workspace_mocker::MockProjectType::Lib,
+            ..Default::default()
+        },
+        MockProject {
+            name: "crate_b".to_string(),
+            version: "0.0.1".to_string(),
+            dependencies: vec![
+                r#"crate_c = { path = "../crate_c", version = "0.0.1" }"#.to_string()
+            ],
+            ty: workspace_mocker::MockProjectType::Lib,
+            ..Default::default()
+        },
+        MockProject {
+            name: "crate_c".to_string(),
+            version: "0.0.1".to_string(),
+            ty: workspace_mocker::MockProjectType::Lib,
+            ..Default::default()
+        },
+    ];
+
+    WorkspaceMocker::try_new(None, members)
+}
+
+/// A workspace with a chain of four releasable crates, `crate_a` depending on `crate_b` and so forth.
+pub fn example_workspace_chain_of_four() -> Fallible<WorkspaceMocker> {
+    use crate::tests::workspace_mocker::{self, MockProject, WorkspaceMocker};
+
+    let changelog = indoc::formatdoc!(
+        r#"
+        # Changelog
+
+        ## [Unreleased]
+        Awesome changes!
+        "#
+    );
+
+    let members = [
+        ("crate_a", Some("crate_b")),
+        ("crate_b", Some("crate_c")),
+        ("crate_c", Some("crate_d")),
+        ("crate_d", None),
+    ]
+    .iter()
+    .map(|(name, dependency)| MockProject {
+        name: name.to_string(),
+        version: "0.0.1".to_string(),
+        dependencies: dependency
+            .iter()
+            .map(|dependency| {
+                format!(
+                    r#"{0} = {{ path = "../{0}", version = "0.0.1" }}"#,
+                    dependency
+                )
+            })
+            .collect(),
+        ty: workspace_mocker::MockProjectType::Lib,
+        changelog: Some(changelog.clone()),
+        ..Default::default()
+    })
+    .collect();
+
+    WorkspaceMocker::try_new(
+        Some(indoc::indoc! {r#"
+        # Changelog
+        "#}),
+        members,
+    )
+}
+
+/// A workspace where `crate_a` optionally depends on `crate_b`.
+pub fn example_workspace_optional_dependency() -> Fallible<WorkspaceMocker> {
+    use crate::tests::workspace_mocker::{self, MockProject, WorkspaceMocker};
+
+    let members = vec![
+        MockProject {
+            name: "crate_a".to_string(),
+            version: "0.0.1".to_string(),
+            dependencies: vec![
+                r#"crate_b = { path = "../crate_b", version = "0.0.1", optional = true }"#
+                    .to_string(),
+            ],
+            ty: workspace_mocker::MockProjectType::Lib,
+            ..Default::default()
+        },
+        MockProject {
+            name: "crate_b".to_string(),
+            version: "0.0.1".to_string(),
+            ty: workspace_mocker::MockProjectType::Lib,
+            ..Default::default()
+        },
+    ];
+
+    WorkspaceMocker::try_new(None, members)
+}
+
+/// A workspace where `crate_a` depends on `crate_b` for development, on `crate_c` for building, and on `crate_d` normally.
+pub fn example_workspace_dep_kinds() -> Fallible<WorkspaceMocker> {
+    use crate::tests::workspace_mocker::{self, MockProject, WorkspaceMocker};
+
+    let changelog = indoc::formatdoc!(
+        r#"
+        # Changelog
+
+        ## [Unreleased]
+        Awesome changes!
+        "#
+    );
+
+    let members = vec![
+        MockProject {
+            name: "crate_a".to_string(),
+            version: "0.0.1".to_string(),
+            dependencies: vec![
+                r#"crate_d = { path = "../crate_d", version = "0.0.1" }"#.to_string()
+            ],
+            dev_dependencies: vec![
+                r#"crate_b = { path = "../crate_b", version = "0.0.1" }"#.to_string()
+            ],
+            build_dependencies: vec![
+                r#"crate_c = { path = "../crate_c", version = "0.0.1" }"#.to_string()
+            ],
+            ty: workspace_mocker::MockProjectType::Lib,
+            changelog: Some(changelog.clone()),
+            ..Default::default()
+        },
+        MockProject {
+            name: "crate_b".to_string(),
+            version: "0.0.1".to_string(),
+            ty: workspace_mocker::MockProjectType::Lib,
+            changelog: Some(changelog.clone()),
+            ..Default::default()
+        },
+        MockProject {
+            name: "crate_c".to_string(),
+            version: "0.0.1".to_string(),
+            ty: workspace_mocker::MockProjectType::Lib,
+            changelog: Some(changelog.clone()),
+            ..Default::default()
+        },
+        MockProject {
+            name: "crate_d".to_string(),
+            version: "0.0.1".to_string(),
+            ty: workspace_mocker::MockProjectType::Lib,
+            changelog: Some(changelog.clone()),
+            ..Default::default()
+        },
+    ];
+
+    WorkspaceMocker::try_new(
+        Some(indoc::indoc! {r#"
+        # Changelog
+        "#}),
+        members,
+    )
+}
+
+/// A workspace with crate names that contain each other.
+pub fn example_workspace_overlapping_names() -> Fallible<WorkspaceMocker> {
+    use crate::tests::workspace_mocker::{self, MockProject, WorkspaceMocker};
+
+    let members = ["hdk", "hdk_derive", "holochain_hdk_compat"]
+        .iter()
+        .map(|name| MockProject {
+            name: name.to_string(),
+            version: "0.0.1".to_string(),
+            ty: workspace_mocker::MockProjectType::Lib,
+            ..Default::default()
+        })
+        .collect();
+
+    WorkspaceMocker::try_new(None, members)
+}
+
+/// A workspace with `publish = false` members.
+/// `crate_a` depends on the unpublishable `crate_private`, and `crate_b` dev-depends on the unpublishable `crate_fixture`.
+pub fn example_workspace_publish_false() -> Fallible<WorkspaceMocker> {
+    use crate::tests::workspace_mocker::{self, MockProject, WorkspaceMocker};
+
+    let changelog = indoc::formatdoc!(
+        r#"
+        # Changelog
+
+        ## [Unreleased]
+        Awesome changes!
+        "#
+    );
+
+    let members = vec![
+        MockProject {
+            name: "crate_a".to_string(),
+            version: "0.0.1".to_string(),
+            dependencies: vec![
+                r#"crate_private = { path = "../crate_private", version = "0.0.1" }"#.to_string(),
+            ],
+            ty: workspace_mocker::MockProjectType::Lib,
+            changelog: Some(changelog.clone()),
+            ..Default::default()
+        },
+        MockProject {
+            name: "crate_b".to_string(),
+            version: "0.0.1".to_string(),
+            dev_dependencies: vec![
+                r#"crate_fixture = { path = "../crate_fixture", version = "0.0.1" }"#.to_string(),
+            ],
+            ty: workspace_mocker::MockProjectType::Lib,
+            changelog: Some(changelog.clone()),
+            ..Default::default()
+        },
+        MockProject {
+            name: "crate_private".to_string(),
+            version: "0.0.1".to_string(),
+            ty: workspace_mocker::MockProjectType::Lib,
+            changelog: Some(changelog.clone()),
+            publish: Some(false),
+            ..Default::default()
+        },
+        MockProject {
+            name: "crate_fixture".to_string(),
+            version: "0.0.1".to_string(),
+            ty: workspace_mocker::MockProjectType::Lib,
+            publish: Some(false),
+            ..Default::default()
+        },
+    ];
+
+    WorkspaceMocker::try_new(
+        Some(indoc::indoc! {r#"
+        # Changelog
+        "#}),
+        members,
+    )
+}
+
+/// A workspace whose members are configured via `[package.metadata.release-automation]`.
+pub fn example_workspace_crate_metadata() -> Fallible<WorkspaceMocker> {
+    use crate::tests::workspace_mocker::{self, MockProject, WorkspaceMocker};
+
+    let changelog = indoc::formatdoc!(
+        r#"
+        # Changelog
+
+        ## [Unreleased]
+        Awesome changes!
+        "#
+    );
+
+    let members = vec![
+        MockProject {
+            name: "crate_skipped".to_string(),
+            version: "0.0.1".to_string(),
+            ty: workspace_mocker::MockProjectType::Lib,
+            changelog: Some(changelog.clone()),
+            release_metadata: Some("skip = true".to_string()),
+            ..Default::default()
+        },
+        MockProject {
+            name: "crate_tagged".to_string(),
+            version: "0.0.1".to_string(),
+            ty: workspace_mocker::MockProjectType::Lib,
+            changelog: Some(changelog.clone()),
+            release_metadata: Some(
+                indoc::indoc! {r#"
+                tag-template = "v{version}"
+                semver-increment = "minor"
+                "#}
+                .to_string(),
+            ),
+            ..Default::default()
+        },
+        MockProject {
+            name: "crate_without_readme".to_string(),
+            version: "0.0.1".to_string(),
+            ty: workspace_mocker::MockProjectType::Lib,
+            changelog: Some(changelog.clone()),
+            readme: None,
+            release_metadata: Some(r#"allowed-blockers = ["MissingReadme"]"#.to_string()),
+            ..Default::default()
+        },
+        MockProject {
+            name: "crate_unconfigured".to_string(),
+            version: "0.0.1".to_string(),
+            ty: workspace_mocker::MockProjectType::Lib,
+            changelog: Some(changelog.clone()),
+            readme: None,
+            ..Default::default()
+        },
+    ];
+
+    WorkspaceMocker::try_new(
+        Some(indoc::indoc! {r#"
+        # Changelog
+        "#}),
+        members,
+    )
+}
+
+/// A workspace whose members lack some of the metadata crates.io requires for publishing.
+pub fn example_workspace_publish_metadata() -> Fallible<WorkspaceMocker> {
+    use crate::tests::workspace_mocker::{self, MockProject, WorkspaceMocker};
+
+    let changelog = indoc::formatdoc!(
+        r#"
+        # Changelog
+
+        ## [Unreleased]
+        Awesome changes!
+        "#
+    );
+
+    let members = vec![
+        MockProject {
+            name: "crate_complete".to_string(),
+            version: "0.0.1".to_string(),
+            ty: workspace_mocker::MockProjectType::Lib,
+            changelog: Some(changelog.clone()),
+            ..Default::default()
+        },
+        MockProject {
+            name: "crate_without_description".to_string(),
+            version: "0.0.1".to_string(),
+            ty: workspace_mocker::MockProjectType::Lib,
+            changelog: Some(changelog.clone()),
+            description: None,
+            ..Default::default()
+        },
+        MockProject {
+            name: "crate_without_license".to_string(),
+            version: "0.0.1".to_string(),
+            ty: workspace_mocker::MockProjectType::Lib,
+            changelog: Some(changelog.clone()),
+            license: None,
+            ..Default::default()
+        },
+        MockProject {
+            name: "crate_without_repository".to_string(),
+            version: "0.0.1".to_string(),
+            ty: workspace_mocker::MockProjectType::Lib,
+            changelog: Some(changelog.clone()),
+            repository: None,
+            ..Default::default()
+        },
+        MockProject {
+            name: "crate_without_any".to_string(),
+            version: "0.0.1".to_string(),
+            ty: workspace_mocker::MockProjectType::Lib,
+            changelog: Some(changelog.clone()),
+            description: None,
+            license: None,
+            repository: None,
+            ..Default::default()
+        },
+    ];
+
+    WorkspaceMocker::try_new(
+        Some(indoc::indoc! {r#"
+        # Changelog
+        "#}),
+        members,
+    )
+}
+
+/// A workspace whose members do and don't ship a license file.
+pub fn example_workspace_license_files() -> Fallible<WorkspaceMocker> {
+    use crate::tests::workspace_mocker::{self, MockProject, WorkspaceMocker};
+
+    let changelog = indoc::formatdoc!(
+        r#"
+        # Changelog
+
+        ## [Unreleased]
+        Awesome changes!
+        "#
+    );
+
+    let members = vec![
+        MockProject {
+            name: "crate_with_license".to_string(),
+            version: "0.0.1".to_string(),
+            ty: workspace_mocker::MockProjectType::Lib,
+            changelog: Some(changelog.clone()),
+            ..Default::default()
+        },
+        MockProject {
+            name: "crate_without_license".to_string(),
+            version: "0.0.1".to_string(),
+            ty: workspace_mocker::MockProjectType::Lib,
+            changelog: Some(changelog.clone()),
+            license_text: None,
+            ..Default::default()
+        },
+        MockProject {
+            name: "crate_with_workspace_license".to_string(),
+            version: "0.0.1".to_string(),
+            ty: workspace_mocker::MockProjectType::Lib,
+            changelog: Some(changelog.clone()),
+            license: None,
+            license_file: Some("../../LICENSE".to_string()),
+            license_text: None,
+            ..Default::default()
+        },
+    ];
+
+    let workspace_mocker = WorkspaceMocker::try_new(
+        Some(indoc::indoc! {r#"
+        # Changelog
+        "#}),
+        members,
+    )?;
+
+    workspace_mocker.add_or_replace_file("LICENSE", "Apache License 2.0");
+    workspace_mocker.commit(None);
+
+    Ok(workspace_mocker)
+}
+
+/// A workspace with members that use wildcard dependency and dev-dependency requirements.
+pub fn example_workspace_wildcard() -> Fallible<WorkspaceMocker> {
+    use crate::tests::workspace_mocker::{self, MockProject, WorkspaceMocker};
+
+    let changelog = indoc::formatdoc!(
+        r#"
+        # Changelog
+
+        ## [Unreleased]
+        Awesome changes!
+        "#
+    );
+
+    let members = vec![
+        MockProject {
+            name: "crate_a".to_string(),
+            version: "0.0.1".to_string(),
+            dependencies: vec![
+                r#"crate_wild = { path = "../crate_wild", version = "0.0.1" }"#.to_string(),
+            ],
+            ty: workspace_mocker::MockProjectType::Lib,
+            changelog: Some(changelog.clone()),
+            ..Default::default()
+        },
+        MockProject {
+            name: "crate_b".to_string(),
+            version: "0.0.1".to_string(),
+            dev_dependencies: vec![
+                r#"crate_wild = { path = "../crate_wild", version = "0.0.1" }"#.to_string(),
+            ],
+            ty: workspace_mocker::MockProjectType::Lib,
+            changelog: Some(changelog.clone()),
+            ..Default::default()
+        },
+        MockProject {
+            name: "crate_wild".to_string(),
+            version: "0.0.1".to_string(),
+            dependencies: vec![
+                r#"crate_leaf = { path = "../crate_leaf", version = "*" }"#.to_string()
+            ],
+            ty: workspace_mocker::MockProjectType::Lib,
+            changelog: Some(changelog.clone()),
+            ..Default::default()
+        },
+        MockProject {
+            name: "crate_dev_wild".to_string(),
+            version: "0.0.1".to_string(),
+            dev_dependencies: vec![
+                r#"crate_leaf = { path = "../crate_leaf", version = "*" }"#.to_string()
+            ],
+            ty: workspace_mocker::MockProjectType::Lib,
+            changelog: Some(changelog.clone()),
+            ..Default::default()
+        },
+        MockProject {
+            name: "crate_leaf".to_string(),
+            version: "0.0.1".to_string(),
+            ty: workspace_mocker::MockProjectType::Lib,
+            changelog: Some(changelog.clone()),
+            ..Default::default()
+        },
+    ];
+
+    WorkspaceMocker::try_new(
+        Some(indoc::indoc! {r#"
+        # Changelog
+        "#}),
+        members,
+    )
+}
+
+/// A workspace with members that depend on crates from a git repository.
+pub fn example_workspace_git_dependency() -> Fallible<WorkspaceMocker> {
+    use crate::tests::workspace_mocker::{self, MockProject, WorkspaceMocker};
+
+    let changelog = indoc::formatdoc!(
+        r#"
+        # Changelog
+
+        ## [Unreleased]
+        Awesome changes!
+        "#
+    );
+
+    let members = vec![
+        MockProject {
+            name: "crate_git".to_string(),
+            version: "0.0.1".to_string(),
+            dependencies: vec![
+                r#"holochain_serialized_bytes = { git = "https://github.com/holochain/holochain-serialization", version = "0.0.1" }"#.to_string(),
+            ],
+            ty: workspace_mocker::MockProjectType::Lib,
+            changelog: Some(changelog.clone()),
+            ..Default::default()
+        },
+        MockProject {
+            name: "crate_git_dev".to_string(),
+            version: "0.0.1".to_string(),
+            dev_dependencies: vec![
+                r#"holochain_serialized_bytes = { git = "https://github.com/holochain/holochain-serialization", version = "0.0.1" }"#.to_string(),
+            ],
+            ty: workspace_mocker::MockProjectType::Lib,
+            changelog: Some(changelog.clone()),
+            ..Default::default()
+        },
+    ];
+
+    WorkspaceMocker::try_new(
+        Some(indoc::indoc! {r#"
+        # Changelog
+        "#}),
+        members,
+    )
+}
+
+/// A workspace with members that have path dependencies with and without a version.
+pub fn example_workspace_path_dep_version() -> Fallible<WorkspaceMocker> {
+    use crate::tests::workspace_mocker::{self, MockProject, WorkspaceMocker};
+
+    let changelog = indoc::formatdoc!(
+        r#"
+        # Changelog
+
+        ## [Unreleased]
+        Awesome changes!
+        "#
+    );
+
+    let members = vec![
+        MockProject {
+            name: "crate_unversioned".to_string(),
+            version: "0.0.1".to_string(),
+            dependencies: vec![r#"crate_leaf = { path = "../crate_leaf" }"#.to_string()],
+            ty: workspace_mocker::MockProjectType::Lib,
+            changelog: Some(changelog.clone()),
+            ..Default::default()
+        },
+        MockProject {
+            name: "crate_versioned".to_string(),
+            version: "0.0.1".to_string(),
+            dependencies: vec![
+                r#"crate_leaf = { path = "../crate_leaf", version = "0.0.1" }"#.to_string(),
+            ],
+            ty: workspace_mocker::MockProjectType::Lib,
+            changelog: Some(changelog.clone()),
+            ..Default::default()
+        },
+        MockProject {
+            name: "crate_unversioned_dev".to_string(),
+            version: "0.0.1".to_string(),
+            dev_dependencies: vec![r#"crate_leaf = { path = "../crate_leaf" }"#.to_string()],
+            ty: workspace_mocker::MockProjectType::Lib,
+            changelog: Some(changelog.clone()),
+            ..Default::default()
+        },
+        MockProject {
+            name: "crate_leaf".to_string(),
+            version: "0.0.1".to_string(),
+            ty: workspace_mocker::MockProjectType::Lib,
+            changelog: Some(changelog.clone()),
+            ..Default::default()
+        },
+    ];
+
+    WorkspaceMocker::try_new(
+        Some(indoc::indoc! {r#"
+        # Changelog
+        "#}),
+        members,
+    )
+}
+
+/// A workspace with members whose keywords and categories do and don't fit the crates.io limits.
+pub fn example_workspace_keywords_and_categories() -> Fallible<WorkspaceMocker> {
+    use crate::tests::workspace_mocker::{self, MockProject, WorkspaceMocker};
+
+    let changelog = indoc::formatdoc!(
+        r#"
+        # Changelog
+
+        ## [Unreleased]
+        Awesome changes!
+        "#
+    );
+
+    let six = |prefix: &str| {
+        (1..=6)
+            .map(|i| format!("{}{}", prefix, i))
+            .collect::<Vec<_>>()
+    };
+
+    let members = vec![
+        MockProject {
+            name: "crate_valid".to_string(),
+            version: "0.0.1".to_string(),
+            ty: workspace_mocker::MockProjectType::Lib,
+            changelog: Some(changelog.clone()),
+            keywords: vec!["holochain".to_string(), "p2p".to_string()],
+            categories: vec!["network-programming".to_string()],
+            ..Default::default()
+        },
+        MockProject {
+            name: "crate_six_keywords".to_string(),
+            version: "0.0.1".to_string(),
+            ty: workspace_mocker::MockProjectType::Lib,
+            changelog: Some(changelog.clone()),
+            keywords: six("keyword"),
+            ..Default::default()
+        },
+        MockProject {
+            name: "crate_six_categories".to_string(),
+            version: "0.0.1".to_string(),
+            ty: workspace_mocker::MockProjectType::Lib,
+            changelog: Some(changelog.clone()),
+            categories: six("category"),
+            ..Default::default()
+        },
+        MockProject {
+            name: "crate_invalid_keyword".to_string(),
+            version: "0.0.1".to_string(),
+            ty: workspace_mocker::MockProjectType::Lib,
+            changelog: Some(changelog.clone()),
+            keywords: vec!["in valid".to_string()],
+            ..Default::default()
+        },
+    ];
+
+    WorkspaceMocker::try_new(
+        Some(indoc::indoc! {r#"
+        # Changelog
+        "#}),
+        members,
+    )
+}
+
+/// A workspace with a large and a small member crate.
+pub fn example_workspace_large_package() -> Fallible<WorkspaceMocker> {
+    use crate::tests::workspace_mocker::{self, MockProject, WorkspaceMocker};
+
+    let changelog = indoc::formatdoc!(
+        r#"
+        # Changelog
+
+        ## [Unreleased]
+        Awesome changes!
+        "#
+    );
+
+    let members = vec![
+        MockProject {
+            name: "crate_large".to_string(),
+            version: "0.0.1".to_string(),
+            ty: workspace_mocker::MockProjectType::Lib,
+            changelog: Some(changelog.clone()),
+            ..Default::default()
+        },
+        MockProject {
+            name: "crate_small".to_string(),
+            version: "0.0.1".to_string(),
+            ty: workspace_mocker::MockProjectType::Lib,
+            changelog: Some(changelog.clone()),
+            ..Default::default()
+        },
+    ];
+
+    let workspace_mocker = WorkspaceMocker::try_new(
+        Some(indoc::indoc! {r#"
+        # Changelog
+        "#}),
+        members,
+    )?;
+
+    workspace_mocker.add_or_replace_file(
+        "crates/crate_large/fixtures/generated.bin",
+        &"0".repeat(64 * 1024),
+    );
+    workspace_mocker.commit(None);
+
+    Ok(workspace_mocker)
+}
+
+/// A workspace with members whose manifest versions match, lead or trail their latest changelog release.
+pub fn example_workspace_version_changelog() -> Fallible<WorkspaceMocker> {
+    use crate::tests::workspace_mocker::{self, MockProject, WorkspaceMocker};
+
+    let changelog = |version: &str| {
+        indoc::formatdoc!(
+            r#"
+            # Changelog
+
+            ## Unreleased
+
+            ## {}
+
+            Initial release.
+            "#,
+            version
+        )
+    };
+
+    let members = vec![
+        MockProject {
+            name: "crate_matching".to_string(),
+            version: "0.0.1".to_string(),
+            ty: workspace_mocker::MockProjectType::Lib,
+            changelog: Some(changelog("0.0.1")),
+            ..Default::default()
+        },
+        MockProject {
+            name: "crate_manifest_ahead".to_string(),
+            version: "0.0.2".to_string(),
+            ty: workspace_mocker::MockProjectType::Lib,
+            changelog: Some(changelog("0.0.1")),
+            ..Default::default()
+        },
+        MockProject {
+            name: "crate_changelog_ahead".to_string(),
+            version: "0.0.1".to_string(),
+            ty: workspace_mocker::MockProjectType::Lib,
+            changelog: Some(changelog("0.0.2")),
+            ..Default::default()
+        },
+    ];
+
+    WorkspaceMocker::try_new(None, members)
+}
+
+/// A workspace with members that declare different rust-versions.
+pub fn example_workspace_msrv() -> Fallible<WorkspaceMocker> {
+    use crate::tests::workspace_mocker::{self, MockProject, WorkspaceMocker};
+
+    let changelog = indoc::formatdoc!(
+        r#"
+        # Changelog
+
+        ## [Unreleased]
+        Awesome changes!
+        "#
+    );
+
+    let members = vec![
+        MockProject {
+            name: "crate_old".to_string(),
+            version: "0.0.1".to_string(),
+            dependencies: vec![
+                r#"crate_mid = { path = "../crate_mid", version = "0.0.1" }"#.to_string(),
+            ],
+            ty: workspace_mocker::MockProjectType::Lib,
+            changelog: Some(changelog.clone()),
+            rust_version: Some("1.60".to_string()),
+            ..Default::default()
+        },
+        MockProject {
+            name: "crate_unspecified".to_string(),
+            version: "0.0.1".to_string(),
+            dependencies: vec![
+                r#"crate_mid = { path = "../crate_mid", version = "0.0.1" }"#.to_string(),
+            ],
+            ty: workspace_mocker::MockProjectType::Lib,
+            changelog: Some(changelog.clone()),
+            ..Default::default()
+        },
+        MockProject {
+            name: "crate_mid".to_string(),
+            version: "0.0.1".to_string(),
+            ty: workspace_mocker::MockProjectType::Lib,
+            changelog: Some(changelog.clone()),
+            rust_version: Some("1.65".to_string()),
+            ..Default::default()
+        },
+        MockProject {
+            name: "crate_new".to_string(),
+            version: "0.0.1".to_string(),
+            ty: workspace_mocker::MockProjectType::Lib,
+            changelog: Some(changelog.clone()),
+            rust_version: Some("1.70".to_string()),
+            ..Default::default()
+        },
+    ];
+
+    WorkspaceMocker::try_new(
+        Some(indoc::indoc! {r#"
+        # Changelog
+        "#}),
+        members,
+    )
+}
+
+/// A workspace where crate_untagged's version is released according to its changelog but lacks the release tag.
+/// The release commit is followed by one that doesn't touch any of the crates.
+pub fn example_workspace_untagged_release() -> Fallible<WorkspaceMocker> {
+    use crate::tests::workspace_mocker::{self, MockProject, WorkspaceMocker};
+
+    let changelog = indoc::indoc! {r#"
+        # Changelog
+
+        ## Unreleased
+
+        ## 0.0.1
+
+        Initial release.
+        "#
+    };
+
+    let members = vec![
+        MockProject {
+            name: "crate_tagged".to_string(),
+            version: "0.0.1".to_string(),
+            ty: workspace_mocker::MockProjectType::Lib,
+            changelog: Some(changelog.to_string()),
+            ..Default::default()
+        },
+        MockProject {
+            name: "crate_untagged".to_string(),
+            version: "0.0.1".to_string(),
+            ty: workspace_mocker::MockProjectType::Lib,
+            changelog: Some(changelog.to_string()),
+            ..Default::default()
+        },
+        MockProject {
+            name: "crate_unpublished".to_string(),
+            version: "0.0.1".to_string(),
+            ty: workspace_mocker::MockProjectType::Lib,
+            changelog: Some(changelog.to_string()),
+            ..Default::default()
+        },
+    ];
+
+    let workspace_mocker = WorkspaceMocker::try_new(None, members)?;
+    workspace_mocker.tag("crate_tagged-0.0.1");
+    workspace_mocker.add_or_replace_file("NOTES.md", "# Notes\n");
+    workspace_mocker.commit(None);
+
+    Ok(workspace_mocker)
+}
+
+/// A workspace where crate_untagged's version 0.1.5 is released but lacks the release tag.
+/// The release commit is followed by one that adds a requirement on crate_dep with the same version to crate_untagged's manifest.
+pub fn example_workspace_untagged_release_with_matching_dependency() -> Fallible<WorkspaceMocker> {
+    use crate::tests::workspace_mocker::{self, MockProject, WorkspaceMocker};
+
+    let changelog = indoc::indoc! {r#"
+        # Changelog
+
+        ## Unreleased
+
+        ## 0.1.5
+
+        Initial release.
+        "#
+    };
+
+    let members = vec![
+        MockProject {
+            name: "crate_dep".to_string(),
+            version: "0.1.5".to_string(),
+            ty: workspace_mocker::MockProjectType::Lib,
+            changelog: Some(changelog.to_string()),
+            ..Default::default()
+        },
+        MockProject {
+            name: "crate_untagged".to_string(),
+            version: "0.1.5".to_string(),
+            ty: workspace_mocker::MockProjectType::Lib,
+            changelog: Some(changelog.to_string()),
+            ..Default::default()
+        },
+    ];
+
+    let workspace_mocker = WorkspaceMocker::try_new(None, members)?;
+    workspace_mocker.tag("crate_dep-0.1.5");
+
+    let manifest_path = "crates/crate_untagged/Cargo.toml";
+    let manifest = std::fs::read_to_string(workspace_mocker.root().join(manifest_path))?;
+    workspace_mocker.add_or_replace_file(
+        manifest_path,
+        &manifest.replacen(
+            "[dependencies]\n",
+            "[dependencies]\ncrate_dep = { path = \"../crate_dep\", version = \"0.1.5\" }\n",
+            1,
+        ),
+    );
+    workspace_mocker.commit(None);
+
+    Ok(workspace_mocker)
+}
+
+/// A workspace in which crate_broken can't be published to crates.io because its manifest restricts it to another registry.
+pub fn example_workspace_broken_manifest() -> Fallible<WorkspaceMocker> {
+    use crate::tests::workspace_mocker::{self, MockProject, WorkspaceMocker};
+
+    let changelog = indoc::formatdoc!(
+        r#"
+        # Changelog
+
+        ## [Unreleased]
+        Awesome changes!
+        "#
+    );
+
+    let members = vec![
+        MockProject {
+            name: "crate_ok".to_string(),
+            version: "0.0.1".to_string(),
+            ty: workspace_mocker::MockProjectType::Lib,
+            changelog: Some(changelog.clone()),
+            ..Default::default()
+        },
+        MockProject {
+            name: "crate_broken".to_string(),
+            version: "0.0.1".to_string(),
+            ty: workspace_mocker::MockProjectType::Lib,
+            changelog: Some(changelog),
+            ..Default::default()
+        },
+    ];
+
+    let workspace_mocker = WorkspaceMocker::try_new(None, members)?;
+    workspace_mocker.add_or_replace_file(
+        "crates/crate_broken/Cargo.toml",
+        indoc::indoc! {r#"
+            [package]
+            name = "crate_broken"
+            version = "0.0.1"
+            authors = []
+            description = "some crate"
+            license = "Apache-2.0"
+            repository = "https://github.com/holochain/holochain"
+            publish = ["internal"]
+            "#
+        },
+    );
+    workspace_mocker.commit(None);
+
+    Ok(workspace_mocker)
+}
+
+/// A crate changelog with unreleased changes on top of the given previous release.
+pub fn released_changelog(previous_release: &str) -> String {
+    indoc::formatdoc!(
+        r#"
+        # Changelog
+
+        ## [Unreleased]
+        Awesome changes!
+
+        ## {}
+
+        The previous release.
+        "#,
+        previous_release
+    )
+}
+
+/// A workspace with pre-release and build metadata versions, where `crate_pre` depends on `crate_build`
+/// and `crate_minor` configures its semver increment mode. Only `crate_pre` has no previous release.
+pub fn example_workspace_prerelease_versions() -> Fallible<WorkspaceMocker> {
+    use crate::tests::workspace_mocker::{self, MockProject, WorkspaceMocker};
+
+    let changelog = indoc::formatdoc!(
+        r#"
+        # Changelog
+
+        ## [Unreleased]
+        Awesome changes!
+        "#
+    );
+
+    let members = vec![
+        MockProject {
+            name: "crate_pre".to_string(),
+            version: "0.2.0-beta.3".to_string(),
+            dependencies: vec![
+                r#"crate_build = { path = "../crate_build", version = "1.2.3" }"#.to_string(),
+            ],
+            ty: workspace_mocker::MockProjectType::Lib,
+            changelog: Some(changelog),
+            ..Default::default()
+        },
+        MockProject {
+            name: "crate_build".to_string(),
+            version: "1.2.3+build.5".to_string(),
+            ty: workspace_mocker::MockProjectType::Lib,
+            changelog: Some(released_changelog("1.2.3")),
+            ..Default::default()
+        },
+        MockProject {
+            name: "crate_minor".to_string(),
+            version: "0.1.0".to_string(),
+            ty: workspace_mocker::MockProjectType::Lib,
+            changelog: Some(released_changelog("0.1.0")),
+            release_metadata: Some(r#"semver-increment = "minor""#.to_string()),
+            ..Default::default()
+        },
+    ];
+
+    WorkspaceMocker::try_new(
+        Some(indoc::indoc! {r#"
+        # Changelog
+        "#}),
+        members,
+    )
+}
+
+/// A workspace where `crate_rc` has been released as `0.2.0-rc.1` and `crate_stable` as `0.1.5`, both with changes since.
+pub fn example_workspace_release_candidate() -> Fallible<WorkspaceMocker> {
+    use crate::tests::workspace_mocker::{self, MockProject, WorkspaceMocker};
+
+    let members = vec![
+        MockProject {
+            name: "crate_rc".to_string(),
+            version: "0.2.0-rc.1".to_string(),
+            ty: workspace_mocker::MockProjectType::Lib,
+            changelog: Some(
+                indoc::indoc! {r#"
+                # Changelog
+
+                ## Unreleased
+                More fixes.
+
+                ## 0.2.0-rc.1
+
+                First release candidate.
+                "#}
+                .to_string(),
+            ),
+            ..Default::default()
+        },
+        MockProject {
+            name: "crate_stable".to_string(),
+            version: "0.1.5".to_string(),
+            ty: workspace_mocker::MockProjectType::Lib,
+            changelog: Some(
+                indoc::indoc! {r#"
+                # Changelog
+
+                ## Unreleased
+                A new feature.
+
+                ## 0.1.5
+
+                Some fixes.
+                "#}
+                .to_string(),
+            ),
+            release_metadata: Some(r#"semver-increment = "minor""#.to_string()),
+            ..Default::default()
+        },
+    ];
+
+    let workspace_mocker = WorkspaceMocker::try_new(None, members)?;
+    workspace_mocker.tag("crate_rc-0.2.0-beta.4");
+    workspace_mocker.tag("crate_rc-0.2.0-rc.1");
+    workspace_mocker.tag("crate_stable-0.1.5");
+
+    for name in ["crate_rc", "crate_stable"] {
+        workspace_mocker.add_or_replace_file(
+            &format!("crates/{}/src/changes.rs", name),
+            "pub fn changes() {}\n",
+        );
+    }
+    workspace_mocker.commit(None);
+
+    Ok(workspace_mocker)
+}
+
+/// A workspace where `crate_foo` has been released as `0.3.0-rc.2` and `crate_bar` as `0.1.0`, both tagged at HEAD
+/// with the `{name}-v{version}` template.
+pub fn example_workspace_promotion() -> Fallible<WorkspaceMocker> {
+    use crate::tests::workspace_mocker::{self, MockProject, WorkspaceMocker};
+
+    let changelog = |version: &str| {
+        indoc::formatdoc!(
+            r#"
+            # Changelog
+
+            ## Unreleased
+
+            ## {}
+
+            Released.
+            "#,
+            version
+        )
+    };
+
+    let members = vec![
+        MockProject {
+            name: "crate_foo".to_string(),
+            version: "0.3.0-rc.2".to_string(),
+            ty: workspace_mocker::MockProjectType::Lib,
+            changelog: Some(changelog("0.3.0-rc.2")),
+            ..Default::default()
+        },
+        MockProject {
+            name: "crate_bar".to_string(),
+            version: "0.1.0".to_string(),
+            ty: workspace_mocker::MockProjectType::Lib,
+            changelog: Some(changelog("0.1.0")),
+            ..Default::default()
+        },
+    ];
+
+    let workspace_mocker = WorkspaceMocker::try_new(None, members)?;
+    workspace_mocker.tag("crate_foo-v0.3.0-rc.1");
+    workspace_mocker.tag("crate_foo-v0.3.0-rc.2");
+    workspace_mocker.tag("crate_bar-v0.1.0");
+
+    Ok(workspace_mocker)
+}
+
+/// A workspace with a chain of crates released as 0.1.0, `crate_a` depending on `crate_b` depending on `crate_c`,
+/// and `crate_d` which only has a dev-dependency on `crate_c`.
+pub fn example_workspace_cascade() -> Fallible<WorkspaceMocker> {
+    use crate::tests::workspace_mocker::{self, MockProject, WorkspaceMocker};
+
+    let changelog = released_changelog("0.1.0");
+
+    let dependency =
+        |name: &str| format!(r#"{0} = {{ path = "../{0}", version = "0.1.0" }}"#, name);
+
+    let members = vec![
+        MockProject {
+            name: "crate_a".to_string(),
+            version: "0.1.0".to_string(),
+            dependencies: vec![dependency("crate_b")],
+            ty: workspace_mocker::MockProjectType::Lib,
+            changelog: Some(changelog.clone()),
+            ..Default::default()
+        },
+        MockProject {
+            name: "crate_b".to_string(),
+            version: "0.1.0".to_string(),
+            dependencies: vec![dependency("crate_c")],
+            ty: workspace_mocker::MockProjectType::Lib,
+            changelog: Some(changelog.clone()),
+            ..Default::default()
+        },
+        MockProject {
+            name: "crate_c".to_string(),
+            version: "0.1.0".to_string(),
+            ty: workspace_mocker::MockProjectType::Lib,
+            changelog: Some(changelog.clone()),
+            ..Default::default()
+        },
+        MockProject {
+            name: "crate_d".to_string(),
+            version: "0.1.0".to_string(),
+            dev_dependencies: vec![dependency("crate_c")],
+            ty: workspace_mocker::MockProjectType::Lib,
+            changelog: Some(changelog.clone()),
+            ..Default::default()
+        },
+    ];
+
+    WorkspaceMocker::try_new(
+        Some(indoc::indoc! {r#"
+        # Changelog
+        "#}),
+        members,
+    )
+}
+
+/// A workspace whose crates declare semver increment modes in their changelog frontmatter.
+/// All crates were previously released as 0.1.0.
+pub fn example_workspace_frontmatter_increments() -> Fallible<WorkspaceMocker> {
+    use crate::tests::workspace_mocker::{self, MockProject, WorkspaceMocker};
+
+    let changelog =
+        |front_matter: &str| format!("{}\n{}", front_matter, released_changelog("0.1.0"));
+
+    let members = vec![
+        MockProject {
+            name: "crate_frontmatter".to_string(),
+            version: "0.1.0".to_string(),
+            ty: workspace_mocker::MockProjectType::Lib,
+            changelog: Some(changelog(indoc::indoc! {r#"
+                ---
+                semver_increment_mode: minor
+                default_semver_increment_mode: fix
+                ---
+                "#})),
+            ..Default::default()
+        },
+        MockProject {
+            name: "crate_metadata".to_string(),
+            version: "0.1.0".to_string(),
+            ty: workspace_mocker::MockProjectType::Lib,
+            changelog: Some(changelog(indoc::indoc! {r#"
+                ---
+                semver_increment_mode: minor
+                ---
+                "#})),
+            release_metadata: Some(r#"semver-increment = "patch""#.to_string()),
+            ..Default::default()
+        },
+        MockProject {
+            name: "crate_rc".to_string(),
+            version: "0.3.0-rc.1".to_string(),
+            ty: workspace_mocker::MockProjectType::Lib,
+            changelog: Some(changelog(indoc::indoc! {r#"
+                ---
+                semver_increment_mode: rc
+                ---
+                "#})),
+            ..Default::default()
+        },
+        MockProject {
+            name: "crate_plain".to_string(),
+            version: "0.1.0".to_string(),
+            ty: workspace_mocker::MockProjectType::Lib,
+            changelog: Some(changelog("")),
+            ..Default::default()
+        },
+    ];
+
+    WorkspaceMocker::try_new(
+        Some(indoc::indoc! {r#"
+        # Changelog
+        "#}),
+        members,
+    )
+}
+
+/// A workspace with two released 0.1 crates that changed since,
+/// `crate_a` by removing a public function and `crate_b` by adding one.
+pub fn example_workspace_semver_checks() -> Fallible<WorkspaceMocker> {
+    use crate::tests::workspace_mocker::{self, MockProject, WorkspaceMocker};
+
+    let changelog = indoc::indoc! {r#"
+        # Changelog
+
+        ## Unreleased
+
+        ## 0.1.0
+
+        Initial release.
+        "#
+    };
+
+    let members = vec![
+        MockProject {
+            name: "crate_a".to_string(),
+            version: "0.1.0".to_string(),
+            ty: workspace_mocker::MockProjectType::Lib,
+            changelog: Some(changelog.to_string()),
+            ..Default::default()
+        },
+        MockProject {
+            name: "crate_b".to_string(),
+            version: "0.1.0".to_string(),
+            ty: workspace_mocker::MockProjectType::Lib,
+            changelog: Some(changelog.to_string()),
+            ..Default::default()
+        },
+    ];
+
+    let workspace_mocker = WorkspaceMocker::try_new(None, members)?;
+    workspace_mocker.add_or_replace_file(
+        "crates/crate_a/src/lib.rs",
+        "pub fn removed() {}\npub fn kept() {}\n",
+    );
+    workspace_mocker.commit(None);
+    workspace_mocker.tag("crate_a-0.1.0");
+    workspace_mocker.tag("crate_b-0.1.0");
+
+    workspace_mocker.add_or_replace_file("crates/crate_a/src/lib.rs", "pub fn kept() {}\n");
+    workspace_mocker.add_or_replace_file("crates/crate_b/src/lib.rs", "pub fn added() {}\n");
+    workspace_mocker.commit(None);
+
+    Ok(workspace_mocker)
+}
+
+/// A workspace with `crate_a` whose manifest is formatted by hand and contains comments,
+/// and `crate_b` with a generated manifest.
+pub fn example_workspace_formatted_manifest() -> Fallible<WorkspaceMocker> {
+    use crate::tests::workspace_mocker::{self, MockProject, WorkspaceMocker};
+
+    let changelog = released_changelog("0.1.0");
+
+    let members = vec![
+        MockProject {
+            name: "crate_a".to_string(),
+            version: "0.1.0".to_string(),
+            ty: workspace_mocker::MockProjectType::Lib,
+            changelog: Some(changelog.clone()),
+            ..Default::default()
+        },
+        MockProject {
+            name: "crate_b".to_string(),
+            version: "0.1.0".to_string(),
+            ty: workspace_mocker::MockProjectType::Lib,
+            changelog: Some(changelog),
+            ..Default::default()
+        },
+    ];
+
+    let workspace_mocker = WorkspaceMocker::try_new(None, members)?;
+    workspace_mocker.add_or_replace_file("crates/crate_a/Cargo.toml", FORMATTED_MANIFEST);
+    workspace_mocker.commit(None);
+
+    Ok(workspace_mocker)
+}
+
+pub const FORMATTED_MANIFEST: &str = r#"# this manifest is formatted by hand
+[package]
+name    = "crate_a"
+# bumped by the release automation
+version = "0.1.0"   # keep in sync with the changelog
+authors = []
+description = "some crate"
+license = "Apache-2.0"
+repository = "https://github.com/holochain/holochain"
+homepage = "https://github.com/holochain/holochain"
+documentation = "https://github.com/holochain/holochain"
+
+[dependencies]
+# none yet
+
+[features]
+default = [ "std" ]
+std     = [ ]
+"#;
+
+/// A workspace with `crate_dep` at 0.1.3 and its dependants, which declare their requirements on it
+/// in different dependency tables and with different styles.
+pub fn example_workspace_dependency_requirements() -> Fallible<WorkspaceMocker> {
+    use crate::tests::workspace_mocker::{self, MockProject, WorkspaceMocker};
+
+    let dependency = |version_req: &str| {
+        format!(
+            r#"crate_dep = {{ path = "../crate_dep", version = "{}" }}"#,
+            version_req
+        )
+    };
+
+    let dependant = |name: &str| MockProject {
+        name: name.to_string(),
+        version: "0.1.0".to_string(),
+        ty: workspace_mocker::MockProjectType::Lib,
+        changelog: Some(released_changelog("0.1.0")),
+        ..Default::default()
+    };
+
+    let members = vec![
+        MockProject {
+            name: "crate_dep".to_string(),
+            version: "0.1.3".to_string(),
+            ty: workspace_mocker::MockProjectType::Lib,
+            changelog: Some(released_changelog("0.1.3")),
+            ..Default::default()
+        },
+        MockProject {
+            dependencies: vec![dependency("0.1")],
+            ..dependant("crate_caret")
+        },
+        MockProject {
+            dev_dependencies: vec![dependency("=0.1.3")],
+            ..dependant("crate_exact")
+        },
+        MockProject {
+            build_dependencies: vec![dependency("~0.1.3")],
+            ..dependant("crate_tilde")
+        },
+        MockProject {
+            dependencies: vec![dependency(">=0.1")],
+            ..dependant("crate_satisfied")
+        },
+        dependant("crate_target"),
+    ];
+
+    let workspace_mocker = WorkspaceMocker::try_new(None, members)?;
+
+    let target_manifest_path = "crates/crate_target/Cargo.toml";
+    let target_manifest =
+        std::fs::read_to_string(workspace_mocker.root().join(target_manifest_path))?;
+    workspace_mocker.add_or_replace_file(
+        target_manifest_path,
+        &format!(
+            "{}\n[target.'cfg(unix)'.dependencies]\n{}\n",
+            target_manifest,
+            dependency("^0.1.3")
+        ),
+    );
+    workspace_mocker.commit(None);
+
+    Ok(workspace_mocker)
+}
+
+/// A workspace with `crate_a` and `crate_b` inheriting the version 0.1.0 from the `[workspace.package]` table,
+/// and `crate_c` with its own version 0.3.0.
+pub fn example_workspace_inherited_versions() -> Fallible<WorkspaceMocker> {
+    use crate::tests::workspace_mocker::{self, MockProject, WorkspaceMocker};
+
+    let members = vec![
+        MockProject {
+            name: "crate_a".to_string(),
+            version: "0.1.0".to_string(),
+            ty: workspace_mocker::MockProjectType::Lib,
+            changelog: Some(released_changelog("0.1.0")),
+            ..Default::default()
+        },
+        MockProject {
+            name: "crate_b".to_string(),
+            version: "0.1.0".to_string(),
+            ty: workspace_mocker::MockProjectType::Lib,
+            changelog: Some(released_changelog("0.1.0")),
+            ..Default::default()
+        },
+        MockProject {
+            name: "crate_c".to_string(),
+            version: "0.3.0".to_string(),
+            ty: workspace_mocker::MockProjectType::Lib,
+            changelog: Some(released_changelog("0.3.0")),
+            ..Default::default()
+        },
+    ];
+
+    let workspace_mocker = WorkspaceMocker::try_new(None, members)?;
+
+    let root_manifest = std::fs::read_to_string(workspace_mocker.root().join("Cargo.toml"))?;
+    workspace_mocker.add_or_replace_file(
+        "Cargo.toml",
+        &format!(
+            "{}\n[workspace.package]\nversion = \"0.1.0\"\n",
+            root_manifest
+        ),
+    );
+
+    for name in ["crate_a", "crate_b"] {
+        let manifest_path = format!("crates/{}/Cargo.toml", name);
+        let manifest = std::fs::read_to_string(workspace_mocker.root().join(&manifest_path))?;
+        workspace_mocker.add_or_replace_file(
+            &manifest_path,
+            &manifest.replace(r#"version = "0.1.0""#, "version = { workspace = true }"),
+        );
+    }
+    workspace_mocker.commit(None);
+
+    Ok(workspace_mocker)
+}
+
+/// A workspace with `crate_dep` at 0.1.3 whose requirement `0.1` is declared in the `[workspace.dependencies]` table
+/// of the root manifest. `crate_inherited` and `crate_inherited_dev` inherit it, while `crate_overridden` and `crate_plain`
+/// declare their own requirements.
+pub fn example_workspace_inherited_dependencies() -> Fallible<WorkspaceMocker> {
+    use crate::tests::workspace_mocker::{self, MockProject, WorkspaceMocker};
+
+    let inherited = r#"crate_dep = { workspace = true }"#.to_string();
+    let dependency = |version_req: &str| {
+        format!(
+            r#"crate_dep = {{ path = "../crate_dep", version = "{}" }}"#,
+            version_req
+        )
+    };
+
+    let dependant = |name: &str| MockProject {
+        name: name.to_string(),
+        version: "0.1.0".to_string(),
+        ty: workspace_mocker::MockProjectType::Lib,
+        changelog: Some(released_changelog("0.1.0")),
+        ..Default::default()
+    };
+
+    let members = vec![
+        MockProject {
+            name: "crate_dep".to_string(),
+            version: "0.1.3".to_string(),
+            ty: workspace_mocker::MockProjectType::Lib,
+            changelog: Some(released_changelog("0.1.3")),
+            ..Default::default()
+        },
+        MockProject {
+            dependencies: vec![inherited.clone()],
+            ..dependant("crate_inherited")
+        },
+        MockProject {
+            dev_dependencies: vec![inherited],
+            ..dependant("crate_inherited_dev")
+        },
+        MockProject {
+            dependencies: vec![dependency("=0.1.3")],
+            ..dependant("crate_overridden")
+        },
+        MockProject {
+            dependencies: vec![dependency("0.1")],
+            ..dependant("crate_plain")
+        },
+    ];
+
+    let workspace_mocker = WorkspaceMocker::try_new(None, members)?;
+
+    let root_manifest = std::fs::read_to_string(workspace_mocker.root().join("Cargo.toml"))?;
+    workspace_mocker.add_or_replace_file(
+        "Cargo.toml",
+        &format!(
+            "{}\n[workspace.dependencies]\ncrate_dep = {{ path = \"crates/crate_dep\", version = \"0.1\" }}\n",
+            root_manifest
+        ),
+    );
+    workspace_mocker.commit(None);
+
+    Ok(workspace_mocker)
+}
+
+/// A workspace with a committed lockfile, where `crate_b` depends on `crate_a`,
+/// which in turn depends on `crate_external` that lives outside of the workspace.
+pub fn example_workspace_external_dependency() -> Fallible<WorkspaceMocker> {
+    use crate::tests::workspace_mocker::{self, MockProject, WorkspaceMocker};
+
+    let changelog = released_changelog("0.1.0");
+
+    let members = vec![
+        MockProject {
+            name: "crate_a".to_string(),
+            version: "0.1.0".to_string(),
+            dependencies: vec![
+                r#"crate_external = { path = "../../external/crate_external", version = "0.5.0" }"#
+                    .to_string(),
+            ],
+            ty: workspace_mocker::MockProjectType::Lib,
+            changelog: Some(changelog.clone()),
+            ..Default::default()
+        },
+        MockProject {
+            name: "crate_b".to_string(),
+            version: "0.1.0".to_string(),
+            dependencies: vec![r#"crate_a = { path = "../crate_a", version = "0.1" }"#.to_string()],
+            ty: workspace_mocker::MockProjectType::Lib,
+            changelog: Some(changelog),
+            ..Default::default()
+        },
+        MockProject {
+            name: "crate_external".to_string(),
+            version: "0.5.0".to_string(),
+            ty: workspace_mocker::MockProjectType::Lib,
+            path: Some("external/crate_external".to_string()),
+            excluded: true,
+            ..Default::default()
+        },
+    ];
+
+    let workspace_mocker = WorkspaceMocker::try_new(None, members)?;
+    workspace_mocker.update_lockfile()?;
+    workspace_mocker.commit(None);
+
+    Ok(workspace_mocker)
+}
+
+/// A workspace with a diamond shaped dependency graph.
+pub fn example_workspace_diamond() -> Fallible<WorkspaceMocker> {
+    use crate::tests::workspace_mocker::{self, MockProject, WorkspaceMocker};
+
+    let members = vec![
+        MockProject {
+            name: "crate_a".to_string(),
+            version: "0.0.1".to_string(),
+            dependencies: vec![
+                r#"crate_b = { path = "../crate_b", version = "0.0.1" }"#.to_string(),
+                r#"crate_c = { path = "../crate_c", version = "0.0.1" }"#.to_string(),
+            ],
+            ty: workspace_mocker::MockProjectType::Lib,
+            ..Default::default()
+        },
+        MockProject {
+            name: "crate_b".to_string(),
+            version: "0.0.1".to_string(),
+            dependencies: vec![
+                r#"crate_d = { path = "../crate_d", version = "0.0.1" }"#.to_string()
+            ],
+            ty: workspace_mocker::MockProjectType::Lib,
+            ..Default::default()
+        },
+        MockProject {
+            name: "crate_c".to_string(),
+            version: "0.0.1".to_string(),
+            dependencies: vec![
+                r#"crate_d = { path = "../crate_d", version = "0.0.1" }"#.to_string()
+            ],
+            ty: workspace_mocker::MockProjectType::Lib,
+            ..Default::default()
+        },
+        MockProject {
+            name: "crate_d".to_string(),
+            version: "0.0.1".to_string(),
+            ty: workspace_mocker::MockProjectType::Lib,
+            ..Default::default()
+        },
+    ];
+
+    WorkspaceMocker::try_new(None, members)
+}
+
+/// A workspace with a dependency cycle spanning three crates.
+pub fn example_workspace_cycle() -> Fallible<WorkspaceMocker> {
+    use crate::tests::workspace_mocker::{self, MockProject, WorkspaceMocker};
+
+    let members = vec![
+        MockProject {
+            name: "crate_a".to_string(),
+            version: "0.0.1".to_string(),
+            dependencies: vec![
+                r#"crate_b = { path = "../crate_b", version = "0.0.1" }"#.to_string()
+            ],
+            ty: workspace_mocker::MockProjectType::Lib,
+            ..Default::default()
+        },
+        MockProject {
+            name: "crate_b".to_string(),
+            version: "0.0.1".to_string(),
+            dependencies: vec![
+                r#"crate_c = { path = "../crate_c", version = "0.0.1" }"#.to_string()
+            ],
+            ty: workspace_mocker::MockProjectType::Lib,
+            ..Default::default()
+        },
+        MockProject {
+            name: "crate_c".to_string(),
+            version: "0.0.1".to_string(),
+            dependencies: vec![
+                r#"crate_a = { path = "../crate_a", version = "0.0.1" }"#.to_string()
+            ],
+            ty: workspace_mocker::MockProjectType::Lib,
+            ..Default::default()
+        },
+    ];
+
+    WorkspaceMocker::try_new(None, members)
+}
+
+/// A workspace with two crates that have been released and tagged at HEAD.
+pub fn example_workspace_released() -> Fallible<WorkspaceMocker> {
+    use crate::tests::workspace_mocker::{self, MockProject, WorkspaceMocker};
+
+    let changelog = indoc::indoc! {r#"
+        # Changelog
+
+        ## Unreleased
+
+        ## 0.0.1
+
+        Initial release.
+        "#
+    };
+
+    let members = vec![
+        MockProject {
+            name: "crate_a".to_string(),
+            version: "0.0.1".to_string(),
+            ty: workspace_mocker::MockProjectType::Lib,
+            changelog: Some(changelog.to_string()),
+            ..Default::default()
+        },
+        MockProject {
+            name: "crate_b".to_string(),
+            version: "0.0.1".to_string(),
+            ty: workspace_mocker::MockProjectType::Lib,
+            changelog: Some(changelog.to_string()),
+            ..Default::default()
+        },
+    ];
+
+    let workspace_mocker = WorkspaceMocker::try_new(None, members)?;
+    workspace_mocker.add_or_replace_file(
+        "crates/crate_a/src/shared.rs",
+        indoc::indoc! {r#"
+            pub fn shared() -> &'static str {
+                "shared functionality that is moved between crates"
+            }
+            "#
+        },
+    );
+    workspace_mocker.commit(None);
+    workspace_mocker.tag("crate_a-0.0.1");
+    workspace_mocker.tag("crate_b-0.0.1");
+
+    Ok(workspace_mocker)
+}
+
+/// A workspace with a `never_release` list in its manifest.
+pub fn example_workspace_never_release() -> Fallible<WorkspaceMocker> {
+    use crate::tests::workspace_mocker::{self, MockProject, WorkspaceMocker};
+
+    let changelog = indoc::formatdoc!(
+        r#"
+        # Changelog
+
+        ## [Unreleased]
+        Awesome changes!
+        "#
+    );
+
+    let members = vec![
+        MockProject {
+            name: "crate_a".to_string(),
+            version: "0.0.1".to_string(),
+            dependencies: vec![
+                r#"crate_b = { path = "../crate_b", version = "0.0.1" }"#.to_string()
+            ],
+            ty: workspace_mocker::MockProjectType::Lib,
+            changelog: Some(changelog.clone()),
+            ..Default::default()
+        },
+        MockProject {
+            name: "crate_b".to_string(),
+            version: "0.0.1".to_string(),
+            dependencies: vec![
+                r#"crate_c = { path = "../crate_c", version = "0.0.1" }"#.to_string()
+            ],
+            ty: workspace_mocker::MockProjectType::Lib,
+            changelog: Some(changelog.clone()),
+            ..Default::default()
+        },
+        MockProject {
+            name: "crate_c".to_string(),
+            version: "0.0.1".to_string(),
+            ty: workspace_mocker::MockProjectType::Lib,
+            changelog: Some(changelog.clone()),
+            ..Default::default()
+        },
+        MockProject {
+            name: "crate_fixture".to_string(),
+            version: "0.0.1".to_string(),
+            ty: workspace_mocker::MockProjectType::Lib,
+            changelog: Some(changelog.clone()),
+            publish: Some(false),
+            ..Default::default()
+        },
+    ];
+
+    WorkspaceMocker::try_new_with_release_metadata(
+        Some(indoc::indoc! {r#"
+        # Changelog
+        "#}),
+        Some(r#"never_release = [ "crate_c", "crate_removed" ]"#),
+        members,
+    )
+}
+
+pub fn example_workspace_nested() -> Fallible<WorkspaceMocker> {
+    use crate::tests::workspace_mocker::{self, MockProject, WorkspaceMocker};
+
+    let changelog = indoc::formatdoc!(
+        r#"
+        # Changelog
+
+        ## [Unreleased]
+        Awesome changes!
+        "#
+    );
+
+    let members = vec![
+        MockProject {
+            name: "crate_a".to_string(),
+            version: "0.0.1".to_string(),
+            ty: workspace_mocker::MockProjectType::Lib,
+            changelog: Some(changelog.clone()),
+            ..Default::default()
+        },
+        MockProject {
+            name: "wasm_a".to_string(),
+            version: "0.0.1".to_string(),
+            ty: workspace_mocker::MockProjectType::Lib,
+            changelog: Some(changelog.clone()),
+            path: Some("test_utils/wasm/wasm_a".to_string()),
+            ..Default::default()
+        },
+        MockProject {
+            name: "wasm_b".to_string(),
+            version: "0.0.1".to_string(),
+            ty: workspace_mocker::MockProjectType::Lib,
+            changelog: Some(changelog.clone()),
+            path: Some("test_utils/wasm/wasm_b".to_string()),
+            ..Default::default()
+        },
+        MockProject {
+            name: "test_fixture".to_string(),
+            version: "0.0.1".to_string(),
+            ty: workspace_mocker::MockProjectType::Lib,
+            changelog: Some(changelog.clone()),
+            path: Some("test_utils/fixture".to_string()),
+            ..Default::default()
+        },
+    ];
+
+    WorkspaceMocker::try_new(
+        Some(indoc::indoc! {r#"
+        # Changelog
+        "#}),
+        members,
+    )
+}
+
+/// A generated workspace with the given number of crates, each depending on the one before it.
+/// The crates with an even index have a tagged release, and every third crate has changed since.
+pub fn example_workspace_generated(count: usize) -> Fallible<WorkspaceMocker> {
+    use crate::tests::workspace_mocker::{self, MockProject, WorkspaceMocker};
+
+    let changelog = indoc::indoc! {r#"
+        # Changelog
+
+        ## Unreleased
+
+        ## 0.0.1
+
+        Initial release.
+        "#
+    };
+
+    let name = |index: usize| format!("crate_{:03}", index);
+
+    let members = (0..count)
+        .map(|index| MockProject {
+            name: name(index),
+            version: "0.0.1".to_string(),
+            dependencies: (index > 0)
+                .then(|| {
+                    format!(
+                        r#"{0} = {{ path = "../{0}", version = "0.0.1" }}"#,
+                        name(index - 1)
+                    )
+                })
+                .into_iter()
+                .collect(),
+            ty: workspace_mocker::MockProjectType::Lib,
+            changelog: Some(changelog.to_string()),
+            ..Default::default()
+        })
+        .collect();
+
+    let workspace_mocker = WorkspaceMocker::try_new(None, members)?;
+    for index in (0..count).step_by(2) {
+        workspace_mocker.tag(&format!("{}-0.0.1", name(index)));
+    }
+    for index in (0..count).step_by(3) {
+        workspace_mocker.add_or_replace_file(
+            &format!("crates/{}/src/changed.rs", name(index)),
+            "pub fn changed() {}\n",
+        );
+    }
+    workspace_mocker.commit(None);
+
+    Ok(workspace_mocker)
+}
+
+/// A workspace in which crate_a contains the excluded crate_vendored, and both are released.
+pub fn example_workspace_nested_excluded() -> Fallible<WorkspaceMocker> {
+    use crate::tests::workspace_mocker::{self, MockProject, WorkspaceMocker};
+
+    let changelog = indoc::indoc! {r#"
+        # Changelog
+
+        ## Unreleased
+
+        ## 0.0.1
+
+        Initial release.
+        "#
+    };
+
+    let members = vec![
+        MockProject {
+            name: "crate_a".to_string(),
+            version: "0.0.1".to_string(),
+            ty: workspace_mocker::MockProjectType::Lib,
+            changelog: Some(changelog.to_string()),
+            ..Default::default()
+        },
+        MockProject {
+            name: "crate_vendored".to_string(),
+            version: "0.0.1".to_string(),
+            ty: workspace_mocker::MockProjectType::Lib,
+            changelog: Some(changelog.to_string()),
+            excluded: true,
+            path: Some("crates/crate_a/vendored".to_string()),
+            ..Default::default()
+        },
+    ];
+
+    let workspace_mocker = WorkspaceMocker::try_new(None, members)?;
+    workspace_mocker.tag("crate_a-0.0.1");
+
+    Ok(workspace_mocker)
+}
+
+/// A workspace in which crate_a depends on crate_common on all platforms, on crate_win only on windows and on crate_unix only on unix.
+pub fn example_workspace_platform_dependencies() -> Fallible<WorkspaceMocker> {
+    use crate::tests::workspace_mocker::{self, MockProject, WorkspaceMocker};
+
+    let changelog = indoc::indoc! {r#"
+        # Changelog
+
+        ## Unreleased
+        "#
+    };
+
+    let members = ["crate_a", "crate_common", "crate_win", "crate_unix"]
+        .iter()
+        .map(|name| MockProject {
+            name: name.to_string(),
+            version: "0.0.1".to_string(),
+            ty: workspace_mocker::MockProjectType::Lib,
+            changelog: Some(changelog.to_string()),
+            ..Default::default()
+        })
+        .collect();
+
+    let workspace_mocker = WorkspaceMocker::try_new(None, members)?;
+    workspace_mocker.add_or_replace_file(
+        "crates/crate_a/Cargo.toml",
+        indoc::indoc! {r#"
+            [package]
+            name = "crate_a"
+            version = "0.0.1"
+            authors = []
+            description = "some crate"
+            license = "Apache-2.0"
+            repository = "https://github.com/holochain/holochain"
+
+            [dependencies]
+            crate_common = { path = "../crate_common", version = "0.0.1" }
+
+            [target.'cfg(windows)'.dependencies]
+            crate_win = { path = "../crate_win", version = "0.0.1" }
+
+            [target.'cfg(unix)'.dependencies]
+            crate_unix = { path = "../crate_unix", version = "0.0.1" }
+            "#
+        },
+    );
+    workspace_mocker.commit(None);
+
+    Ok(workspace_mocker)
+}
+
+/// A workspace in which the changelog of crate_broken contains a release heading without a title.
+pub fn example_workspace_broken_changelog() -> Fallible<WorkspaceMocker> {
+    use crate::tests::workspace_mocker::{self, MockProject, WorkspaceMocker};
+
+    let members = vec![
+        MockProject {
+            name: "crate_ok".to_string(),
+            version: "0.0.1".to_string(),
+            ty: workspace_mocker::MockProjectType::Lib,
+            changelog: Some(
+                indoc::indoc! {r#"
+                    # Changelog
+
+                    ## Unreleased
+
+                    ## 0.0.1
+                    "#
+                }
+                .to_string(),
+            ),
+            ..Default::default()
+        },
+        MockProject {
+            name: "crate_broken".to_string(),
+            version: "0.0.1".to_string(),
+            ty: workspace_mocker::MockProjectType::Lib,
+            changelog: Some(
+                indoc::indoc! {r#"
+                    # Changelog
+
+                    ## Unreleased
+
+                    ##
+
+                    ## 0.0.1
+                    "#
+                }
+                .to_string(),
+            ),
+            ..Default::default()
+        },
+    ];
+
+    let workspace_mocker = WorkspaceMocker::try_new(None, members)?;
+    workspace_mocker.tag("crate_ok-0.0.1");
+    workspace_mocker.tag("crate_broken-0.0.1");
+
+    Ok(workspace_mocker)
+}
+
+/// A workspace with a crate that was renamed from crate_old after its previous release,
+/// which was tagged under the old name and is referred to by the given frontmatter override.
+pub fn example_workspace_renamed_crate(previous_release_tag: &str) -> Fallible<WorkspaceMocker> {
+    use crate::tests::workspace_mocker::{self, MockProject, WorkspaceMocker};
+
+    let members = vec![MockProject {
+        name: "crate_renamed".to_string(),
+        version: "0.0.9".to_string(),
+        ty: workspace_mocker::MockProjectType::Lib,
+        changelog: Some(indoc::formatdoc!(
+            r#"
+            ---
+            previous_release_tag: "{}"
+            ---
+            # Changelog
+
+            ## Unreleased
+
+            ## 0.0.9
+            "#,
+            previous_release_tag
+        )),
+        ..Default::default()
+    }];
+
+    let workspace_mocker = WorkspaceMocker::try_new(None, members)?;
+    workspace_mocker.tag("crate_old-0.0.9");
+    workspace_mocker.add_or_replace_file("crates/crate_renamed/README.md", "renamed");
+    workspace_mocker.commit(None);
+
+    Ok(workspace_mocker)
+}
+
+/// A workspace in which the changelog of crate_a lists its unreleased section below a release without a date,
+/// and the changelog of crate_b is canonical.
+pub fn example_workspace_malformed_changelogs() -> Fallible<WorkspaceMocker> {
+    use crate::tests::workspace_mocker::{self, MockProject, WorkspaceMocker};
+
+    let members = vec![
+        MockProject {
+            name: "crate_a".to_string(),
+            version: "0.1.0".to_string(),
+            ty: workspace_mocker::MockProjectType::Lib,
+            changelog: Some(
+                indoc::indoc! {r#"
+                ---
+                default_semver_increment_mode: patch
+                ---
+                # Changelog
+
+                ## 0.1.0
+                - the initial release
+
+                ## Unreleased
+                - a change
+                "#
+                }
+                .to_string(),
+            ),
+            ..Default::default()
+        },
+        MockProject {
+            name: "crate_b".to_string(),
+            version: "0.0.1".to_string(),
+            ty: workspace_mocker::MockProjectType::Lib,
+            changelog: Some(
+                indoc::indoc! {r#"
+                # Changelog
+
+                ## Unreleased
+
+                ## 0.0.1 - 2021-06-30
+
+                - the initial release
+                "#
+                }
+                .to_string(),
+            ),
+            ..Default::default()
+        },
+    ];
+
+    WorkspaceMocker::try_new(None, members)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -38,6 +38,14 @@ Each release involves three branches:
 13. Create and merge a PR to develop
 14. Push the tags upstream
 
+## Exit codes
+
+Besides `0` on success, the commands exit with the following codes so CI can tell the outcomes apart:
+- `1`: an unexpected error
+- `2`: no crates are selected for the release
+- `3`: crates that are required for the release are blocked
+- `4`: a precondition isn't met, e.g. the working tree is dirty
+
 ## Related projects and rationale
 
 There was an attempt to use a modified version of [cargo-release](https://github.com/sunng87/cargo-release) but the opionions on the desired workflow currently suggest to build it out from scratch.
@@ -59,25 +67,57 @@ use release_automation as lib;
 
 use lib::*;
 
-use log::debug;
+use lib::exit_code::ErrorCode;
 use structopt::StructOpt;
-
-fn main() -> CommandResult {
-    let args = cli::Args::from_args();
-
-    env_logger::builder()
-        .filter_level(args.log_level.to_level_filter())
-        .filter(Some("cargo::core::workspace"), log::LevelFilter::Error)
-        .parse_filters(&args.log_filters)
-        .format_timestamp(None)
+use tracing::debug;
+
+fn main() {
+    let mut args = cli::Args::from_args();
+
+    // RUST_LOG keeps working like it did with env_logger
+    let directives = [
+        args.log_level.to_string().to_lowercase(),
+        std::env::var("RUST_LOG").unwrap_or_default(),
+        "cargo::core::workspace=error".to_string(),
+        args.log_filters.clone(),
+    ]
+    .into_iter()
+    .filter(|directive| !directive.is_empty())
+    .collect::<Vec<_>>()
+    .join(",");
+
+    // this also installs the compatibility layer which forwards the `log` records of the dependencies, e.g. cargo
+    tracing_subscriber::fmt()
+        .with_env_filter(tracing_subscriber::EnvFilter::new(directives))
+        .with_writer(std::io::stderr)
+        .without_time()
         .init();
 
     debug!("args: {:#?}", args);
 
-    match &args.cmd {
+    // allows running the commands from any directory within the workspace
+    match crate_selection::ReleaseWorkspace::discover_root(&args.workspace_path) {
+        Ok(root_path) => args.workspace_path = root_path,
+        Err(err) => {
+            eprintln!("Error: {:?}", err);
+            std::process::exit(err.code() as i32);
+        }
+    }
+
+    let result = match &args.cmd {
         cli::Commands::Changelog(cmd_args) => crate::changelog::cmd(&args, cmd_args),
         cli::Commands::Check(cmd_args) => crate::check::cmd(&args, cmd_args),
         cli::Commands::Release(cmd_args) => crate::release::cmd(&args, cmd_args),
         cli::Commands::Crate(cmd_args) => crate::crate_::cmd(&args, cmd_args),
+        cli::Commands::Graph(cmd_args) => crate::graph::cmd(&args, cmd_args),
+        cli::Commands::Rollback(cmd_args) => crate::rollback::cmd(&args, cmd_args),
+        cli::Commands::Plan(cmd_args) => crate::plan::cmd(&args, cmd_args),
+        cli::Commands::Explain(cmd_args) => crate::check::explain(&args, cmd_args),
+        cli::Commands::Resume(cmd_args) => crate::release::resume(&args, cmd_args),
+    };
+
+    if let Err(err) = result {
+        eprintln!("Error: {:?}", err);
+        std::process::exit(err.code() as i32);
     }
 }